@@ -0,0 +1,588 @@
+use crate::error::{validate_tolerances, NumericalMethodsError};
+
+// Adaptive Gauss-Kronrod quadrature for closures.
+//
+// Each interval is integrated with a Gauss rule and the Kronrod extension
+// which reuses its nodes, so that the difference between the two results
+// estimates the error at no extra cost. The interval with the largest error
+// estimate is repeatedly bisected until the total error estimate meets the
+// tolerance (the QUADPACK QAG algorithm). The error estimate of each
+// interval follows QUADPACK, which scales |Kronrod - Gauss| to reflect the
+// much higher accuracy of the Kronrod result.
+
+/// # Gauss-Kronrod rule
+///
+/// ## Description
+/// `GaussKronrodRule` selects the pair of rules used on each interval by
+/// `adaptive_integrate`.
+///
+/// - `G7K15` uses the 7 point Gauss rule and its 15 point Kronrod extension,
+///   which is exact for polynomials of degree up to 22.
+/// - `G10K21` uses the 10 point Gauss rule and its 21 point Kronrod
+///   extension, which is exact for polynomials of degree up to 31. It is
+///   more efficient for very smooth integrands.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GaussKronrodRule {
+    G7K15,
+    G10K21,
+}
+
+/// # Adaptive quadrature options
+///
+/// ## Description
+/// `AdaptiveQuadratureOptions` controls `adaptive_integrate`. The integration
+/// stops once the estimated error is at most
+/// max(`absolute_tolerance`, `relative_tolerance` * |integral|), or once the
+/// domain has been divided into `max_subdivisions` intervals.
+///
+/// ## Example use case
+/// ```
+/// let options = AdaptiveQuadratureOptions {
+///     rule: GaussKronrodRule::G10K21,
+///     relative_tolerance: 1e-12,
+///     ..Default::default()
+/// };
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveQuadratureOptions {
+    pub rule: GaussKronrodRule,
+    pub absolute_tolerance: f64,
+    pub relative_tolerance: f64,
+    pub max_subdivisions: usize,
+}
+
+impl Default for AdaptiveQuadratureOptions {
+    fn default() -> Self {
+        AdaptiveQuadratureOptions {
+            rule: GaussKronrodRule::G7K15,
+            absolute_tolerance: 1e-10,
+            relative_tolerance: 1e-10,
+            max_subdivisions: 1000,
+        }
+    }
+}
+
+/// # Quadrature warning
+///
+/// ## Description
+/// `QuadratureWarning` describes why an adaptive integral may not meet its
+/// tolerance. The integral and its error estimate are still returned.
+///
+/// - `SubdivisionLimitReached` means that the domain was divided into
+///   `max_subdivisions` intervals before the error estimate met the
+///   tolerance, usually because of a singularity or discontinuity, or a
+///   tolerance close to machine precision.
+/// - `MinimumWidthReached` means that the interval from `lower` to `upper`
+///   was too narrow to bisect in floating point before the error estimate
+///   met the tolerance, usually because of a discontinuity and a tolerance
+///   below the rounding error of the integral.
+/// - `LevelLimitReached` means that a double exponential rule halved its
+///   step `max_levels` times before the error estimate met the tolerance,
+///   usually because of a singularity inside the interval, or an integrand
+///   which decays too slowly at an infinite limit.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuadratureWarning {
+    SubdivisionLimitReached { max_subdivisions: usize },
+    MinimumWidthReached { lower: f64, upper: f64 },
+    LevelLimitReached { max_levels: usize },
+}
+
+/// # Quadrature result
+///
+/// ## Description
+/// `QuadratureResult` is returned by `adaptive_integrate` and the double
+/// exponential rules in `double_exponential_quadrature.rs`. `value` is the
+/// approximate integral, `error_estimate` is an estimate of its absolute
+/// error, and `evaluations` is the number of times the integrand was
+/// evaluated. `warning` is `None` if the tolerance was met.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadratureResult {
+    pub value: f64,
+    pub error_estimate: f64,
+    pub evaluations: usize,
+    pub warning: Option<QuadratureWarning>,
+}
+
+/// # Adaptive integrate
+///
+/// ## Description
+/// `adaptive_integrate` approximates the integral of `func` from
+/// `lower_limit` to `upper_limit` to the tolerances in `options`, using
+/// adaptive Gauss-Kronrod quadrature with bisection. Unlike the fixed rules
+/// in `numerical_integration.rs` and `quadrature.rs`, it concentrates the
+/// evaluations where `func` is hard to integrate, and returns an error
+/// estimate. If `lower_limit` is greater than `upper_limit`, the integral is
+/// negated.
+///
+/// `adaptive_integrate` panics if the integral fails. See
+/// `try_adaptive_integrate` for a version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want the integral of sqrt(x) ln(x) from 0 to 1, which is
+/// -4 / 9, to about 10 significant figures. The code below does this.
+/// ```
+/// let result = adaptive_integrate(
+///     |x| x.sqrt() * x.ln(),
+///     0.0,
+///     1.0,
+///     &AdaptiveQuadratureOptions::default(),
+/// );
+/// assert!(result.warning.is_none());
+/// ```
+///
+pub fn adaptive_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &AdaptiveQuadratureOptions,
+) -> QuadratureResult
+where
+    F: Fn(f64) -> f64,
+{
+    match try_adaptive_integrate(func, lower_limit, upper_limit, options) {
+        Ok(result) => result,
+        Err(error) => panic!("Integral failed to evaluate. {}", error),
+    }
+}
+
+/// # Try adaptive integrate
+///
+/// ## Description
+/// `try_adaptive_integrate` is the fallible version of `adaptive_integrate`.
+/// It returns an `InvalidParameter` error if the limits are not finite, if
+/// the tolerances are negative or both zero, or if `max_subdivisions` is 0.
+///
+/// Reaching the subdivision limit, or an interval too narrow to bisect, is
+/// not an error, and is reported by the `warning` of the result instead.
+///
+/// ## Example use case
+/// ```
+/// let result = try_adaptive_integrate(f64::exp, 0.0, 1.0, &options)?;
+/// ```
+///
+pub fn try_adaptive_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &AdaptiveQuadratureOptions,
+) -> Result<QuadratureResult, NumericalMethodsError>
+where
+    F: Fn(f64) -> f64,
+{
+    if !(lower_limit.is_finite() && upper_limit.is_finite()) {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The limits of integration must be finite.".to_string(),
+        ));
+    }
+    validate_tolerances(
+        options.absolute_tolerance,
+        options.relative_tolerance,
+    )?;
+    if options.max_subdivisions == 0 {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The maximum number of subdivisions must be positive.".to_string(),
+        ));
+    }
+
+    let evaluations_per_interval = match options.rule {
+        GaussKronrodRule::G7K15 => 15,
+        GaussKronrodRule::G10K21 => 21,
+    };
+    let estimate = |lower: f64, upper: f64| {
+        let (value, error) =
+            gauss_kronrod_estimate(&func, lower, upper, options.rule);
+        Interval {
+            lower,
+            upper,
+            value,
+            error,
+        }
+    };
+
+    let mut intervals = vec![estimate(lower_limit, upper_limit)];
+    let mut value = intervals[0].value;
+    let mut error_estimate = intervals[0].error;
+    let tolerance = |value: f64| {
+        options
+            .absolute_tolerance
+            .max(options.relative_tolerance * value.abs())
+    };
+
+    let mut unsplittable_interval = None;
+    while error_estimate > tolerance(value)
+        && intervals.len() < options.max_subdivisions
+    {
+        // Bisects the interval with the largest error estimate.
+        let (index, _) = intervals
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.error.total_cmp(&b.error))
+            .unwrap_or((0, &intervals[0]));
+        let interval = intervals.swap_remove(index);
+        let midpoint = 0.5 * (interval.lower + interval.upper);
+
+        // Stops if the interval cannot be bisected any further.
+        if midpoint == interval.lower || midpoint == interval.upper {
+            unsplittable_interval = Some((interval.lower, interval.upper));
+            intervals.push(interval);
+            break;
+        }
+
+        let left = estimate(interval.lower, midpoint);
+        let right = estimate(midpoint, interval.upper);
+        value += left.value + right.value - interval.value;
+        error_estimate += left.error + right.error - interval.error;
+        intervals.push(left);
+        intervals.push(right);
+    }
+
+    // Sums from scratch to remove the rounding errors of the updates.
+    let value: f64 = intervals.iter().map(|interval| interval.value).sum();
+    let error_estimate: f64 =
+        intervals.iter().map(|interval| interval.error).sum();
+    let limit_reached = match unsplittable_interval {
+        Some((lower, upper)) => {
+            QuadratureWarning::MinimumWidthReached { lower, upper }
+        }
+        None => QuadratureWarning::SubdivisionLimitReached {
+            max_subdivisions: options.max_subdivisions,
+        },
+    };
+    let warning = (error_estimate > tolerance(value)).then_some(limit_reached);
+
+    Ok(QuadratureResult {
+        value,
+        error_estimate,
+        evaluations: (2 * intervals.len() - 1) * evaluations_per_interval,
+        warning,
+    })
+}
+
+/// # Interval
+///
+/// ## Description
+/// `Interval` is an interval of the adaptive subdivision, with the Kronrod
+/// estimate of the integral over it and its error estimate.
+///
+struct Interval {
+    lower: f64,
+    upper: f64,
+    value: f64,
+    error: f64,
+}
+
+/// # Gauss-Kronrod estimate
+///
+/// ## Description
+/// `gauss_kronrod_estimate` returns the Kronrod estimate of the integral of
+/// `func` from `lower` to `upper` with `rule`, and the QUADPACK estimate of
+/// its absolute error.
+///
+fn gauss_kronrod_estimate<F>(
+    func: &F,
+    lower: f64,
+    upper: f64,
+    rule: GaussKronrodRule,
+) -> (f64, f64)
+where
+    F: Fn(f64) -> f64,
+{
+    let (kronrod_nodes, kronrod_weights, gauss_weights): (
+        &[f64],
+        &[f64],
+        &[f64],
+    ) = match rule {
+        GaussKronrodRule::G7K15 => (&K15_NODES, &K15_WEIGHTS, &G7_WEIGHTS),
+        GaussKronrodRule::G10K21 => (&K21_NODES, &K21_WEIGHTS, &G10_WEIGHTS),
+    };
+
+    let midpoint = 0.5 * (lower + upper);
+    let half_length = 0.5 * (upper - lower);
+    let last = kronrod_nodes.len() - 1;
+
+    // The nodes are stored for x >= 0, with the centre last. The Gauss nodes
+    // are the odd-numbered Kronrod nodes.
+    let mut values = Vec::with_capacity(2 * last + 1);
+    for &node in &kronrod_nodes[..last] {
+        values.push((
+            func(midpoint - half_length * node),
+            func(midpoint + half_length * node),
+        ));
+    }
+    let centre_value = func(midpoint);
+
+    let mut kronrod = kronrod_weights[last] * centre_value;
+    let mut absolute = kronrod_weights[last] * centre_value.abs();
+    let mut gauss = if last.is_multiple_of(2) {
+        0.0
+    } else {
+        gauss_weights[last / 2] * centre_value
+    };
+    for (j, &(left, right)) in values.iter().enumerate() {
+        kronrod += kronrod_weights[j] * (left + right);
+        absolute += kronrod_weights[j] * (left.abs() + right.abs());
+        if j % 2 == 1 {
+            gauss += gauss_weights[j / 2] * (left + right);
+        }
+    }
+
+    // The integral of |f - mean|, used to scale the error estimate.
+    let mean = 0.5 * kronrod;
+    let mut deviation = kronrod_weights[last] * (centre_value - mean).abs();
+    for (j, &(left, right)) in values.iter().enumerate() {
+        deviation +=
+            kronrod_weights[j] * ((left - mean).abs() + (right - mean).abs());
+    }
+
+    let value = kronrod * half_length;
+    let absolute = absolute * half_length.abs();
+    let deviation = deviation * half_length.abs();
+    let mut error = ((kronrod - gauss) * half_length).abs();
+    if deviation != 0.0 && error != 0.0 {
+        error = deviation * (200.0 * error / deviation).powf(1.5).min(1.0);
+    }
+    if absolute > f64::MIN_POSITIVE / (50.0 * f64::EPSILON) {
+        error = error.max(50.0 * f64::EPSILON * absolute);
+    }
+
+    (value, error)
+}
+
+// Nodes and weights of the Gauss-Kronrod rules on [-1, 1], from QUADPACK.
+// The nodes are the non-negative Kronrod nodes in decreasing order, and the
+// Gauss weights belong to the odd-numbered Kronrod nodes.
+
+const K15_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_5,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_48,
+    0.0,
+];
+
+const K15_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_224,
+    0.063_092_092_629_978_56,
+    0.104_790_010_322_250_19,
+    0.140_653_259_715_525_92,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_42,
+    0.204_432_940_075_298_89,
+    0.209_482_141_084_727_82,
+];
+
+const G7_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_64,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+const K21_NODES: [f64; 11] = [
+    0.995_657_163_025_808_1,
+    0.973_906_528_517_171_7,
+    0.930_157_491_355_708_2,
+    0.865_063_366_688_984_5,
+    0.780_817_726_586_416_9,
+    0.679_409_568_299_024_4,
+    0.562_757_134_668_604_7,
+    0.433_395_394_129_247_2,
+    0.294_392_862_701_460_2,
+    0.148_874_338_981_631_22,
+    0.0,
+];
+
+const K21_WEIGHTS: [f64; 11] = [
+    0.011_694_638_867_371_874,
+    0.032_558_162_307_964_725,
+    0.054_755_896_574_351_995,
+    0.075_039_674_810_919_96,
+    0.093_125_454_583_697_6,
+    0.109_387_158_802_297_64,
+    0.123_491_976_262_065_84,
+    0.134_709_217_311_473_34,
+    0.142_775_938_577_060_09,
+    0.147_739_104_901_338_49,
+    0.149_445_554_002_916_9,
+];
+
+const G10_WEIGHTS: [f64; 5] = [
+    0.066_671_344_308_688_14,
+    0.149_451_349_150_580_6,
+    0.219_086_362_515_982_04,
+    0.269_266_719_309_996_35,
+    0.295_524_224_714_752_87,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quadrature::{QuadratureMethod, QuadratureRule};
+
+    const RULES: [GaussKronrodRule; 2] =
+        [GaussKronrodRule::G7K15, GaussKronrodRule::G10K21];
+
+    #[test]
+    fn test_rules_match_golub_welsch() {
+        // The Gauss nodes and weights agree with the Golub-Welsch rules, and
+        // the Kronrod rules are exact for polynomials of degree 22 and 31.
+        let cases = [
+            (&K15_NODES[..], &G7_WEIGHTS[..], 7, 22),
+            (&K21_NODES[..], &G10_WEIGHTS[..], 10, 31),
+        ];
+        for (rule, (nodes, weights, num_points, degree)) in
+            RULES.into_iter().zip(cases)
+        {
+            let gauss_rule =
+                QuadratureRule::new(QuadratureMethod::GaussLegendre {
+                    num_points,
+                });
+            for (k, weight) in weights.iter().enumerate() {
+                let node = nodes[2 * k + 1];
+                let i = gauss_rule
+                    .nodes
+                    .iter()
+                    .position(|x| (x - node).abs() < 1e-14)
+                    .unwrap();
+                assert!((gauss_rule.weights[i] - weight).abs() < 1e-14);
+            }
+
+            for power in [degree - 1, degree] {
+                let (value, _) = gauss_kronrod_estimate(
+                    &|x: f64| (x + 1.0).powi(power),
+                    -1.0,
+                    1.0,
+                    rule,
+                );
+                let exact = 2.0_f64.powi(power + 1) / (power + 1) as f64;
+                assert!((value - exact).abs() < 1e-12 * exact, "{:?}", rule);
+            }
+        }
+    }
+
+    #[test]
+    fn test_adaptive_integrate() {
+        // Smooth, peaked and singular integrands with known integrals.
+        type Integrand = fn(f64) -> f64;
+        let cases: [(Integrand, f64, f64, f64); 4] = [
+            (f64::exp, 0.0, 1.0, std::f64::consts::E - 1.0),
+            (
+                |x| 1.0 / (1e-4 + x * x),
+                -1.0,
+                1.0,
+                200.0 * 100.0_f64.atan(),
+            ),
+            (|x| x.sqrt() * x.ln(), 0.0, 1.0, -4.0 / 9.0),
+            (|x| 1.0 / x.sqrt(), 0.0, 1.0, 2.0),
+        ];
+        for rule in RULES {
+            let options = AdaptiveQuadratureOptions {
+                rule,
+                ..Default::default()
+            };
+            for (func, lower, upper, exact) in cases {
+                let result = adaptive_integrate(func, lower, upper, &options);
+                let tolerance = 1e-10 * exact.abs();
+                assert!(result.warning.is_none(), "{:?} {}", rule, exact);
+                assert!(result.error_estimate <= tolerance.max(1e-10));
+                assert!(
+                    (result.value - exact).abs() <= tolerance.max(1e-10),
+                    "{:?} gave {} rather than {}.",
+                    rule,
+                    result.value,
+                    exact
+                );
+            }
+        }
+
+        // A single interval is enough for a polynomial.
+        let result = adaptive_integrate(
+            |x| x * x,
+            1.0,
+            0.0,
+            &AdaptiveQuadratureOptions::default(),
+        );
+        assert!((result.value + 1.0 / 3.0).abs() < 1e-15);
+        assert_eq!(result.evaluations, 15);
+    }
+
+    #[test]
+    fn test_subdivision_limit_warning() {
+        let options = AdaptiveQuadratureOptions {
+            max_subdivisions: 5,
+            ..Default::default()
+        };
+        let result = adaptive_integrate(|x| 1.0 / x.sqrt(), 0.0, 1.0, &options);
+        assert_eq!(
+            result.warning,
+            Some(QuadratureWarning::SubdivisionLimitReached {
+                max_subdivisions: 5
+            })
+        );
+        assert_eq!(result.evaluations, 9 * 15);
+        assert!(result.error_estimate > 1e-10);
+        assert!((result.value - 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_minimum_width_warning() {
+        // Over an interval only 16 floating point numbers wide, the step's
+        // rounding error estimates cannot meet the tiny tolerance, so
+        // bisection stops at an interval too narrow to split, long before the
+        // subdivision limit.
+        let options = AdaptiveQuadratureOptions {
+            absolute_tolerance: 1e-300,
+            relative_tolerance: 0.0,
+            ..Default::default()
+        };
+        let jump = 1.0 + 6.0 * f64::EPSILON;
+        let result = adaptive_integrate(
+            |x| if x < jump { 1.0 } else { 2.0 },
+            1.0,
+            1.0 + 16.0 * f64::EPSILON,
+            &options,
+        );
+        match result.warning {
+            Some(QuadratureWarning::MinimumWidthReached { lower, upper }) => {
+                assert_eq!(upper - lower, f64::EPSILON);
+            }
+            warning => panic!("Unexpected warning {:?}.", warning),
+        }
+        assert!(result.evaluations <= 31 * 15);
+        assert!(
+            (result.value - 26.0 * f64::EPSILON).abs() <= 2.0 * f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_try_adaptive_integrate_errors() {
+        let options = AdaptiveQuadratureOptions::default();
+        assert!(matches!(
+            try_adaptive_integrate(f64::exp, f64::NEG_INFINITY, 0.0, &options),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        for options in [
+            AdaptiveQuadratureOptions {
+                absolute_tolerance: 0.0,
+                relative_tolerance: 0.0,
+                ..options
+            },
+            AdaptiveQuadratureOptions {
+                max_subdivisions: 0,
+                ..options
+            },
+        ] {
+            assert!(matches!(
+                try_adaptive_integrate(f64::exp, 0.0, 1.0, &options),
+                Err(NumericalMethodsError::InvalidParameter(_))
+            ));
+        }
+    }
+}
@@ -1,42 +1,120 @@
+use crate::error::NumericalMethodsError;
+use crate::grid::Grid;
+
+/// # Boundary Condition
+///
+/// ## Description
+/// `BoundaryCondition` represents the boundary condition (BC) at one end of
+/// the domain of a 1D boundary value problem (BVP) for a function u(x).
+///
+/// - `Dirichlet(value)` fixes u = value.
+/// - `Neumann(value)` fixes u' = value.
+/// - `Robin { a, b, c }` imposes a * u + b * u' = c. At least one of `a` and
+///   `b` must be non-zero.
+/// - `Periodic` identifies the two ends of the domain, so that u and u' are
+///   equal at both ends. It must be used at both ends.
+///
+/// At the boundaries, u' is approximated with a second order one-sided
+/// finite difference, so BVPs need at least 3 grid points.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundaryCondition {
+    Dirichlet(f64),
+    Neumann(f64),
+    Robin { a: f64, b: f64, c: f64 },
+    Periodic,
+}
+
 /// # Boundary Conditions
 ///
 /// ## Description
 /// `BoundaryConditions` stores the boundary conditions (BCs) for a 1D boundary
 /// value problem (BVP).
 ///
-/// Currently, only Dirichlet BCs are supported. Support for more BCs will be
-/// implemented in the future.
-///
-/// The Dirichlet BCs are stored as a two numbers, `left_bc` and `right_bc`,
-/// which represent the values of the function at the left and right boundaries
-/// of the domain, respectively.
+/// The BCs are stored as two `BoundaryCondition`s, `left_bc` and `right_bc`,
+/// which apply at the left and right boundaries of the domain, respectively.
 ///
 /// ## Example use case
-/// Suppose that we have a BVP for a function f(x), with Dirichlet BCs f(0) = 0
-/// and f(1) = 1. We can represent the BCs with a `BoundaryConditions` struct
+/// Suppose that we have a BVP for a function f(x), with BCs f(0) = 0 and
+/// f'(1) = 1. We can represent the BCs with a `BoundaryConditions` struct
 /// with the code below.
 /// ```
-/// left_bc = 0.0;
-/// right_bc = 1.0;
-/// let dirichlet_bcs = BoundaryConditions { left_bc, right_bc };     
+/// let bcs = BoundaryConditions::new(
+///     BoundaryCondition::Dirichlet(0.0),
+///     BoundaryCondition::Neumann(1.0),
+/// );
 /// ```
 ///
-/// ## Todo
-/// Add support for more boundary conditions.
-///
 #[derive(Debug, Clone, PartialEq)]
 pub struct BoundaryConditions {
-    pub left_bc: f64,
-    pub right_bc: f64,
+    pub left_bc: BoundaryCondition,
+    pub right_bc: BoundaryCondition,
+}
+
+/// # Boundary row
+///
+/// ## Description
+/// `BoundaryRow` represents a BC as the linear equation
+/// sum(coefficient * u[index]) = value, where the sum runs over the pairs
+/// `(index, coefficient)` in `coefficients`.
+///
+/// The residual of the BC is the left hand side minus `value`, and
+/// `coefficients` are the non-zero elements of its row of the Jacobian
+/// matrix.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BoundaryRow {
+    pub coefficients: Vec<(usize, f64)>,
+    pub value: f64,
+}
+
+impl BoundaryRow {
+    /// # Residual
+    ///
+    /// ## Description
+    /// `residual` returns the residual of the BC for the function values
+    /// `function_values`.
+    ///
+    pub fn residual(&self, function_values: &[f64]) -> f64 {
+        self.coefficients
+            .iter()
+            .map(|&(index, coefficient)| coefficient * function_values[index])
+            .sum::<f64>()
+            - self.value
+    }
 }
 
 impl BoundaryConditions {
+    /// # New boundary conditions
+    ///
+    /// ## Description
+    /// `new` creates a new `BoundaryConditions` struct with the left boundary
+    /// condition `left_bc` and the right boundary condition `right_bc`.
+    ///
+    /// ## Example use case
+    /// Suppose that we have a BVP for a function f(x), with BCs
+    /// f(0) + f'(0) = 1 and f'(1) = 0. We can represent the BCs with the code
+    /// below.
+    /// ```
+    /// let bcs = BoundaryConditions::new(
+    ///     BoundaryCondition::Robin { a: 1.0, b: 1.0, c: 1.0 },
+    ///     BoundaryCondition::Neumann(0.0),
+    /// );
+    /// ```
+    ///
+    pub fn new(
+        left_bc: BoundaryCondition,
+        right_bc: BoundaryCondition,
+    ) -> Self {
+        BoundaryConditions { left_bc, right_bc }
+    }
+
     /// # New Dirichlet boundary conditions
     ///
     /// ## Description
     /// `new_dirichlet_bcs` creates a new `BoundaryConditions` struct with the
-    /// left boundary condition `left_bc` and the right boundary condition
-    /// `right_bc`.
+    /// Dirichlet boundary conditions f = `left_bc` at the left boundary and
+    /// f = `right_bc` at the right boundary.
     ///
     /// ## Example use case
     /// Suppose that we have a BVP for a function f(x), with Dirichlet BCs f(0)
@@ -47,13 +125,276 @@ impl BoundaryConditions {
     /// ```
     ///
     pub fn new_dirichlet_bcs(left_bc: f64, right_bc: f64) -> Self {
-        BoundaryConditions { left_bc, right_bc }
+        BoundaryConditions {
+            left_bc: BoundaryCondition::Dirichlet(left_bc),
+            right_bc: BoundaryCondition::Dirichlet(right_bc),
+        }
+    }
+
+    /// # New Neumann boundary conditions
+    ///
+    /// ## Description
+    /// `new_neumann_bcs` creates a new `BoundaryConditions` struct with the
+    /// Neumann boundary conditions f' = `left_bc` at the left boundary and
+    /// f' = `right_bc` at the right boundary.
+    ///
+    /// Note that a BVP with Neumann BCs at both ends may not have a unique
+    /// solution.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let neumann_bcs = BoundaryConditions::new_neumann_bcs(0.0, 0.0);
+    /// ```
+    ///
+    pub fn new_neumann_bcs(left_bc: f64, right_bc: f64) -> Self {
+        BoundaryConditions {
+            left_bc: BoundaryCondition::Neumann(left_bc),
+            right_bc: BoundaryCondition::Neumann(right_bc),
+        }
+    }
+
+    /// # New periodic boundary conditions
+    ///
+    /// ## Description
+    /// `new_periodic_bcs` creates a new `BoundaryConditions` struct with
+    /// periodic boundary conditions.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let periodic_bcs = BoundaryConditions::new_periodic_bcs();
+    /// ```
+    ///
+    pub fn new_periodic_bcs() -> Self {
+        BoundaryConditions {
+            left_bc: BoundaryCondition::Periodic,
+            right_bc: BoundaryCondition::Periodic,
+        }
+    }
+
+    /// # Is periodic
+    ///
+    /// ## Description
+    /// `is_periodic` returns true if either BC is periodic.
+    ///
+    pub fn is_periodic(&self) -> bool {
+        self.left_bc == BoundaryCondition::Periodic
+            || self.right_bc == BoundaryCondition::Periodic
+    }
+
+    /// # Validate
+    ///
+    /// ## Description
+    /// `validate` checks that a periodic BC is used at both ends or neither,
+    /// and that each Robin BC has a non-zero `a` or `b`. If not, an
+    /// `InvalidBoundaryConditions` error is returned.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let bcs = BoundaryConditions::new(
+    ///     BoundaryCondition::Periodic,
+    ///     BoundaryCondition::Dirichlet(0.0),
+    /// );
+    /// assert!(bcs.validate().is_err());
+    /// ```
+    ///
+    pub fn validate(&self) -> Result<(), NumericalMethodsError> {
+        if (self.left_bc == BoundaryCondition::Periodic)
+            != (self.right_bc == BoundaryCondition::Periodic)
+        {
+            return Err(NumericalMethodsError::InvalidBoundaryConditions(
+                "Periodic boundary conditions must be used at both ends."
+                    .to_string(),
+            ));
+        }
+
+        for bc in [self.left_bc, self.right_bc] {
+            if let BoundaryCondition::Robin { a, b, .. } = bc {
+                if a == 0.0 && b == 0.0 {
+                    return Err(
+                        NumericalMethodsError::InvalidBoundaryConditions(
+                            "Robin boundary conditions need a non-zero a or b."
+                                .to_string(),
+                        ),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Boundary rows
+    ///
+    /// ## Description
+    /// `boundary_rows` returns the left and right BCs as `BoundaryRow`s on
+    /// `grid`, which must have at least 3 grid points.
+    ///
+    /// For periodic BCs, the left row equates the function values at the two
+    /// ends and the right row equates the one-sided derivatives at the two
+    /// ends.
+    ///
+    pub(crate) fn boundary_rows(&self, grid: &Grid) -> [BoundaryRow; 2] {
+        let last = grid.grid_points.len() - 1;
+        let left_derivative =
+            one_sided_derivative_coefficients(grid, Side::Left);
+        let right_derivative =
+            one_sided_derivative_coefficients(grid, Side::Right);
+
+        if self.is_periodic() {
+            let mut derivative_difference = left_derivative.to_vec();
+            derivative_difference.extend(
+                right_derivative
+                    .iter()
+                    .map(|&(index, coefficient)| (index, -coefficient)),
+            );
+            return [
+                BoundaryRow {
+                    coefficients: vec![(0, 1.0), (last, -1.0)],
+                    value: 0.0,
+                },
+                BoundaryRow {
+                    coefficients: derivative_difference,
+                    value: 0.0,
+                },
+            ];
+        }
+
+        [
+            boundary_row(self.left_bc, 0, left_derivative),
+            boundary_row(self.right_bc, last, right_derivative),
+        ]
+    }
+
+    /// # State residuals
+    ///
+    /// ## Description
+    /// `state_residuals` returns the residuals of the left and right BCs,
+    /// given the states [u, u'] at the left and right ends of the domain,
+    /// `left_state` and `right_state`. It is used by the shooting methods,
+    /// which know u' exactly rather than through finite differences.
+    ///
+    /// For periodic BCs, the residuals are the differences between the values
+    /// and between the derivatives at the two ends.
+    ///
+    pub(crate) fn state_residuals(
+        &self,
+        left_state: [f64; 2],
+        right_state: [f64; 2],
+    ) -> [f64; 2] {
+        if self.is_periodic() {
+            return [
+                left_state[0] - right_state[0],
+                left_state[1] - right_state[1],
+            ];
+        }
+
+        [(self.left_bc, left_state), (self.right_bc, right_state)].map(
+            |(bc, [u, u_derivative])| {
+                let (a, b, c) = robin_coefficients(bc);
+                a * u + b * u_derivative - c
+            },
+        )
+    }
+}
+
+/// # Side
+///
+/// ## Description
+/// `Side` specifies an end of the domain.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// # One-sided derivative coefficients
+///
+/// ## Description
+/// `one_sided_derivative_coefficients` returns the coefficients of the second
+/// order one-sided finite difference approximation to u' at the `side` end of
+/// `grid`, which uses the three grid points nearest that end. The grid may be
+/// non-uniform.
+///
+fn one_sided_derivative_coefficients(
+    grid: &Grid,
+    side: Side,
+) -> [(usize, f64); 3] {
+    let points = &grid.grid_points;
+    let last = points.len() - 1;
+
+    // h1 is the width of the end grid cell and h2 the width of its
+    // neighbour.
+    match side {
+        Side::Left => {
+            let h1 = points[1] - points[0];
+            let h2 = points[2] - points[1];
+            [
+                (0, -(2.0 * h1 + h2) / (h1 * (h1 + h2))),
+                (1, (h1 + h2) / (h1 * h2)),
+                (2, -h1 / (h2 * (h1 + h2))),
+            ]
+        }
+        Side::Right => {
+            let h1 = points[last] - points[last - 1];
+            let h2 = points[last - 1] - points[last - 2];
+            [
+                (last, (2.0 * h1 + h2) / (h1 * (h1 + h2))),
+                (last - 1, -(h1 + h2) / (h1 * h2)),
+                (last - 2, h1 / (h2 * (h1 + h2))),
+            ]
+        }
+    }
+}
+
+/// # Boundary row
+///
+/// ## Description
+/// `boundary_row` returns the non-periodic BC `bc` at the grid point with
+/// index `index` as a `BoundaryRow`, given the one-sided derivative
+/// coefficients at that grid point.
+///
+fn boundary_row(
+    bc: BoundaryCondition,
+    index: usize,
+    derivative: [(usize, f64); 3],
+) -> BoundaryRow {
+    let (a, b, c) = robin_coefficients(bc);
+
+    let mut coefficients: Vec<(usize, f64)> = derivative
+        .iter()
+        .map(|&(i, coefficient)| (i, b * coefficient))
+        .collect();
+    coefficients[0].1 += a;
+    debug_assert_eq!(coefficients[0].0, index);
+
+    BoundaryRow {
+        coefficients,
+        value: c,
+    }
+}
+
+/// # Robin coefficients
+///
+/// ## Description
+/// `robin_coefficients` writes the non-periodic BC `bc` as the Robin BC
+/// a * u + b * u' = c, and returns (a, b, c).
+///
+pub(crate) fn robin_coefficients(bc: BoundaryCondition) -> (f64, f64, f64) {
+    match bc {
+        BoundaryCondition::Dirichlet(value) => (1.0, 0.0, value),
+        BoundaryCondition::Neumann(value) => (0.0, 1.0, value),
+        BoundaryCondition::Robin { a, b, c } => (a, b, c),
+        BoundaryCondition::Periodic => {
+            unreachable!("Periodic BCs are handled separately.")
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grid::test_grids;
 
     #[test]
     fn test_new_dirichlet_bcs() {
@@ -61,22 +402,22 @@ mod tests {
         let left_bc = 0.0;
         let right_bc = 1.0;
         let bcs = BoundaryConditions::new_dirichlet_bcs(left_bc, right_bc);
-        assert_eq!(bcs.left_bc, left_bc);
-        assert_eq!(bcs.right_bc, right_bc);
+        assert_eq!(bcs.left_bc, BoundaryCondition::Dirichlet(left_bc));
+        assert_eq!(bcs.right_bc, BoundaryCondition::Dirichlet(right_bc));
 
         // Tests BCs with negative values
         let left_bc = -1.0;
         let right_bc = -2.0;
         let bcs = BoundaryConditions::new_dirichlet_bcs(left_bc, right_bc);
-        assert_eq!(bcs.left_bc, left_bc);
-        assert_eq!(bcs.right_bc, right_bc);
+        assert_eq!(bcs.left_bc, BoundaryCondition::Dirichlet(left_bc));
+        assert_eq!(bcs.right_bc, BoundaryCondition::Dirichlet(right_bc));
 
         // Tests BCs with small values
         let left_bc = 1e-6;
         let right_bc = 1e-7;
         let bcs = BoundaryConditions::new_dirichlet_bcs(left_bc, right_bc);
-        assert_eq!(bcs.left_bc, left_bc);
-        assert_eq!(bcs.right_bc, right_bc);
+        assert_eq!(bcs.left_bc, BoundaryCondition::Dirichlet(left_bc));
+        assert_eq!(bcs.right_bc, BoundaryCondition::Dirichlet(right_bc));
     }
 
     #[test]
@@ -87,7 +428,7 @@ mod tests {
         let debug_str = format!("{:?}", bcs);
         assert_eq!(
             debug_str,
-            "BoundaryConditions { left_bc: 0.0, right_bc: 1.0 }"
+            "BoundaryConditions { left_bc: Dirichlet(0.0), right_bc: Dirichlet(1.0) }"
         );
     }
 
@@ -97,8 +438,8 @@ mod tests {
         let right_bc = 1.0;
         let bcs = BoundaryConditions::new_dirichlet_bcs(left_bc, right_bc);
         let cloned_bcs = bcs.clone();
-        assert_eq!(cloned_bcs.left_bc, left_bc);
-        assert_eq!(cloned_bcs.right_bc, right_bc);
+        assert_eq!(cloned_bcs.left_bc, BoundaryCondition::Dirichlet(left_bc));
+        assert_eq!(cloned_bcs.right_bc, BoundaryCondition::Dirichlet(right_bc));
     }
 
     #[test]
@@ -118,6 +459,9 @@ mod tests {
         let bcs1 = BoundaryConditions::new_dirichlet_bcs(f64::NAN, 1.0);
         let bcs2 = BoundaryConditions::new_dirichlet_bcs(f64::NAN, 1.0);
         assert_ne!(bcs1, bcs2);
+
+        // Tests that BCs of different kinds are not equal.
+        assert_ne!(bcs, BoundaryConditions::new_neumann_bcs(0.0, 1.0));
     }
 
     #[test]
@@ -126,12 +470,122 @@ mod tests {
         let left_bc = 1.0;
         let right_bc = 1.0;
         let bcs = BoundaryConditions::new_dirichlet_bcs(left_bc, right_bc);
-        assert_eq!(bcs.left_bc, left_bc);
-        assert_eq!(bcs.right_bc, right_bc);
+        assert_eq!(bcs.left_bc, BoundaryCondition::Dirichlet(left_bc));
+        assert_eq!(bcs.right_bc, BoundaryCondition::Dirichlet(right_bc));
 
         // Tests case where BCs are NaN or infinity
         let bc = BoundaryConditions::new_dirichlet_bcs(f64::NAN, f64::INFINITY);
-        assert!(bc.left_bc.is_nan());
-        assert_eq!(bc.right_bc, f64::INFINITY);
+        assert!(
+            matches!(bc.left_bc, BoundaryCondition::Dirichlet(x) if x.is_nan())
+        );
+        assert_eq!(bc.right_bc, BoundaryCondition::Dirichlet(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(BoundaryConditions::new_dirichlet_bcs(0.0, 1.0)
+            .validate()
+            .is_ok());
+        assert!(BoundaryConditions::new_periodic_bcs().validate().is_ok());
+        assert!(BoundaryConditions::new(
+            BoundaryCondition::Robin {
+                a: 0.0,
+                b: 1.0,
+                c: 2.0
+            },
+            BoundaryCondition::Neumann(0.0),
+        )
+        .validate()
+        .is_ok());
+
+        let invalid_bcs = [
+            BoundaryConditions::new(
+                BoundaryCondition::Dirichlet(0.0),
+                BoundaryCondition::Periodic,
+            ),
+            BoundaryConditions::new(
+                BoundaryCondition::Robin {
+                    a: 0.0,
+                    b: 0.0,
+                    c: 1.0,
+                },
+                BoundaryCondition::Dirichlet(0.0),
+            ),
+        ];
+        for bcs in invalid_bcs {
+            assert!(matches!(
+                bcs.validate(),
+                Err(NumericalMethodsError::InvalidBoundaryConditions(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_one_sided_derivatives_are_second_order() {
+        // The one-sided derivatives are exact for quadratics on any grid.
+        let func = |x: f64| 2.0 * x * x - 3.0 * x + 1.0;
+        let derivative = |x: f64| 4.0 * x - 3.0;
+        for (name, grid) in test_grids(0.5, 2.0, 7) {
+            let values: Vec<f64> =
+                grid.grid_points.iter().map(|&x| func(x)).collect();
+            let bcs = BoundaryConditions::new_neumann_bcs(
+                derivative(0.5),
+                derivative(2.0),
+            );
+            for row in bcs.boundary_rows(&grid) {
+                assert!(
+                    row.residual(&values).abs() < 1e-10,
+                    "One-sided derivative failed on the {} grid.",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_boundary_rows() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let values = vec![1.0, 2.0, 4.0, 3.0, 1.0];
+
+        let bcs = BoundaryConditions::new(
+            BoundaryCondition::Dirichlet(0.5),
+            BoundaryCondition::Robin {
+                a: 2.0,
+                b: 0.5,
+                c: 1.0,
+            },
+        );
+        let [left_row, right_row] = bcs.boundary_rows(&grid);
+        assert_eq!(left_row.residual(&values), 0.5);
+
+        // u'(1) is approximated by (3 * 1 - 4 * 3 + 4) / (2 * 0.25) = -10.
+        assert!(
+            (right_row.residual(&values) - (2.0 - 5.0 - 1.0)).abs() < 1e-12
+        );
+
+        // Periodic BCs compare the values and derivatives at the two ends.
+        let [left_row, right_row] =
+            BoundaryConditions::new_periodic_bcs().boundary_rows(&grid);
+        assert_eq!(left_row.residual(&values), 0.0);
+        // u'(0) is approximated by (-3 * 1 + 4 * 2 - 4) / 0.5 = 2.
+        assert!((right_row.residual(&values) - (2.0 + 10.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_state_residuals() {
+        let bcs = BoundaryConditions::new(
+            BoundaryCondition::Neumann(1.0),
+            BoundaryCondition::Robin {
+                a: 2.0,
+                b: 0.5,
+                c: 1.0,
+            },
+        );
+        assert_eq!(bcs.state_residuals([3.0, 2.0], [1.0, -4.0]), [1.0, -1.0]);
+        assert_eq!(
+            BoundaryConditions::new_periodic_bcs()
+                .state_residuals([3.0, 2.0], [1.0, -4.0]),
+            [2.0, 6.0]
+        );
     }
 }
@@ -1,18 +1,21 @@
 use crate::boundary_conditions::BoundaryConditions;
+use crate::error::NumericalMethodsError;
 use crate::grid_function::GridFunction;
+use crate::linear_solvers::BorderedBandedMatrix;
 use nalgebra::LU;
 
 /// # Get Jacobian matrix
 ///
 /// ## Description
-/// `get_jacobian_matrix` takes a DE function, `de_func`, and a trial solution,
-/// `grid_func`, as inputs, and returns the Jacobian matrix of `de_func` at
-/// `grid_func`. The Jacobian matrix is stored as a flat vector, in row-major
-/// order.
+/// `get_jacobian_matrix` takes a DE function, `de_func`, a trial solution,
+/// `grid_func`, and boundary conditions, `boundary_conditions`, as inputs, and
+/// returns the Jacobian matrix of `de_func` at `grid_func`. The Jacobian
+/// matrix is stored as a flat vector, in row-major order.
 ///
 /// To enforce the boundary conditions (BCs), the first and last rows of the
-/// Jacobian matrix are set equal to [1, 0, 0, ..., 0] and [0, 0, ..., 0, 1],
-/// respectively.
+/// Jacobian matrix are set equal to the coefficients of the left and right
+/// BCs. For Dirichlet BCs these are [1, 0, 0, ..., 0] and [0, 0, ..., 0, 1],
+/// respectively. The BCs are linear, so these rows are exact.
 ///
 /// For the internal rows, the element in row i and column j of the Jacobian
 /// matrix is equal to the partial derivative of the ith component of the
@@ -30,6 +33,7 @@ use nalgebra::LU;
 /// let jacobian_matrix = get_jacobian_matrix(
 ///     differential_equation_function,
 ///     &initial_guess_grid_func,
+///     &boundary_conditions,
 ///     1e-6,
 /// );
 /// ```
@@ -37,6 +41,7 @@ use nalgebra::LU;
 pub fn get_jacobian_matrix<F>(
     de_func: &F,
     grid_func: &GridFunction,
+    boundary_conditions: &BoundaryConditions,
     step_size: f64,
 ) -> Vec<f64>
 where
@@ -46,37 +51,122 @@ where
     // The number of rows/columns in the Jacobian matrix is equal to the number
     // of function values in grid_func.
     let matrix_size = grid_func.function_values.len();
+    let mut jacobian_matrix = vec![0.0; matrix_size * matrix_size];
+
+    // Handles the first and last rows of the Jacobian matrix.
+    let [left_row, right_row] =
+        boundary_conditions.boundary_rows(&grid_func.grid);
+    for (j, coefficient) in left_row.coefficients {
+        jacobian_matrix[j] += coefficient;
+    }
+    for (j, coefficient) in right_row.coefficients {
+        jacobian_matrix[(matrix_size - 1) * matrix_size + j] += coefficient;
+    }
+
+    // Handles the interior rows of the Jacobian matrix one column at a time,
+    // so that de_func is only evaluated once per column.
+    let residual = de_func(grid_func).function_values;
+    let mut perturbed_grid_func = grid_func.clone();
+    for j in 0..matrix_size {
+        perturbed_grid_func.function_values[j] += step_size;
+        let perturbed_residual = de_func(&perturbed_grid_func).function_values;
+        perturbed_grid_func.function_values[j] = grid_func.function_values[j];
+
+        // Calculates the (i, j) element of the Jacobian matrix using the
+        // forwards difference approximation.
+        for i in 1..(matrix_size - 1) {
+            jacobian_matrix[i * matrix_size + j] =
+                (perturbed_residual[i] - residual[i]) / step_size;
+        }
+    }
+
+    jacobian_matrix
+}
+
+/// # Get banded Jacobian matrix
+///
+/// ## Description
+/// `get_banded_jacobian_matrix` is a version of `get_jacobian_matrix` for DE
+/// functions whose ith residual only depends on the function values within
+/// `bandwidth` grid points of the ith grid point. The Jacobian matrix is then
+/// a `BorderedBandedMatrix` with lower and upper bandwidths `bandwidth`, or 2
+/// if `bandwidth` is smaller, to make room for the one-sided derivatives in
+/// the BCs.
+///
+/// Periodic BCs couple the first and last grid points, so their two rows are
+/// stored as the full rows of the `BorderedBandedMatrix`. For other BCs there
+/// are no full rows.
+///
+/// Columns of the Jacobian matrix which are more than 2 * `bandwidth` apart
+/// affect different rows, so they are perturbed together. This means that
+/// `de_func` is only evaluated 2 * `bandwidth` + 2 times, however many grid
+/// points there are. For example, a second order central difference
+/// approximation to y'' has bandwidth 1, and needs 4 evaluations.
+///
+/// If `de_func` depends on function values further than `bandwidth` grid
+/// points away, the Jacobian matrix will be wrong.
+///
+/// ## Example use case
+/// ```
+/// let jacobian_matrix = get_banded_jacobian_matrix(
+///     &differential_equation_function,
+///     &initial_guess_grid_func,
+///     &boundary_conditions,
+///     1e-7,
+///     1,
+/// );
+/// ```
+///
+pub fn get_banded_jacobian_matrix<F>(
+    de_func: &F,
+    grid_func: &GridFunction,
+    boundary_conditions: &BoundaryConditions,
+    step_size: f64,
+    bandwidth: usize,
+) -> BorderedBandedMatrix
+where
+    F: Fn(&GridFunction) -> GridFunction,
+{
+    let matrix_size = grid_func.function_values.len();
+    let matrix_bandwidth = bandwidth.max(2);
+    let mut jacobian_matrix = BorderedBandedMatrix::new(
+        matrix_size,
+        matrix_bandwidth,
+        matrix_bandwidth,
+    );
+
+    // Handles the first and last rows of the Jacobian matrix.
+    let [left_row, right_row] =
+        boundary_conditions.boundary_rows(&grid_func.grid);
+    jacobian_matrix.set_row(0, &left_row.coefficients);
+    jacobian_matrix.set_row(matrix_size - 1, &right_row.coefficients);
+
+    // Column j affects rows j - bandwidth to j + bandwidth, so the columns
+    // j, j + num_groups, j + 2 * num_groups, ... can be perturbed together.
+    let num_groups = (2 * bandwidth + 1).min(matrix_size);
+    let residual = de_func(grid_func).function_values;
+    let mut perturbed_grid_func = grid_func.clone();
+
+    for group in 0..num_groups {
+        for j in (group..matrix_size).step_by(num_groups) {
+            perturbed_grid_func.function_values[j] += step_size;
+        }
+        let perturbed_residual = de_func(&perturbed_grid_func).function_values;
 
-    // Pre-allocates memory for the Jacobian matrix.
-    let mut jacobian_matrix: Vec<f64> =
-        Vec::with_capacity(matrix_size * matrix_size);
-
-    // Iterate over the matrix elements in row-major order.
-    // i iterates over the matrix rows and j iterates over the matrix columns.
-    for i in 0..(matrix_size) {
-        for j in 0..matrix_size {
-            if i == 0 {
-                // Handles the first row of the Jacobian matrix.
-                jacobian_matrix.push(if j == 0 { 1.0 } else { 0.0 });
-            } else if i == matrix_size - 1 {
-                // Handles the last row of the Jacobian matrix.
-                jacobian_matrix.push(if j == matrix_size - 1 {
-                    1.0
-                } else {
-                    0.0
-                });
-            } else {
-                // Handles the interior rows of the Jacobian matrix.
-                let mut perturbed_grid_func = grid_func.clone();
-                perturbed_grid_func.function_values[j] += step_size;
-
-                // Calculates the (i, j) element of the Jacobian matrix using
-                // the forwards difference approximation.
-                let jacobian_entry = (de_func(&perturbed_grid_func)
-                    .function_values[i]
-                    - de_func(&grid_func).function_values[i])
-                    / step_size;
-                jacobian_matrix.push(jacobian_entry);
+        for j in (group..matrix_size).step_by(num_groups) {
+            perturbed_grid_func.function_values[j] =
+                grid_func.function_values[j];
+
+            // Calculates the interior elements of column j using the
+            // forwards difference approximation.
+            let first_row = j.saturating_sub(bandwidth).max(1);
+            let last_row = (j + bandwidth).min(matrix_size - 2);
+            for i in first_row..=last_row {
+                jacobian_matrix.band.set(
+                    i,
+                    j,
+                    (perturbed_residual[i] - residual[i]) / step_size,
+                );
             }
         }
     }
@@ -94,8 +184,9 @@ where
 /// The residual vector is the vector of function values of `de_func` evaluated
 /// at `grid_func`, i.e. `de_func(&grid_func).function_values`.
 ///
-/// The first and last elements of the residual vector are modified to drive the
-/// solution to satisfy the boundary conditions.
+/// The first and last elements of the residual vector are replaced by the
+/// residuals of the boundary conditions, to drive the solution to satisfy the
+/// boundary conditions.
 ///
 /// ## Example use case
 /// Suppose we have a DE function, `de_func`, a trial solution,
@@ -109,9 +200,6 @@ where
 /// );
 /// ```
 ///
-/// ## Todo
-/// Modify the function so that it can handle mixed and Neumann BCs.
-///
 fn get_residual_vector<F>(
     de_func: &F,
     grid_func: &GridFunction,
@@ -125,10 +213,11 @@ where
 
     // Modifies the first and last elements of the residual vector to enforce
     // the boundary conditions.
-    residual_vector[0] =
-        grid_func.function_values[0] - boundary_conditions.left_bc;
+    let [left_row, right_row] =
+        boundary_conditions.boundary_rows(&grid_func.grid);
+    residual_vector[0] = left_row.residual(&grid_func.function_values);
     residual_vector[length - 1] =
-        grid_func.function_values[length - 1] - boundary_conditions.right_bc;
+        right_row.residual(&grid_func.function_values);
 
     residual_vector
 }
@@ -144,7 +233,8 @@ where
 /// order. `vector` is a flat vector that represents a column vector.
 ///
 /// `solve_linear_system` uses an LU decomposition algorithm from the nalgebra
-/// library to solve the system of linear equations.
+/// library to solve the system of linear equations. If the matrix is
+/// singular, a `SingularJacobian` error is returned.
 ///
 /// ## Example use case
 /// Suppose we have a matrix `matrix` and a vector `vector`. The code below
@@ -154,128 +244,874 @@ where
 /// let matrix = vec![1.0, 2.0, 3.0, 4.0];
 /// let vector = vec![5.0, 6.0];
 /// let matrix_size = 2;
-/// let solution = solve_linear_system(&matrix, &vector, matrix_size);
+/// let solution = solve_linear_system(&matrix, &vector, matrix_size)?;
 /// ```
 ///
-fn solve_linear_system(
-    matrix: &Vec<f64>,
-    vector: &Vec<f64>,
+pub(crate) fn solve_linear_system(
+    matrix: &[f64],
+    vector: &[f64],
     matrix_size: usize,
-) -> Vec<f64> {
+) -> Result<Vec<f64>, NumericalMethodsError> {
     // Creates a dense matrix from matrix.
     let matrix =
-        nalgebra::DMatrix::from_row_slice(matrix_size, matrix_size, &matrix);
+        nalgebra::DMatrix::from_row_slice(matrix_size, matrix_size, matrix);
 
     // Creates a dense vector from vector.
-    let vector = nalgebra::DVector::from_column_slice(&vector);
+    let vector = nalgebra::DVector::from_column_slice(vector);
 
     // Perform LU decomposition and solve the system of linear equations.
     let lu = LU::new(matrix);
-    let solution = lu.solve(&vector).unwrap();
+    let solution = lu
+        .solve(&vector)
+        .ok_or(NumericalMethodsError::SingularJacobian)?;
 
     // Convert the solution to a Vec<f64>.
-    solution.data.as_vec().clone()
+    Ok(solution.data.as_vec().clone())
 }
 
-/// # Newton's method step
+/// # Newton options
 ///
 /// ## Description
-/// `newtons_method_step` takes a DE function, `de_func` and a trial solution,
-/// `grid_func_guess`, as inputs, and returns the updated the trial solution
-/// of the DE using Newton's method.
+/// `NewtonOptions` controls when `newtons_method` stops iterating.
+///
+/// Newton's method stops with `converged` set to true when the max norm of
+/// the residual vector falls below `absolute_tolerance`, or below
+/// `relative_tolerance` times the max norm of the initial residual vector, or
+/// when the max norm of the Newton update falls below `step_tolerance`. It
+/// stops with `converged` set to false after `max_iterations` iterations, or
+/// if the residual vector is no longer finite.
 ///
-/// `step_size` is the step size used in the finite difference approximation
-/// when calculating the Jacobian matrix. `step_size` should be small.
+/// `finite_difference_step` is the step size used in the finite difference
+/// approximation of the Jacobian matrix, and `jacobian_structure` chooses
+/// between a dense and a banded Jacobian matrix.
 ///
 /// ## Example use case
-/// Suppose we have a DE function, `differential_equation_function`, and a trial
-/// solution, `initial_guess_func`. The code below calculates the updated trial
-/// solution of the DE using Newton's method.
+/// Suppose that we want Newton's method to use the default tolerances but
+/// stop after at most 20 iterations. The code below creates these options.
 /// ```
-/// let grid = Grid::new_uniform_grid(0.0, 1.0, 10);
-/// let initial_guess_grid_func = GridFunction::new_grid_function(&grid,
-/// initial_guess_func);
-/// let updated_guess_grid_func = newtons_method_step
-/// (differential_equation_function, &initial_guess_grid_func, 1e-6);
+/// let options = NewtonOptions {
+///     max_iterations: 20,
+///     ..Default::default()
+/// };
 /// ```
 ///
-/// ## Todo
-/// Currently, to calculate the updated guess, newtons_method_step uses an LU
-/// decomposition algorithm to solve a system of linear equations. I would like
-/// to experiment with other algorithms and see which algorithm is the fastest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NewtonOptions {
+    pub absolute_tolerance: f64,
+    pub relative_tolerance: f64,
+    pub step_tolerance: f64,
+    pub max_iterations: usize,
+    pub finite_difference_step: f64,
+    pub jacobian_structure: JacobianStructure,
+}
+
+impl Default for NewtonOptions {
+    fn default() -> Self {
+        NewtonOptions {
+            absolute_tolerance: 1e-10,
+            relative_tolerance: 1e-12,
+            step_tolerance: 1e-12,
+            max_iterations: 50,
+            finite_difference_step: 1e-7,
+            jacobian_structure: JacobianStructure::Dense,
+        }
+    }
+}
+
+/// # Jacobian structure
+///
+/// ## Description
+/// `JacobianStructure` specifies how `newtons_method` assembles and solves
+/// the Jacobian matrix.
+///
+/// - `Dense` assembles the full Jacobian matrix with `get_jacobian_matrix`
+///   and solves it with a dense LU decomposition. This works for any DE
+///   function, but takes O(n^3) operations per iteration for n grid points.
+/// - `Banded` assembles a `BorderedBandedMatrix` with
+///   `get_banded_jacobian_matrix` and solves it with a banded LU
+///   decomposition. This takes O(n) operations per iteration, but needs the
+///   ith residual of the DE function to only depend on the function values
+///   within `bandwidth` grid points of the ith grid point. The rows of
+///   periodic BCs, which couple the two ends of the domain, are handled by
+///   `BorderedBandedMatrix::solve` at the cost of a few more banded solves.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JacobianStructure {
+    Dense,
+    Banded { bandwidth: usize },
+}
+
+/// # Newton result
+///
+/// ## Description
+/// `NewtonResult` is returned by `newtons_method`. It contains the final
+/// trial solution `solution`, the max norm of the residual vector before the
+/// first iteration and after each iteration `residual_norms`, the number of
+/// iterations performed `iterations`, and whether the stopping criteria in
+/// `NewtonOptions` were met `converged`.
+///
+/// The type of the solution `S` is a `GridFunction` by default. Solvers for
+/// other kinds of unknowns, such as `newtons_method_system`, return a
+/// `NewtonResult` with a different solution type.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewtonResult<S = GridFunction> {
+    pub solution: S,
+    pub residual_norms: Vec<f64>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl<S> NewtonResult<S> {
+    /// # Map solution
+    ///
+    /// ## Description
+    /// `map_solution` applies `func` to the solution, keeping the record of
+    /// the convergence of Newton's method.
+    ///
+    pub(crate) fn map_solution<T, F>(self, func: F) -> NewtonResult<T>
+    where
+        F: FnOnce(S) -> T,
+    {
+        NewtonResult {
+            solution: func(self.solution),
+            residual_norms: self.residual_norms,
+            iterations: self.iterations,
+            converged: self.converged,
+        }
+    }
+}
+
+/// # Max norm
+///
+/// ## Description
+/// `max_norm` returns the largest absolute value of the elements of `vector`.
+/// If any element is NaN, NaN is returned.
+///
+pub(crate) fn max_norm(vector: &[f64]) -> f64 {
+    vector.iter().fold(0.0, |norm: f64, x| {
+        if x.is_nan() || norm.is_nan() {
+            f64::NAN
+        } else {
+            norm.max(x.abs())
+        }
+    })
+}
+
+/// # Newton's method step
+///
+/// ## Description
+/// `newtons_method_step` takes a DE function, `de_func`, a trial solution,
+/// `grid_func_guess`, boundary conditions, `boundary_conditions`, and the
+/// residual vector at the trial solution, `residual_vector`, as inputs, and
+/// returns the Newton update Δ. The
+/// updated trial solution is `grid_func_guess + Δ`.
+///
+/// The Jacobian matrix is assembled and solved as specified by
+/// `options.jacobian_structure`, using the step size
+/// `options.finite_difference_step`.
+///
+/// If the Jacobian matrix is singular, a `SingularJacobian` error is returned.
+///
+/// ## Example use case
+/// Suppose we have a DE function, `differential_equation_function`, a trial
+/// solution, `grid_func_guess`, and its residual vector, `residual_vector`.
+/// The code below calculates the Newton update.
+/// ```
+/// let update = newtons_method_step(
+///     &differential_equation_function,
+///     &grid_func_guess,
+///     &boundary_conditions,
+///     &residual_vector,
+///     &NewtonOptions::default(),
+/// )?;
+/// ```
 ///
 fn newtons_method_step<F>(
-    de_func: F,
+    de_func: &F,
     grid_func_guess: &GridFunction,
     boundary_conditions: &BoundaryConditions,
-    step_size: f64,
-) -> GridFunction
+    residual_vector: &[f64],
+    options: &NewtonOptions,
+) -> Result<Vec<f64>, NumericalMethodsError>
 where
     F: Fn(&GridFunction) -> GridFunction,
 {
-    let jacobian_matrix =
-        get_jacobian_matrix(&de_func, &grid_func_guess, step_size);
-    let residual_vector =
-        get_residual_vector(&de_func, &grid_func_guess, &boundary_conditions);
-    let matrix_size = grid_func_guess.function_values.len();
+    let step_size = options.finite_difference_step;
 
     // Solves the system of linear equations J * Δ = -F for Δ, where J is the
     // Jacobian matrix, Δ is the update to grid_func_guess, and F is the
     // residual vector.
-    let grid_func_update =
-        solve_linear_system(&jacobian_matrix, &residual_vector, matrix_size);
-
-    // Adds grid_func_update to grid_func_guess to get the next guess.
-    let updated_guess_values: Vec<f64> = grid_func_guess
-        .function_values
-        .iter()
-        .zip(grid_func_update.iter())
-        .map(|(x, y)| x + y)
-        .collect();
-
-    GridFunction {
-        grid: grid_func_guess.grid.clone(),
-        function_values: updated_guess_values,
+    let negative_residual_vector: Vec<f64> =
+        residual_vector.iter().map(|x| -x).collect();
+
+    match options.jacobian_structure {
+        JacobianStructure::Dense => {
+            let jacobian_matrix = get_jacobian_matrix(
+                de_func,
+                grid_func_guess,
+                boundary_conditions,
+                step_size,
+            );
+            let matrix_size = grid_func_guess.function_values.len();
+            solve_linear_system(
+                &jacobian_matrix,
+                &negative_residual_vector,
+                matrix_size,
+            )
+        }
+        JacobianStructure::Banded { bandwidth } => {
+            let jacobian_matrix = get_banded_jacobian_matrix(
+                de_func,
+                grid_func_guess,
+                boundary_conditions,
+                step_size,
+                bandwidth,
+            );
+            solve_banded_jacobian(&jacobian_matrix, &negative_residual_vector)
+        }
     }
 }
 
+/// # Solve banded Jacobian
+///
+/// ## Description
+/// `solve_banded_jacobian` solves `jacobian_matrix * update = vector` for the
+/// Newton update, returning a `SingularJacobian` error if the Jacobian matrix
+/// is singular.
+///
+pub(crate) fn solve_banded_jacobian(
+    jacobian_matrix: &BorderedBandedMatrix,
+    vector: &[f64],
+) -> Result<Vec<f64>, NumericalMethodsError> {
+    jacobian_matrix.solve(vector).map_err(|error| match error {
+        NumericalMethodsError::SingularMatrix => {
+            NumericalMethodsError::SingularJacobian
+        }
+        error => error,
+    })
+}
+
 /// # Newton's method
 ///
 /// ## Description
-/// `newtons_method` takes a DE function, `de_func`, boundary conditions
-/// `boundary_conditions`a trial solution, `grid_func_initial_guess`, and a
-/// maximum number of iterations, `num_iterations`, as inputs, and returns the
-/// approximate solution of the DE using Newton's method.
+/// `newtons_method` takes a DE function, `de_func`, boundary conditions,
+/// `boundary_conditions`, a trial solution, `grid_func_initial_guess`, and
+/// stopping criteria, `options`, as inputs, and approximately solves the DE
+/// using Newton's method.
 ///
-/// ## Example use case
-/// Todo: add example use case
+/// `de_func` returns the residual of the DE at each grid point. Its first and
+/// last values are replaced by the residuals of the boundary conditions.
+///
+/// `newtons_method` returns a `NewtonResult`, which contains the approximate
+/// solution and a record of the convergence of Newton's method. Check
+/// `converged` before using the solution.
 ///
-/// ## Todo
-/// Modify the `newtons_method` so that it stops iterating when the solution is
-/// within a certain tolerance of the true solution, rather than when a maximum
-/// number of iterations is reacher.
+/// `newtons_method` panics if the solve fails. See `try_newtons_method` for a
+/// version which returns an error instead.
 ///
-/// Currently, this algorithm isn't working - I'm still troubleshooting.
+/// ## Example use case
+/// Suppose that we want to solve y'' = 1.5 * y^2 with y(0) = 4 and y(1) = 1,
+/// starting from a straight line. The code below does this.
+/// ```
+/// let grid = Grid::new_uniform_grid(0.0, 1.0, 41);
+/// let boundary_conditions = BoundaryConditions::new_dirichlet_bcs(4.0, 1.0);
+/// let initial_guess = GridFunction::new_grid_function(&grid, |x| 4.0 - 3.0 * x);
+/// let de_func = |y: &GridFunction| {
+///     let y_xx = y
+///         .central_difference_derivative()
+///         .central_difference_derivative();
+///     y_xx - 1.5 * y.powi(2)
+/// };
+/// let result = newtons_method(
+///     de_func,
+///     &boundary_conditions,
+///     &initial_guess,
+///     &NewtonOptions::default(),
+/// );
+/// assert!(result.converged);
+/// ```
 ///
 pub fn newtons_method<F>(
     de_func: F,
     boundary_conditions: &BoundaryConditions,
     grid_func_initial_guess: &GridFunction,
-    num_iterations: usize,
-) -> GridFunction
+    options: &NewtonOptions,
+) -> NewtonResult
 where
     F: Fn(&GridFunction) -> GridFunction,
 {
-    let mut grid_func_guess = grid_func_initial_guess.clone();
+    match try_newtons_method(
+        de_func,
+        boundary_conditions,
+        grid_func_initial_guess,
+        options,
+    ) {
+        Ok(newton_result) => newton_result,
+        Err(error) => panic!("Newton's method failed. {}", error),
+    }
+}
+
+/// # Try Newton's method
+///
+/// ## Description
+/// `try_newtons_method` is the fallible version of `newtons_method`. It
+/// returns an error if `grid_func_initial_guess` is not valid or has fewer
+/// than 3 grid points, if `boundary_conditions` are not valid, or if the
+/// Jacobian matrix is singular at any step.
+///
+/// Failing to converge within `options.max_iterations` iterations is not an
+/// error; `converged` is set to false in the returned `NewtonResult`.
+///
+/// ## Example use case
+/// ```
+/// let result = try_newtons_method(
+///     differential_equation_function,
+///     &boundary_conditions,
+///     &grid_func_initial_guess,
+///     &NewtonOptions::default(),
+/// )?;
+/// ```
+///
+pub fn try_newtons_method<F>(
+    de_func: F,
+    boundary_conditions: &BoundaryConditions,
+    grid_func_initial_guess: &GridFunction,
+    options: &NewtonOptions,
+) -> Result<NewtonResult, NumericalMethodsError>
+where
+    F: Fn(&GridFunction) -> GridFunction,
+{
+    grid_func_initial_guess.validate_with_min_points(3)?;
+    boundary_conditions.validate()?;
+
+    let grid = &grid_func_initial_guess.grid;
+    let to_grid_func = |values: &[f64]| GridFunction {
+        grid: grid.clone(),
+        function_values: values.to_vec(),
+    };
+
+    let newton_result = newton_iteration(
+        grid_func_initial_guess.function_values.clone(),
+        |values| {
+            get_residual_vector(
+                &de_func,
+                &to_grid_func(values),
+                boundary_conditions,
+            )
+        },
+        |values, residual_vector| {
+            newtons_method_step(
+                &de_func,
+                &to_grid_func(values),
+                boundary_conditions,
+                residual_vector,
+                options,
+            )
+        },
+        options,
+    )?;
+
+    Ok(newton_result.map_solution(|values| to_grid_func(&values)))
+}
+
+/// # Newton iteration
+///
+/// ## Description
+/// `newton_iteration` runs Newton's method on a general system of nonlinear
+/// equations F(x) = 0, starting from `initial_guess`, with the stopping
+/// criteria in `options`.
+///
+/// `residual_func` returns the residual vector F(x). `update_func` takes x
+/// and F(x), and returns the Newton update Δ, which solves J * Δ = -F(x),
+/// where J is the Jacobian matrix of F at x. Any error returned by
+/// `update_func` is passed on.
+///
+/// `newton_iteration` is shared by the BVP solvers and the implicit time
+/// steppers, which differ in how they assemble and solve the Jacobian
+/// matrix.
+///
+/// ## Example use case
+/// ```
+/// let newton_result = newton_iteration(
+///     vec![1.0],
+///     |x| vec![x[0] * x[0] - 2.0],
+///     |x, residual| Ok(vec![-residual[0] / (2.0 * x[0])]),
+///     &NewtonOptions::default(),
+/// )?;
+/// ```
+///
+pub(crate) fn newton_iteration<R, U>(
+    initial_guess: Vec<f64>,
+    mut residual_func: R,
+    mut update_func: U,
+    options: &NewtonOptions,
+) -> Result<NewtonResult<Vec<f64>>, NumericalMethodsError>
+where
+    R: FnMut(&[f64]) -> Vec<f64>,
+    U: FnMut(&[f64], &[f64]) -> Result<Vec<f64>, NumericalMethodsError>,
+{
+    let mut guess = initial_guess;
+    let mut residual_vector = residual_func(&guess);
+    let mut residual_norm = max_norm(&residual_vector);
+
+    let tolerance = options
+        .absolute_tolerance
+        .max(options.relative_tolerance * residual_norm);
+    let mut residual_norms = vec![residual_norm];
+    let mut iterations = 0;
+    let mut converged = residual_norm <= tolerance;
 
-    for _ in 0..num_iterations {
+    while !converged
+        && residual_norm.is_finite()
+        && iterations < options.max_iterations
+    {
         // Updates the guess using Newton's method.
-        grid_func_guess = newtons_method_step(
-            &de_func,
-            &grid_func_guess,
+        let update = update_func(&guess, &residual_vector)?;
+        for (x, dx) in guess.iter_mut().zip(update.iter()) {
+            *x += dx;
+        }
+        iterations += 1;
+
+        residual_vector = residual_func(&guess);
+        residual_norm = max_norm(&residual_vector);
+        residual_norms.push(residual_norm);
+
+        converged = residual_norm <= tolerance
+            || max_norm(&update) <= options.step_tolerance;
+    }
+
+    Ok(NewtonResult {
+        solution: guess,
+        residual_norms,
+        iterations,
+        converged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_conditions::BoundaryCondition;
+    use crate::grid::{max_error, Grid};
+
+    #[test]
+    fn test_solve_linear_system() {
+        let matrix = vec![2.0, 1.0, 1.0, 3.0];
+        let vector = vec![3.0, 5.0];
+        let solution = solve_linear_system(&matrix, &vector, 2).unwrap();
+        assert!((solution[0] - 0.8).abs() < 1e-12);
+        assert!((solution[1] - 1.4).abs() < 1e-12);
+
+        // A singular matrix gives an error rather than a panic.
+        let matrix = vec![1.0, 2.0, 2.0, 4.0];
+        assert_eq!(
+            solve_linear_system(&matrix, &vector, 2),
+            Err(NumericalMethodsError::SingularJacobian)
+        );
+    }
+
+    #[test]
+    fn test_try_newtons_method_errors() {
+        let boundary_conditions =
+            BoundaryConditions::new_dirichlet_bcs(0.0, 1.0);
+
+        // Too few grid points.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 2);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x);
+        assert_eq!(
+            try_newtons_method(
+                |grid_func: &GridFunction| grid_func.clone(),
+                &boundary_conditions,
+                &grid_func,
+                &NewtonOptions::default(),
+            ),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 3,
+                actual: 2
+            })
+        );
+
+        // A DE function which does not depend on the solution gives a
+        // singular Jacobian.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x);
+        assert_eq!(
+            try_newtons_method(
+                |grid_func: &GridFunction| {
+                    GridFunction::new_constant_grid_function(
+                        &grid_func.grid,
+                        1.0,
+                    )
+                },
+                &boundary_conditions,
+                &grid_func,
+                &NewtonOptions::default(),
+            ),
+            Err(NumericalMethodsError::SingularJacobian)
+        );
+    }
+
+    /// The DE function for y'' = 1.5 * y^2 on a uniform grid, whose solution
+    /// with y(0) = 4 and y(1) = 1 is y = 4 / (1 + x)^2.
+    fn quadratic_de_func(grid_func: &GridFunction) -> GridFunction {
+        let values = &grid_func.function_values;
+        let step_size =
+            grid_func.grid.grid_points[1] - grid_func.grid.grid_points[0];
+        let mut residual = vec![0.0; values.len()];
+        for i in 1..(values.len() - 1) {
+            residual[i] = (values[i - 1] - 2.0 * values[i] + values[i + 1])
+                / step_size.powi(2)
+                - 1.5 * values[i].powi(2);
+        }
+        GridFunction::try_new_grid_function_from_values(
+            &grid_func.grid,
+            residual,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_newtons_method_converges_quadratically() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 41);
+        let boundary_conditions =
+            BoundaryConditions::new_dirichlet_bcs(4.0, 1.0);
+        let initial_guess =
+            GridFunction::new_grid_function(&grid, |x| 4.0 - 3.0 * x);
+
+        let result = newtons_method(
+            quadratic_de_func,
+            &boundary_conditions,
+            &initial_guess,
+            &NewtonOptions::default(),
+        );
+        assert!(result.converged, "Newton's method did not converge.");
+        assert_eq!(result.residual_norms.len(), result.iterations + 1);
+        assert!(result.iterations < 10);
+
+        // Once the residual is small, each iteration roughly squares it,
+        // until rounding errors take over.
+        for pair in result.residual_norms.windows(2) {
+            if pair[0] < 1.0 && pair[1] > 1e-8 {
+                assert!(
+                    pair[1] < 10.0 * pair[0].powi(2),
+                    "Convergence is not quadratic: {:?}",
+                    result.residual_norms
+                );
+            }
+        }
+
+        // The discretization error is O(h^2).
+        for (x, y) in grid
+            .grid_points
+            .iter()
+            .zip(result.solution.function_values.iter())
+        {
+            assert!((y - 4.0 / (1.0 + x).powi(2)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_newtons_method_stopping_criteria() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 21);
+        let boundary_conditions =
+            BoundaryConditions::new_dirichlet_bcs(4.0, 1.0);
+        let initial_guess =
+            GridFunction::new_grid_function(&grid, |x| 4.0 - 3.0 * x);
+
+        // Running out of iterations is reported rather than an error.
+        let options = NewtonOptions {
+            max_iterations: 1,
+            ..Default::default()
+        };
+        let result = newtons_method(
+            quadratic_de_func,
+            &boundary_conditions,
+            &initial_guess,
+            &options,
+        );
+        assert_eq!(result.iterations, 1);
+        assert!(!result.converged);
+
+        // An exact initial guess converges without any iterations.
+        let exact_solution = newtons_method(
+            quadratic_de_func,
+            &boundary_conditions,
+            &initial_guess,
+            &NewtonOptions::default(),
+        )
+        .solution;
+        let result = newtons_method(
+            quadratic_de_func,
+            &boundary_conditions,
+            &exact_solution,
+            &NewtonOptions::default(),
+        );
+        assert_eq!(result.iterations, 0);
+        assert!(result.converged);
+    }
+
+    #[test]
+    fn test_banded_jacobian_matches_dense_jacobian() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 12);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| 1.0 + x * x);
+        let robin_bcs = BoundaryConditions::new(
+            BoundaryCondition::Neumann(1.0),
+            BoundaryCondition::Robin {
+                a: 1.0,
+                b: 2.0,
+                c: 0.0,
+            },
+        );
+
+        for boundary_conditions in
+            [robin_bcs, BoundaryConditions::new_periodic_bcs()]
+        {
+            let dense = get_jacobian_matrix(
+                &quadratic_de_func,
+                &grid_func,
+                &boundary_conditions,
+                1e-7,
+            );
+
+            // The bandwidth can be larger than needed.
+            for bandwidth in [1, 2, 20] {
+                let banded = get_banded_jacobian_matrix(
+                    &quadratic_de_func,
+                    &grid_func,
+                    &boundary_conditions,
+                    1e-7,
+                    bandwidth,
+                );
+                for i in 0..12 {
+                    for j in 0..12 {
+                        assert_eq!(
+                            banded.get(i, j),
+                            dense[i * 12 + j],
+                            "Banded Jacobian failed at ({}, {}).",
+                            i,
+                            j
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_banded_newtons_method_on_large_grid() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 100_001);
+        let boundary_conditions =
+            BoundaryConditions::new_dirichlet_bcs(4.0, 1.0);
+        let initial_guess =
+            GridFunction::new_grid_function(&grid, |x| 4.0 - 3.0 * x);
+        let options = NewtonOptions {
+            jacobian_structure: JacobianStructure::Banded { bandwidth: 1 },
+            ..Default::default()
+        };
+
+        let result = newtons_method(
+            quadratic_de_func,
+            &boundary_conditions,
+            &initial_guess,
+            &options,
+        );
+        assert!(result.converged, "{:?}", result.residual_norms);
+        for (x, y) in grid
+            .grid_points
+            .iter()
+            .zip(result.solution.function_values.iter())
+            .step_by(1000)
+        {
+            assert!((y - 4.0 / (1.0 + x).powi(2)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_banded_newtons_method_singular_jacobian() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x);
+        let options = NewtonOptions {
+            jacobian_structure: JacobianStructure::Banded { bandwidth: 1 },
+            ..Default::default()
+        };
+        assert_eq!(
+            try_newtons_method(
+                |grid_func: &GridFunction| {
+                    GridFunction::new_constant_grid_function(
+                        &grid_func.grid,
+                        1.0,
+                    )
+                },
+                &BoundaryConditions::new_dirichlet_bcs(0.0, 1.0),
+                &grid_func,
+                &options,
+            ),
+            Err(NumericalMethodsError::SingularJacobian)
+        );
+    }
+
+    /// The DE function for u'' - u = source(x), using the three point
+    /// approximation to u'' on a non-uniform grid.
+    fn linear_de_func(
+        grid_func: &GridFunction,
+        source: fn(f64) -> f64,
+    ) -> GridFunction {
+        let points = &grid_func.grid.grid_points;
+        let values = &grid_func.function_values;
+        let mut residual = vec![0.0; values.len()];
+        for i in 1..(values.len() - 1) {
+            let h_left = points[i] - points[i - 1];
+            let h_right = points[i + 1] - points[i];
+            let second_derivative = 2.0
+                * ((values[i + 1] - values[i]) / h_right
+                    - (values[i] - values[i - 1]) / h_left)
+                / (h_left + h_right);
+            residual[i] = second_derivative - values[i] - source(points[i]);
+        }
+        GridFunction::try_new_grid_function_from_values(
+            &grid_func.grid,
+            residual,
+        )
+        .unwrap()
+    }
+
+    /// Solves u'' - u = source(x) on a uniform grid and returns the maximum
+    /// error compared to exact_solution.
+    fn linear_bvp_error(
+        boundary_conditions: &BoundaryConditions,
+        source: fn(f64) -> f64,
+        exact_solution: fn(f64) -> f64,
+        end: f64,
+        num_points: usize,
+        jacobian_structure: JacobianStructure,
+    ) -> f64 {
+        let grid = Grid::new_uniform_grid(0.0, end, num_points);
+        let initial_guess =
+            GridFunction::new_constant_grid_function(&grid, 0.0);
+        let options = NewtonOptions {
+            jacobian_structure,
+            ..Default::default()
+        };
+        let result = newtons_method(
+            |grid_func: &GridFunction| linear_de_func(grid_func, source),
             boundary_conditions,
-            1e-6,
+            &initial_guess,
+            &options,
+        );
+        assert!(result.converged);
+
+        max_error(&result.solution, exact_solution)
+    }
+
+    #[test]
+    fn test_newtons_method_boundary_conditions() {
+        // u = sinh(x) solves u'' - u = 0, with u(0) = 0, u'(0) = 1,
+        // u(0) + u'(0) = 1 and 2u(1) + u'(1) = 2sinh(1) + cosh(1).
+        let right_robin = BoundaryCondition::Robin {
+            a: 2.0,
+            b: 1.0,
+            c: 2.0 * 1.0_f64.sinh() + 1.0_f64.cosh(),
+        };
+        let cases = [
+            BoundaryConditions::new(
+                BoundaryCondition::Neumann(1.0),
+                BoundaryCondition::Dirichlet(1.0_f64.sinh()),
+            ),
+            BoundaryConditions::new(
+                BoundaryCondition::Dirichlet(0.0),
+                BoundaryCondition::Neumann(1.0_f64.cosh()),
+            ),
+            BoundaryConditions::new(
+                BoundaryCondition::Robin {
+                    a: 1.0,
+                    b: 1.0,
+                    c: 1.0,
+                },
+                right_robin,
+            ),
+        ];
+
+        for boundary_conditions in cases {
+            for jacobian_structure in [
+                JacobianStructure::Dense,
+                JacobianStructure::Banded { bandwidth: 1 },
+            ] {
+                let coarse_error = linear_bvp_error(
+                    &boundary_conditions,
+                    |_| 0.0,
+                    f64::sinh,
+                    1.0,
+                    21,
+                    jacobian_structure,
+                );
+                let fine_error = linear_bvp_error(
+                    &boundary_conditions,
+                    |_| 0.0,
+                    f64::sinh,
+                    1.0,
+                    41,
+                    jacobian_structure,
+                );
+
+                // The BCs are second order, so halving the grid spacing
+                // divides the error by about 4.
+                assert!(coarse_error < 5e-3, "{:?}", boundary_conditions);
+                assert!(
+                    coarse_error / fine_error > 3.5,
+                    "{:?} converged with ratio {}.",
+                    boundary_conditions,
+                    coarse_error / fine_error
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_newtons_method_periodic_boundary_conditions() {
+        // u = sin(x) solves u'' - u = -2sin(x) with periodic BCs on
+        // [0, 2 PI]. The banded Jacobian has full rows for the BCs.
+        for jacobian_structure in [
+            JacobianStructure::Dense,
+            JacobianStructure::Banded { bandwidth: 1 },
+        ] {
+            let coarse_error = linear_bvp_error(
+                &BoundaryConditions::new_periodic_bcs(),
+                |x| -2.0 * x.sin(),
+                f64::sin,
+                2.0 * std::f64::consts::PI,
+                41,
+                jacobian_structure,
+            );
+            let fine_error = linear_bvp_error(
+                &BoundaryConditions::new_periodic_bcs(),
+                |x| -2.0 * x.sin(),
+                f64::sin,
+                2.0 * std::f64::consts::PI,
+                81,
+                jacobian_structure,
+            );
+            assert!(coarse_error < 1e-2);
+            assert!(coarse_error / fine_error > 3.5);
+        }
+    }
+
+    #[test]
+    fn test_try_newtons_method_invalid_boundary_conditions() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x);
+        let boundary_conditions = BoundaryConditions::new(
+            BoundaryCondition::Periodic,
+            BoundaryCondition::Dirichlet(0.0),
         );
+        assert!(matches!(
+            try_newtons_method(
+                quadratic_de_func,
+                &boundary_conditions,
+                &grid_func,
+                &NewtonOptions::default(),
+            ),
+            Err(NumericalMethodsError::InvalidBoundaryConditions(_))
+        ));
     }
-    grid_func_guess
 }
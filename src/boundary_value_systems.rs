@@ -0,0 +1,700 @@
+use crate::boundary_conditions::BoundaryConditions;
+use crate::boundary_value_problems::{
+    newton_iteration, solve_banded_jacobian, solve_linear_system,
+    JacobianStructure, NewtonOptions, NewtonResult,
+};
+use crate::error::NumericalMethodsError;
+use crate::linear_solvers::BorderedBandedMatrix;
+use crate::vector_grid_function::VectorGridFunction;
+
+// Systems of coupled BVPs.
+//
+// The unknowns of a system with m components on a grid with n grid points are
+// stored in a single vector of length n * m, ordered by grid point and then by
+// component (see `VectorGridFunction::to_interleaved_values`). The Jacobian
+// matrix is then made up of m by m blocks, one for each pair of grid points,
+// and the blocks are zero for grid points which do not interact. For DE
+// functions built from central differences, the Jacobian matrix is block
+// tridiagonal.
+
+/// # Get system residual vector
+///
+/// ## Description
+/// `get_system_residual_vector` returns the residual vector of the system DE
+/// function `de_func` at `vector_grid_func`, in interleaved order.
+///
+/// For each component, the residuals at the first and last grid points are
+/// replaced by the residuals of that component's boundary conditions.
+///
+fn get_system_residual_vector<F>(
+    de_func: &F,
+    vector_grid_func: &VectorGridFunction,
+    boundary_conditions: &[BoundaryConditions],
+) -> Vec<f64>
+where
+    F: Fn(&VectorGridFunction) -> VectorGridFunction,
+{
+    let num_components = vector_grid_func.num_components();
+    let num_points = vector_grid_func.grid().grid_points.len();
+    let mut residual_vector = de_func(vector_grid_func).to_interleaved_values();
+
+    for (c, (component, bcs)) in vector_grid_func
+        .components
+        .iter()
+        .zip(boundary_conditions.iter())
+        .enumerate()
+    {
+        let [left_row, right_row] = bcs.boundary_rows(&component.grid);
+        residual_vector[c] = left_row.residual(&component.function_values);
+        residual_vector[(num_points - 1) * num_components + c] =
+            right_row.residual(&component.function_values);
+    }
+
+    residual_vector
+}
+
+/// # Get system Jacobian matrix
+///
+/// ## Description
+/// `get_system_jacobian_matrix` is the version of `get_jacobian_matrix` for
+/// systems. It returns the Jacobian matrix of the system DE function
+/// `de_func` at `vector_grid_func` as a flat vector in row-major order, with
+/// the rows and columns in interleaved order.
+///
+/// The rows for the first and last grid points of each component are set
+/// equal to the coefficients of that component's boundary conditions.
+///
+/// ## Example use case
+/// ```
+/// let jacobian_matrix = get_system_jacobian_matrix(
+///     &de_func,
+///     &initial_guess,
+///     &[u_boundary_conditions, v_boundary_conditions],
+///     1e-7,
+/// );
+/// ```
+///
+pub fn get_system_jacobian_matrix<F>(
+    de_func: &F,
+    vector_grid_func: &VectorGridFunction,
+    boundary_conditions: &[BoundaryConditions],
+    step_size: f64,
+) -> Vec<f64>
+where
+    F: Fn(&VectorGridFunction) -> VectorGridFunction,
+{
+    let num_components = vector_grid_func.num_components();
+    let num_points = vector_grid_func.grid().grid_points.len();
+    let matrix_size = num_points * num_components;
+    let mut jacobian_matrix = vec![0.0; matrix_size * matrix_size];
+
+    for (i, elements) in
+        system_boundary_rows(vector_grid_func, boundary_conditions)
+    {
+        for (j, value) in elements {
+            jacobian_matrix[i * matrix_size + j] += value;
+        }
+    }
+
+    // Handles the interior rows of the Jacobian matrix one column at a time.
+    let values = vector_grid_func.to_interleaved_values();
+    let residual = de_func(vector_grid_func).to_interleaved_values();
+    let mut perturbed_values = values.clone();
+    let grid = vector_grid_func.grid();
+
+    for j in 0..matrix_size {
+        perturbed_values[j] += step_size;
+        let perturbed_residual =
+            de_func(&VectorGridFunction::from_interleaved_values(
+                grid,
+                num_components,
+                &perturbed_values,
+            ))
+            .to_interleaved_values();
+        perturbed_values[j] = values[j];
+
+        for i in num_components..(matrix_size - num_components) {
+            jacobian_matrix[i * matrix_size + j] =
+                (perturbed_residual[i] - residual[i]) / step_size;
+        }
+    }
+
+    jacobian_matrix
+}
+
+/// # Get banded system Jacobian matrix
+///
+/// ## Description
+/// `get_banded_system_jacobian_matrix` is the version of
+/// `get_banded_jacobian_matrix` for systems. The residuals at each grid point
+/// must only depend on the components within `bandwidth` grid points of it.
+///
+/// With m components, the Jacobian matrix is a `BorderedBandedMatrix` whose
+/// lower and upper bandwidths are (`bandwidth` + 1) * m - 1, or 3 * m - 1 if
+/// `bandwidth` is smaller than 2. `de_func` is evaluated
+/// (2 * `bandwidth` + 1) * m + 1 times, however many grid points there are.
+/// The two rows of each component with periodic BCs are stored as full rows.
+///
+/// ## Example use case
+/// ```
+/// let jacobian_matrix = get_banded_system_jacobian_matrix(
+///     &de_func,
+///     &initial_guess,
+///     &[u_boundary_conditions, v_boundary_conditions],
+///     1e-7,
+///     1,
+/// );
+/// ```
+///
+pub fn get_banded_system_jacobian_matrix<F>(
+    de_func: &F,
+    vector_grid_func: &VectorGridFunction,
+    boundary_conditions: &[BoundaryConditions],
+    step_size: f64,
+    bandwidth: usize,
+) -> BorderedBandedMatrix
+where
+    F: Fn(&VectorGridFunction) -> VectorGridFunction,
+{
+    let num_components = vector_grid_func.num_components();
+    let num_points = vector_grid_func.grid().grid_points.len();
+    let matrix_size = num_points * num_components;
+    let matrix_bandwidth = (bandwidth.max(2) + 1) * num_components - 1;
+    let mut jacobian_matrix = BorderedBandedMatrix::new(
+        matrix_size,
+        matrix_bandwidth,
+        matrix_bandwidth,
+    );
+
+    for (i, elements) in
+        system_boundary_rows(vector_grid_func, boundary_conditions)
+    {
+        jacobian_matrix.set_row(i, &elements);
+    }
+
+    // The unknown at grid point p affects the residuals at grid points
+    // p - bandwidth to p + bandwidth, so unknowns whose grid points are
+    // 2 * bandwidth + 1 apart, and which belong to the same component, can be
+    // perturbed together.
+    let group_stride = (2 * bandwidth + 1).min(num_points) * num_components;
+    let values = vector_grid_func.to_interleaved_values();
+    let residual = de_func(vector_grid_func).to_interleaved_values();
+    let mut perturbed_values = values.clone();
+    let grid = vector_grid_func.grid();
+
+    for group in 0..group_stride {
+        for j in (group..matrix_size).step_by(group_stride) {
+            perturbed_values[j] += step_size;
+        }
+        let perturbed_residual =
+            de_func(&VectorGridFunction::from_interleaved_values(
+                grid,
+                num_components,
+                &perturbed_values,
+            ))
+            .to_interleaved_values();
+
+        for j in (group..matrix_size).step_by(group_stride) {
+            perturbed_values[j] = values[j];
+
+            // Calculates the interior elements of column j, which lie in the
+            // rows of the grid points within bandwidth of its grid point.
+            let point = j / num_components;
+            let first_point = point.saturating_sub(bandwidth).max(1);
+            let last_point = (point + bandwidth).min(num_points - 2);
+            for i in (first_point * num_components)
+                ..((last_point + 1) * num_components)
+            {
+                jacobian_matrix.band.set(
+                    i,
+                    j,
+                    (perturbed_residual[i] - residual[i]) / step_size,
+                );
+            }
+        }
+    }
+
+    jacobian_matrix
+}
+
+/// # System boundary rows
+///
+/// ## Description
+/// `system_boundary_rows` returns the boundary condition rows of the system
+/// Jacobian matrix, as the index of each row and its non-zero elements as
+/// (column, value) pairs.
+///
+fn system_boundary_rows(
+    vector_grid_func: &VectorGridFunction,
+    boundary_conditions: &[BoundaryConditions],
+) -> Vec<(usize, Vec<(usize, f64)>)> {
+    let num_components = vector_grid_func.num_components();
+    let last_point = vector_grid_func.grid().grid_points.len() - 1;
+    let mut rows = vec![];
+
+    for (c, bcs) in boundary_conditions.iter().enumerate() {
+        let [left_row, right_row] = bcs.boundary_rows(vector_grid_func.grid());
+        for (point, row) in [(0, left_row), (last_point, right_row)] {
+            let elements = row
+                .coefficients
+                .iter()
+                .map(|&(j, coefficient)| (j * num_components + c, coefficient))
+                .collect();
+            rows.push((point * num_components + c, elements));
+        }
+    }
+
+    rows
+}
+
+/// # Newton's method system step
+///
+/// ## Description
+/// `newtons_method_system_step` returns the Newton update Δ, in interleaved
+/// order, for the system DE function `de_func` at `vector_grid_func`, given
+/// its residual vector `residual_vector`.
+///
+fn newtons_method_system_step<F>(
+    de_func: &F,
+    vector_grid_func: &VectorGridFunction,
+    boundary_conditions: &[BoundaryConditions],
+    residual_vector: &[f64],
+    options: &NewtonOptions,
+) -> Result<Vec<f64>, NumericalMethodsError>
+where
+    F: Fn(&VectorGridFunction) -> VectorGridFunction,
+{
+    let step_size = options.finite_difference_step;
+
+    // Solves the system of linear equations J * Δ = -F for Δ.
+    let negative_residual_vector: Vec<f64> =
+        residual_vector.iter().map(|x| -x).collect();
+
+    match options.jacobian_structure {
+        JacobianStructure::Dense => {
+            let jacobian_matrix = get_system_jacobian_matrix(
+                de_func,
+                vector_grid_func,
+                boundary_conditions,
+                step_size,
+            );
+            solve_linear_system(
+                &jacobian_matrix,
+                &negative_residual_vector,
+                residual_vector.len(),
+            )
+        }
+        JacobianStructure::Banded { bandwidth } => {
+            let jacobian_matrix = get_banded_system_jacobian_matrix(
+                de_func,
+                vector_grid_func,
+                boundary_conditions,
+                step_size,
+                bandwidth,
+            );
+            solve_banded_jacobian(&jacobian_matrix, &negative_residual_vector)
+        }
+    }
+}
+
+/// # Newton's method for systems
+///
+/// ## Description
+/// `newtons_method_system` is the version of `newtons_method` for systems of
+/// coupled DEs. It takes a system DE function, `de_func`, one set of boundary
+/// conditions for each component, `boundary_conditions`, a trial solution,
+/// `initial_guess`, and stopping criteria, `options`, as inputs.
+///
+/// `de_func` returns a `VectorGridFunction` whose ith component is the
+/// residual of the ith DE. The first and last values of the ith residual are
+/// replaced by the residuals of `boundary_conditions[i]`.
+///
+/// With `JacobianStructure::Banded { bandwidth }`, `bandwidth` is measured in
+/// grid points, as for a single DE.
+///
+/// `newtons_method_system` panics if the solve fails. See
+/// `try_newtons_method_system` for a version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want to solve the beam equation u'''' = 1 with
+/// u = u'' = 0 at both ends, by writing it as the system u'' = v, v'' = 1.
+/// The code below does this.
+/// ```
+/// let grid = Grid::new_uniform_grid(0.0, 1.0, 41);
+/// let de_func = |w: &VectorGridFunction| {
+///     let (u, v) = (&w.components[0], &w.components[1]);
+///     let u_xx = u
+///         .central_difference_derivative()
+///         .central_difference_derivative();
+///     let v_xx = v
+///         .central_difference_derivative()
+///         .central_difference_derivative();
+///     VectorGridFunction::new_vector_grid_function(vec![u_xx - v, v_xx - 1.0])
+/// };
+/// let result = newtons_method_system(
+///     de_func,
+///     &[
+///         BoundaryConditions::new_dirichlet_bcs(0.0, 0.0),
+///         BoundaryConditions::new_dirichlet_bcs(0.0, 0.0),
+///     ],
+///     &VectorGridFunction::new_constant_vector_grid_function(&grid, &[0.0, 0.0]),
+///     &NewtonOptions::default(),
+/// );
+/// ```
+///
+pub fn newtons_method_system<F>(
+    de_func: F,
+    boundary_conditions: &[BoundaryConditions],
+    initial_guess: &VectorGridFunction,
+    options: &NewtonOptions,
+) -> NewtonResult<VectorGridFunction>
+where
+    F: Fn(&VectorGridFunction) -> VectorGridFunction,
+{
+    match try_newtons_method_system(
+        de_func,
+        boundary_conditions,
+        initial_guess,
+        options,
+    ) {
+        Ok(newton_result) => newton_result,
+        Err(error) => panic!("Newton's method failed. {}", error),
+    }
+}
+
+/// # Try Newton's method for systems
+///
+/// ## Description
+/// `try_newtons_method_system` is the fallible version of
+/// `newtons_method_system`. It returns an error if `initial_guess` is not
+/// valid or has fewer than 3 grid points, if there is not one valid set of
+/// boundary conditions for each component, if `de_func` does not return one
+/// residual for each component and grid point, or if the Jacobian matrix is
+/// singular at any step.
+///
+/// ## Example use case
+/// ```
+/// let result = try_newtons_method_system(
+///     de_func,
+///     &boundary_conditions,
+///     &initial_guess,
+///     &NewtonOptions::default(),
+/// )?;
+/// ```
+///
+pub fn try_newtons_method_system<F>(
+    de_func: F,
+    boundary_conditions: &[BoundaryConditions],
+    initial_guess: &VectorGridFunction,
+    options: &NewtonOptions,
+) -> Result<NewtonResult<VectorGridFunction>, NumericalMethodsError>
+where
+    F: Fn(&VectorGridFunction) -> VectorGridFunction,
+{
+    initial_guess.validate_with_min_points(3)?;
+    let num_components = initial_guess.num_components();
+    if boundary_conditions.len() != num_components {
+        return Err(NumericalMethodsError::InvalidBoundaryConditions(format!(
+            "Expected {} sets of boundary conditions, one for each component, but found {}.",
+            num_components,
+            boundary_conditions.len()
+        )));
+    }
+    for bcs in boundary_conditions {
+        bcs.validate()?;
+    }
+
+    // Checks the shape of the residual once, so that it can be relied on
+    // during the iteration.
+    let residual = de_func(initial_guess);
+    let num_values = initial_guess.to_interleaved_values().len();
+    if residual.num_components() != num_components
+        || residual.components.iter().any(|component| {
+            component.function_values.len()
+                != initial_guess.grid().grid_points.len()
+        })
+    {
+        return Err(NumericalMethodsError::LengthMismatch {
+            expected: num_values,
+            actual: residual
+                .components
+                .iter()
+                .map(|component| component.function_values.len())
+                .sum(),
+        });
+    }
+
+    let grid = initial_guess.grid();
+    let to_vector_grid_func = |values: &[f64]| {
+        VectorGridFunction::from_interleaved_values(
+            grid,
+            num_components,
+            values,
+        )
+    };
+
+    let newton_result = newton_iteration(
+        initial_guess.to_interleaved_values(),
+        |values| {
+            get_system_residual_vector(
+                &de_func,
+                &to_vector_grid_func(values),
+                boundary_conditions,
+            )
+        },
+        |values, residual_vector| {
+            newtons_method_system_step(
+                &de_func,
+                &to_vector_grid_func(values),
+                boundary_conditions,
+                residual_vector,
+                options,
+            )
+        },
+        options,
+    )?;
+
+    Ok(newton_result.map_solution(|values| to_vector_grid_func(&values)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_conditions::BoundaryCondition;
+    use crate::grid::{max_error, Grid};
+    use crate::grid_function::GridFunction;
+
+    /// The three point approximation to the second derivative at the
+    /// interior points of a uniform grid. The end values are zero.
+    fn second_derivative(grid_func: &GridFunction) -> Vec<f64> {
+        let values = &grid_func.function_values;
+        let points = &grid_func.grid.grid_points;
+        let step_size = points[1] - points[0];
+        let mut second_derivative = vec![0.0; values.len()];
+        for i in 1..(values.len() - 1) {
+            second_derivative[i] = (values[i - 1] - 2.0 * values[i]
+                + values[i + 1])
+                / step_size.powi(2);
+        }
+        second_derivative
+    }
+
+    // The manufactured solution u = 1 + sin(PI x), v = x^2 of the
+    // reaction-diffusion system u'' = u * v - g(x), v'' = u + v^2 - h(x).
+    const PI: f64 = std::f64::consts::PI;
+
+    fn exact_u(x: f64) -> f64 {
+        1.0 + (PI * x).sin()
+    }
+
+    fn exact_v(x: f64) -> f64 {
+        x * x
+    }
+
+    fn reaction_diffusion_de_func(
+        w: &VectorGridFunction,
+    ) -> VectorGridFunction {
+        let (u, v) = (&w.components[0], &w.components[1]);
+        let (u_xx, v_xx) = (second_derivative(u), second_derivative(v));
+        let points = &u.grid.grid_points;
+
+        let mut u_residual = vec![0.0; points.len()];
+        let mut v_residual = vec![0.0; points.len()];
+        for (i, &x) in points.iter().enumerate() {
+            let (u_i, v_i) = (u.function_values[i], v.function_values[i]);
+            let g = exact_u(x) * exact_v(x) + PI * PI * (PI * x).sin();
+            let h = exact_u(x) + exact_v(x).powi(2) - 2.0;
+            u_residual[i] = u_xx[i] - u_i * v_i + g;
+            v_residual[i] = v_xx[i] - u_i - v_i * v_i + h;
+        }
+
+        VectorGridFunction {
+            components: vec![
+                GridFunction {
+                    grid: u.grid.clone(),
+                    function_values: u_residual,
+                },
+                GridFunction {
+                    grid: u.grid.clone(),
+                    function_values: v_residual,
+                },
+            ],
+        }
+    }
+
+    fn reaction_diffusion_bcs() -> [BoundaryConditions; 2] {
+        [
+            BoundaryConditions::new_dirichlet_bcs(1.0, 1.0),
+            BoundaryConditions::new(
+                BoundaryCondition::Dirichlet(0.0),
+                BoundaryCondition::Neumann(2.0),
+            ),
+        ]
+    }
+
+    fn solution_error(num_points: usize, options: &NewtonOptions) -> f64 {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, num_points);
+        let initial_guess =
+            VectorGridFunction::new_constant_vector_grid_function(
+                &grid,
+                &[1.0, 0.0],
+            );
+        let result = newtons_method_system(
+            reaction_diffusion_de_func,
+            &reaction_diffusion_bcs(),
+            &initial_guess,
+            options,
+        );
+        assert!(result.converged, "{:?}", result.residual_norms);
+
+        let [u, v] = [0, 1].map(|c| &result.solution.components[c]);
+        max_error(u, exact_u).max(max_error(v, exact_v))
+    }
+
+    #[test]
+    fn test_newtons_method_system() {
+        for jacobian_structure in [
+            JacobianStructure::Dense,
+            JacobianStructure::Banded { bandwidth: 1 },
+        ] {
+            let options = NewtonOptions {
+                jacobian_structure,
+                ..Default::default()
+            };
+            let coarse_error = solution_error(21, &options);
+            let fine_error = solution_error(41, &options);
+            assert!(coarse_error < 1e-2);
+            assert!(
+                coarse_error / fine_error > 3.5,
+                "Convergence ratio {}.",
+                coarse_error / fine_error
+            );
+        }
+    }
+
+    #[test]
+    fn test_banded_system_jacobian_matches_dense() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 9);
+        let w = VectorGridFunction::new_vector_grid_function(vec![
+            GridFunction::new_grid_function(&grid, exact_u),
+            GridFunction::new_grid_function(&grid, |x| x - 0.5),
+        ]);
+        let [u_bcs, v_bcs] = reaction_diffusion_bcs();
+        let periodic_bcs = BoundaryConditions::new_periodic_bcs();
+
+        for bcs in [[u_bcs.clone(), v_bcs], [u_bcs, periodic_bcs]] {
+            let dense = get_system_jacobian_matrix(
+                &reaction_diffusion_de_func,
+                &w,
+                &bcs,
+                1e-7,
+            );
+            let banded = get_banded_system_jacobian_matrix(
+                &reaction_diffusion_de_func,
+                &w,
+                &bcs,
+                1e-7,
+                1,
+            );
+            for i in 0..18 {
+                for j in 0..18 {
+                    assert_eq!(
+                        banded.get(i, j),
+                        dense[i * 18 + j],
+                        "Banded Jacobian failed at ({}, {}).",
+                        i,
+                        j
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_banded_newtons_method_system_periodic() {
+        // u = cos(x), v = sin(x) solves u'' - u + v' = -cos(x),
+        // v'' - v - u' = -sin(x) with periodic BCs on [0, 2 PI]. The banded
+        // solve agrees with the dense solve.
+        let grid = Grid::new_uniform_grid(0.0, 2.0 * PI, 41);
+        let de_func = |w: &VectorGridFunction| {
+            let (u, v) = (&w.components[0], &w.components[1]);
+            let u_x = u.central_difference_derivative();
+            let v_x = v.central_difference_derivative();
+            let u_residual =
+                u_x.central_difference_derivative() - u.clone() + v_x.clone();
+            let v_residual =
+                v_x.central_difference_derivative() - v.clone() - u_x;
+            VectorGridFunction::new_vector_grid_function(vec![
+                u_residual.map_with_x(|x, r| r + x.cos()),
+                v_residual.map_with_x(|x, r| r + x.sin()),
+            ])
+        };
+        let bcs = [
+            BoundaryConditions::new_periodic_bcs(),
+            BoundaryConditions::new_periodic_bcs(),
+        ];
+        let initial_guess =
+            VectorGridFunction::new_constant_vector_grid_function(
+                &grid,
+                &[0.0, 0.0],
+            );
+
+        let solutions = [
+            JacobianStructure::Dense,
+            JacobianStructure::Banded { bandwidth: 2 },
+        ]
+        .map(|jacobian_structure| {
+            let result = newtons_method_system(
+                de_func,
+                &bcs,
+                &initial_guess,
+                &NewtonOptions {
+                    jacobian_structure,
+                    ..Default::default()
+                },
+            );
+            assert!(result.converged, "{:?}", result.residual_norms);
+            result.solution.to_interleaved_values()
+        });
+        for (dense, banded) in solutions[0].iter().zip(&solutions[1]) {
+            assert!((dense - banded).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_try_newtons_method_system_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let initial_guess =
+            VectorGridFunction::new_constant_vector_grid_function(
+                &grid,
+                &[1.0, 0.0],
+            );
+
+        // One set of BCs for two components.
+        assert!(matches!(
+            try_newtons_method_system(
+                reaction_diffusion_de_func,
+                &reaction_diffusion_bcs()[..1],
+                &initial_guess,
+                &NewtonOptions::default(),
+            ),
+            Err(NumericalMethodsError::InvalidBoundaryConditions(_))
+        ));
+
+        // A DE function with the wrong number of residuals.
+        assert_eq!(
+            try_newtons_method_system(
+                |w: &VectorGridFunction| VectorGridFunction {
+                    components: vec![w.components[0].clone()],
+                },
+                &reaction_diffusion_bcs(),
+                &initial_guess,
+                &NewtonOptions::default(),
+            ),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 10,
+                actual: 5
+            })
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::boundary_conditions::BoundaryConditions;
+use crate::boundary_value_problems::{
+    try_newtons_method, NewtonOptions, NewtonResult,
+};
+use crate::error::NumericalMethodsError;
+use crate::grid_function::GridFunction;
+
+/// # Boundary value solver
+///
+/// ## Description
+/// `BoundaryValueSolver` is the common interface of the solvers for second
+/// order BVPs of the form u'' = f(x, u, u'), with BCs given by
+/// `BoundaryConditions`.
+///
+/// The right hand side f is passed as a closure `rhs(x, u, u')`, and the
+/// solution is returned as a `GridFunction` on the `Grid` of
+/// `initial_guess`, so that the results of different solvers can be compared
+/// directly. The solvers are:
+///
+/// - `FiniteDifferenceSolver`, which discretises the DE on the grid and runs
+///   `newtons_method`.
+/// - `ShootingSolver`, which integrates the DE as an IVP from the left end of
+///   the domain and adjusts the initial values until the BCs are met.
+/// - `MultipleShootingSolver`, which shoots over several subintervals at
+///   once, and matches the solutions where the subintervals meet.
+///
+/// ## Example use case
+/// Suppose that we want to solve u'' = -u with u(0) = 0 and u(1) = 1, both
+/// by finite differences and by shooting. The code below does this.
+/// ```
+/// let grid = Grid::new_uniform_grid(0.0, 1.0, 21);
+/// let initial_guess = GridFunction::new_grid_function(&grid, |x| x);
+/// let bcs = BoundaryConditions::new_dirichlet_bcs(0.0, 1.0);
+/// let rhs = |_x: f64, u: f64, _u_derivative: f64| -u;
+///
+/// let fd_result =
+///     FiniteDifferenceSolver::default().solve(rhs, &bcs, &initial_guess);
+/// let shooting_result =
+///     ShootingSolver::default().solve(rhs, &bcs, &initial_guess);
+/// ```
+///
+pub trait BoundaryValueSolver {
+    /// # Try solve
+    ///
+    /// ## Description
+    /// `try_solve` solves the BVP u'' = `rhs`(x, u, u') with BCs
+    /// `boundary_conditions`, starting from `initial_guess`. It returns an
+    /// error if `initial_guess` has too few grid points for the solver, if the
+    /// BCs are not valid, or if a Jacobian matrix is singular.
+    ///
+    fn try_solve<F>(
+        &self,
+        rhs: F,
+        boundary_conditions: &BoundaryConditions,
+        initial_guess: &GridFunction,
+    ) -> Result<NewtonResult, NumericalMethodsError>
+    where
+        F: Fn(f64, f64, f64) -> f64;
+
+    /// # Solve
+    ///
+    /// ## Description
+    /// `solve` is the infallible version of `try_solve`, which panics if the
+    /// solve fails.
+    ///
+    fn solve<F>(
+        &self,
+        rhs: F,
+        boundary_conditions: &BoundaryConditions,
+        initial_guess: &GridFunction,
+    ) -> NewtonResult
+    where
+        F: Fn(f64, f64, f64) -> f64,
+    {
+        match self.try_solve(rhs, boundary_conditions, initial_guess) {
+            Ok(newton_result) => newton_result,
+            Err(error) => panic!("BVP solve failed. {}", error),
+        }
+    }
+}
+
+/// # Finite difference solver
+///
+/// ## Description
+/// `FiniteDifferenceSolver` solves u'' = f(x, u, u') by replacing u'' and u'
+/// with second order central differences on the grid of the initial guess,
+/// which may be non-uniform, and solving the resulting equations with
+/// `newtons_method` using `options`. It needs at least 3 grid points.
+///
+/// ## Example use case
+/// ```
+/// let solver = FiniteDifferenceSolver {
+///     options: NewtonOptions {
+///         jacobian_structure: JacobianStructure::Banded { bandwidth: 1 },
+///         ..Default::default()
+///     },
+/// };
+/// let result = solver.solve(rhs, &bcs, &initial_guess);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FiniteDifferenceSolver {
+    pub options: NewtonOptions,
+}
+
+impl BoundaryValueSolver for FiniteDifferenceSolver {
+    fn try_solve<F>(
+        &self,
+        rhs: F,
+        boundary_conditions: &BoundaryConditions,
+        initial_guess: &GridFunction,
+    ) -> Result<NewtonResult, NumericalMethodsError>
+    where
+        F: Fn(f64, f64, f64) -> f64,
+    {
+        let de_func = |grid_func: &GridFunction| {
+            let points = &grid_func.grid.grid_points;
+            let u = &grid_func.function_values;
+
+            // The end values are replaced by the BCs in `newtons_method`.
+            let mut residual = vec![0.0; u.len()];
+            for i in 1..(u.len() - 1) {
+                let h1 = points[i] - points[i - 1];
+                let h2 = points[i + 1] - points[i];
+                let denominator = h1 * h2 * (h1 + h2);
+                let second_derivative = 2.0
+                    * (h2 * u[i - 1] - (h1 + h2) * u[i] + h1 * u[i + 1])
+                    / denominator;
+                let first_derivative = (h1 * h1 * u[i + 1]
+                    + (h2 * h2 - h1 * h1) * u[i]
+                    - h2 * h2 * u[i - 1])
+                    / denominator;
+                residual[i] =
+                    second_derivative - rhs(points[i], u[i], first_derivative);
+            }
+
+            GridFunction {
+                grid: grid_func.grid.clone(),
+                function_values: residual,
+            }
+        };
+
+        try_newtons_method(
+            de_func,
+            boundary_conditions,
+            initial_guess,
+            &self.options,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_value_problems::JacobianStructure;
+    use crate::grid::test_grids;
+
+    #[test]
+    fn test_finite_difference_solver() {
+        // u'' = u' - x with u(0) = 0 and u(1) = 1.5 has the solution
+        // u = x^2 / 2 + x, for which the central differences are exact.
+        let bcs = BoundaryConditions::new_dirichlet_bcs(0.0, 1.5);
+        for (name, grid) in test_grids(0.0, 1.0, 11) {
+            let initial_guess =
+                GridFunction::new_constant_grid_function(&grid, 0.0);
+            let solver = FiniteDifferenceSolver {
+                options: NewtonOptions {
+                    jacobian_structure: JacobianStructure::Banded {
+                        bandwidth: 1,
+                    },
+                    ..Default::default()
+                },
+            };
+            let result = solver.solve(
+                |x, _u, u_derivative| u_derivative - x,
+                &bcs,
+                &initial_guess,
+            );
+            assert!(result.converged, "{}", name);
+            for (x, u) in grid
+                .grid_points
+                .iter()
+                .zip(&result.solution.function_values)
+            {
+                assert!((u - (x * x / 2.0 + x)).abs() < 1e-9, "{}", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,734 @@
+use crate::error::NumericalMethodsError;
+use crate::grid::{Grid, GRID_TOLERANCE};
+use crate::grid_function::GridFunction;
+use crate::interpolation::locate_cell;
+use crate::linear_solvers::{solve_cyclic_tridiagonal, solve_tridiagonal};
+
+/// # Spline end condition
+///
+/// ## Description
+/// `SplineEndCondition` specifies the extra condition imposed at each end of
+/// a `CubicSpline`.
+///
+/// - `Natural` sets the second derivative of the spline to zero at both ends.
+/// - `Clamped` sets the first derivative of the spline to `start_derivative`
+///   and `end_derivative` at the start and end respectively.
+/// - `NotAKnot` makes the third derivative of the spline continuous at the
+///   second and second to last grid points, so that the first two and last
+///   two grid cells share a cubic. It needs no derivative information and is
+///   exact for cubics, but needs at least 4 grid points.
+/// - `Periodic` makes the first and second derivatives of the spline equal
+///   at the two ends. The first and last function values must be equal.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplineEndCondition {
+    Natural,
+    Clamped {
+        start_derivative: f64,
+        end_derivative: f64,
+    },
+    NotAKnot,
+    Periodic,
+}
+
+/// # Cubic spline
+///
+/// ## Description
+/// `CubicSpline` represents a cubic spline interpolant of a `GridFunction`. On
+/// each grid cell the spline is a cubic polynomial, and the spline and its
+/// first two derivatives are continuous at the interior grid points.
+///
+/// The spline is stored as the grid points `grid`, the function values
+/// `function_values`, and the second derivatives of the spline at the grid
+/// points `second_derivatives`. The second derivatives are found by solving a
+/// tridiagonal system of linear equations, which is cyclic for periodic
+/// splines.
+///
+/// The spline can be evaluated and differentiated twice at any point of its
+/// `Grid`, and integrated exactly between any two points. Its derivatives
+/// are smooth, so they are often better behaved than finite difference
+/// derivatives of the same data.
+///
+/// ## Example use case
+/// Suppose that we have a `GridFunction` `grid_func` and we want to evaluate
+/// its natural cubic spline interpolant at x = 0.55. The code below does this.
+/// ```
+/// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+/// let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+/// let spline = CubicSpline::new(&grid_func, SplineEndCondition::Natural);
+/// let value = spline.evaluate(0.55);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubicSpline {
+    pub grid: Grid,
+    pub function_values: Vec<f64>,
+    pub second_derivatives: Vec<f64>,
+}
+
+impl CubicSpline {
+    /// # New cubic spline
+    ///
+    /// ## Description
+    /// `new` creates the cubic spline interpolant of `grid_func` with the end
+    /// condition `end_condition`.
+    ///
+    /// If `grid_func` is not valid, has too few grid points, or does not
+    /// satisfy `end_condition`, `new` panics. See `try_new` for a version
+    /// which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let spline = CubicSpline::new(&grid_func, SplineEndCondition::Natural);
+    /// ```
+    ///
+    pub fn new(
+        grid_func: &GridFunction,
+        end_condition: SplineEndCondition,
+    ) -> Self {
+        match CubicSpline::try_new(grid_func, end_condition) {
+            Ok(spline) => spline,
+            Err(error) => panic!("Cubic spline failed to construct. {}", error),
+        }
+    }
+
+    /// # Try new cubic spline
+    ///
+    /// ## Description
+    /// `try_new` is the fallible version of `new`. It returns an error if
+    /// `grid_func` is not valid or has fewer than 2 grid points (4 for
+    /// `NotAKnot`). For `Periodic`, it returns an
+    /// `InvalidBoundaryConditions` error if the first and last function
+    /// values are not equal.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let spline =
+    ///     CubicSpline::try_new(&grid_func, SplineEndCondition::Natural)?;
+    /// ```
+    ///
+    pub fn try_new(
+        grid_func: &GridFunction,
+        end_condition: SplineEndCondition,
+    ) -> Result<Self, NumericalMethodsError> {
+        let min_points = match end_condition {
+            SplineEndCondition::NotAKnot => 4,
+            _ => 2,
+        };
+        grid_func.validate_with_min_points(min_points)?;
+
+        let grid_points = &grid_func.grid.grid_points;
+        let function_values = &grid_func.function_values;
+        let num_points = grid_points.len();
+
+        // step_sizes[i] is the width of the ith grid cell and slopes[i] is the
+        // gradient of the straight line through the ends of the ith grid cell.
+        let step_sizes: Vec<f64> = grid_points
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect();
+        let slopes: Vec<f64> = function_values
+            .windows(2)
+            .zip(step_sizes.iter())
+            .map(|(pair, step_size)| (pair[1] - pair[0]) / step_size)
+            .collect();
+
+        // Assembles the tridiagonal system for the second derivatives.
+        let mut lower = vec![0.0; num_points - 1];
+        let mut diagonal = vec![0.0; num_points];
+        let mut upper = vec![0.0; num_points - 1];
+        let mut rhs = vec![0.0; num_points];
+
+        // At the interior grid points, the first derivative is continuous.
+        for i in 1..(num_points - 1) {
+            lower[i - 1] = step_sizes[i - 1];
+            diagonal[i] = 2.0 * (step_sizes[i - 1] + step_sizes[i]);
+            upper[i] = step_sizes[i];
+            rhs[i] = 6.0 * (slopes[i] - slopes[i - 1]);
+        }
+
+        // The first and last rows enforce the end conditions.
+        let last = num_points - 1;
+        let second_derivatives = match end_condition {
+            SplineEndCondition::Natural => {
+                diagonal[0] = 1.0;
+                diagonal[last] = 1.0;
+                solve_tridiagonal(&lower, &diagonal, &upper, &rhs)?
+            }
+            SplineEndCondition::Clamped {
+                start_derivative,
+                end_derivative,
+            } => {
+                diagonal[0] = 2.0 * step_sizes[0];
+                upper[0] = step_sizes[0];
+                rhs[0] = 6.0 * (slopes[0] - start_derivative);
+
+                lower[last - 1] = step_sizes[last - 1];
+                diagonal[last] = 2.0 * step_sizes[last - 1];
+                rhs[last] = 6.0 * (end_derivative - slopes[last - 1]);
+                solve_tridiagonal(&lower, &diagonal, &upper, &rhs)?
+            }
+            SplineEndCondition::NotAKnot => {
+                // The not-a-knot conditions give the first and last second
+                // derivatives in terms of their two neighbours. Substituting
+                // them into the second and second to last rows leaves a
+                // tridiagonal system for the interior second derivatives.
+                let (h0, h1) = (step_sizes[0], step_sizes[1]);
+                let (a, b) = (step_sizes[last - 2], step_sizes[last - 1]);
+                let mut lower = lower[1..(last - 1)].to_vec();
+                let mut diagonal = diagonal[1..last].to_vec();
+                let mut upper = upper[1..(last - 1)].to_vec();
+                let size = diagonal.len();
+                diagonal[0] += h0 * (h0 + h1) / h1;
+                upper[0] -= h0 * h0 / h1;
+                diagonal[size - 1] += b * (a + b) / a;
+                lower[size - 2] -= b * b / a;
+
+                let interior = solve_tridiagonal(
+                    &lower,
+                    &diagonal,
+                    &upper,
+                    &rhs[1..last],
+                )?;
+                let first = ((h0 + h1) * interior[0] - h0 * interior[1]) / h1;
+                let end =
+                    ((a + b) * interior[size - 1] - b * interior[size - 2]) / a;
+
+                let mut second_derivatives = vec![first];
+                second_derivatives.extend(interior);
+                second_derivatives.push(end);
+                second_derivatives
+            }
+            SplineEndCondition::Periodic => {
+                let scale = function_values[0]
+                    .abs()
+                    .max(function_values[last].abs())
+                    .max(1.0);
+                if (function_values[0] - function_values[last]).abs()
+                    > GRID_TOLERANCE * scale
+                {
+                    return Err(
+                        NumericalMethodsError::InvalidBoundaryConditions(
+                            "The first and last function values of a periodic \
+                         spline must be equal."
+                                .to_string(),
+                        ),
+                    );
+                }
+
+                // The last second derivative equals the first, and the first
+                // row joins the last grid cell to the first.
+                let h_last = step_sizes[last - 1];
+                diagonal[0] = 2.0 * (h_last + step_sizes[0]);
+                upper[0] = step_sizes[0];
+                rhs[0] = 6.0 * (slopes[0] - slopes[last - 1]);
+
+                let mut second_derivatives = solve_cyclic_tridiagonal(
+                    &lower[..(last - 1)],
+                    &diagonal[..last],
+                    &upper[..(last - 1)],
+                    (h_last, h_last),
+                    &rhs[..last],
+                )?;
+                second_derivatives.push(second_derivatives[0]);
+                second_derivatives
+            }
+        };
+
+        Ok(CubicSpline {
+            grid: grid_func.grid.clone(),
+            function_values: function_values.clone(),
+            second_derivatives,
+        })
+    }
+
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the value of the spline at the point `x`.
+    ///
+    /// If `x` lies outside the `Grid` of the spline, `evaluate` panics. See
+    /// `try_evaluate` for a version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let value = spline.evaluate(0.55);
+    /// ```
+    ///
+    pub fn evaluate(&self, x: f64) -> f64 {
+        match self.try_evaluate(x) {
+            Ok(value) => value,
+            Err(error) => panic!("Cubic spline failed to evaluate. {}", error),
+        }
+    }
+
+    /// # Try evaluate
+    ///
+    /// ## Description
+    /// `try_evaluate` is the fallible version of `evaluate`. It returns an
+    /// `OutOfDomain` error if `x` lies outside the `Grid` of the spline.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let value = spline.try_evaluate(0.55)?;
+    /// ```
+    ///
+    pub fn try_evaluate(&self, x: f64) -> Result<f64, NumericalMethodsError> {
+        let cell = self.locate(x)?;
+        let (m_start, m_end) = cell.second_derivatives;
+
+        // The cubic on the grid cell, written in terms of the function
+        // values and second derivatives at the ends of the cell.
+        Ok((m_start * cell.distance_to_end.powi(3)
+            + m_end * cell.distance_to_start.powi(3))
+            / (6.0 * cell.step_size)
+            + cell.linear_coefficients.0 * cell.distance_to_end
+            + cell.linear_coefficients.1 * cell.distance_to_start)
+    }
+
+    /// # Derivative
+    ///
+    /// ## Description
+    /// `derivative` returns the first derivative of the spline at the point
+    /// `x`.
+    ///
+    /// If `x` lies outside the `Grid` of the spline, `derivative` panics. See
+    /// `try_derivative` for a version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let slope = spline.derivative(0.55);
+    /// ```
+    ///
+    pub fn derivative(&self, x: f64) -> f64 {
+        match self.try_derivative(x) {
+            Ok(value) => value,
+            Err(error) => panic!("Cubic spline failed to evaluate. {}", error),
+        }
+    }
+
+    /// # Try derivative
+    ///
+    /// ## Description
+    /// `try_derivative` is the fallible version of `derivative`. It returns
+    /// an `OutOfDomain` error if `x` lies outside the `Grid` of the spline.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let slope = spline.try_derivative(0.55)?;
+    /// ```
+    ///
+    pub fn try_derivative(&self, x: f64) -> Result<f64, NumericalMethodsError> {
+        let cell = self.locate(x)?;
+        let (m_start, m_end) = cell.second_derivatives;
+
+        Ok((m_end * cell.distance_to_start.powi(2)
+            - m_start * cell.distance_to_end.powi(2))
+            / (2.0 * cell.step_size)
+            + cell.linear_coefficients.1
+            - cell.linear_coefficients.0)
+    }
+
+    /// # Second derivative
+    ///
+    /// ## Description
+    /// `second_derivative` returns the second derivative of the spline at the
+    /// point `x`, which is linear on each grid cell.
+    ///
+    /// If `x` lies outside the `Grid` of the spline, `second_derivative`
+    /// panics. See `try_second_derivative` for a version which returns an
+    /// error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let curvature = spline.second_derivative(0.55);
+    /// ```
+    ///
+    pub fn second_derivative(&self, x: f64) -> f64 {
+        match self.try_second_derivative(x) {
+            Ok(value) => value,
+            Err(error) => panic!("Cubic spline failed to evaluate. {}", error),
+        }
+    }
+
+    /// # Try second derivative
+    ///
+    /// ## Description
+    /// `try_second_derivative` is the fallible version of
+    /// `second_derivative`. It returns an `OutOfDomain` error if `x` lies
+    /// outside the `Grid` of the spline.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let curvature = spline.try_second_derivative(0.55)?;
+    /// ```
+    ///
+    pub fn try_second_derivative(
+        &self,
+        x: f64,
+    ) -> Result<f64, NumericalMethodsError> {
+        let cell = self.locate(x)?;
+        let (m_start, m_end) = cell.second_derivatives;
+
+        Ok(
+            (m_start * cell.distance_to_end + m_end * cell.distance_to_start)
+                / cell.step_size,
+        )
+    }
+
+    /// # Integrate
+    ///
+    /// ## Description
+    /// `integrate` returns the exact integral of the spline from
+    /// `lower_limit` to `upper_limit`. If `lower_limit` is greater than
+    /// `upper_limit`, the integral is negated.
+    ///
+    /// If either limit lies outside the `Grid` of the spline, `integrate`
+    /// panics. See `try_integrate` for a version which returns an error
+    /// instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = spline.integrate(0.0, 0.55);
+    /// ```
+    ///
+    pub fn integrate(&self, lower_limit: f64, upper_limit: f64) -> f64 {
+        match self.try_integrate(lower_limit, upper_limit) {
+            Ok(integral) => integral,
+            Err(error) => panic!("Integral failed to evaluate. {}", error),
+        }
+    }
+
+    /// # Try integrate
+    ///
+    /// ## Description
+    /// `try_integrate` is the fallible version of `integrate`. It returns an
+    /// `OutOfDomain` error if either limit lies outside the `Grid` of the
+    /// spline.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = spline.try_integrate(0.0, 0.55)?;
+    /// ```
+    ///
+    pub fn try_integrate(
+        &self,
+        lower_limit: f64,
+        upper_limit: f64,
+    ) -> Result<f64, NumericalMethodsError> {
+        Ok(self.integral_from_start(upper_limit)?
+            - self.integral_from_start(lower_limit)?)
+    }
+
+    /// # Integral from start
+    ///
+    /// ## Description
+    /// `integral_from_start` returns the integral of the spline from the
+    /// first grid point to `x`, summing the exact integrals over the whole
+    /// grid cells before `x` and the part of the grid cell containing `x`.
+    ///
+    fn integral_from_start(
+        &self,
+        x: f64,
+    ) -> Result<f64, NumericalMethodsError> {
+        let cell = self.locate(x)?;
+        let grid_points = &self.grid.grid_points;
+        let function_values = &self.function_values;
+        let second_derivatives = &self.second_derivatives;
+
+        // Each whole grid cell contributes the trapezoidal rule plus a
+        // correction from the second derivatives.
+        let whole_cells: f64 = (0..cell.index)
+            .map(|i| {
+                let step_size = grid_points[i + 1] - grid_points[i];
+                0.5 * step_size * (function_values[i] + function_values[i + 1])
+                    - step_size.powi(3)
+                        * (second_derivatives[i] + second_derivatives[i + 1])
+                        / 24.0
+            })
+            .sum();
+
+        // An antiderivative of the cubic on the grid cell, taken from the
+        // start of the grid cell.
+        let (m_start, m_end) = cell.second_derivatives;
+        let antiderivative = |distance_to_start: f64, distance_to_end: f64| {
+            (m_end * distance_to_start.powi(4)
+                - m_start * distance_to_end.powi(4))
+                / (24.0 * cell.step_size)
+                + 0.5 * cell.linear_coefficients.1 * distance_to_start.powi(2)
+                - 0.5 * cell.linear_coefficients.0 * distance_to_end.powi(2)
+        };
+        let partial_cell =
+            antiderivative(cell.distance_to_start, cell.distance_to_end)
+                - antiderivative(0.0, cell.step_size);
+
+        Ok(whole_cells + partial_cell)
+    }
+
+    /// # Locate
+    ///
+    /// ## Description
+    /// `locate` finds the grid cell containing `x`, and returns the data
+    /// needed to evaluate the cubic on it.
+    ///
+    fn locate(&self, x: f64) -> Result<SplineCell, NumericalMethodsError> {
+        let (i, x) = locate_cell(&self.grid, x)?;
+
+        let grid_points = &self.grid.grid_points;
+        let function_values = &self.function_values;
+        let second_derivatives = &self.second_derivatives;
+        let step_size = grid_points[i + 1] - grid_points[i];
+
+        Ok(SplineCell {
+            index: i,
+            step_size,
+            distance_to_start: x - grid_points[i],
+            distance_to_end: grid_points[i + 1] - x,
+            second_derivatives: (
+                second_derivatives[i],
+                second_derivatives[i + 1],
+            ),
+            linear_coefficients: (
+                function_values[i] / step_size
+                    - second_derivatives[i] * step_size / 6.0,
+                function_values[i + 1] / step_size
+                    - second_derivatives[i + 1] * step_size / 6.0,
+            ),
+        })
+    }
+}
+
+/// # Spline cell
+///
+/// ## Description
+/// `SplineCell` describes the position of a point in the grid cell `index`
+/// of a `CubicSpline`. On the grid cell, the spline is
+///
+/// (M_0 A^3 + M_1 B^3) / (6h) + c_0 A + c_1 B,
+///
+/// where h is `step_size`, A is `distance_to_end`, B is
+/// `distance_to_start`, (M_0, M_1) are `second_derivatives` at the ends of
+/// the grid cell and (c_0, c_1) are `linear_coefficients`.
+///
+struct SplineCell {
+    index: usize,
+    step_size: f64,
+    distance_to_start: f64,
+    distance_to_end: f64,
+    second_derivatives: (f64, f64),
+    linear_coefficients: (f64, f64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::test_grids;
+
+    #[test]
+    fn test_natural_spline_reproduces_linear_functions() {
+        // The second derivative of a linear function is zero, so the natural
+        // spline reproduces it exactly.
+        for (name, grid) in test_grids(0.0, 2.0, 11) {
+            let grid_func =
+                GridFunction::new_grid_function(&grid, |x| 3.0 * x - 1.0);
+            let spline =
+                CubicSpline::new(&grid_func, SplineEndCondition::Natural);
+            for x in [0.0, 0.13, 0.77, 1.5, 2.0] {
+                assert!(
+                    (spline.evaluate(x) - (3.0 * x - 1.0)).abs() < 1e-12,
+                    "Natural spline failed on the {} grid.",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_clamped_spline_reproduces_cubics() {
+        // A clamped spline with the exact end derivatives reproduces any
+        // cubic exactly.
+        let func = |x: f64| x.powi(3) - 2.0 * x * x + 0.5;
+        let derivative = |x: f64| 3.0 * x * x - 4.0 * x;
+        for (name, grid) in test_grids(-1.0, 2.0, 9) {
+            let grid_func = GridFunction::new_grid_function(&grid, func);
+            let spline = CubicSpline::new(
+                &grid_func,
+                SplineEndCondition::Clamped {
+                    start_derivative: derivative(-1.0),
+                    end_derivative: derivative(2.0),
+                },
+            );
+            for x in [-1.0, -0.3, 0.4, 1.1, 1.9, 2.0] {
+                assert!(
+                    (spline.evaluate(x) - func(x)).abs() < 1e-10,
+                    "Clamped spline failed on the {} grid.",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_not_a_knot_spline_reproduces_cubics() {
+        // The not-a-knot spline reproduces any cubic exactly, with its
+        // derivatives and integrals, without any derivative information.
+        let func = |x: f64| x.powi(3) - 2.0 * x * x + 0.5;
+        let derivative = |x: f64| 3.0 * x * x - 4.0 * x;
+        let second_derivative = |x: f64| 6.0 * x - 4.0;
+        let antiderivative =
+            |x: f64| x.powi(4) / 4.0 - 2.0 * x.powi(3) / 3.0 + 0.5 * x;
+        for (name, grid) in test_grids(-1.0, 2.0, 9) {
+            let grid_func = GridFunction::new_grid_function(&grid, func);
+            let spline =
+                CubicSpline::new(&grid_func, SplineEndCondition::NotAKnot);
+            for x in [-1.0, -0.3, 0.4, 1.1, 1.9, 2.0] {
+                assert!(
+                    (spline.evaluate(x) - func(x)).abs() < 1e-10
+                        && (spline.derivative(x) - derivative(x)).abs() < 1e-9
+                        && (spline.second_derivative(x) - second_derivative(x))
+                            .abs()
+                            < 1e-8,
+                    "Not-a-knot spline failed on the {} grid.",
+                    name
+                );
+            }
+            let exact = antiderivative(1.7) - antiderivative(-0.6);
+            assert!((spline.integrate(-0.6, 1.7) - exact).abs() < 1e-10);
+            assert!((spline.integrate(1.7, -0.6) + exact).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_periodic_spline() {
+        let period = 2.0 * std::f64::consts::PI;
+        let grid = Grid::new_uniform_grid(0.0, period, 41);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        let spline = CubicSpline::new(&grid_func, SplineEndCondition::Periodic);
+
+        for x in [0.0, 0.3, 2.0, 4.4, period] {
+            assert!((spline.evaluate(x) - x.sin()).abs() < 1e-5);
+            assert!((spline.derivative(x) - x.cos()).abs() < 1e-3);
+            assert!((spline.second_derivative(x) + x.sin()).abs() < 1e-2);
+        }
+
+        // The derivatives match at the two ends.
+        assert!(
+            (spline.derivative(0.0) - spline.derivative(period)).abs() < 1e-12
+        );
+        assert_eq!(spline.second_derivatives[0], spline.second_derivatives[40]);
+        assert!(spline.integrate(0.0, period).abs() < 1e-12);
+
+        // With 3 grid points, the two grid cells join at both ends.
+        let grid = Grid::new_grid_from_points(vec![0.0, 0.5, 2.0]);
+        let grid_func = GridFunction::try_new_grid_function_from_values(
+            &grid,
+            vec![1.0, -1.0, 1.0],
+        )
+        .unwrap();
+        let spline = CubicSpline::new(&grid_func, SplineEndCondition::Periodic);
+        assert!(
+            (spline.derivative(0.0) - spline.derivative(2.0)).abs() < 1e-12
+        );
+        assert!(
+            (spline.second_derivative(0.0) - spline.second_derivative(2.0))
+                .abs()
+                < 1e-12
+        );
+        assert!(
+            (spline.derivative(0.5 - 1e-9) - spline.derivative(0.5 + 1e-9))
+                .abs()
+                < 1e-6
+        );
+    }
+
+    #[test]
+    fn test_spline_derivatives_and_integrals_converge() {
+        // The clamped spline of exp has errors of order h^4 in its values
+        // and integrals, and h^3 in its first derivative.
+        let errors = |num_points: usize| {
+            let grid = Grid::new_uniform_grid(0.0, 1.0, num_points);
+            let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+            let spline = CubicSpline::new(
+                &grid_func,
+                SplineEndCondition::Clamped {
+                    start_derivative: 1.0,
+                    end_derivative: 1.0_f64.exp(),
+                },
+            );
+            let derivative_error = (0..=50)
+                .map(|k| {
+                    let x = k as f64 / 50.0;
+                    (spline.derivative(x) - x.exp()).abs()
+                })
+                .fold(0.0, f64::max);
+            let integral_error = (spline.integrate(0.1, 0.93)
+                - (0.93_f64.exp() - 0.1_f64.exp()))
+            .abs();
+            (derivative_error, integral_error)
+        };
+
+        let (coarse_derivative, coarse_integral) = errors(11);
+        let (fine_derivative, fine_integral) = errors(21);
+        assert!(coarse_derivative < 1e-4);
+        assert!(coarse_integral < 1e-6);
+        assert!(coarse_derivative / fine_derivative > 6.0);
+        assert!(coarse_integral / fine_integral > 12.0);
+    }
+
+    #[test]
+    fn test_spline_interpolates_grid_points() {
+        let grid = Grid::new_chebyshev_grid(0.0, 3.0, 15);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+        let spline = CubicSpline::new(&grid_func, SplineEndCondition::Natural);
+        for (&x, &y) in grid
+            .grid_points
+            .iter()
+            .zip(grid_func.function_values.iter())
+        {
+            assert!((spline.evaluate(x) - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_spline_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 1);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            CubicSpline::try_new(&grid_func, SplineEndCondition::Natural),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 1
+            })
+        );
+
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 3);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            CubicSpline::try_new(&grid_func, SplineEndCondition::NotAKnot),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 4,
+                actual: 3
+            })
+        );
+        assert!(matches!(
+            CubicSpline::try_new(&grid_func, SplineEndCondition::Periodic),
+            Err(NumericalMethodsError::InvalidBoundaryConditions(_))
+        ));
+
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        let spline = CubicSpline::new(&grid_func, SplineEndCondition::Natural);
+        assert!(matches!(
+            spline.try_integrate(0.0, 1.5),
+            Err(NumericalMethodsError::OutOfDomain { .. })
+        ));
+        assert_eq!(
+            spline.try_evaluate(1.5),
+            Err(NumericalMethodsError::OutOfDomain {
+                point: 1.5,
+                lower: 0.0,
+                upper: 1.0
+            })
+        );
+    }
+}
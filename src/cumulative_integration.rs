@@ -0,0 +1,360 @@
+use std::ops::Range;
+
+use crate::error::NumericalMethodsError;
+use crate::grid_function::GridFunction;
+use crate::interpolation::locate_cell;
+
+/// # Cumulative integral method
+///
+/// ## Description
+/// `CumulativeIntegralMethod` specifies how a `GridFunction` is integrated
+/// over each grid cell by `cumulative_integral` and `definite_integral`.
+///
+/// - `Trapezoidal` joins neighbouring function values with straight lines,
+///   and is second order accurate.
+/// - `PiecewiseQuadratic` fits a quadratic through each grid cell and the next
+///   grid point (the previous grid point for the last grid cell), as for
+///   `InterpolationMethod::PiecewiseQuadratic`, and is third order accurate.
+///   It needs at least 3 grid points.
+/// - `PiecewiseCubic` fits a cubic through each grid cell and the grid points
+///   on either side of it (the nearest four grid points at the ends), and is
+///   fourth order accurate. It needs at least 4 grid points.
+///
+/// Each interpolant is built from the actual grid points, so all of the
+/// methods are accurate on non-uniform grids.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CumulativeIntegralMethod {
+    Trapezoidal,
+    PiecewiseQuadratic,
+    PiecewiseCubic,
+}
+
+impl CumulativeIntegralMethod {
+    /// # Min points
+    ///
+    /// ## Description
+    /// `min_points` returns the number of grid points needed by the method.
+    ///
+    fn min_points(self) -> usize {
+        match self {
+            CumulativeIntegralMethod::Trapezoidal => 2,
+            CumulativeIntegralMethod::PiecewiseQuadratic => 3,
+            CumulativeIntegralMethod::PiecewiseCubic => 4,
+        }
+    }
+
+    /// # Stencil
+    ///
+    /// ## Description
+    /// `stencil` returns the range of grid points whose interpolant is
+    /// integrated over grid cell `i`, on a grid of `num_points` grid points.
+    ///
+    fn stencil(self, i: usize, num_points: usize) -> Range<usize> {
+        let first = match self {
+            CumulativeIntegralMethod::Trapezoidal => i,
+            CumulativeIntegralMethod::PiecewiseQuadratic => {
+                i.min(num_points - 3)
+            }
+            CumulativeIntegralMethod::PiecewiseCubic => {
+                i.saturating_sub(1).min(num_points - 4)
+            }
+        };
+        first..(first + self.min_points())
+    }
+}
+
+impl GridFunction {
+    /// # Cumulative integral
+    ///
+    /// ## Description
+    /// `cumulative_integral` returns the running integral
+    /// F(x) = integral of f from x_0 to x of the current `GridFunction` f, as
+    /// a `GridFunction` on the same `Grid`, so that F(x_0) = 0 and the last
+    /// value is the integral over the whole grid. The integral over each grid
+    /// cell is computed with `method`.
+    ///
+    /// If there are too few grid points for `method`, `cumulative_integral`
+    /// panics. See `try_cumulative_integral` for a version which returns an
+    /// error instead.
+    ///
+    /// ## Example use case
+    /// Suppose that we have a probability density on a grid, and we want its
+    /// cumulative distribution function. The code below does this.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(-5.0, 5.0, 101);
+    /// let density = GridFunction::new_grid_function(&grid, |x| {
+    ///     (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+    /// });
+    /// let distribution = density
+    ///     .cumulative_integral(CumulativeIntegralMethod::PiecewiseCubic);
+    /// ```
+    ///
+    pub fn cumulative_integral(
+        &self,
+        method: CumulativeIntegralMethod,
+    ) -> GridFunction {
+        match self.try_cumulative_integral(method) {
+            Ok(grid_func) => grid_func,
+            Err(error) => panic!("Cumulative integral failed. {}", error),
+        }
+    }
+
+    /// # Definite integral
+    ///
+    /// ## Description
+    /// `definite_integral` approximates the integral of the current
+    /// `GridFunction` from `lower_limit` to `upper_limit`, which may be any
+    /// points within its `Grid`. It evaluates the `cumulative_integral` at
+    /// the limits, integrating the interpolant used by `method` over the
+    /// partial grid cells at the limits. If `lower_limit` is greater than
+    /// `upper_limit`, the integral is negated.
+    ///
+    /// If either limit lies outside the `Grid` of the current `GridFunction`,
+    /// or there are too few grid points for `method`, `definite_integral`
+    /// panics. See `try_definite_integral` for a version which returns an
+    /// error instead.
+    ///
+    /// ## Example use case
+    /// Suppose that we want the integral of sin(x) from x = 0.25 to x = 0.8,
+    /// which do not lie on the grid. The code below does this.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+    /// let integral = grid_func.definite_integral(
+    ///     0.25,
+    ///     0.8,
+    ///     CumulativeIntegralMethod::PiecewiseCubic,
+    /// );
+    /// ```
+    ///
+    pub fn definite_integral(
+        &self,
+        lower_limit: f64,
+        upper_limit: f64,
+        method: CumulativeIntegralMethod,
+    ) -> f64 {
+        match self.try_definite_integral(lower_limit, upper_limit, method) {
+            Ok(integral) => integral,
+            Err(error) => panic!("Integral failed to evaluate. {}", error),
+        }
+    }
+
+    /// # Try cumulative integral
+    ///
+    /// ## Description
+    /// `try_cumulative_integral` is the fallible version of
+    /// `cumulative_integral`. It returns an error if the current
+    /// `GridFunction` is not valid, or if it has too few grid points for
+    /// `method`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let antiderivative = grid_func
+    ///     .try_cumulative_integral(CumulativeIntegralMethod::Trapezoidal)?;
+    /// ```
+    ///
+    pub fn try_cumulative_integral(
+        &self,
+        method: CumulativeIntegralMethod,
+    ) -> Result<GridFunction, NumericalMethodsError> {
+        self.validate_with_min_points(method.min_points())?;
+
+        let grid_points = &self.grid.grid_points;
+        let num_points = grid_points.len();
+        let mut function_values = Vec::with_capacity(num_points);
+        function_values.push(0.0);
+        for i in 0..(num_points - 1) {
+            let integral = function_values[i]
+                + self.cell_integral(i, grid_points[i + 1], method);
+            function_values.push(integral);
+        }
+
+        Ok(GridFunction {
+            grid: self.grid.clone(),
+            function_values,
+        })
+    }
+
+    /// # Try definite integral
+    ///
+    /// ## Description
+    /// `try_definite_integral` is the fallible version of
+    /// `definite_integral`. It returns an error if the current `GridFunction`
+    /// is not valid, if it has too few grid points for `method`, or if either
+    /// limit lies outside its `Grid`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = grid_func.try_definite_integral(
+    ///     0.25,
+    ///     0.8,
+    ///     CumulativeIntegralMethod::Trapezoidal,
+    /// )?;
+    /// ```
+    ///
+    pub fn try_definite_integral(
+        &self,
+        lower_limit: f64,
+        upper_limit: f64,
+        method: CumulativeIntegralMethod,
+    ) -> Result<f64, NumericalMethodsError> {
+        let cumulative_integral = self.try_cumulative_integral(method)?;
+
+        let antiderivative = |x: f64| {
+            let (i, x) = locate_cell(&self.grid, x)?;
+            Ok::<f64, NumericalMethodsError>(
+                cumulative_integral.function_values[i]
+                    + self.cell_integral(i, x, method),
+            )
+        };
+
+        Ok(antiderivative(upper_limit)? - antiderivative(lower_limit)?)
+    }
+
+    /// # Cell integral
+    ///
+    /// ## Description
+    /// `cell_integral` returns the integral from the start of grid cell `i`
+    /// to `x` of the interpolant used by `method` over that cell.
+    ///
+    fn cell_integral(
+        &self,
+        i: usize,
+        x: f64,
+        method: CumulativeIntegralMethod,
+    ) -> f64 {
+        let stencil = method.stencil(i, self.grid.grid_points.len());
+        self.stencil_integral(stencil, self.grid.grid_points[i], x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{max_error, test_grids, Grid};
+
+    const ALL_METHODS: [CumulativeIntegralMethod; 3] = [
+        CumulativeIntegralMethod::Trapezoidal,
+        CumulativeIntegralMethod::PiecewiseQuadratic,
+        CumulativeIntegralMethod::PiecewiseCubic,
+    ];
+
+    #[test]
+    fn test_cumulative_integral_reproduces_polynomials() {
+        // Each method is exact for polynomials up to its degree.
+        type Polynomial = fn(f64) -> f64;
+        let cases: [(CumulativeIntegralMethod, Polynomial, Polynomial); 3] = [
+            (
+                CumulativeIntegralMethod::Trapezoidal,
+                |x| 2.0 * x - 1.0,
+                |x| x * x - x,
+            ),
+            (
+                CumulativeIntegralMethod::PiecewiseQuadratic,
+                |x| 3.0 * x * x,
+                |x| x * x * x,
+            ),
+            (
+                CumulativeIntegralMethod::PiecewiseCubic,
+                |x| 4.0 * x * x * x - 1.0,
+                |x| x.powi(4) - x,
+            ),
+        ];
+        for (name, grid) in test_grids(-1.0, 2.0, 13) {
+            for (method, func, antiderivative) in cases {
+                let grid_func = GridFunction::new_grid_function(&grid, func);
+                let cumulative_integral = grid_func.cumulative_integral(method);
+                for (&x, value) in grid
+                    .grid_points
+                    .iter()
+                    .zip(&cumulative_integral.function_values)
+                {
+                    let exact = antiderivative(x) - antiderivative(-1.0);
+                    assert!(
+                        (value - exact).abs() < 1e-10,
+                        "{:?} failed on the {} grid.",
+                        method,
+                        name
+                    );
+                }
+
+                let integral = grid_func.definite_integral(0.3, -0.45, method);
+                let exact = antiderivative(-0.45) - antiderivative(0.3);
+                assert!(
+                    (integral - exact).abs() < 1e-10,
+                    "{:?} failed on the {} grid.",
+                    method,
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cumulative_integral_convergence_order() {
+        // Halving the grid spacing should reduce the maximum error by about
+        // 2^p for a method of order p.
+        let error = |num_points: usize, method| {
+            let grid = Grid::new_uniform_grid(0.0, 2.0, num_points);
+            let grid_func = GridFunction::new_grid_function(&grid, f64::cos);
+            max_error(&grid_func.cumulative_integral(method), f64::sin)
+        };
+
+        for (method, order) in ALL_METHODS.into_iter().zip([2.0, 3.0, 4.0]) {
+            let ratio = error(21, method) / error(41, method);
+            assert!(
+                ratio > 2.0_f64.powf(order - 0.5),
+                "{:?} converged with ratio {}.",
+                method,
+                ratio
+            );
+        }
+    }
+
+    #[test]
+    fn test_definite_integral_matches_whole_grid_rules() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+        let integral = grid_func.definite_integral(
+            0.0,
+            1.0,
+            CumulativeIntegralMethod::Trapezoidal,
+        );
+        assert!(
+            (integral - grid_func.integrate_trapezoidal_rule()).abs() < 1e-14
+        );
+
+        for method in ALL_METHODS {
+            let integral = grid_func.definite_integral(0.25, 0.25, method);
+            assert_eq!(integral, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_try_definite_integral_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 3);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+        assert_eq!(
+            grid_func.try_definite_integral(
+                0.0,
+                1.5,
+                CumulativeIntegralMethod::Trapezoidal
+            ),
+            Err(NumericalMethodsError::OutOfDomain {
+                point: 1.5,
+                lower: 0.0,
+                upper: 1.0
+            })
+        );
+        assert_eq!(
+            grid_func.try_cumulative_integral(
+                CumulativeIntegralMethod::PiecewiseCubic
+            ),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 4,
+                actual: 3
+            })
+        );
+    }
+}
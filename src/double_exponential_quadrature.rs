@@ -0,0 +1,586 @@
+use crate::adaptive_quadrature::{QuadratureResult, QuadratureWarning};
+use crate::error::{validate_tolerances, NumericalMethodsError};
+
+// Double exponential quadrature for closures.
+//
+// Each rule substitutes x = phi(t), where phi maps the real line onto the
+// interval of integration and phi'(t) decays double exponentially as
+// |t| grows, and applies the trapezoidal rule with step h in t. Endpoint
+// singularities are squashed by the decay of phi', and the trapezoidal rule
+// converges exponentially quickly for the resulting integrand, so halving h
+// roughly doubles the number of correct digits. Each level halves h and
+// reuses the previous points, and the change between levels estimates the
+// error.
+//
+// - tanh-sinh: phi(t) = tanh(PI / 2 sinh(t)) on [-1, 1].
+// - exp-sinh: phi(t) = exp(PI / 2 sinh(t)) on [0, infinity).
+// - sinh-sinh: phi(t) = sinh(PI / 2 sinh(t)) on (-infinity, infinity).
+//
+// The trapezoidal sums are truncated where phi'(t) underflows for a finite
+// end, and where phi(t) reaches about 10^30 for an infinite end.
+
+const FINITE_END_MAX_T: f64 = 6.0;
+const INFINITE_END_MAX_T: f64 = 4.5;
+
+/// # Double exponential options
+///
+/// ## Description
+/// `DoubleExponentialOptions` controls `tanh_sinh_integrate`,
+/// `exp_sinh_integrate` and `sinh_sinh_integrate`. Each level halves the
+/// step of the trapezoidal rule, starting from a step of 1. The integration
+/// stops once the change between two levels is at most
+/// max(`absolute_tolerance`, `relative_tolerance` * |integral|), or once
+/// `max_levels` levels of halving have been done.
+///
+/// ## Example use case
+/// ```
+/// let options = DoubleExponentialOptions {
+///     relative_tolerance: 1e-12,
+///     ..Default::default()
+/// };
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DoubleExponentialOptions {
+    pub absolute_tolerance: f64,
+    pub relative_tolerance: f64,
+    pub max_levels: usize,
+}
+
+impl Default for DoubleExponentialOptions {
+    fn default() -> Self {
+        DoubleExponentialOptions {
+            absolute_tolerance: 1e-10,
+            relative_tolerance: 1e-10,
+            max_levels: 10,
+        }
+    }
+}
+
+/// # Tanh-sinh integrate
+///
+/// ## Description
+/// `tanh_sinh_integrate` approximates the integral of `func` from
+/// `lower_limit` to `upper_limit`, which must be finite, by tanh-sinh
+/// quadrature. It handles integrable singularities at the limits, such as
+/// 1 / sqrt(x) or ln(x) at x = 0, and never evaluates `func` at the limits.
+/// If `lower_limit` is greater than `upper_limit`, the integral is negated.
+///
+/// `func` cannot be evaluated closer to a limit than the rounding error of
+/// the limit, so a singularity at a nonzero limit loses some accuracy. For
+/// example, 1 / sqrt(1 - x) on [0, 1] is only accurate to about 1e-8, while
+/// 1 / sqrt(x) on [0, 1] is accurate to machine precision. Shift the
+/// variable of integration so that a strong singularity is at 0.
+///
+/// `tanh_sinh_integrate` panics if the integral fails. See
+/// `try_tanh_sinh_integrate` for a version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want the integral of 1 / sqrt(x) from 0 to 1, which is 2.
+/// The code below computes it.
+/// ```
+/// let result = tanh_sinh_integrate(
+///     |x| 1.0 / x.sqrt(),
+///     0.0,
+///     1.0,
+///     &DoubleExponentialOptions::default(),
+/// );
+/// assert!(result.warning.is_none());
+/// ```
+///
+pub fn tanh_sinh_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &DoubleExponentialOptions,
+) -> QuadratureResult
+where
+    F: Fn(f64) -> f64,
+{
+    match try_tanh_sinh_integrate(func, lower_limit, upper_limit, options) {
+        Ok(result) => result,
+        Err(error) => panic!("Integral failed to evaluate. {}", error),
+    }
+}
+
+/// # Exp-sinh integrate
+///
+/// ## Description
+/// `exp_sinh_integrate` approximates the integral of `func` from
+/// `lower_limit` to `upper_limit` by exp-sinh quadrature. Exactly one limit
+/// must be infinite, such as [0, infinity) or (-infinity, 2]. `func` must
+/// decay at the infinite end faster than 1 / |x|, and may have an integrable
+/// singularity at the finite end, which is treated as in
+/// `tanh_sinh_integrate`.
+///
+/// `exp_sinh_integrate` panics if the integral fails. See
+/// `try_exp_sinh_integrate` for a version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want the integral of exp(-x) / sqrt(x) from 0 to
+/// infinity, which is sqrt(PI). The code below computes it.
+/// ```
+/// let result = exp_sinh_integrate(
+///     |x| (-x).exp() / x.sqrt(),
+///     0.0,
+///     f64::INFINITY,
+///     &DoubleExponentialOptions::default(),
+/// );
+/// ```
+///
+pub fn exp_sinh_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &DoubleExponentialOptions,
+) -> QuadratureResult
+where
+    F: Fn(f64) -> f64,
+{
+    match try_exp_sinh_integrate(func, lower_limit, upper_limit, options) {
+        Ok(result) => result,
+        Err(error) => panic!("Integral failed to evaluate. {}", error),
+    }
+}
+
+/// # Sinh-sinh integrate
+///
+/// ## Description
+/// `sinh_sinh_integrate` approximates the integral of `func` over the whole
+/// real line by sinh-sinh quadrature. The limits must be -infinity and
+/// infinity, and are swapped to negate the integral. `func` must decay at
+/// both ends faster than 1 / |x|.
+///
+/// `sinh_sinh_integrate` panics if the integral fails. See
+/// `try_sinh_sinh_integrate` for a version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want the integral of 1 / (1 + x^2) over the real line,
+/// which is PI. The code below computes it.
+/// ```
+/// let result = sinh_sinh_integrate(
+///     |x| 1.0 / (1.0 + x * x),
+///     f64::NEG_INFINITY,
+///     f64::INFINITY,
+///     &DoubleExponentialOptions::default(),
+/// );
+/// ```
+///
+pub fn sinh_sinh_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &DoubleExponentialOptions,
+) -> QuadratureResult
+where
+    F: Fn(f64) -> f64,
+{
+    match try_sinh_sinh_integrate(func, lower_limit, upper_limit, options) {
+        Ok(result) => result,
+        Err(error) => panic!("Integral failed to evaluate. {}", error),
+    }
+}
+
+/// # Try tanh-sinh integrate
+///
+/// ## Description
+/// `try_tanh_sinh_integrate` is the fallible version of
+/// `tanh_sinh_integrate`. It returns an `InvalidParameter` error if the
+/// limits are not finite, if the tolerances are negative or both zero, or if
+/// `max_levels` is 0.
+///
+/// Reaching the level limit is not an error, and is reported by the
+/// `warning` of the result instead.
+///
+/// ## Example use case
+/// ```
+/// let result = try_tanh_sinh_integrate(f64::ln, 0.0, 1.0, &options)?;
+/// ```
+///
+pub fn try_tanh_sinh_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &DoubleExponentialOptions,
+) -> Result<QuadratureResult, NumericalMethodsError>
+where
+    F: Fn(f64) -> f64,
+{
+    if !(lower_limit.is_finite() && upper_limit.is_finite()) {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The limits of tanh-sinh quadrature must be finite.".to_string(),
+        ));
+    }
+    validate_options(options)?;
+
+    let half_length = 0.5 * (upper_limit - lower_limit);
+    let node = |t: f64| {
+        // The distance to the nearest limit is 1 - tanh(|u|) times
+        // half_length, which is computed without cancellation.
+        let u = 0.5 * std::f64::consts::PI * t.sinh();
+        let decay = (-2.0 * u.abs()).exp();
+        let distance = half_length * 2.0 * decay / (1.0 + decay);
+        let x = if t < 0.0 {
+            lower_limit + distance
+        } else {
+            upper_limit - distance
+        };
+        let weight =
+            half_length * 0.5 * std::f64::consts::PI * t.cosh() * 4.0 * decay
+                / (1.0 + decay).powi(2);
+        (x, weight)
+    };
+
+    Ok(double_exponential_sum(
+        func,
+        node,
+        (FINITE_END_MAX_T, FINITE_END_MAX_T),
+        (lower_limit, upper_limit),
+        options,
+    ))
+}
+
+/// # Try exp-sinh integrate
+///
+/// ## Description
+/// `try_exp_sinh_integrate` is the fallible version of
+/// `exp_sinh_integrate`. It returns an `InvalidParameter` error if there is
+/// not exactly one infinite limit, if the tolerances are negative or both
+/// zero, or if `max_levels` is 0.
+///
+/// ## Example use case
+/// ```
+/// let result = try_exp_sinh_integrate(
+///     |x| x.exp(),
+///     f64::NEG_INFINITY,
+///     0.0,
+///     &options,
+/// )?;
+/// ```
+///
+pub fn try_exp_sinh_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &DoubleExponentialOptions,
+) -> Result<QuadratureResult, NumericalMethodsError>
+where
+    F: Fn(f64) -> f64,
+{
+    let limits_valid = (lower_limit.is_finite() && upper_limit.is_infinite())
+        || (lower_limit.is_infinite() && upper_limit.is_finite());
+    if !limits_valid {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "Exp-sinh quadrature needs exactly one infinite limit.".to_string(),
+        ));
+    }
+    validate_options(options)?;
+
+    // Integrates from the finite limit towards the infinite one, in the
+    // direction given by the sign of the infinite limit, and negates the
+    // result if the finite limit is the upper limit.
+    let (finite_limit, infinite_limit, orientation) = if lower_limit.is_finite()
+    {
+        (lower_limit, upper_limit, 1.0)
+    } else {
+        (upper_limit, lower_limit, -1.0)
+    };
+    let direction = infinite_limit.signum();
+    let node = |t: f64| {
+        let offset = (0.5 * std::f64::consts::PI * t.sinh()).exp();
+        let x = finite_limit + direction * offset;
+        let weight = orientation
+            * direction
+            * 0.5
+            * std::f64::consts::PI
+            * t.cosh()
+            * offset;
+        (x, weight)
+    };
+
+    Ok(double_exponential_sum(
+        func,
+        node,
+        (FINITE_END_MAX_T, INFINITE_END_MAX_T),
+        (finite_limit, finite_limit),
+        options,
+    ))
+}
+
+/// # Try sinh-sinh integrate
+///
+/// ## Description
+/// `try_sinh_sinh_integrate` is the fallible version of
+/// `sinh_sinh_integrate`. It returns an `InvalidParameter` error if the
+/// limits are not infinite with opposite signs, if the tolerances are
+/// negative or both zero, or if `max_levels` is 0.
+///
+/// ## Example use case
+/// ```
+/// let result = try_sinh_sinh_integrate(
+///     |x| (-x * x).exp(),
+///     f64::NEG_INFINITY,
+///     f64::INFINITY,
+///     &options,
+/// )?;
+/// ```
+///
+pub fn try_sinh_sinh_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    options: &DoubleExponentialOptions,
+) -> Result<QuadratureResult, NumericalMethodsError>
+where
+    F: Fn(f64) -> f64,
+{
+    let limits_valid = lower_limit.is_infinite()
+        && upper_limit.is_infinite()
+        && lower_limit != upper_limit;
+    if !limits_valid {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "Sinh-sinh quadrature needs the limits -infinity and infinity."
+                .to_string(),
+        ));
+    }
+    validate_options(options)?;
+
+    let orientation = upper_limit.signum();
+    let node = |t: f64| {
+        let u = 0.5 * std::f64::consts::PI * t.sinh();
+        let weight =
+            orientation * 0.5 * std::f64::consts::PI * t.cosh() * u.cosh();
+        (u.sinh(), weight)
+    };
+
+    Ok(double_exponential_sum(
+        func,
+        node,
+        (INFINITE_END_MAX_T, INFINITE_END_MAX_T),
+        (f64::NAN, f64::NAN),
+        options,
+    ))
+}
+
+/// # Validate options
+///
+/// ## Description
+/// `validate_options` checks that the tolerances are non-negative and not
+/// both zero, and that `max_levels` is positive.
+///
+fn validate_options(
+    options: &DoubleExponentialOptions,
+) -> Result<(), NumericalMethodsError> {
+    validate_tolerances(
+        options.absolute_tolerance,
+        options.relative_tolerance,
+    )?;
+    if options.max_levels == 0 {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The maximum number of levels must be positive.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// # Double exponential sum
+///
+/// ## Description
+/// `double_exponential_sum` applies the trapezoidal rule in t to
+/// func(x(t)) w(t) on [-`max_t.0`, `max_t.1`], where `node` returns x(t) and
+/// the weight w(t) = x'(t), halving the step at each level until the
+/// tolerances in `options` are met.
+///
+/// Nodes which round onto one of `finite_limits` are skipped, because an
+/// integrand with an endpoint singularity cannot be evaluated there. Their
+/// weights are negligible.
+///
+fn double_exponential_sum<F, N>(
+    func: F,
+    node: N,
+    max_t: (f64, f64),
+    finite_limits: (f64, f64),
+    options: &DoubleExponentialOptions,
+) -> QuadratureResult
+where
+    F: Fn(f64) -> f64,
+    N: Fn(f64) -> (f64, f64),
+{
+    let mut evaluations = 0;
+    let mut term = |t: f64| {
+        let (x, weight) = node(t);
+        if x == finite_limits.0 || x == finite_limits.1 || weight == 0.0 {
+            return 0.0;
+        }
+        evaluations += 1;
+        weight * func(x)
+    };
+
+    // Level 0 uses the integer values of t, and each later level adds the
+    // odd multiples of its step.
+    let mut sum: f64 = (-(max_t.0 as i64)..=(max_t.1 as i64))
+        .map(|k| term(k as f64))
+        .sum();
+    let mut value = sum;
+    let mut error_estimate = f64::INFINITY;
+    let tolerance = |value: f64| {
+        options
+            .absolute_tolerance
+            .max(options.relative_tolerance * value.abs())
+    };
+
+    for level in 1..=options.max_levels {
+        let step = 0.5_f64.powi(level as i32);
+        let min_index = -((max_t.0 / step) as i64);
+        let max_index = (max_t.1 / step) as i64;
+        sum += (min_index..=max_index)
+            .filter(|k| k % 2 != 0)
+            .map(|k| term(k as f64 * step))
+            .sum::<f64>();
+
+        let new_value = step * sum;
+        error_estimate = (new_value - value).abs();
+        value = new_value;
+        if error_estimate <= tolerance(value) {
+            break;
+        }
+    }
+
+    let warning = (error_estimate > tolerance(value)).then_some(
+        QuadratureWarning::LevelLimitReached {
+            max_levels: options.max_levels,
+        },
+    );
+
+    QuadratureResult {
+        value,
+        error_estimate,
+        evaluations,
+        warning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PI: f64 = std::f64::consts::PI;
+
+    #[test]
+    fn test_tanh_sinh_integrate() {
+        let options = DoubleExponentialOptions::default();
+        type Case = (fn(f64) -> f64, f64, f64, f64);
+        let cases: [Case; 4] = [
+            (|x| 1.0 / x.sqrt(), 0.0, 1.0, 2.0),
+            (f64::ln, 0.0, 1.0, -1.0),
+            (|x| x.powf(-0.9), 0.0, 1.0, 10.0),
+            (f64::exp, -1.0, 2.0, 2.0_f64.exp() - (-1.0_f64).exp()),
+        ];
+        for (func, lower_limit, upper_limit, exact) in cases {
+            let result =
+                tanh_sinh_integrate(func, lower_limit, upper_limit, &options);
+            assert!(result.warning.is_none());
+            assert!((result.value - exact).abs() < 1e-9 * exact.abs());
+
+            // Swapping the limits negates the integral.
+            let result =
+                tanh_sinh_integrate(func, upper_limit, lower_limit, &options);
+            assert!((result.value + exact).abs() < 1e-9 * exact.abs());
+        }
+
+        // A singularity at a nonzero limit loses some accuracy.
+        let result = tanh_sinh_integrate(
+            |x| 1.0 / (1.0 - x * x).sqrt(),
+            -1.0,
+            1.0,
+            &options,
+        );
+        assert!((result.value - PI).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_exp_sinh_integrate() {
+        let options = DoubleExponentialOptions::default();
+        let infinity = f64::INFINITY;
+        type Case = (fn(f64) -> f64, f64, f64, f64);
+        let cases: [Case; 5] = [
+            (|x| (-x).exp(), 0.0, infinity, 1.0),
+            (|x| 1.0 / (1.0 + x * x), 0.0, infinity, PI / 2.0),
+            (|x| (-x).exp() / x.sqrt(), 0.0, infinity, PI.sqrt()),
+            (f64::exp, -infinity, 0.0, 1.0),
+            (|x| 1.0 / (x * x), infinity, 1.0, -1.0),
+        ];
+        for (func, lower_limit, upper_limit, exact) in cases {
+            let result =
+                exp_sinh_integrate(func, lower_limit, upper_limit, &options);
+            assert!(result.warning.is_none());
+            assert!(
+                (result.value - exact).abs() < 1e-9 * exact.abs(),
+                "exp_sinh_integrate failed on [{}, {}].",
+                lower_limit,
+                upper_limit
+            );
+        }
+    }
+
+    #[test]
+    fn test_sinh_sinh_integrate() {
+        let options = DoubleExponentialOptions::default();
+        let infinity = f64::INFINITY;
+        type Case = (fn(f64) -> f64, f64, f64, f64);
+        let cases: [Case; 3] = [
+            (|x| (-x * x).exp(), -infinity, infinity, PI.sqrt()),
+            (|x| 1.0 / (1.0 + x * x), -infinity, infinity, PI),
+            (|x| 1.0 / (1.0 + x * x), infinity, -infinity, -PI),
+        ];
+        for (func, lower_limit, upper_limit, exact) in cases {
+            let result =
+                sinh_sinh_integrate(func, lower_limit, upper_limit, &options);
+            assert!(result.warning.is_none());
+            assert!((result.value - exact).abs() < 1e-9 * exact.abs());
+        }
+    }
+
+    #[test]
+    fn test_level_limit_and_errors() {
+        let options = DoubleExponentialOptions {
+            max_levels: 1,
+            ..Default::default()
+        };
+        let result = tanh_sinh_integrate(f64::exp, 0.0, 1.0, &options);
+        assert_eq!(
+            result.warning,
+            Some(QuadratureWarning::LevelLimitReached { max_levels: 1 })
+        );
+        assert!(result.error_estimate > 1e-10);
+
+        let options = DoubleExponentialOptions::default();
+        let infinity = f64::INFINITY;
+        assert!(matches!(
+            try_tanh_sinh_integrate(f64::exp, 0.0, infinity, &options),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_exp_sinh_integrate(f64::exp, 0.0, 1.0, &options),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_sinh_sinh_integrate(f64::exp, 0.0, infinity, &options),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_sinh_sinh_integrate(f64::exp, infinity, infinity, &options),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+
+        let options = DoubleExponentialOptions {
+            max_levels: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            try_tanh_sinh_integrate(f64::exp, 0.0, 1.0, &options),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+    }
+}
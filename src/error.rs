@@ -0,0 +1,189 @@
+use std::fmt;
+
+/// # Numerical methods error
+///
+/// ## Description
+/// `NumericalMethodsError` is the error type returned by the fallible (`try_`)
+/// functions in this crate.
+///
+/// - `InvalidGrid` is returned when a `Grid` cannot be constructed from the
+///   given parameters, or when an existing `Grid` is not valid for an
+///   operation. It contains a message describing the problem.
+/// - `InvalidBoundaryConditions` is returned when a set of boundary
+///   conditions is inconsistent. It contains a message describing the
+///   problem.
+/// - `GridMismatch` is returned when two `GridFunctions` that should share the
+///   same `Grid` do not.
+/// - `SingularJacobian` is returned when the linear system in a Newton step
+///   cannot be solved.
+/// - `SingularMatrix` is returned when any other linear system cannot be
+///   solved.
+/// - `InsufficientPoints` is returned when an operation needs more grid points
+///   than are available.
+/// - `LengthMismatch` is returned when the number of function values in a
+///   `GridFunction` is not equal to the number of grid points.
+/// - `OutOfDomain` is returned when a `GridFunction` is needed at a point
+///   outside of its `Grid`.
+/// - `InvalidParameter` is returned when a numerical parameter, such as a
+///   step size or a tolerance, is not valid. It contains a message describing
+///   the problem.
+/// - `StepSizeTooSmall` is returned when an adaptive time stepper needs a
+///   step size below its minimum step size to meet its error tolerances.
+/// - `MaxStepsExceeded` is returned when a time stepper takes its maximum
+///   number of steps without reaching the end time.
+///
+/// ## Example use case
+/// Suppose that we try to create a uniform grid whose start point is greater
+/// than its end point. The code below returns an `InvalidGrid` error rather
+/// than an empty grid.
+/// ```
+/// let result = Grid::try_new_uniform_grid(1.0, 0.0, 11);
+/// assert!(matches!(result, Err(NumericalMethodsError::InvalidGrid(_))));
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumericalMethodsError {
+    InvalidGrid(String),
+    InvalidBoundaryConditions(String),
+    GridMismatch,
+    SingularJacobian,
+    SingularMatrix,
+    InsufficientPoints { required: usize, actual: usize },
+    LengthMismatch { expected: usize, actual: usize },
+    OutOfDomain { point: f64, lower: f64, upper: f64 },
+    InvalidParameter(String),
+    StepSizeTooSmall { time: f64, step_size: f64 },
+    MaxStepsExceeded { max_steps: usize, time: f64 },
+}
+
+impl fmt::Display for NumericalMethodsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumericalMethodsError::InvalidGrid(message) => {
+                write!(f, "Invalid grid. {}", message)
+            }
+            NumericalMethodsError::InvalidBoundaryConditions(message) => {
+                write!(f, "Invalid boundary conditions. {}", message)
+            }
+            NumericalMethodsError::GridMismatch => {
+                write!(f, "The grid functions do not share the same grid.")
+            }
+            NumericalMethodsError::SingularJacobian => {
+                write!(f, "The Jacobian matrix is singular.")
+            }
+            NumericalMethodsError::SingularMatrix => {
+                write!(f, "The matrix is singular.")
+            }
+            NumericalMethodsError::InsufficientPoints { required, actual } => {
+                write!(
+                    f,
+                    "At least {} grid points are required, but only {} were provided.",
+                    required, actual
+                )
+            }
+            NumericalMethodsError::LengthMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Expected {} function values, but found {}.",
+                    expected, actual
+                )
+            }
+            NumericalMethodsError::OutOfDomain {
+                point,
+                lower,
+                upper,
+            } => {
+                write!(
+                    f,
+                    "The point {} lies outside the domain [{}, {}].",
+                    point, lower, upper
+                )
+            }
+            NumericalMethodsError::InvalidParameter(message) => {
+                write!(f, "Invalid parameter. {}", message)
+            }
+            NumericalMethodsError::StepSizeTooSmall { time, step_size } => {
+                write!(
+                    f,
+                    "The step size {} at time {} is below the minimum step size.",
+                    step_size, time
+                )
+            }
+            NumericalMethodsError::MaxStepsExceeded { max_steps, time } => {
+                write!(
+                    f,
+                    "The maximum of {} steps was reached at time {}.",
+                    max_steps, time
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for NumericalMethodsError {}
+
+/// # Validate tolerances
+///
+/// ## Description
+/// `validate_tolerances` checks that `absolute_tolerance` and
+/// `relative_tolerance` are non-negative and not both zero, and returns an
+/// `InvalidParameter` error if they are not. It is used by the adaptive
+/// quadrature rules and time steppers.
+///
+pub(crate) fn validate_tolerances(
+    absolute_tolerance: f64,
+    relative_tolerance: f64,
+) -> Result<(), NumericalMethodsError> {
+    let tolerances_valid = absolute_tolerance >= 0.0
+        && relative_tolerance >= 0.0
+        && absolute_tolerance + relative_tolerance > 0.0;
+    if !tolerances_valid {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The tolerances must be non-negative, and not both zero."
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = NumericalMethodsError::InvalidGrid(
+            "The grid points must be finite.".to_string(),
+        );
+        assert_eq!(
+            error.to_string(),
+            "Invalid grid. The grid points must be finite."
+        );
+
+        let error = NumericalMethodsError::InsufficientPoints {
+            required: 3,
+            actual: 1,
+        };
+        assert_eq!(
+            error.to_string(),
+            "At least 3 grid points are required, but only 1 were provided."
+        );
+
+        let error = NumericalMethodsError::LengthMismatch {
+            expected: 3,
+            actual: 2,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Expected 3 function values, but found 2."
+        );
+    }
+
+    #[test]
+    fn test_error_trait() {
+        let error: Box<dyn std::error::Error> =
+            Box::new(NumericalMethodsError::SingularJacobian);
+        assert_eq!(error.to_string(), "The Jacobian matrix is singular.");
+    }
+}
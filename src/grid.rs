@@ -1,3 +1,5 @@
+use crate::error::NumericalMethodsError;
+
 /// # Grid
 ///
 /// ## Description
@@ -10,8 +12,8 @@
 /// let grid = Grid { grid_points };
 /// ```
 ///
-/// ## Todo
-/// Add functionality to create non-uniform grids.
+/// Non-uniform grids can be created with `new_chebyshev_grid`,
+/// `new_geometric_grid`, `new_tanh_grid` and `new_grid_from_points`.
 ///
 #[derive(Debug, Clone, PartialEq)]
 pub struct Grid {
@@ -69,6 +71,683 @@ impl Grid {
 
         Grid { grid_points }
     }
+
+    /// # New Chebyshev grid
+    ///
+    /// ## Description
+    /// `new_chebyshev_grid` creates a `Grid` of `num_points`
+    /// Chebyshev-Gauss-Lobatto points between `start_point` and `end_point`
+    /// inclusive. The points are the extrema of the Chebyshev polynomial of
+    /// degree `num_points - 1`, mapped from [-1, 1] onto
+    /// [`start_point`, `end_point`], and are sorted in increasing order. The
+    /// points cluster towards both ends of the domain.
+    ///
+    /// The edge cases are handled in the same way as `new_uniform_grid`.
+    ///
+    /// ## Example use case
+    /// Suppose that we want to create a Chebyshev grid with 11 points between
+    /// 0.0 and 1.0 inclusive. The code below does this.
+    /// ```
+    /// let grid = Grid::new_chebyshev_grid(0.0, 1.0, 11);
+    /// ```
+    ///
+    pub fn new_chebyshev_grid(
+        start_point: f64,
+        end_point: f64,
+        num_points: usize,
+    ) -> Self {
+        if let Some(grid) =
+            edge_case_grid(start_point, end_point, num_points, true)
+        {
+            return grid;
+        }
+
+        let midpoint = 0.5 * (start_point + end_point);
+        let half_width = 0.5 * (end_point - start_point);
+        let degree = (num_points - 1) as f64;
+
+        // Maps the Chebyshev-Gauss-Lobatto points -cos(k*pi/degree), which lie
+        // in [-1, 1], onto [start_point, end_point].
+        let mut grid_points: Vec<f64> = (0..num_points)
+            .map(|k| {
+                let theta = std::f64::consts::PI * (k as f64) / degree;
+                midpoint - half_width * theta.cos()
+            })
+            .collect();
+
+        // Sets the end points exactly, to avoid rounding errors.
+        grid_points[0] = start_point;
+        grid_points[num_points - 1] = end_point;
+
+        Grid { grid_points }
+    }
+
+    /// # New geometric grid
+    ///
+    /// ## Description
+    /// `new_geometric_grid` creates a `Grid` of `num_points` points between
+    /// `start_point` and `end_point` inclusive, where the width of each grid
+    /// cell is `ratio` times the width of the previous grid cell.
+    ///
+    /// If `ratio` is less than 1, the points cluster towards `end_point`. If
+    /// `ratio` is greater than 1, the points cluster towards `start_point`. If
+    /// `ratio` is equal to 1, the grid is uniform.
+    ///
+    /// If `ratio` is not a positive, finite number, or is so far from 1 that
+    /// the grid points would not be distinct in floating point (for example a
+    /// `ratio` of 10 with 400 points), the function returns an empty grid.
+    /// The other edge cases are handled in the same way as
+    /// `new_uniform_grid`.
+    ///
+    /// ## Example use case
+    /// Suppose that we want to create a grid with 11 points between 0.0 and
+    /// 1.0 inclusive, where each grid cell is 10% wider than the previous one.
+    /// The code below does this.
+    /// ```
+    /// let grid = Grid::new_geometric_grid(0.0, 1.0, 11, 1.1);
+    /// ```
+    ///
+    pub fn new_geometric_grid(
+        start_point: f64,
+        end_point: f64,
+        num_points: usize,
+        ratio: f64,
+    ) -> Self {
+        // The ratio must be positive and finite.
+        let ratio_valid = ratio > 0.0 && ratio.is_finite();
+        if let Some(grid) =
+            edge_case_grid(start_point, end_point, num_points, ratio_valid)
+        {
+            return grid;
+        }
+        if ratio == 1.0 {
+            return Grid::new_uniform_grid(start_point, end_point, num_points);
+        }
+
+        // The cell widths form a geometric series, whose sum must be equal to
+        // the length of the domain.
+        let num_cells = (num_points - 1) as i32;
+        let length = end_point - start_point;
+        let first_step_size =
+            length * (ratio - 1.0) / (ratio.powi(num_cells) - 1.0);
+
+        let mut grid_points: Vec<f64> = Vec::with_capacity(num_points);
+        let mut point = start_point;
+        let mut step_size = first_step_size;
+        grid_points.push(point);
+        for _ in 1..num_points {
+            point += step_size;
+            step_size *= ratio;
+            grid_points.push(point);
+        }
+
+        // Sets the final point exactly, to avoid accumulated rounding errors.
+        grid_points[num_points - 1] = end_point;
+
+        // For extreme ratios, ratio^num_cells overflows or the smallest cells
+        // underflow, so that grid points are repeated.
+        if grid_points.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Grid {
+                grid_points: vec![],
+            };
+        }
+
+        Grid { grid_points }
+    }
+
+    /// # New tanh grid
+    ///
+    /// ## Description
+    /// `new_tanh_grid` creates a `Grid` of `num_points` points between
+    /// `start_point` and `end_point` inclusive, where the points are clustered
+    /// using a hyperbolic tangent stretching function. This type of grid is
+    /// useful for resolving boundary layers.
+    ///
+    /// `clustering` controls whether the points cluster towards the start,
+    /// the end, or both ends of the domain. `stretching` controls the strength
+    /// of the clustering. A `stretching` of 0 gives a uniform grid, and larger
+    /// values give stronger clustering.
+    ///
+    /// If `stretching` is negative or not finite, the function returns an
+    /// empty grid. The other edge cases are handled in the same way as
+    /// `new_uniform_grid`.
+    ///
+    /// ## Example use case
+    /// Suppose that we want to create a grid with 21 points between 0.0 and
+    /// 1.0 inclusive, where the points are clustered towards both ends. The
+    /// code below does this.
+    /// ```
+    /// let grid = Grid::new_tanh_grid(0.0, 1.0, 21, 3.0, GridClustering::Both);
+    /// ```
+    ///
+    pub fn new_tanh_grid(
+        start_point: f64,
+        end_point: f64,
+        num_points: usize,
+        stretching: f64,
+        clustering: GridClustering,
+    ) -> Self {
+        // The stretching must be non-negative and finite.
+        let stretching_valid = stretching >= 0.0 && stretching.is_finite();
+        if let Some(grid) =
+            edge_case_grid(start_point, end_point, num_points, stretching_valid)
+        {
+            return grid;
+        }
+        if stretching == 0.0 {
+            return Grid::new_uniform_grid(start_point, end_point, num_points);
+        }
+
+        let length = end_point - start_point;
+        let num_cells = (num_points - 1) as f64;
+
+        // Maps a uniform computational coordinate xi in [0, 1] onto [0, 1],
+        // such that the mapping is flat near the ends where points cluster.
+        let stretching_function = |xi: f64| -> f64 {
+            match clustering {
+                GridClustering::Start => {
+                    1.0 + (stretching * (xi - 1.0)).tanh() / stretching.tanh()
+                }
+                GridClustering::End => {
+                    (stretching * xi).tanh() / stretching.tanh()
+                }
+                GridClustering::Both => {
+                    0.5 * (1.0
+                        + (stretching * (xi - 0.5)).tanh()
+                            / (0.5 * stretching).tanh())
+                }
+            }
+        };
+
+        let mut grid_points: Vec<f64> = (0..num_points)
+            .map(|i| {
+                start_point + length * stretching_function(i as f64 / num_cells)
+            })
+            .collect();
+
+        // Sets the end points exactly, to avoid rounding errors.
+        grid_points[0] = start_point;
+        grid_points[num_points - 1] = end_point;
+
+        Grid { grid_points }
+    }
+
+    /// # New grid from points
+    ///
+    /// ## Description
+    /// `new_grid_from_points` creates a `Grid` from an arbitrary vector of
+    /// points `grid_points`.
+    ///
+    /// The points must be finite and sorted in strictly increasing order. If
+    /// they are not, the function panics.
+    ///
+    /// ## Example use case
+    /// Suppose that we want to create a grid from the points 0.0, 0.1, 0.5 and
+    /// 1.0. The code below does this.
+    /// ```
+    /// let grid = Grid::new_grid_from_points(vec![0.0, 0.1, 0.5, 1.0]);
+    /// ```
+    ///
+    pub fn new_grid_from_points(grid_points: Vec<f64>) -> Self {
+        let grid = Grid { grid_points };
+
+        // Error handling - the points must be finite and strictly increasing.
+        if let Err(error) = grid.validate() {
+            panic!("Grid failed to construct. {}", error);
+        }
+
+        grid
+    }
+
+    /// # Validate
+    ///
+    /// ## Description
+    /// `validate` checks that the grid points are finite and sorted in
+    /// strictly increasing order. If they are not, an `InvalidGrid` error is
+    /// returned.
+    ///
+    /// ## Example use case
+    /// Suppose that we have constructed a `Grid` directly from a vector of
+    /// points, and we want to check that it is valid. The code below does
+    /// this.
+    /// ```
+    /// let grid = Grid { grid_points: vec![0.0, 0.5, 0.25] };
+    /// assert!(grid.validate().is_err());
+    /// ```
+    ///
+    pub fn validate(&self) -> Result<(), NumericalMethodsError> {
+        if self.grid_points.iter().any(|x| !x.is_finite()) {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The grid points must be finite.".to_string(),
+            ));
+        }
+
+        if self.grid_points.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The grid points must be strictly increasing.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// # Is compatible
+    ///
+    /// ## Description
+    /// `is_compatible` checks whether the current `Grid` and `grid` have the
+    /// same number of points, and whether each pair of corresponding points
+    /// agree to within `tolerance` times the length of the current `Grid`.
+    ///
+    /// `GridFunctions` can only be combined point by point if their `Grids`
+    /// are compatible.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_1 = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 21);
+    /// assert!(!grid_1.is_compatible(&grid_2, GRID_TOLERANCE));
+    /// ```
+    ///
+    pub fn is_compatible(&self, grid: &Grid, tolerance: f64) -> bool {
+        if self.grid_points.len() != grid.grid_points.len() {
+            return false;
+        }
+
+        // The tolerance is relative to the length of the grid. Single point
+        // grids use an absolute tolerance.
+        let length = match (self.grid_points.first(), self.grid_points.last()) {
+            (Some(first), Some(last)) if last > first => last - first,
+            _ => 1.0,
+        };
+
+        self.grid_points
+            .iter()
+            .zip(grid.grid_points.iter())
+            .all(|(a, b)| (a - b).abs() <= tolerance * length)
+    }
+
+    /// # Find cell
+    ///
+    /// ## Description
+    /// `find_cell` uses a binary search to find the index `i` of the grid cell
+    /// [`grid_points[i]`, `grid_points[i + 1]`] which contains the point `x`.
+    /// If `x` lies outside the grid, or the grid has fewer than 2 points,
+    /// `None` is returned.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// assert_eq!(grid.find_cell(0.25), Some(2));
+    /// ```
+    ///
+    pub fn find_cell(&self, x: f64) -> Option<usize> {
+        let grid_points = &self.grid_points;
+        let num_points = grid_points.len();
+
+        if num_points < 2
+            || !(x >= grid_points[0] && x <= grid_points[num_points - 1])
+        {
+            return None;
+        }
+
+        // The number of grid points less than or equal to x. Points at the
+        // end of the grid belong to the final cell.
+        let num_points_below = grid_points.partition_point(|&point| point <= x);
+
+        Some((num_points_below - 1).min(num_points - 2))
+    }
+}
+
+/// # Grid tolerance
+///
+/// ## Description
+/// `GRID_TOLERANCE` is the default relative tolerance used to decide whether
+/// two `Grids` are compatible (see `Grid::is_compatible`).
+///
+pub const GRID_TOLERANCE: f64 = 1e-10;
+
+// Fallible grid constructors.
+impl Grid {
+    /// # Try new uniform grid
+    ///
+    /// ## Description
+    /// `try_new_uniform_grid` is the fallible version of `new_uniform_grid`.
+    /// Rather than returning an empty or single point grid for invalid inputs,
+    /// it returns an `InvalidGrid` error if `start_point` and `end_point` are
+    /// not finite or `start_point` is not less than `end_point`, and an
+    /// `InsufficientPoints` error if `num_points` is less than 2.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::try_new_uniform_grid(0.0, 1.0, 11)?;
+    /// ```
+    ///
+    pub fn try_new_uniform_grid(
+        start_point: f64,
+        end_point: f64,
+        num_points: usize,
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_grid_parameters(start_point, end_point, num_points)?;
+        Ok(Grid::new_uniform_grid(start_point, end_point, num_points))
+    }
+
+    /// # Try new Chebyshev grid
+    ///
+    /// ## Description
+    /// `try_new_chebyshev_grid` is the fallible version of
+    /// `new_chebyshev_grid`. The errors are the same as for
+    /// `try_new_uniform_grid`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::try_new_chebyshev_grid(0.0, 1.0, 11)?;
+    /// ```
+    ///
+    pub fn try_new_chebyshev_grid(
+        start_point: f64,
+        end_point: f64,
+        num_points: usize,
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_grid_parameters(start_point, end_point, num_points)?;
+        Ok(Grid::new_chebyshev_grid(start_point, end_point, num_points))
+    }
+
+    /// # Try new geometric grid
+    ///
+    /// ## Description
+    /// `try_new_geometric_grid` is the fallible version of
+    /// `new_geometric_grid`. In addition to the errors returned by
+    /// `try_new_uniform_grid`, it returns an `InvalidGrid` error if `ratio` is
+    /// not a positive, finite number, or is too far from 1 for the grid points
+    /// to be distinct.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::try_new_geometric_grid(0.0, 1.0, 11, 1.1)?;
+    /// ```
+    ///
+    pub fn try_new_geometric_grid(
+        start_point: f64,
+        end_point: f64,
+        num_points: usize,
+        ratio: f64,
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_grid_parameters(start_point, end_point, num_points)?;
+
+        if !(ratio > 0.0 && ratio.is_finite()) {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The ratio must be positive and finite.".to_string(),
+            ));
+        }
+
+        let grid =
+            Grid::new_geometric_grid(start_point, end_point, num_points, ratio);
+        if grid.grid_points.is_empty() {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The ratio is too far from 1 for the grid points to be \
+                 distinct."
+                    .to_string(),
+            ));
+        }
+
+        Ok(grid)
+    }
+
+    /// # Try new tanh grid
+    ///
+    /// ## Description
+    /// `try_new_tanh_grid` is the fallible version of `new_tanh_grid`. In
+    /// addition to the errors returned by `try_new_uniform_grid`, it returns
+    /// an `InvalidGrid` error if `stretching` is negative or not finite.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid =
+    ///     Grid::try_new_tanh_grid(0.0, 1.0, 21, 3.0, GridClustering::Both)?;
+    /// ```
+    ///
+    pub fn try_new_tanh_grid(
+        start_point: f64,
+        end_point: f64,
+        num_points: usize,
+        stretching: f64,
+        clustering: GridClustering,
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_grid_parameters(start_point, end_point, num_points)?;
+
+        if !(stretching >= 0.0 && stretching.is_finite()) {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The stretching must be non-negative and finite.".to_string(),
+            ));
+        }
+
+        let grid = Grid::new_tanh_grid(
+            start_point,
+            end_point,
+            num_points,
+            stretching,
+            clustering,
+        );
+
+        // For extreme stretching, the smallest cells can underflow.
+        grid.validate()?;
+
+        Ok(grid)
+    }
+
+    /// # Try new grid from points
+    ///
+    /// ## Description
+    /// `try_new_grid_from_points` is the fallible version of
+    /// `new_grid_from_points`. It returns an `InvalidGrid` error if the points
+    /// are not finite or not strictly increasing, and an `InsufficientPoints`
+    /// error if there are fewer than 2 points.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::try_new_grid_from_points(vec![0.0, 0.1, 0.5, 1.0])?;
+    /// ```
+    ///
+    pub fn try_new_grid_from_points(
+        grid_points: Vec<f64>,
+    ) -> Result<Self, NumericalMethodsError> {
+        if grid_points.len() < 2 {
+            return Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: grid_points.len(),
+            });
+        }
+
+        let grid = Grid { grid_points };
+        grid.validate()?;
+
+        Ok(grid)
+    }
+}
+
+/// # Validate grid parameters
+///
+/// ## Description
+/// `validate_grid_parameters` checks the parameters shared by the grid
+/// constructors. It returns an error if `start_point` or `end_point` is not
+/// finite, if `start_point` is not less than `end_point`, or if `num_points`
+/// is less than 2.
+///
+fn validate_grid_parameters(
+    start_point: f64,
+    end_point: f64,
+    num_points: usize,
+) -> Result<(), NumericalMethodsError> {
+    if !(start_point.is_finite() && end_point.is_finite()) {
+        return Err(NumericalMethodsError::InvalidGrid(
+            "The start and end points must be finite.".to_string(),
+        ));
+    }
+
+    if start_point >= end_point {
+        return Err(NumericalMethodsError::InvalidGrid(
+            "The start point must be less than the end point.".to_string(),
+        ));
+    }
+
+    if num_points < 2 {
+        return Err(NumericalMethodsError::InsufficientPoints {
+            required: 2,
+            actual: num_points,
+        });
+    }
+
+    Ok(())
+}
+
+/// # Edge case grid
+///
+/// ## Description
+/// `edge_case_grid` handles the edge cases of the non-uniform grid
+/// constructors in the same way as `new_uniform_grid`. It returns an empty
+/// grid if `start_point` is not less than `end_point`, if `num_points` is 0,
+/// or if `parameter_valid` is false, and a grid containing only
+/// `start_point` if `num_points` is 1. Otherwise, it returns `None`.
+///
+fn edge_case_grid(
+    start_point: f64,
+    end_point: f64,
+    num_points: usize,
+    parameter_valid: bool,
+) -> Option<Grid> {
+    if start_point >= end_point || num_points == 0 || !parameter_valid {
+        return Some(Grid {
+            grid_points: vec![],
+        });
+    }
+    if num_points == 1 {
+        return Some(Grid {
+            grid_points: vec![start_point],
+        });
+    }
+
+    None
+}
+
+/// # Grid clustering
+///
+/// ## Description
+/// `GridClustering` specifies where the points of a stretched grid (see
+/// `Grid::new_tanh_grid`) are clustered: towards the start of the domain,
+/// towards the end of the domain, or towards both ends of the domain.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridClustering {
+    Start,
+    End,
+    Both,
+}
+
+/// # Test grids
+///
+/// ## Description
+/// `test_grids` returns a labelled collection of uniform and non-uniform grids
+/// of `num_points` points between `start_point` and `end_point`, so that
+/// routines acting on a `Grid` can be tested against every type of grid.
+///
+#[cfg(test)]
+pub(crate) fn test_grids(
+    start_point: f64,
+    end_point: f64,
+    num_points: usize,
+) -> Vec<(&'static str, Grid)> {
+    // Perturbs a uniform grid to create an irregular grid.
+    let uniform_grid =
+        Grid::new_uniform_grid(start_point, end_point, num_points);
+    let step_size = (end_point - start_point) / (num_points as f64 - 1.0);
+    let mut irregular_points = uniform_grid.grid_points.clone();
+    for (i, point) in irregular_points
+        .iter_mut()
+        .enumerate()
+        .take(num_points - 1)
+        .skip(1)
+    {
+        *point += 0.3 * step_size * (i as f64).sin();
+    }
+
+    vec![
+        ("uniform", uniform_grid),
+        (
+            "chebyshev",
+            Grid::new_chebyshev_grid(start_point, end_point, num_points),
+        ),
+        (
+            "geometric",
+            Grid::new_geometric_grid(start_point, end_point, num_points, 0.98),
+        ),
+        (
+            "tanh start",
+            Grid::new_tanh_grid(
+                start_point,
+                end_point,
+                num_points,
+                2.0,
+                GridClustering::Start,
+            ),
+        ),
+        (
+            "tanh end",
+            Grid::new_tanh_grid(
+                start_point,
+                end_point,
+                num_points,
+                2.0,
+                GridClustering::End,
+            ),
+        ),
+        (
+            "tanh both",
+            Grid::new_tanh_grid(
+                start_point,
+                end_point,
+                num_points,
+                2.0,
+                GridClustering::Both,
+            ),
+        ),
+        ("from points", Grid::new_grid_from_points(irregular_points)),
+    ]
+}
+
+/// # Max error
+///
+/// ## Description
+/// `max_error` returns the largest absolute difference between the function
+/// values of `grid_func` and `exact` at the grid points.
+///
+#[cfg(test)]
+pub(crate) fn max_error<E>(
+    grid_func: &crate::grid_function::GridFunction,
+    exact: E,
+) -> f64
+where
+    E: Fn(f64) -> f64,
+{
+    grid_func
+        .grid
+        .grid_points
+        .iter()
+        .zip(&grid_func.function_values)
+        .map(|(&x, y)| (y - exact(x)).abs())
+        .fold(0.0, f64::max)
+}
+
+/// # Max cell width
+///
+/// ## Description
+/// `max_cell_width` returns the width of the widest cell of `grid`.
+///
+#[cfg(test)]
+pub(crate) fn max_cell_width(grid: &Grid) -> f64 {
+    grid.grid_points
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .fold(0.0, f64::max)
 }
 
 /// ## Todo
@@ -76,7 +755,6 @@ impl Grid {
 /// Add tests which test floating point precision. For example, a grid with  
 /// start_point and end_point close together, or a grid with a large number of
 /// points.
-/// Test construction of a non-uniform grid, once this functionality is added.
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,9 +763,11 @@ mod tests {
     fn test_new_uniform_grid() {
         let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
 
-        for (a, b) in grid.grid_points.iter().zip(
-            vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0].iter(),
-        ) {
+        for (a, b) in grid
+            .grid_points
+            .iter()
+            .zip([0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0].iter())
+        {
             assert!((a - b).abs() < 1e-10, "new_uniform_grid failed to create a grid of 11 points with sufficient precision.");
         }
     }
@@ -169,4 +849,340 @@ mod tests {
             "new_uniform_grid failed to handle edge case with start_point greater than end_point."
         );
     }
+
+    /// Checks that the grid points are strictly increasing and start and end
+    /// exactly at start_point and end_point.
+    fn assert_valid_grid(grid: &Grid, start_point: f64, end_point: f64) {
+        let grid_points = &grid.grid_points;
+        assert_eq!(grid_points[0], start_point, "Grid has the wrong start.");
+        assert_eq!(
+            grid_points[grid_points.len() - 1],
+            end_point,
+            "Grid has the wrong end."
+        );
+        assert!(
+            grid_points.windows(2).all(|pair| pair[0] < pair[1]),
+            "Grid points are not strictly increasing."
+        );
+    }
+
+    #[test]
+    fn test_new_chebyshev_grid() {
+        let grid = Grid::new_chebyshev_grid(-1.0, 1.0, 5);
+        let expected = [-1.0, -f64::sqrt(0.5), 0.0, f64::sqrt(0.5), 1.0];
+        for (a, b) in grid.grid_points.iter().zip(expected.iter()) {
+            assert!(
+                (a - b).abs() < 1e-12,
+                "new_chebyshev_grid failed to create the Chebyshev-Gauss-Lobatto points on [-1, 1]."
+            );
+        }
+
+        // The points cluster towards both ends of the domain.
+        let grid = Grid::new_chebyshev_grid(2.0, 5.0, 21);
+        assert_valid_grid(&grid, 2.0, 5.0);
+        let grid_points = &grid.grid_points;
+        assert!(
+            grid_points[1] - grid_points[0] < grid_points[11] - grid_points[10],
+            "new_chebyshev_grid failed to cluster points towards the ends."
+        );
+        assert!(
+            (grid_points[10] - 3.5).abs() < 1e-12,
+            "new_chebyshev_grid failed to place the middle point at the midpoint."
+        );
+    }
+
+    #[test]
+    fn test_new_geometric_grid() {
+        let grid = Grid::new_geometric_grid(0.0, 7.0, 4, 2.0);
+        assert_eq!(
+            grid.grid_points,
+            vec![0.0, 1.0, 3.0, 7.0],
+            "new_geometric_grid failed to create a grid with ratio 2."
+        );
+
+        let grid = Grid::new_geometric_grid(0.0, 1.0, 51, 0.9);
+        assert_valid_grid(&grid, 0.0, 1.0);
+        let grid_points = &grid.grid_points;
+        for i in 1..(grid_points.len() - 1) {
+            let ratio = (grid_points[i + 1] - grid_points[i])
+                / (grid_points[i] - grid_points[i - 1]);
+            assert!(
+                (ratio - 0.9).abs() < 1e-9,
+                "new_geometric_grid failed to create cells with a constant ratio."
+            );
+        }
+
+        // A ratio of 1 gives a uniform grid.
+        assert_eq!(
+            Grid::new_geometric_grid(0.0, 1.0, 11, 1.0),
+            Grid::new_uniform_grid(0.0, 1.0, 11),
+            "new_geometric_grid failed to create a uniform grid with ratio 1."
+        );
+    }
+
+    #[test]
+    fn test_new_tanh_grid() {
+        for clustering in [
+            GridClustering::Start,
+            GridClustering::End,
+            GridClustering::Both,
+        ] {
+            let grid = Grid::new_tanh_grid(0.0, 2.0, 41, 3.0, clustering);
+            assert_valid_grid(&grid, 0.0, 2.0);
+        }
+
+        let first_cell =
+            |grid: &Grid| grid.grid_points[1] - grid.grid_points[0];
+        let last_cell = |grid: &Grid| {
+            let n = grid.grid_points.len();
+            grid.grid_points[n - 1] - grid.grid_points[n - 2]
+        };
+
+        let grid =
+            Grid::new_tanh_grid(0.0, 1.0, 41, 3.0, GridClustering::Start);
+        assert!(
+            first_cell(&grid) < 0.1 * last_cell(&grid),
+            "new_tanh_grid failed to cluster points towards the start."
+        );
+
+        let grid = Grid::new_tanh_grid(0.0, 1.0, 41, 3.0, GridClustering::End);
+        assert!(
+            last_cell(&grid) < 0.1 * first_cell(&grid),
+            "new_tanh_grid failed to cluster points towards the end."
+        );
+
+        let grid = Grid::new_tanh_grid(0.0, 1.0, 41, 3.0, GridClustering::Both);
+        assert!(
+            (first_cell(&grid) - last_cell(&grid)).abs() < 1e-12
+                && first_cell(&grid) < grid.grid_points[21] - grid.grid_points[20],
+            "new_tanh_grid failed to cluster points symmetrically towards both ends."
+        );
+
+        // A stretching of 0 gives a uniform grid.
+        assert_eq!(
+            Grid::new_tanh_grid(0.0, 1.0, 11, 0.0, GridClustering::Both),
+            Grid::new_uniform_grid(0.0, 1.0, 11),
+            "new_tanh_grid failed to create a uniform grid with stretching 0."
+        );
+    }
+
+    #[test]
+    fn test_non_uniform_grid_edge_cases() {
+        let empty: Vec<f64> = vec![];
+
+        assert_eq!(Grid::new_chebyshev_grid(1.0, 0.0, 11).grid_points, empty);
+        assert_eq!(Grid::new_chebyshev_grid(0.0, 1.0, 0).grid_points, empty);
+        assert_eq!(
+            Grid::new_chebyshev_grid(0.0, 1.0, 1).grid_points,
+            vec![0.0]
+        );
+
+        assert_eq!(
+            Grid::new_geometric_grid(1.0, 0.0, 11, 2.0).grid_points,
+            empty
+        );
+        assert_eq!(
+            Grid::new_geometric_grid(0.0, 1.0, 11, 0.0).grid_points,
+            empty
+        );
+        assert_eq!(
+            Grid::new_geometric_grid(0.0, 1.0, 11, f64::NAN).grid_points,
+            empty
+        );
+        assert_eq!(
+            Grid::new_geometric_grid(0.0, 1.0, 1, 2.0).grid_points,
+            vec![0.0]
+        );
+        // 10^399 overflows, and 0.1^399 underflows.
+        assert_eq!(
+            Grid::new_geometric_grid(0.0, 1.0, 400, 10.0).grid_points,
+            empty
+        );
+        assert_eq!(
+            Grid::new_geometric_grid(0.0, 1.0, 400, 0.1).grid_points,
+            empty
+        );
+
+        let both = GridClustering::Both;
+        assert_eq!(
+            Grid::new_tanh_grid(1.0, 0.0, 11, 2.0, both).grid_points,
+            empty
+        );
+        assert_eq!(
+            Grid::new_tanh_grid(0.0, 1.0, 11, -1.0, both).grid_points,
+            empty
+        );
+        assert_eq!(
+            Grid::new_tanh_grid(0.0, 1.0, 1, 2.0, both).grid_points,
+            vec![0.0]
+        );
+    }
+
+    #[test]
+    fn test_new_grid_from_points() {
+        let grid_points = vec![0.0, 0.1, 0.5, 1.0];
+        let grid = Grid::new_grid_from_points(grid_points.clone());
+        assert_eq!(
+            grid.grid_points, grid_points,
+            "new_grid_from_points failed to store the grid points."
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    fn test_new_grid_from_points_unsorted() {
+        Grid::new_grid_from_points(vec![0.0, 0.5, 0.1, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    fn test_new_grid_from_points_repeated() {
+        Grid::new_grid_from_points(vec![0.0, 0.5, 0.5, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "finite")]
+    fn test_new_grid_from_points_not_finite() {
+        Grid::new_grid_from_points(vec![0.0, f64::NAN, 1.0]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        assert_eq!(grid.validate(), Ok(()));
+
+        let grid = Grid {
+            grid_points: vec![0.0, 0.5, 0.25],
+        };
+        assert!(
+            matches!(
+                grid.validate(),
+                Err(NumericalMethodsError::InvalidGrid(_))
+            ),
+            "validate failed to detect unsorted grid points."
+        );
+
+        let grid = Grid {
+            grid_points: vec![0.0, f64::INFINITY],
+        };
+        assert!(
+            matches!(
+                grid.validate(),
+                Err(NumericalMethodsError::InvalidGrid(_))
+            ),
+            "validate failed to detect infinite grid points."
+        );
+    }
+
+    #[test]
+    fn test_try_constructors() {
+        // Valid inputs give the same grid as the infallible constructors.
+        assert_eq!(
+            Grid::try_new_uniform_grid(0.0, 1.0, 11),
+            Ok(Grid::new_uniform_grid(0.0, 1.0, 11))
+        );
+        assert_eq!(
+            Grid::try_new_chebyshev_grid(0.0, 1.0, 11),
+            Ok(Grid::new_chebyshev_grid(0.0, 1.0, 11))
+        );
+        assert_eq!(
+            Grid::try_new_geometric_grid(0.0, 1.0, 11, 1.1),
+            Ok(Grid::new_geometric_grid(0.0, 1.0, 11, 1.1))
+        );
+        assert_eq!(
+            Grid::try_new_tanh_grid(0.0, 1.0, 11, 2.0, GridClustering::End),
+            Ok(Grid::new_tanh_grid(0.0, 1.0, 11, 2.0, GridClustering::End))
+        );
+        assert_eq!(
+            Grid::try_new_grid_from_points(vec![0.0, 0.3, 1.0]),
+            Ok(Grid::new_grid_from_points(vec![0.0, 0.3, 1.0]))
+        );
+
+        // Invalid inputs give errors.
+        assert!(matches!(
+            Grid::try_new_uniform_grid(1.0, 0.0, 11),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+        assert!(matches!(
+            Grid::try_new_uniform_grid(0.0, f64::NAN, 11),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+        assert_eq!(
+            Grid::try_new_chebyshev_grid(0.0, 1.0, 1),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert!(matches!(
+            Grid::try_new_geometric_grid(0.0, 1.0, 11, -1.0),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+        assert!(matches!(
+            Grid::try_new_geometric_grid(0.0, 1.0, 400, 10.0),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+        assert!(matches!(
+            Grid::try_new_tanh_grid(0.0, 1.0, 11, -1.0, GridClustering::Both),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+        assert!(matches!(
+            Grid::try_new_grid_from_points(vec![0.0, 1.0, 1.0]),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+        assert_eq!(
+            Grid::try_new_grid_from_points(vec![]),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        assert!(grid.is_compatible(&grid.clone(), GRID_TOLERANCE));
+
+        // Small perturbations are within the tolerance.
+        let mut grid_perturbed = grid.clone();
+        grid_perturbed.grid_points[3] += 1e-13;
+        assert!(grid.is_compatible(&grid_perturbed, GRID_TOLERANCE));
+
+        // Large perturbations are not.
+        grid_perturbed.grid_points[3] += 1e-3;
+        assert!(!grid.is_compatible(&grid_perturbed, GRID_TOLERANCE));
+
+        // Grids with different numbers of points are not compatible.
+        let grid_other = Grid::new_uniform_grid(0.0, 1.0, 21);
+        assert!(!grid.is_compatible(&grid_other, GRID_TOLERANCE));
+
+        // Grids with the same number of points but different spacing are not
+        // compatible.
+        let grid_other = Grid::new_chebyshev_grid(0.0, 1.0, 11);
+        assert!(!grid.is_compatible(&grid_other, GRID_TOLERANCE));
+
+        // Empty grids are compatible with each other.
+        let empty_grid = Grid::new_uniform_grid(0.0, 1.0, 0);
+        assert!(empty_grid.is_compatible(&empty_grid.clone(), GRID_TOLERANCE));
+    }
+
+    #[test]
+    fn test_find_cell() {
+        let grid = Grid::new_grid_from_points(vec![0.0, 0.1, 0.5, 2.0]);
+        assert_eq!(grid.find_cell(0.0), Some(0));
+        assert_eq!(grid.find_cell(0.05), Some(0));
+        assert_eq!(grid.find_cell(0.1), Some(1));
+        assert_eq!(grid.find_cell(1.0), Some(2));
+        assert_eq!(grid.find_cell(2.0), Some(2));
+
+        // Points outside the grid.
+        assert_eq!(grid.find_cell(-0.1), None);
+        assert_eq!(grid.find_cell(2.1), None);
+        assert_eq!(grid.find_cell(f64::NAN), None);
+
+        // Grids with fewer than 2 points have no cells.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 1);
+        assert_eq!(grid.find_cell(0.0), None);
+    }
 }
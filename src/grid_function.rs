@@ -1,3 +1,4 @@
+use crate::error::NumericalMethodsError;
 use crate::grid::Grid;
 
 /// # Grid function
@@ -19,9 +20,8 @@ use crate::grid::Grid;
 /// let grid_func = GridFunction { grid, function_values };
 /// ```
 ///
-/// ## Todo
-/// Add checks to ensure that the length of `function_values` is equal to the
-/// length of `grid_points`.
+/// The length of `function_values` should be equal to the length of
+/// `grid_points`. This can be checked with `validate`.
 ///
 #[derive(Debug, Clone, PartialEq)]
 pub struct GridFunction {
@@ -91,15 +91,142 @@ impl GridFunction {
             function_values,
         }
     }
+
+    /// # Validate
+    ///
+    /// ## Description
+    /// `validate` checks that the `Grid` of the current `GridFunction` is valid
+    /// (see `Grid::validate`), and that the number of function values is equal
+    /// to the number of grid points. If it is not, an error is returned.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 3);
+    /// let grid_func = GridFunction { grid, function_values: vec![0.0, 1.0] };
+    /// assert!(grid_func.validate().is_err());
+    /// ```
+    ///
+    pub fn validate(&self) -> Result<(), NumericalMethodsError> {
+        self.grid.validate()?;
+
+        if self.function_values.len() != self.grid.grid_points.len() {
+            return Err(NumericalMethodsError::LengthMismatch {
+                expected: self.grid.grid_points.len(),
+                actual: self.function_values.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// # Validate with minimum number of points
+    ///
+    /// ## Description
+    /// `validate_with_min_points` checks that the current `GridFunction` is
+    /// valid, and that it has at least `min_points` grid points. It is used by
+    /// the fallible (`try_`) methods of `GridFunction`.
+    ///
+    pub(crate) fn validate_with_min_points(
+        &self,
+        min_points: usize,
+    ) -> Result<(), NumericalMethodsError> {
+        self.validate()?;
+
+        let num_points = self.grid.grid_points.len();
+        if num_points < min_points {
+            return Err(NumericalMethodsError::InsufficientPoints {
+                required: min_points,
+                actual: num_points,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// Fallible grid function constructors.
+impl GridFunction {
+    /// # Try new grid function
+    ///
+    /// ## Description
+    /// `try_new_grid_function` is the fallible version of `new_grid_function`.
+    /// It returns an error if `grid` is not valid or is empty.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 5.0, 6);
+    /// let grid_func = GridFunction::try_new_grid_function(&grid, f64::sin)?;
+    /// ```
+    ///
+    pub fn try_new_grid_function<F>(
+        grid: &Grid,
+        func: F,
+    ) -> Result<Self, NumericalMethodsError>
+    where
+        F: Fn(f64) -> f64,
+    {
+        let grid_func = GridFunction::new_grid_function(grid, func);
+        grid_func.validate_with_min_points(1)?;
+        Ok(grid_func)
+    }
+
+    /// # Try new constant grid function
+    ///
+    /// ## Description
+    /// `try_new_constant_grid_function` is the fallible version of
+    /// `new_constant_grid_function`. It returns an error if `grid` is not
+    /// valid or is empty.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 5.0, 6);
+    /// let grid_func =
+    ///     GridFunction::try_new_constant_grid_function(&grid, 2.0)?;
+    /// ```
+    ///
+    pub fn try_new_constant_grid_function(
+        grid: &Grid,
+        scalar: f64,
+    ) -> Result<Self, NumericalMethodsError> {
+        let grid_func = GridFunction::new_constant_grid_function(grid, scalar);
+        grid_func.validate_with_min_points(1)?;
+        Ok(grid_func)
+    }
+
+    /// # Try new grid function from values
+    ///
+    /// ## Description
+    /// `try_new_grid_function_from_values` creates a `GridFunction` from a
+    /// `Grid` `grid` and a vector of function values `function_values`. It
+    /// returns an error if `grid` is not valid or is empty, or if the number
+    /// of function values is not equal to the number of grid points.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 2.0, 3);
+    /// let grid_func = GridFunction::try_new_grid_function_from_values(
+    ///     &grid,
+    ///     vec![0.0, 1.0, 4.0],
+    /// )?;
+    /// ```
+    ///
+    pub fn try_new_grid_function_from_values(
+        grid: &Grid,
+        function_values: Vec<f64>,
+    ) -> Result<Self, NumericalMethodsError> {
+        let grid_func = GridFunction {
+            grid: grid.clone(),
+            function_values,
+        };
+        grid_func.validate_with_min_points(1)?;
+        Ok(grid_func)
+    }
 }
 
 /// ## Todo
 /// Test new_grid_function and new_constant_grid_function with extremely large
 /// and small numbers.
 /// Add tests with randomly generated grids.
-/// Once check for the length of `function_values` is implemented, add tests
-/// for when the length of `function_values` is not equal to the length of
-/// `grid_points`.
 /// Implement more tests with discontinuous functions.
 /// Add explicit test that the grid in GridFunction is a clone of the input
 /// grid.
@@ -277,4 +404,82 @@ mod tests {
             "new_constant_grid_function failed with an empty grid."
         );
     }
+
+    #[test]
+    fn test_validate() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 3);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(grid_func.validate(), Ok(()));
+
+        // Too few function values.
+        let grid_func = GridFunction {
+            grid: grid.clone(),
+            function_values: vec![0.0, 1.0],
+        };
+        assert_eq!(
+            grid_func.validate(),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+
+        // Invalid grid.
+        let grid_func = GridFunction {
+            grid: Grid {
+                grid_points: vec![1.0, 0.0],
+            },
+            function_values: vec![0.0, 1.0],
+        };
+        assert!(matches!(
+            grid_func.validate(),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+    }
+
+    #[test]
+    fn test_try_constructors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 6);
+        assert_eq!(
+            GridFunction::try_new_grid_function(&grid, f64::sin),
+            Ok(GridFunction::new_grid_function(&grid, f64::sin))
+        );
+        assert_eq!(
+            GridFunction::try_new_constant_grid_function(&grid, 2.0),
+            Ok(GridFunction::new_constant_grid_function(&grid, 2.0))
+        );
+        assert_eq!(
+            GridFunction::try_new_grid_function_from_values(
+                &grid,
+                vec![2.0; 6]
+            ),
+            Ok(GridFunction::new_constant_grid_function(&grid, 2.0))
+        );
+
+        // Empty grid.
+        let empty_grid = Grid::new_uniform_grid(0.0, 1.0, 0);
+        assert_eq!(
+            GridFunction::try_new_grid_function(&empty_grid, f64::sin),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 1,
+                actual: 0
+            })
+        );
+        assert!(
+            GridFunction::try_new_constant_grid_function(&empty_grid, 2.0)
+                .is_err()
+        );
+
+        // Wrong number of function values.
+        assert_eq!(
+            GridFunction::try_new_grid_function_from_values(
+                &grid,
+                vec![2.0; 5]
+            ),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 6,
+                actual: 5
+            })
+        );
+    }
 }
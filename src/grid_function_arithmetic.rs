@@ -1,5 +1,25 @@
+use crate::error::NumericalMethodsError;
 use crate::grid_function::GridFunction;
 
+/// # Grid mismatch policy
+///
+/// ## Description
+/// `GridMismatchPolicy` specifies what the fallible arithmetic operations do
+/// when the two `GridFunctions` do not have compatible `Grids` (see
+/// `Grid::is_compatible`).
+///
+/// - `Error` returns a `GridMismatch` error.
+/// - `Resample` resamples the second `GridFunction` onto the `Grid` of the
+///   first `GridFunction` using piecewise linear interpolation. An
+///   `OutOfDomain` error is returned if the first `Grid` extends beyond the
+///   second.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridMismatchPolicy {
+    Error,
+    Resample,
+}
+
 impl GridFunction {
     /// # Grid function add
     ///
@@ -7,9 +27,9 @@ impl GridFunction {
     /// `add` adds the `GridFunction` `grid_func` to the current `GridFunction`
     /// and returns the result.
     ///
-    /// The current `GridFunction` and `grid_func` should both have the same
-    /// `Grid`. If they have different `Grids`, the `Grid` from the current
-    /// `GridFunction` will be used.
+    /// The current `GridFunction` and `grid_func` must have compatible
+    /// `Grids`. If they do not, `add` panics. See `try_add` for a version which
+    /// returns an error or resamples `grid_func` instead.
     ///
     /// ## Example use case
     /// Suppose that we have two `GridFunctions` `grid_func_1` and `grid_func_2`
@@ -21,39 +41,10 @@ impl GridFunction {
     /// let grid_func_sum = grid_func_1.add(&grid_func_2);
     /// ```
     ///
-    /// ## Todo
-    /// Change handling of edge case where the two `GridFunctions` have
-    /// different `Grids`. Currently, the `Grid` from the current `GridFunction`
-    /// is used. This may not be the best way to handle this case.
-    ///
-    pub fn add(self: &Self, grid_func: &GridFunction) -> Self {
-        let grid = self.grid.clone();
-
-        let function_values_1 = self.function_values.clone();
-        let mut function_values_2 = grid_func.function_values.clone();
-        let length_difference: i32 =
-            (function_values_1.len() as i32) - (function_values_2.len() as i32);
-
-        // If function_values_2 has fewer elements than function_values_1, adds
-        // zeroes to the end of function_values_2 until the two vectors are the
-        // same length.
-        if length_difference > 0 {
-            for _ in 0..length_difference {
-                function_values_2.push(0.0);
-            }
-        }
-
-        // Iterates over all the elements in function_values_1 and adds them to
-        // the elements in function_values_2.
-        let function_values: Vec<f64> = function_values_1
-            .iter()
-            .zip(function_values_2.iter())
-            .map(|(x, y)| x + y)
-            .collect();
-
-        GridFunction {
-            grid,
-            function_values,
+    pub fn add(&self, grid_func: &GridFunction) -> Self {
+        match self.try_add(grid_func, GridMismatchPolicy::Error) {
+            Ok(grid_func_sum) => grid_func_sum,
+            Err(error) => panic!("Addition failed. {}", error),
         }
     }
 
@@ -63,9 +54,9 @@ impl GridFunction {
     /// `subtract` subtracts the `GridFunction` `grid_func` from the current
     /// `GridFunction` and returns the result.
     ///
-    /// The current `GridFunction` and `grid_func` should both have the same
-    /// `Grid`. If they have different `Grids`, the `Grid` from the current
-    /// `GridFunction` will be used.
+    /// The current `GridFunction` and `grid_func` must have compatible
+    /// `Grids`. If they do not, `subtract` panics. See `try_subtract` for a
+    /// version which returns an error or resamples `grid_func` instead.
     ///
     /// ## Example use case
     /// Suppose that we have two `GridFunctions` `grid_func_1` and `grid_func_2`
@@ -78,39 +69,10 @@ impl GridFunction {
     /// let grid_func_difference = grid_func_1.subtract(&grid_func_2);
     /// ```
     ///
-    /// ## Todo
-    /// Change handling of edge case where the two `GridFunctions` have
-    /// different `Grids`. Currently, the `Grid` from the current `GridFunction`
-    /// is used. This may not be the best way to handle this case.
-    ///
-    pub fn subtract(self: &Self, grid_func: &GridFunction) -> Self {
-        let grid = self.grid.clone();
-
-        let function_values_1 = self.function_values.clone();
-        let mut function_values_2 = grid_func.function_values.clone();
-        let length_difference: i32 =
-            (function_values_1.len() as i32) - (function_values_2.len() as i32);
-
-        // If function_values_2 has fewer elements than function_values_1, adds
-        // zeroes to the end of function_values_2 until the two vectors are the
-        // same length.
-        if length_difference > 0 {
-            for _ in 0..length_difference {
-                function_values_2.push(0.0);
-            }
-        }
-
-        // Iterates over all the elements in function_values_1 and adds them to
-        // the elements in function_values_2.
-        let function_values: Vec<f64> = function_values_1
-            .iter()
-            .zip(function_values_2.iter())
-            .map(|(x, y)| x - y)
-            .collect();
-
-        GridFunction {
-            grid,
-            function_values,
+    pub fn subtract(&self, grid_func: &GridFunction) -> Self {
+        match self.try_subtract(grid_func, GridMismatchPolicy::Error) {
+            Ok(grid_func_difference) => grid_func_difference,
+            Err(error) => panic!("Subtraction failed. {}", error),
         }
     }
 
@@ -120,9 +82,9 @@ impl GridFunction {
     /// `multiply` multiplies the current `GridFunction` by the `GridFunction`
     /// `grid_func` and returns the result.
     ///
-    /// The current `GridFunction` and `grid_func` should both have the same
-    /// `Grid`. If they have different `Grids`, the `Grid` from the current
-    /// `GridFunction` will be used.
+    /// The current `GridFunction` and `grid_func` must have compatible
+    /// `Grids`. If they do not, `multiply` panics. See `try_multiply` for a
+    /// version which returns an error or resamples `grid_func` instead.
     ///
     /// ## Example use case
     /// Suppose that we have two `GridFunctions` `grid_func_1` and `grid_func_2`
@@ -131,42 +93,13 @@ impl GridFunction {
     /// let grid = Grid::new_uniform_grid(0.0, 5.0, 6);
     /// let grid_func_1 = GridFunction::new_constant_grid_function(&grid, 2.0);
     /// let grid_func_2 = GridFunction::new_constant_grid_function(&grid, 3.0);
-    /// let grid_func_difference = grid_func_1.multiply(&grid_func_2);
+    /// let grid_func_product = grid_func_1.multiply(&grid_func_2);
     /// ```
     ///
-    /// ## Todo
-    /// Change handling of edge case where the two `GridFunctions` have
-    /// different `Grids`. Currently, the `Grid` from the current `GridFunction`
-    /// is used. This may not be the best way to handle this case.
-    ///
-    pub fn multiply(self: &Self, grid_func: &GridFunction) -> Self {
-        let grid = self.grid.clone();
-
-        let function_values_1 = self.function_values.clone();
-        let mut function_values_2 = grid_func.function_values.clone();
-        let length_difference: i32 =
-            (function_values_1.len() as i32) - (function_values_2.len() as i32);
-
-        // If function_values_2 has fewer elements than function_values_1, adds
-        // zeroes to the end of function_values_2 until the two vectors are the
-        // same length.
-        if length_difference > 0 {
-            for _ in 0..length_difference {
-                function_values_2.push(0.0);
-            }
-        }
-
-        // Iterates over all the elements in function_values_1 and adds them to
-        // the elements in function_values_2.
-        let function_values: Vec<f64> = function_values_1
-            .iter()
-            .zip(function_values_2.iter())
-            .map(|(x, y)| x * y)
-            .collect();
-
-        GridFunction {
-            grid,
-            function_values,
+    pub fn multiply(&self, grid_func: &GridFunction) -> Self {
+        match self.try_multiply(grid_func, GridMismatchPolicy::Error) {
+            Ok(grid_func_product) => grid_func_product,
+            Err(error) => panic!("Multiplication failed. {}", error),
         }
     }
 
@@ -176,9 +109,9 @@ impl GridFunction {
     /// `divide` divides the current `GridFunction` by the `GridFunction`
     /// `grid_func` and returns the result.
     ///
-    /// The current `GridFunction` and `grid_func` should both have the same
-    /// `Grid`. If they have different `Grids`, the `Grid` from the current
-    /// `GridFunction` will be used.
+    /// The current `GridFunction` and `grid_func` must have compatible
+    /// `Grids`. If they do not, `divide` panics. See `try_divide` for a
+    /// version which returns an error or resamples `grid_func` instead.
     ///
     /// ## Example use case
     /// Suppose that we have two `GridFunctions` `grid_func_1` and `grid_func_2`
@@ -187,42 +120,13 @@ impl GridFunction {
     /// let grid = Grid::new_uniform_grid(0.0, 5.0, 6);
     /// let grid_func_1 = GridFunction::new_constant_grid_function(&grid, 2.0);
     /// let grid_func_2 = GridFunction::new_constant_grid_function(&grid, 3.0);
-    /// let grid_func_difference = grid_func_1.divide(&grid_func_2);
+    /// let grid_func_quotient = grid_func_1.divide(&grid_func_2);
     /// ```
     ///
-    /// ## Todo
-    /// Change handling of edge case where the two `GridFunctions` have
-    /// different `Grids`. Currently, the `Grid` from the current `GridFunction`
-    /// is used. This may not be the best way to handle this case.
-    ///
-    pub fn divide(self: &Self, grid_func: &GridFunction) -> Self {
-        let grid = self.grid.clone();
-
-        let function_values_1 = self.function_values.clone();
-        let mut function_values_2 = grid_func.function_values.clone();
-        let length_difference: i32 =
-            (function_values_1.len() as i32) - (function_values_2.len() as i32);
-
-        // If function_values_2 has fewer elements than function_values_1, adds
-        // zeroes to the end of function_values_2 until the two vectors are the
-        // same length.
-        if length_difference > 0 {
-            for _ in 0..length_difference {
-                function_values_2.push(0.0);
-            }
-        }
-
-        // Iterates over all the elements in function_values_1 and adds them to
-        // the elements in function_values_2.
-        let function_values: Vec<f64> = function_values_1
-            .iter()
-            .zip(function_values_2.iter())
-            .map(|(x, y)| x / y)
-            .collect();
-
-        GridFunction {
-            grid,
-            function_values,
+    pub fn divide(&self, grid_func: &GridFunction) -> Self {
+        match self.try_divide(grid_func, GridMismatchPolicy::Error) {
+            Ok(grid_func_quotient) => grid_func_quotient,
+            Err(error) => panic!("Division failed. {}", error),
         }
     }
 
@@ -241,7 +145,7 @@ impl GridFunction {
     /// let scaled_grid_func = grid_func.scale(2.0);
     /// ```
     ///
-    pub fn scale(self: &Self, scalar: f64) -> Self {
+    pub fn scale(&self, scalar: f64) -> Self {
         let grid = self.grid.clone();
         let function_values = self.function_values.clone();
 
@@ -257,10 +161,102 @@ impl GridFunction {
     }
 }
 
+// Fallible grid function arithmetic.
+impl GridFunction {
+    /// # Try grid function add
+    ///
+    /// ## Description
+    /// `try_add` is the fallible version of `add`. It returns an error if
+    /// either `GridFunction` is not valid. If the two `GridFunctions` do not
+    /// have compatible `Grids`, `policy` decides whether a `GridMismatch`
+    /// error is returned or `grid_func` is resampled onto the `Grid` of the
+    /// current `GridFunction`.
+    ///
+    /// ## Example use case
+    /// Suppose that we have two `GridFunctions` with different resolutions,
+    /// and we want to add them together on the `Grid` of the first. The code
+    /// below does this.
+    /// ```
+    /// let grid_1 = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 21);
+    /// let grid_func_1 = GridFunction::new_grid_function(&grid_1, f64::sin);
+    /// let grid_func_2 = GridFunction::new_grid_function(&grid_2, f64::cos);
+    /// let grid_func_sum =
+    ///     grid_func_1.try_add(&grid_func_2, GridMismatchPolicy::Resample)?;
+    /// ```
+    ///
+    pub fn try_add(
+        &self,
+        grid_func: &GridFunction,
+        policy: GridMismatchPolicy,
+    ) -> Result<Self, NumericalMethodsError> {
+        self.try_zip_with(grid_func, policy, |x, y| x + y)
+    }
+
+    /// # Try grid function subtract
+    ///
+    /// ## Description
+    /// `try_subtract` is the fallible version of `subtract`. The errors and
+    /// `policy` are the same as for `try_add`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_difference =
+    ///     grid_func_1.try_subtract(&grid_func_2, GridMismatchPolicy::Error)?;
+    /// ```
+    ///
+    pub fn try_subtract(
+        &self,
+        grid_func: &GridFunction,
+        policy: GridMismatchPolicy,
+    ) -> Result<Self, NumericalMethodsError> {
+        self.try_zip_with(grid_func, policy, |x, y| x - y)
+    }
+
+    /// # Try grid function multiply
+    ///
+    /// ## Description
+    /// `try_multiply` is the fallible version of `multiply`. The errors and
+    /// `policy` are the same as for `try_add`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_product =
+    ///     grid_func_1.try_multiply(&grid_func_2, GridMismatchPolicy::Error)?;
+    /// ```
+    ///
+    pub fn try_multiply(
+        &self,
+        grid_func: &GridFunction,
+        policy: GridMismatchPolicy,
+    ) -> Result<Self, NumericalMethodsError> {
+        self.try_zip_with(grid_func, policy, |x, y| x * y)
+    }
+
+    /// # Try grid function divide
+    ///
+    /// ## Description
+    /// `try_divide` is the fallible version of `divide`. The errors and
+    /// `policy` are the same as for `try_add`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_quotient =
+    ///     grid_func_1.try_divide(&grid_func_2, GridMismatchPolicy::Error)?;
+    /// ```
+    ///
+    pub fn try_divide(
+        &self,
+        grid_func: &GridFunction,
+        policy: GridMismatchPolicy,
+    ) -> Result<Self, NumericalMethodsError> {
+        self.try_zip_with(grid_func, policy, |x, y| x / y)
+    }
+}
+
 /// ## Todo
 /// Add tests for arithmetic involving very large and very small numbers.
 /// Quantitatively test floating point errors.
-/// Explicitly test what happens when different grids are used.
 /// Add tests with non-uniform grids.
 /// Add tests for edge cases when one or more of the function_values is
 /// f64::MAx or f64::MIN.
@@ -344,88 +340,172 @@ mod tests {
     }
 
     #[test]
-    fn test_arithmetic_operations_different_grids() {
-        // This code tests the edge case where the two GridFunctions have
-        // different Grids.
-        // Case 1 is when grid_func_1 has more elements than grid_func_2.
-        // Case 2 is when grid_func_2 has more elements than grid_func_1.
-
-        // Case 1: grid_func_1 has more elements than grid_func_2.
+    fn test_arithmetic_operations_different_lengths() {
+        // This code tests the edge case where the number of function values
+        // does not match the number of grid points.
+        // Case 1 is when grid_func_2 is missing a function value.
+        // Case 2 is when grid_func_1 is missing a function value.
         let grid = Grid::new_uniform_grid(0.0, 1.0, 6);
         let grid_func_1 = GridFunction::new_constant_grid_function(&grid, 4.0);
         let grid_func_2 = GridFunction::new_constant_grid_function(&grid, 2.0);
+        let expected_error = NumericalMethodsError::LengthMismatch {
+            expected: 6,
+            actual: 5,
+        };
+
+        // Case 1: grid_func_2 is missing a function value.
         let mut grid_func_2_short = grid_func_2.clone();
         grid_func_2_short.function_values.pop();
+        for policy in [GridMismatchPolicy::Error, GridMismatchPolicy::Resample]
+        {
+            assert_eq!(
+                grid_func_1.try_add(&grid_func_2_short, policy),
+                Err(expected_error.clone()),
+                "Case 1 addition failed."
+            );
+            assert_eq!(
+                grid_func_1.try_subtract(&grid_func_2_short, policy),
+                Err(expected_error.clone()),
+                "Case 1 subtraction failed."
+            );
+            assert_eq!(
+                grid_func_1.try_multiply(&grid_func_2_short, policy),
+                Err(expected_error.clone()),
+                "Case 1 multiplication failed."
+            );
+            assert_eq!(
+                grid_func_1.try_divide(&grid_func_2_short, policy),
+                Err(expected_error.clone()),
+                "Case 1 division failed."
+            );
+        }
 
-        // Test addition.
-        let grid_func_sum = grid_func_1.add(&grid_func_2_short);
+        // Case 2: grid_func_1 is missing a function value.
+        let mut grid_func_1_short = grid_func_1.clone();
+        grid_func_1_short.function_values.pop();
         assert_eq!(
-            grid_func_sum.function_values,
-            vec![6.0, 6.0, 6.0, 6.0, 6.0, 4.0],
-            "Case 1 addition failed."
+            grid_func_1_short.try_add(&grid_func_2, GridMismatchPolicy::Error),
+            Err(expected_error),
+            "Case 2 addition failed."
         );
+    }
 
-        // Test subtraction.
-        let grid_func_difference = grid_func_1.subtract(&grid_func_2_short);
-        assert_eq!(
-            grid_func_difference.function_values,
-            vec![2.0, 2.0, 2.0, 2.0, 2.0, 4.0],
-            "Case 1 subtraction failed."
-        );
+    #[test]
+    fn test_arithmetic_operations_different_grids() {
+        // This code tests the edge case where the two GridFunctions have
+        // different Grids.
+        let grid_1 = Grid::new_uniform_grid(0.0, 1.0, 6);
+        let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func_1 =
+            GridFunction::new_constant_grid_function(&grid_1, 4.0);
+        let grid_func_2 =
+            GridFunction::new_constant_grid_function(&grid_2, 2.0);
+        let policy = GridMismatchPolicy::Error;
 
-        // Test multiplication.
-        let grid_func_product = grid_func_1.multiply(&grid_func_2_short);
         assert_eq!(
-            grid_func_product.function_values,
-            vec![8.0, 8.0, 8.0, 8.0, 8.0, 0.0],
-            "Case 1 multiplication failed."
+            grid_func_1.try_add(&grid_func_2, policy),
+            Err(NumericalMethodsError::GridMismatch),
+            "Addition failed."
         );
-
-        // Test division.
-        let grid_func_quotient = grid_func_1.divide(&grid_func_2_short);
         assert_eq!(
-            grid_func_quotient.function_values,
-            vec![2.0, 2.0, 2.0, 2.0, 2.0, f64::INFINITY],
-            "Case 1 division failed."
+            grid_func_1.try_subtract(&grid_func_2, policy),
+            Err(NumericalMethodsError::GridMismatch),
+            "Subtraction failed."
         );
-
-        // Case 2: grid_func_2 has more elements than grid_func_1.
-        let grid = Grid::new_uniform_grid(0.0, 1.0, 6);
-        let grid_func_1 = GridFunction::new_constant_grid_function(&grid, 4.0);
-        let grid_func_2 = GridFunction::new_constant_grid_function(&grid, 2.0);
-        let mut grid_func_1_short = grid_func_1.clone();
-        grid_func_1_short.function_values.pop();
-
-        // Test addition.
-        let grid_func_sum = grid_func_1_short.add(&grid_func_2);
         assert_eq!(
-            grid_func_sum.function_values,
-            vec![6.0; 5],
-            "Case 2 addition failed."
+            grid_func_1.try_multiply(&grid_func_2, policy),
+            Err(NumericalMethodsError::GridMismatch),
+            "Multiplication failed."
         );
-
-        // Test subtraction.
-        let grid_func_difference = grid_func_1_short.subtract(&grid_func_2);
         assert_eq!(
-            grid_func_difference.function_values,
-            vec![2.0; 5],
-            "Case 2 subtraction failed."
+            grid_func_1.try_divide(&grid_func_2, policy),
+            Err(NumericalMethodsError::GridMismatch),
+            "Division failed."
         );
 
-        // Test multiplication.
-        let grid_func_product = grid_func_1_short.multiply(&grid_func_2);
+        // Grids with the same number of points but different spacing are
+        // also detected.
+        let grid_3 = Grid::new_chebyshev_grid(0.0, 1.0, 6);
+        let grid_func_3 =
+            GridFunction::new_constant_grid_function(&grid_3, 2.0);
         assert_eq!(
-            grid_func_product.function_values,
-            vec![8.0; 5],
-            "Case 2 multiplication failed."
+            grid_func_1.try_add(&grid_func_3, policy),
+            Err(NumericalMethodsError::GridMismatch),
+            "Addition with a non-uniform grid failed."
         );
 
-        // Test division.
-        let grid_func_quotient = grid_func_1_short.divide(&grid_func_2);
+        // Grids which agree to within the tolerance are compatible.
+        let mut grid_4 = grid_1.clone();
+        grid_4.grid_points[2] += 1e-14;
+        let grid_func_4 =
+            GridFunction::new_constant_grid_function(&grid_4, 2.0);
+        let grid_func_sum = grid_func_1.try_add(&grid_func_4, policy).unwrap();
+        assert_eq!(grid_func_sum.function_values, vec![6.0; 6]);
+        assert_eq!(grid_func_sum.grid, grid_1);
+    }
+
+    #[test]
+    #[should_panic(expected = "do not share the same grid")]
+    fn test_add_different_grids_panics() {
+        let grid_1 = Grid::new_uniform_grid(0.0, 1.0, 6);
+        let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func_1 =
+            GridFunction::new_constant_grid_function(&grid_1, 4.0);
+        let grid_func_2 =
+            GridFunction::new_constant_grid_function(&grid_2, 2.0);
+        grid_func_1.add(&grid_func_2);
+    }
+
+    #[test]
+    fn test_arithmetic_operations_resample() {
+        // grid_func_2 is resampled onto the grid of grid_func_1. Linear
+        // interpolation is exact for linear functions.
+        let grid_1 = Grid::new_chebyshev_grid(0.0, 1.0, 9);
+        let grid_2 = Grid::new_uniform_grid(-1.0, 2.0, 7);
+        let grid_func_1 = GridFunction::new_grid_function(&grid_1, |x| x * x);
+        let grid_func_2 =
+            GridFunction::new_grid_function(&grid_2, |x| 2.0 * x + 1.0);
+        let policy = GridMismatchPolicy::Resample;
+
+        let grid_func_sum = grid_func_1.try_add(&grid_func_2, policy).unwrap();
+        assert_eq!(grid_func_sum.grid, grid_1);
+        for (value, &x) in grid_func_sum
+            .function_values
+            .iter()
+            .zip(grid_1.grid_points.iter())
+        {
+            assert!(
+                (value - (x * x + 2.0 * x + 1.0)).abs() < 1e-12,
+                "Resampled addition failed."
+            );
+        }
+
+        let grid_func_quotient =
+            grid_func_1.try_divide(&grid_func_2, policy).unwrap();
+        for (value, &x) in grid_func_quotient
+            .function_values
+            .iter()
+            .zip(grid_1.grid_points.iter())
+        {
+            assert!(
+                (value - x * x / (2.0 * x + 1.0)).abs() < 1e-12,
+                "Resampled division failed."
+            );
+        }
+
+        // Resampling fails if the first grid extends beyond the second.
+        let grid_func_wide = GridFunction::new_grid_function(
+            &Grid::new_uniform_grid(0.0, 3.0, 7),
+            |x| x,
+        );
         assert_eq!(
-            grid_func_quotient.function_values,
-            vec![2.0; 5],
-            "Case 2 division failed."
+            grid_func_wide.try_add(&grid_func_2, policy),
+            Err(NumericalMethodsError::OutOfDomain {
+                point: 2.5,
+                lower: -1.0,
+                upper: 2.0
+            }),
+            "Resampling outside the domain failed."
         );
     }
 
@@ -481,77 +561,33 @@ mod tests {
         let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 6);
         let grid_func_2 =
             GridFunction::new_constant_grid_function(&grid_2, 2.0);
-
-        // Test addition.
-        let grid_func_sum = grid_func_1.add(&grid_func_2);
         assert_eq!(
-            grid_func_sum.function_values,
-            vec![],
+            grid_func_1.try_add(&grid_func_2, GridMismatchPolicy::Error),
+            Err(NumericalMethodsError::GridMismatch),
             "Case 2 addition failed."
         );
 
-        // Test subtraction.
-        let grid_func_difference = grid_func_1.subtract(&grid_func_2);
-        assert_eq!(
-            grid_func_difference.function_values,
-            vec![],
-            "Case 2 subtraction failed."
-        );
-
-        // Test multiplication.
-        let grid_func_product = grid_func_1.multiply(&grid_func_2);
+        // Resampling onto an empty grid gives an empty GridFunction.
+        let grid_func_sum = grid_func_1
+            .try_add(&grid_func_2, GridMismatchPolicy::Resample)
+            .unwrap();
         assert_eq!(
-            grid_func_product.function_values,
-            vec![],
-            "Case 2 multiplication failed."
-        );
-
-        // Test division.
-        let grid_func_quotient = grid_func_1.divide(&grid_func_2);
-        assert_eq!(
-            grid_func_quotient.function_values,
+            grid_func_sum.function_values,
             vec![],
-            "Case 2 division failed."
+            "Case 2 resampled addition failed."
         );
 
         // Case 3: grid_func_2 is empty and grid_func_1 isn't.
-        let grid_1 = Grid::new_uniform_grid(0.0, 1.0, 6);
-        let grid_func_1 =
-            GridFunction::new_constant_grid_function(&grid_1, 4.0);
-        let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 0);
-        let grid_func_2 =
-            GridFunction::new_constant_grid_function(&grid_2, 2.0);
-
-        // Test addition.
-        let grid_func_sum = grid_func_1.add(&grid_func_2);
         assert_eq!(
-            grid_func_sum.function_values,
-            vec![4.0; 6],
+            grid_func_2.try_add(&grid_func_1, GridMismatchPolicy::Error),
+            Err(NumericalMethodsError::GridMismatch),
             "Case 3 addition failed."
         );
-
-        // Test subtraction.
-        let grid_func_difference = grid_func_1.subtract(&grid_func_2);
-        assert_eq!(
-            grid_func_difference.function_values,
-            vec![4.0; 6],
-            "Case 3 subtraction failed."
-        );
-
-        // Test multiplication.
-        let grid_func_product = grid_func_1.multiply(&grid_func_2);
-        assert_eq!(
-            grid_func_product.function_values,
-            vec![0.0; 6],
-            "Case 3 multiplication failed."
-        );
-
-        // Test division.
-        let grid_func_quotient = grid_func_1.divide(&grid_func_2);
-        assert_eq!(
-            grid_func_quotient.function_values,
-            vec![f64::INFINITY; 6],
-            "Case 3 division failed."
+        assert!(
+            grid_func_2
+                .try_add(&grid_func_1, GridMismatchPolicy::Resample)
+                .is_err(),
+            "Case 3 resampled addition failed."
         );
     }
 
@@ -0,0 +1,496 @@
+use crate::error::NumericalMethodsError;
+use crate::grid::GRID_TOLERANCE;
+use crate::grid_function::GridFunction;
+use crate::grid_function_arithmetic::GridMismatchPolicy;
+use crate::grid_function_resampling::ResampleMethod;
+use crate::interpolation::InterpolationMethod;
+
+// Combinators.
+impl GridFunction {
+    /// # Map
+    ///
+    /// ## Description
+    /// `map` applies the real-valued function of a real variable `func` to
+    /// each function value of the current `GridFunction`, and returns the
+    /// result as a new `GridFunction` on the same `Grid`.
+    ///
+    /// ## Example use case
+    /// Suppose that we have a `GridFunction` `grid_func` representing y(x),
+    /// and we want the `GridFunction` representing y(x)^2 + 1. The code below
+    /// does this.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+    /// let grid_func_mapped = grid_func.map(|y| y * y + 1.0);
+    /// ```
+    ///
+    pub fn map<F>(&self, func: F) -> Self
+    where
+        F: Fn(f64) -> f64,
+    {
+        let function_values: Vec<f64> =
+            self.function_values.iter().map(|&y| func(y)).collect();
+
+        GridFunction {
+            grid: self.grid.clone(),
+            function_values,
+        }
+    }
+
+    /// # Map with x
+    ///
+    /// ## Description
+    /// `map_with_x` applies the function `func` to each grid point `x` and the
+    /// corresponding function value `y` of the current `GridFunction`, and
+    /// returns the result as a new `GridFunction` on the same `Grid`.
+    ///
+    /// ## Example use case
+    /// Suppose that we have a `GridFunction` `grid_func` representing y(x),
+    /// and we want the `GridFunction` representing x * y(x). The code below
+    /// does this.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+    /// let grid_func_mapped = grid_func.map_with_x(|x, y| x * y);
+    /// ```
+    ///
+    pub fn map_with_x<F>(&self, func: F) -> Self
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let function_values: Vec<f64> = self
+            .grid
+            .grid_points
+            .iter()
+            .zip(self.function_values.iter())
+            .map(|(&x, &y)| func(x, y))
+            .collect();
+
+        GridFunction {
+            grid: self.grid.clone(),
+            function_values,
+        }
+    }
+
+    /// # Zip with
+    ///
+    /// ## Description
+    /// `zip_with` applies the function `func` to each pair of function values
+    /// of the current `GridFunction` and `grid_func`, and returns the result
+    /// as a new `GridFunction` on the same `Grid`.
+    ///
+    /// The two `GridFunctions` must have compatible `Grids`, in the same way as
+    /// for the arithmetic methods. If they do not, `zip_with` panics. See
+    /// `try_zip_with` for a version which returns an error or resamples
+    /// `grid_func` instead.
+    ///
+    /// ## Example use case
+    /// Suppose that we have two `GridFunctions` `grid_func_1` and `grid_func_2`
+    /// and we want the `GridFunction` representing max(y_1(x), y_2(x)). The
+    /// code below does this.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func_1 = GridFunction::new_grid_function(&grid, f64::sin);
+    /// let grid_func_2 = GridFunction::new_grid_function(&grid, f64::cos);
+    /// let grid_func_max = grid_func_1.zip_with(&grid_func_2, f64::max);
+    /// ```
+    ///
+    pub fn zip_with<F>(&self, grid_func: &GridFunction, func: F) -> Self
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        match self.try_zip_with(grid_func, GridMismatchPolicy::Error, func) {
+            Ok(grid_func_zipped) => grid_func_zipped,
+            Err(error) => panic!("Zip with failed. {}", error),
+        }
+    }
+
+    /// # Try zip with
+    ///
+    /// ## Description
+    /// `try_zip_with` is the fallible version of `zip_with`. It returns an
+    /// error if either `GridFunction` is not valid. If the two `GridFunctions`
+    /// do not have compatible `Grids`, `policy` decides whether a
+    /// `GridMismatch` error is returned or `grid_func` is resampled onto the
+    /// `Grid` of the current `GridFunction`.
+    ///
+    /// The fallible arithmetic methods, such as `try_add`, are implemented
+    /// with `try_zip_with`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_max = grid_func_1.try_zip_with(
+    ///     &grid_func_2,
+    ///     GridMismatchPolicy::Resample,
+    ///     f64::max,
+    /// )?;
+    /// ```
+    ///
+    pub fn try_zip_with<F>(
+        &self,
+        grid_func: &GridFunction,
+        policy: GridMismatchPolicy,
+        func: F,
+    ) -> Result<Self, NumericalMethodsError>
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        self.validate()?;
+        grid_func.validate()?;
+
+        let resampled_grid_func;
+        let other_values =
+            if self.grid.is_compatible(&grid_func.grid, GRID_TOLERANCE) {
+                &grid_func.function_values
+            } else {
+                match policy {
+                    GridMismatchPolicy::Error => {
+                        return Err(NumericalMethodsError::GridMismatch);
+                    }
+                    GridMismatchPolicy::Resample => {
+                        resampled_grid_func = grid_func.try_resample(
+                            &self.grid,
+                            ResampleMethod::Interpolate(
+                                InterpolationMethod::PiecewiseLinear,
+                            ),
+                        )?;
+                        &resampled_grid_func.function_values
+                    }
+                }
+            };
+
+        // Iterates over all the elements in both sets of function values and
+        // applies func to each pair.
+        let function_values: Vec<f64> = self
+            .function_values
+            .iter()
+            .zip(other_values.iter())
+            .map(|(&x, &y)| func(x, y))
+            .collect();
+
+        Ok(GridFunction {
+            grid: self.grid.clone(),
+            function_values,
+        })
+    }
+}
+
+// Elementwise mathematical functions.
+//
+// Each method applies the `f64` method of the same name to every function
+// value, and returns the result as a new `GridFunction` on the same `Grid`.
+impl GridFunction {
+    /// # Sin
+    ///
+    /// ## Description
+    /// `sin` returns the `GridFunction` representing sin(y(x)).
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_sin = grid_func.sin();
+    /// ```
+    ///
+    pub fn sin(&self) -> Self {
+        self.map(f64::sin)
+    }
+
+    /// # Cos
+    ///
+    /// ## Description
+    /// `cos` returns the `GridFunction` representing cos(y(x)).
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_cos = grid_func.cos();
+    /// ```
+    ///
+    pub fn cos(&self) -> Self {
+        self.map(f64::cos)
+    }
+
+    /// # Tan
+    ///
+    /// ## Description
+    /// `tan` returns the `GridFunction` representing tan(y(x)). Function
+    /// values near an odd multiple of PI / 2 give very large results.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_tan = grid_func.tan();
+    /// ```
+    ///
+    pub fn tan(&self) -> Self {
+        self.map(f64::tan)
+    }
+
+    /// # Sinh
+    ///
+    /// ## Description
+    /// `sinh` returns the `GridFunction` representing sinh(y(x)).
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_sinh = grid_func.sinh();
+    /// ```
+    ///
+    pub fn sinh(&self) -> Self {
+        self.map(f64::sinh)
+    }
+
+    /// # Cosh
+    ///
+    /// ## Description
+    /// `cosh` returns the `GridFunction` representing cosh(y(x)).
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_cosh = grid_func.cosh();
+    /// ```
+    ///
+    pub fn cosh(&self) -> Self {
+        self.map(f64::cosh)
+    }
+
+    /// # Tanh
+    ///
+    /// ## Description
+    /// `tanh` returns the `GridFunction` representing tanh(y(x)).
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_tanh = grid_func.tanh();
+    /// ```
+    ///
+    pub fn tanh(&self) -> Self {
+        self.map(f64::tanh)
+    }
+
+    /// # Exp
+    ///
+    /// ## Description
+    /// `exp` returns the `GridFunction` representing exp(y(x)). Function
+    /// values above about 709 give infinity.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_exp = grid_func.exp();
+    /// ```
+    ///
+    pub fn exp(&self) -> Self {
+        self.map(f64::exp)
+    }
+
+    /// # Ln
+    ///
+    /// ## Description
+    /// `ln` returns the `GridFunction` representing the natural logarithm
+    /// ln(y(x)). Negative function values give NaN, and zero function values
+    /// give negative infinity, as for `f64::ln`. No error is returned, and
+    /// `validate` does not check for NaN.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_ln = grid_func.ln();
+    /// ```
+    ///
+    pub fn ln(&self) -> Self {
+        self.map(f64::ln)
+    }
+
+    /// # Sqrt
+    ///
+    /// ## Description
+    /// `sqrt` returns the `GridFunction` representing sqrt(y(x)). Negative
+    /// function values give NaN, as for `f64::sqrt`. No error is returned,
+    /// and `validate` does not check for NaN.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_sqrt = grid_func.sqrt();
+    /// ```
+    ///
+    pub fn sqrt(&self) -> Self {
+        self.map(f64::sqrt)
+    }
+
+    /// # Abs
+    ///
+    /// ## Description
+    /// `abs` returns the `GridFunction` representing |y(x)|.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_abs = grid_func.abs();
+    /// ```
+    ///
+    pub fn abs(&self) -> Self {
+        self.map(f64::abs)
+    }
+
+    /// # Powi
+    ///
+    /// ## Description
+    /// `powi` returns the `GridFunction` representing y(x)^`exponent` for an
+    /// integer `exponent`. It is defined for negative function values.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_cubed = grid_func.powi(3);
+    /// ```
+    ///
+    pub fn powi(&self, exponent: i32) -> Self {
+        self.map(|y| y.powi(exponent))
+    }
+
+    /// # Powf
+    ///
+    /// ## Description
+    /// `powf` returns the `GridFunction` representing y(x)^`exponent` for a
+    /// real `exponent`. Negative function values give NaN unless `exponent`
+    /// is a whole number, as for `f64::powf`. No error is returned, and
+    /// `validate` does not check for NaN. Use `powi` for whole exponents.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_func_power = grid_func.powf(1.5);
+    /// ```
+    ///
+    pub fn powf(&self, exponent: f64) -> Self {
+        self.map(|y| y.powf(exponent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn test_map() {
+        let grid = Grid::new_chebyshev_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x);
+
+        let grid_func_mapped = grid_func.map(|y| y * y + 1.0);
+        assert_eq!(
+            grid_func_mapped,
+            GridFunction::new_grid_function(&grid, |x| x * x + 1.0),
+            "map failed."
+        );
+
+        let grid_func_mapped = grid_func.map_with_x(|x, y| x + 2.0 * y);
+        assert_eq!(
+            grid_func_mapped,
+            GridFunction::new_grid_function(&grid, |x| 3.0 * x),
+            "map_with_x failed."
+        );
+    }
+
+    #[test]
+    fn test_zip_with() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func_1 = GridFunction::new_grid_function(&grid, |x| x);
+        let grid_func_2 = GridFunction::new_grid_function(&grid, |x| 1.0 - x);
+
+        let grid_func_max = grid_func_1.zip_with(&grid_func_2, f64::max);
+        assert_eq!(
+            grid_func_max,
+            GridFunction::new_grid_function(&grid, |x| x.max(1.0 - x)),
+            "zip_with failed."
+        );
+
+        // The arithmetic methods agree with zip_with.
+        assert_eq!(
+            grid_func_1.zip_with(&grid_func_2, |x, y| x * y),
+            grid_func_1.multiply(&grid_func_2)
+        );
+    }
+
+    #[test]
+    fn test_try_zip_with_compatibility() {
+        let grid_1 = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 21);
+        let grid_func_1 = GridFunction::new_grid_function(&grid_1, |x| x);
+        let grid_func_2 = GridFunction::new_grid_function(&grid_2, |x| x);
+
+        assert_eq!(
+            grid_func_1.try_zip_with(
+                &grid_func_2,
+                GridMismatchPolicy::Error,
+                f64::max
+            ),
+            Err(NumericalMethodsError::GridMismatch)
+        );
+
+        let grid_func_difference = grid_func_1
+            .try_zip_with(&grid_func_2, GridMismatchPolicy::Resample, |x, y| {
+                x - y
+            })
+            .unwrap();
+        assert!(grid_func_difference
+            .function_values
+            .iter()
+            .all(|y| y.abs() < 1e-12));
+    }
+
+    #[test]
+    #[should_panic(expected = "do not share the same grid")]
+    fn test_zip_with_different_grids_panics() {
+        let grid_func_1 = GridFunction::new_constant_grid_function(
+            &Grid::new_uniform_grid(0.0, 1.0, 6),
+            1.0,
+        );
+        let grid_func_2 = GridFunction::new_constant_grid_function(
+            &Grid::new_chebyshev_grid(0.0, 1.0, 6),
+            1.0,
+        );
+        grid_func_1.zip_with(&grid_func_2, f64::max);
+    }
+
+    #[test]
+    fn test_elementwise_functions() {
+        let grid = Grid::new_uniform_grid(0.1, 1.0, 10);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x);
+
+        type ScalarFunction = fn(f64) -> f64;
+        let cases: Vec<(GridFunction, ScalarFunction)> = vec![
+            (grid_func.sin(), f64::sin),
+            (grid_func.cos(), f64::cos),
+            (grid_func.tan(), f64::tan),
+            (grid_func.sinh(), f64::sinh),
+            (grid_func.cosh(), f64::cosh),
+            (grid_func.tanh(), f64::tanh),
+            (grid_func.exp(), f64::exp),
+            (grid_func.ln(), f64::ln),
+            (grid_func.sqrt(), f64::sqrt),
+            (grid_func.abs(), f64::abs),
+            (grid_func.powi(3), |x| x.powi(3)),
+            (grid_func.powf(1.5), |x| x.powf(1.5)),
+        ];
+
+        for (grid_func_result, func) in cases {
+            assert_eq!(
+                grid_func_result,
+                GridFunction::new_grid_function(&grid, func),
+                "Elementwise function failed."
+            );
+        }
+    }
+
+    #[test]
+    fn test_nonlinear_residual() {
+        // The residual of y'' + sin(y) = 0 can be written with the elementwise
+        // functions and operators.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let y = GridFunction::new_grid_function(&grid, |x| x * x);
+        let residual = y
+            .central_difference_derivative()
+            .central_difference_derivative()
+            + y.sin();
+
+        assert_eq!(residual.grid, grid);
+        assert!(
+            (residual.function_values[5] - (2.0 + 0.25_f64.sin())).abs()
+                < 1e-10
+        );
+    }
+}
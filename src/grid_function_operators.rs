@@ -0,0 +1,321 @@
+use crate::grid_function::GridFunction;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+// Operator overloading for GridFunction.
+//
+// The binary operators between two `GridFunctions` call the arithmetic
+// methods in `grid_function_arithmetic.rs`, so they panic if the two
+// `GridFunctions` do not have compatible `Grids`. Use the `try_` arithmetic
+// methods to handle this case without panicking.
+//
+// The binary operators between a `GridFunction` and an `f64` apply the
+// operation to each function value.
+//
+// Each operator is implemented for both owned and borrowed `GridFunctions`,
+// so that expressions such as `&y_xx + &y` and `2.0 * y_x - y` can be written
+// without explicit clones.
+//
+// Note that when `std::ops::Add` is in scope, `grid_func_1.add(&grid_func_2)`
+// resolves to the trait method, which takes `grid_func_1` by value. Write
+// `GridFunction::add(&grid_func_1, &grid_func_2)` to call the arithmetic
+// method explicitly.
+
+/// # Implement grid function operator
+///
+/// ## Description
+/// `impl_grid_function_operator` implements the binary operator `$trait` for
+/// every combination of owned and borrowed `GridFunction` operands, by calling
+/// the `GridFunction` method `$grid_func_method`. It also implements the
+/// compound assignment operator `$assign_trait` for owned and borrowed
+/// right-hand sides.
+///
+macro_rules! impl_grid_function_operator {
+    (
+        $trait:ident,
+        $method:ident,
+        $assign_trait:ident,
+        $assign_method:ident,
+        $grid_func_method:ident
+    ) => {
+        impl $trait<&GridFunction> for &GridFunction {
+            type Output = GridFunction;
+
+            fn $method(self, grid_func: &GridFunction) -> GridFunction {
+                GridFunction::$grid_func_method(self, grid_func)
+            }
+        }
+
+        impl $trait<GridFunction> for &GridFunction {
+            type Output = GridFunction;
+
+            fn $method(self, grid_func: GridFunction) -> GridFunction {
+                GridFunction::$grid_func_method(self, &grid_func)
+            }
+        }
+
+        impl $trait<&GridFunction> for GridFunction {
+            type Output = GridFunction;
+
+            fn $method(self, grid_func: &GridFunction) -> GridFunction {
+                GridFunction::$grid_func_method(&self, grid_func)
+            }
+        }
+
+        impl $trait<GridFunction> for GridFunction {
+            type Output = GridFunction;
+
+            fn $method(self, grid_func: GridFunction) -> GridFunction {
+                GridFunction::$grid_func_method(&self, &grid_func)
+            }
+        }
+
+        impl $assign_trait<&GridFunction> for GridFunction {
+            fn $assign_method(&mut self, grid_func: &GridFunction) {
+                *self = GridFunction::$grid_func_method(self, grid_func);
+            }
+        }
+
+        impl $assign_trait<GridFunction> for GridFunction {
+            fn $assign_method(&mut self, grid_func: GridFunction) {
+                *self = GridFunction::$grid_func_method(self, &grid_func);
+            }
+        }
+    };
+}
+
+/// # Implement scalar operator
+///
+/// ## Description
+/// `impl_scalar_operator` implements the binary operator `$trait` between
+/// owned and borrowed `GridFunctions` and `f64`, with the `f64` on either
+/// side. `$operation` is applied to each function value `x` and the scalar
+/// `s` when the `GridFunction` is on the left, and `$reversed_operation` when
+/// the `GridFunction` is on the right. It also implements the compound
+/// assignment operator `$assign_trait` with an `f64` right-hand side.
+///
+macro_rules! impl_scalar_operator {
+    (
+        $trait:ident,
+        $method:ident,
+        $assign_trait:ident,
+        $assign_method:ident,
+        $operation:expr,
+        $reversed_operation:expr
+    ) => {
+        impl $trait<f64> for &GridFunction {
+            type Output = GridFunction;
+
+            fn $method(self, scalar: f64) -> GridFunction {
+                self.map(|x| ($operation)(x, scalar))
+            }
+        }
+
+        impl $trait<f64> for GridFunction {
+            type Output = GridFunction;
+
+            fn $method(self, scalar: f64) -> GridFunction {
+                self.map(|x| ($operation)(x, scalar))
+            }
+        }
+
+        impl $trait<&GridFunction> for f64 {
+            type Output = GridFunction;
+
+            fn $method(self, grid_func: &GridFunction) -> GridFunction {
+                grid_func.map(|x| ($reversed_operation)(x, self))
+            }
+        }
+
+        impl $trait<GridFunction> for f64 {
+            type Output = GridFunction;
+
+            fn $method(self, grid_func: GridFunction) -> GridFunction {
+                grid_func.map(|x| ($reversed_operation)(x, self))
+            }
+        }
+
+        impl $assign_trait<f64> for GridFunction {
+            fn $assign_method(&mut self, scalar: f64) {
+                let operation = $operation;
+                for x in self.function_values.iter_mut() {
+                    *x = operation(*x, scalar);
+                }
+            }
+        }
+    };
+}
+
+impl_grid_function_operator!(Add, add, AddAssign, add_assign, add);
+impl_grid_function_operator!(Sub, sub, SubAssign, sub_assign, subtract);
+impl_grid_function_operator!(Mul, mul, MulAssign, mul_assign, multiply);
+impl_grid_function_operator!(Div, div, DivAssign, div_assign, divide);
+
+impl_scalar_operator!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    |x: f64, s: f64| x + s,
+    |x: f64, s: f64| s + x
+);
+impl_scalar_operator!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    |x: f64, s: f64| x - s,
+    |x: f64, s: f64| s - x
+);
+impl_scalar_operator!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    |x: f64, s: f64| x * s,
+    |x: f64, s: f64| s * x
+);
+impl_scalar_operator!(
+    Div,
+    div,
+    DivAssign,
+    div_assign,
+    |x: f64, s: f64| x / s,
+    |x: f64, s: f64| s / x
+);
+
+impl Neg for &GridFunction {
+    type Output = GridFunction;
+
+    fn neg(self) -> GridFunction {
+        self.scale(-1.0)
+    }
+}
+
+impl Neg for GridFunction {
+    type Output = GridFunction;
+
+    fn neg(self) -> GridFunction {
+        self.scale(-1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn grid_funcs() -> (GridFunction, GridFunction) {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 6);
+        (
+            GridFunction::new_constant_grid_function(&grid, 4.0),
+            GridFunction::new_constant_grid_function(&grid, 2.0),
+        )
+    }
+
+    #[test]
+    fn test_binary_operators() {
+        let (grid_func_1, grid_func_2) = grid_funcs();
+
+        // Each combination of owned and borrowed operands gives the same
+        // result as the arithmetic methods.
+        let sum = GridFunction::add(&grid_func_1, &grid_func_2);
+        assert_eq!(&grid_func_1 + &grid_func_2, sum);
+        assert_eq!(&grid_func_1 + grid_func_2.clone(), sum);
+        assert_eq!(grid_func_1.clone() + &grid_func_2, sum);
+        assert_eq!(grid_func_1.clone() + grid_func_2.clone(), sum);
+
+        let difference = grid_func_1.subtract(&grid_func_2);
+        assert_eq!(&grid_func_1 - &grid_func_2, difference);
+        assert_eq!(grid_func_1.clone() - grid_func_2.clone(), difference);
+
+        let product = grid_func_1.multiply(&grid_func_2);
+        assert_eq!(&grid_func_1 * &grid_func_2, product);
+        assert_eq!(grid_func_1.clone() * grid_func_2.clone(), product);
+
+        let quotient = grid_func_1.divide(&grid_func_2);
+        assert_eq!(&grid_func_1 / &grid_func_2, quotient);
+        assert_eq!(grid_func_1.clone() / grid_func_2.clone(), quotient);
+
+        assert_eq!((-&grid_func_1).function_values, vec![-4.0; 6]);
+        assert_eq!((-grid_func_1).function_values, vec![-4.0; 6]);
+    }
+
+    #[test]
+    fn test_scalar_operators() {
+        let (grid_func, _) = grid_funcs();
+
+        assert_eq!((&grid_func + 1.0).function_values, vec![5.0; 6]);
+        assert_eq!((1.0 + &grid_func).function_values, vec![5.0; 6]);
+        assert_eq!((&grid_func - 1.0).function_values, vec![3.0; 6]);
+        assert_eq!((1.0 - &grid_func).function_values, vec![-3.0; 6]);
+        assert_eq!((&grid_func * 0.5).function_values, vec![2.0; 6]);
+        assert_eq!((0.5 * &grid_func).function_values, vec![2.0; 6]);
+        assert_eq!((&grid_func / 2.0).function_values, vec![2.0; 6]);
+        assert_eq!((2.0 / &grid_func).function_values, vec![0.5; 6]);
+
+        assert_eq!(2.0 * grid_func.clone(), grid_func.scale(2.0));
+        assert_eq!(grid_func.clone() * 2.0, grid_func.scale(2.0));
+        assert_eq!(
+            (grid_func.clone() / 0.0).function_values,
+            vec![f64::INFINITY; 6]
+        );
+    }
+
+    #[test]
+    fn test_assignment_operators() {
+        let (grid_func_1, grid_func_2) = grid_funcs();
+
+        let mut grid_func = grid_func_1.clone();
+        grid_func += &grid_func_2;
+        assert_eq!(grid_func.function_values, vec![6.0; 6]);
+        grid_func -= grid_func_2.clone();
+        assert_eq!(grid_func.function_values, vec![4.0; 6]);
+        grid_func *= &grid_func_2;
+        assert_eq!(grid_func.function_values, vec![8.0; 6]);
+        grid_func /= &grid_func_2;
+        assert_eq!(grid_func.function_values, vec![4.0; 6]);
+
+        grid_func += 1.0;
+        assert_eq!(grid_func.function_values, vec![5.0; 6]);
+        grid_func -= 2.0;
+        assert_eq!(grid_func.function_values, vec![3.0; 6]);
+        grid_func *= 4.0;
+        assert_eq!(grid_func.function_values, vec![12.0; 6]);
+        grid_func /= 3.0;
+        assert_eq!(grid_func.function_values, vec![4.0; 6]);
+        assert_eq!(grid_func.grid, grid_func_1.grid);
+    }
+
+    #[test]
+    fn test_de_residual_expression() {
+        // The residual of y'' + y = 0 for y = sin(x), written with operators,
+        // matches the residual written with the arithmetic methods.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let y = GridFunction::new_grid_function(&grid, f64::sin);
+        let y_xx = y
+            .central_difference_derivative()
+            .central_difference_derivative();
+
+        assert_eq!(&y_xx + &y, GridFunction::add(&y_xx, &y));
+        assert_eq!(
+            2.0 * &y_xx - &y / 3.0,
+            y_xx.scale(2.0).subtract(&y.scale(1.0 / 3.0))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "do not share the same grid")]
+    fn test_operator_different_grids_panics() {
+        let grid_func_1 = GridFunction::new_constant_grid_function(
+            &Grid::new_uniform_grid(0.0, 1.0, 6),
+            1.0,
+        );
+        let grid_func_2 = GridFunction::new_constant_grid_function(
+            &Grid::new_uniform_grid(0.0, 1.0, 7),
+            1.0,
+        );
+        let _ = grid_func_1 * grid_func_2;
+    }
+}
@@ -0,0 +1,427 @@
+use crate::error::NumericalMethodsError;
+use crate::grid::{Grid, GRID_TOLERANCE};
+use crate::grid_function::GridFunction;
+use crate::interpolation::{locate_cell, Interpolant, InterpolationMethod};
+
+/// # Resample method
+///
+/// ## Description
+/// `ResampleMethod` specifies how `GridFunction::resample` transfers a
+/// `GridFunction` onto a different `Grid`.
+///
+/// - `Interpolate` samples the interpolant given by the `InterpolationMethod`
+///   at each new grid point. This is the natural choice for moving onto a
+///   finer `Grid` (prolongation).
+/// - `FullWeighting` replaces the value at each interior new grid point with
+///   a weighted average of the old function values near it, using the hat
+///   function of the new `Grid` as the weights. The end points are sampled
+///   with piecewise linear interpolation. On nested uniform grids with twice
+///   the spacing this is the familiar (1/4, 1/2, 1/4) stencil.
+/// - `IntegralConserving` replaces the value at each new grid point with the
+///   mean of the piecewise linear interpolant over its control volume, which
+///   runs between the midpoints of its neighbouring grid cells. The integral
+///   of the `GridFunction` over the new `Grid` is preserved.
+///
+/// `FullWeighting` and `IntegralConserving` are intended for moving onto a
+/// coarser `Grid` (restriction), where they smooth out oscillations that
+/// cannot be represented on the coarser `Grid`.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResampleMethod {
+    Interpolate(InterpolationMethod),
+    FullWeighting,
+    IntegralConserving,
+}
+
+impl GridFunction {
+    /// # Resample
+    ///
+    /// ## Description
+    /// `resample` transfers the current `GridFunction` onto `grid` using
+    /// `method`, and returns the result as a new `GridFunction` on `grid`.
+    ///
+    /// `grid` must lie within the `Grid` of the current `GridFunction`, up to
+    /// `GRID_TOLERANCE` times its length. If it does not, `resample` panics.
+    /// See `try_resample` for a version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// Suppose that we have solved a BVP on a 21-point grid and want to use
+    /// the solution as the initial guess on a 41-point grid. The code below
+    /// does this.
+    /// ```
+    /// let grid_fine = Grid::new_uniform_grid(0.0, PI, 41);
+    /// let initial_guess = solution.resample(
+    ///     &grid_fine,
+    ///     ResampleMethod::Interpolate(InterpolationMethod::PiecewiseLinear),
+    /// );
+    /// ```
+    ///
+    pub fn resample(&self, grid: &Grid, method: ResampleMethod) -> Self {
+        match self.try_resample(grid, method) {
+            Ok(grid_func) => grid_func,
+            Err(error) => panic!("Resampling failed. {}", error),
+        }
+    }
+
+    /// # Try resample
+    ///
+    /// ## Description
+    /// `try_resample` is the fallible version of `resample`. It returns an
+    /// `OutOfDomain` error if `grid` extends beyond the `Grid` of the current
+    /// `GridFunction`, and an error if either is not valid or the current
+    /// `GridFunction` has too few grid points for `method`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid_coarse = Grid::new_uniform_grid(0.0, PI, 11);
+    /// let restricted =
+    ///     solution.try_resample(&grid_coarse, ResampleMethod::FullWeighting)?;
+    /// ```
+    ///
+    pub fn try_resample(
+        &self,
+        grid: &Grid,
+        method: ResampleMethod,
+    ) -> Result<Self, NumericalMethodsError> {
+        self.validate_with_min_points(2)?;
+        grid.validate()?;
+
+        // Reports the first new grid point outside the old Grid.
+        let new_points = &grid.grid_points;
+        for &x in new_points {
+            locate_cell(&self.grid, x)?;
+        }
+
+        let function_values = match method {
+            ResampleMethod::Interpolate(interpolation_method) => {
+                self.try_evaluate_many(new_points, interpolation_method)?
+            }
+            // A single grid point has no neighbours to average over.
+            _ if new_points.len() < 2 => self.try_evaluate_many(
+                new_points,
+                InterpolationMethod::PiecewiseLinear,
+            )?,
+            ResampleMethod::FullWeighting => full_weighting(self, new_points)?,
+            ResampleMethod::IntegralConserving => {
+                integral_conserving(self, new_points)?
+            }
+        };
+
+        Ok(GridFunction {
+            grid: grid.clone(),
+            function_values,
+        })
+    }
+}
+
+/// # Full weighting
+///
+/// ## Description
+/// `full_weighting` returns the full weighting of `grid_func` at each of
+/// `new_points`, which must contain at least 2 points lying within the `Grid`
+/// of `grid_func`.
+///
+/// If a new grid point is not also an old grid point, the piecewise linear
+/// interpolant at the new grid point is included in the average with weight
+/// 1, so that the average is always defined.
+///
+fn full_weighting(
+    grid_func: &GridFunction,
+    new_points: &[f64],
+) -> Result<Vec<f64>, NumericalMethodsError> {
+    let old_points = &grid_func.grid.grid_points;
+    let old_values = &grid_func.function_values;
+    let num_new_points = new_points.len();
+    let tolerance = GRID_TOLERANCE
+        * (old_points[old_points.len() - 1] - old_points[0]).max(1.0);
+
+    let interpolant =
+        Interpolant::try_new(grid_func, InterpolationMethod::PiecewiseLinear)?;
+
+    let mut function_values = Vec::with_capacity(num_new_points);
+    function_values.push(interpolant.try_evaluate(new_points[0])?);
+
+    for j in 1..(num_new_points - 1) {
+        let (left, centre, right) =
+            (new_points[j - 1], new_points[j], new_points[j + 1]);
+
+        // Finds the old grid points strictly inside the support of the hat
+        // function by binary search.
+        let start = old_points.partition_point(|&x| x <= left);
+        let end = old_points.partition_point(|&x| x < right);
+
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        let mut contains_centre = false;
+        for i in start..end {
+            let x = old_points[i];
+            let weight = if x <= centre {
+                (x - left) / (centre - left)
+            } else {
+                (right - x) / (right - centre)
+            };
+            weighted_sum += weight * old_values[i];
+            total_weight += weight;
+            contains_centre |= (x - centre).abs() <= tolerance;
+        }
+
+        if !contains_centre {
+            weighted_sum += interpolant.try_evaluate(centre)?;
+            total_weight += 1.0;
+        }
+
+        function_values.push(weighted_sum / total_weight);
+    }
+
+    function_values
+        .push(interpolant.try_evaluate(new_points[num_new_points - 1])?);
+
+    Ok(function_values)
+}
+
+/// # Integral conserving
+///
+/// ## Description
+/// `integral_conserving` returns the mean of the piecewise linear interpolant
+/// of `grid_func` over the control volume of each of `new_points`, which must
+/// contain at least 2 points lying within the `Grid` of `grid_func`.
+///
+fn integral_conserving(
+    grid_func: &GridFunction,
+    new_points: &[f64],
+) -> Result<Vec<f64>, NumericalMethodsError> {
+    let old_points = &grid_func.grid.grid_points;
+    let old_values = &grid_func.function_values;
+
+    // cumulative_integrals[i] is the integral of the piecewise linear
+    // interpolant from the first old grid point to the ith old grid point.
+    let mut cumulative_integrals = vec![0.0; old_points.len()];
+    for i in 1..old_points.len() {
+        cumulative_integrals[i] = cumulative_integrals[i - 1]
+            + 0.5
+                * (old_values[i - 1] + old_values[i])
+                * (old_points[i] - old_points[i - 1]);
+    }
+
+    // Integrates the piecewise linear interpolant from the first old grid
+    // point to x.
+    let interpolant =
+        Interpolant::try_new(grid_func, InterpolationMethod::PiecewiseLinear)?;
+    let antiderivative = |x: f64| -> Result<f64, NumericalMethodsError> {
+        let (i, x) = locate_cell(&grid_func.grid, x)?;
+        let value_at_x = interpolant.try_evaluate(x)?;
+        Ok(cumulative_integrals[i]
+            + 0.5 * (old_values[i] + value_at_x) * (x - old_points[i]))
+    };
+
+    // The control volumes are bounded by the ends of the new Grid and the
+    // midpoints of the new grid cells.
+    let num_new_points = new_points.len();
+    let mut boundaries = Vec::with_capacity(num_new_points + 1);
+    boundaries.push(new_points[0]);
+    boundaries
+        .extend(new_points.windows(2).map(|pair| 0.5 * (pair[0] + pair[1])));
+    boundaries.push(new_points[num_new_points - 1]);
+
+    let integrals = boundaries
+        .iter()
+        .map(|&x| antiderivative(x))
+        .collect::<Result<Vec<f64>, NumericalMethodsError>>()?;
+
+    Ok(integrals
+        .windows(2)
+        .zip(boundaries.windows(2))
+        .map(|(integral, boundary)| {
+            (integral[1] - integral[0]) / (boundary[1] - boundary[0])
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic_spline::SplineEndCondition;
+    use crate::grid::test_grids;
+
+    #[test]
+    fn test_interpolate_matches_evaluate_many() {
+        let grid_coarse = Grid::new_chebyshev_grid(0.0, 1.0, 21);
+        let grid_fine = Grid::new_uniform_grid(0.0, 1.0, 41);
+        let grid_func = GridFunction::new_grid_function(&grid_coarse, f64::exp);
+
+        for interpolation_method in [
+            InterpolationMethod::PiecewiseLinear,
+            InterpolationMethod::CubicSpline(SplineEndCondition::Natural),
+        ] {
+            let resampled = grid_func.resample(
+                &grid_fine,
+                ResampleMethod::Interpolate(interpolation_method),
+            );
+            assert_eq!(resampled.grid, grid_fine);
+            assert_eq!(
+                resampled.function_values,
+                grid_func.evaluate_many(
+                    &grid_fine.grid_points,
+                    interpolation_method
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_full_weighting_stencil() {
+        // On nested uniform grids, full weighting is the (1/4, 1/2, 1/4)
+        // stencil in the interior and injection at the ends.
+        let grid_fine = Grid::new_uniform_grid(0.0, 1.0, 9);
+        let grid_coarse = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_func = GridFunction::try_new_grid_function_from_values(
+            &grid_fine,
+            vec![1.0, 2.0, 0.0, 4.0, 8.0, 0.0, 4.0, 2.0, 3.0],
+        )
+        .unwrap();
+
+        let restricted =
+            grid_func.resample(&grid_coarse, ResampleMethod::FullWeighting);
+        let expected = [1.0, 1.5, 5.0, 2.5, 3.0];
+        for (value, expected_value) in
+            restricted.function_values.iter().zip(expected)
+        {
+            assert!((value - expected_value).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_restriction_of_smooth_functions() {
+        // Restricting a smooth function should be close to sampling it.
+        for (name, grid) in test_grids(0.0, 1.0, 81) {
+            let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+            let grid_coarse = Grid::new_uniform_grid(0.0, 1.0, 11);
+            for method in [
+                ResampleMethod::FullWeighting,
+                ResampleMethod::IntegralConserving,
+            ] {
+                let restricted = grid_func.resample(&grid_coarse, method);
+
+                // The control volumes of the end points are half grid cells,
+                // so their means are offset from the end values by O(h).
+                for (x, value) in grid_coarse
+                    .grid_points
+                    .iter()
+                    .zip(restricted.function_values)
+                {
+                    assert!(
+                        (value - x.sin()).abs() < 0.05,
+                        "{:?} failed on the {} grid.",
+                        method,
+                        name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_integral_conserving_preserves_integral() {
+        let trapezoidal_rule = |grid_func: &GridFunction| -> f64 {
+            let points = &grid_func.grid.grid_points;
+            let values = &grid_func.function_values;
+            (1..points.len())
+                .map(|i| {
+                    0.5 * (values[i - 1] + values[i])
+                        * (points[i] - points[i - 1])
+                })
+                .sum()
+        };
+
+        for (name, grid) in test_grids(-1.0, 2.0, 37) {
+            let grid_func =
+                GridFunction::new_grid_function(&grid, |x| (3.0 * x).cos());
+            let grid_coarse = Grid::new_chebyshev_grid(-1.0, 2.0, 10);
+            let restricted = grid_func
+                .resample(&grid_coarse, ResampleMethod::IntegralConserving);
+
+            // Each value is the mean over its control volume, so the control
+            // volume weighted sum is the integral of the old GridFunction.
+            let points = &grid_coarse.grid_points;
+            let num_points = points.len();
+            let integral: f64 = (0..num_points)
+                .map(|j| {
+                    let left = if j == 0 {
+                        points[0]
+                    } else {
+                        0.5 * (points[j - 1] + points[j])
+                    };
+                    let right = if j == num_points - 1 {
+                        points[j]
+                    } else {
+                        0.5 * (points[j] + points[j + 1])
+                    };
+                    restricted.function_values[j] * (right - left)
+                })
+                .sum();
+
+            assert!(
+                (integral - trapezoidal_rule(&grid_func)).abs() < 1e-12,
+                "Integral not conserved on the {} grid.",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_resample_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+
+        for method in [
+            ResampleMethod::Interpolate(InterpolationMethod::PiecewiseLinear),
+            ResampleMethod::FullWeighting,
+            ResampleMethod::IntegralConserving,
+        ] {
+            assert_eq!(
+                grid_func
+                    .try_resample(&Grid::new_uniform_grid(0.5, 1.5, 5), method),
+                Err(NumericalMethodsError::OutOfDomain {
+                    point: 1.25,
+                    lower: 0.0,
+                    upper: 1.0
+                })
+            );
+
+            // Empty and single point Grids can still be resampled onto.
+            let resampled = grid_func
+                .try_resample(&Grid::new_uniform_grid(0.0, 1.0, 0), method)
+                .unwrap();
+            assert!(resampled.function_values.is_empty());
+            let resampled = grid_func
+                .try_resample(&Grid::new_grid_from_points(vec![0.5]), method)
+                .unwrap();
+            assert!(
+                (resampled.function_values[0] - 0.5_f64.sin()).abs() < 1e-2
+            );
+        }
+
+        let grid_func_single = GridFunction::new_constant_grid_function(
+            &Grid::new_uniform_grid(0.0, 1.0, 1),
+            1.0,
+        );
+        assert_eq!(
+            grid_func_single.try_resample(&grid, ResampleMethod::FullWeighting),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Resampling failed.")]
+    fn test_resample_panics() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        grid_func.resample(
+            &Grid::new_uniform_grid(-1.0, 1.0, 11),
+            ResampleMethod::FullWeighting,
+        );
+    }
+}
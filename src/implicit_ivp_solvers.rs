@@ -0,0 +1,1134 @@
+use std::cell::Cell;
+
+use crate::boundary_value_problems::{
+    newton_iteration, solve_linear_system, JacobianStructure, NewtonOptions,
+};
+use crate::error::NumericalMethodsError;
+use crate::initial_value_problems::{
+    error_norm, initial_step_size, validate_adaptive_step_options,
+    AdaptiveStepOptions, DenseSegment, Trajectory,
+};
+use crate::linear_solvers::BandedMatrix;
+
+// Implicit time steppers for stiff IVPs.
+//
+// Each step solves a system of nonlinear equations with `newton_iteration`.
+// The Jacobian matrix of f is evaluated once per step, at the start of the
+// step, and the iteration matrix built from it is kept fixed during the
+// Newton iteration (simplified Newton). If the iteration fails to converge,
+// the step is rejected and retried with half the step size.
+
+/// # Implicit options
+///
+/// ## Description
+/// `ImplicitOptions` controls the implicit time steppers. `step_options`
+/// controls the adaptive step size, as for the explicit adaptive methods.
+/// `newton_options` controls the Newton iteration in each step, and its
+/// `jacobian_structure` and `finite_difference_step` control how the
+/// Jacobian matrix of f is stored and approximated: `Banded { bandwidth }`
+/// should be used when the ith component of f only depends on the
+/// components of y within `bandwidth` of i, such as for a method of lines
+/// discretisation.
+///
+/// ## Example use case
+/// ```
+/// let options = ImplicitOptions {
+///     newton_options: NewtonOptions {
+///         jacobian_structure: JacobianStructure::Banded { bandwidth: 1 },
+///         ..ImplicitOptions::default().newton_options
+///     },
+///     ..Default::default()
+/// };
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImplicitOptions {
+    pub step_options: AdaptiveStepOptions,
+    pub newton_options: NewtonOptions,
+}
+
+impl Default for ImplicitOptions {
+    fn default() -> Self {
+        ImplicitOptions {
+            step_options: AdaptiveStepOptions::default(),
+            newton_options: NewtonOptions {
+                relative_tolerance: 1e-8,
+                max_iterations: 10,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// # Implicit scheme
+///
+/// ## Description
+/// `ImplicitScheme` lists the implicit time steppers.
+///
+/// - `BackwardEuler` is first order. Its error is estimated from the change
+///   in f over the step.
+/// - `Bdf2` is the variable step, second order backward differentiation
+///   formula. Its first step is a backward Euler step, and its error is
+///   estimated from the difference between the solution and a quadratic
+///   predictor.
+/// - `RadauIIA` is the three stage, fifth order Radau IIA method, with the
+///   third order error estimate of Hairer and Wanner.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ImplicitScheme {
+    BackwardEuler,
+    Bdf2,
+    RadauIIA,
+}
+
+impl ImplicitScheme {
+    /// # Error order
+    ///
+    /// ## Description
+    /// `error_order` returns the order of the method used to estimate the
+    /// error, which sets how the step size responds to the error norm.
+    ///
+    fn error_order(&self) -> i32 {
+        match self {
+            ImplicitScheme::BackwardEuler => 1,
+            ImplicitScheme::Bdf2 => 2,
+            ImplicitScheme::RadauIIA => 3,
+        }
+    }
+}
+
+/// # State Jacobian
+///
+/// ## Description
+/// `StateJacobian` stores the Jacobian matrix of f with respect to the state,
+/// either as a dense row-major matrix or as a `BandedMatrix`.
+///
+#[derive(Debug, Clone, PartialEq)]
+enum StateJacobian {
+    Dense { size: usize, elements: Vec<f64> },
+    Banded(BandedMatrix),
+}
+
+impl StateJacobian {
+    fn size(&self) -> usize {
+        match self {
+            StateJacobian::Dense { size, .. } => *size,
+            StateJacobian::Banded(matrix) => matrix.size,
+        }
+    }
+
+    fn get(&self, row: usize, column: usize) -> f64 {
+        match self {
+            StateJacobian::Dense { size, elements } => {
+                elements[row * size + column]
+            }
+            StateJacobian::Banded(matrix) => matrix.get(row, column),
+        }
+    }
+}
+
+/// # Iteration matrix
+///
+/// ## Description
+/// `IterationMatrix` is the matrix of the linear systems solved by the
+/// implicit time steppers, stored to match the `StateJacobian`.
+///
+#[derive(Debug, Clone, PartialEq)]
+enum IterationMatrix {
+    Dense { size: usize, elements: Vec<f64> },
+    Banded(BandedMatrix),
+}
+
+impl IterationMatrix {
+    /// # New
+    ///
+    /// ## Description
+    /// `new` returns the iteration matrix I - C ⊗ J of a method with k
+    /// stages, where C is the k by k matrix `coefficients` and J is
+    /// `jacobian`. The unknowns are ordered by state component and then by
+    /// stage, so that a banded J gives a banded iteration matrix.
+    ///
+    fn new(jacobian: &StateJacobian, coefficients: &[Vec<f64>]) -> Self {
+        let n = jacobian.size();
+        let k = coefficients.len();
+        let size = n * k;
+
+        // Adds the elements of -C ⊗ J in the (i, j) block.
+        let block =
+            |i: usize, j: usize, add: &mut dyn FnMut(usize, usize, f64)| {
+                let jacobian_element = jacobian.get(i, j);
+                for (s, row) in coefficients.iter().enumerate() {
+                    for (r, c) in row.iter().enumerate() {
+                        add(i * k + s, j * k + r, -c * jacobian_element);
+                    }
+                }
+            };
+
+        match jacobian {
+            StateJacobian::Dense { .. } => {
+                let mut elements = vec![0.0; size * size];
+                for i in 0..size {
+                    elements[i * size + i] = 1.0;
+                }
+                for i in 0..n {
+                    for j in 0..n {
+                        block(i, j, &mut |row, column, value| {
+                            elements[row * size + column] += value
+                        });
+                    }
+                }
+                IterationMatrix::Dense { size, elements }
+            }
+            StateJacobian::Banded(matrix) => {
+                let bandwidth =
+                    matrix.lower_bandwidth.max(matrix.upper_bandwidth);
+                let matrix_bandwidth = (bandwidth + 1) * k - 1;
+                let mut iteration_matrix =
+                    BandedMatrix::new(size, matrix_bandwidth, matrix_bandwidth);
+                for i in 0..size {
+                    iteration_matrix.set(i, i, 1.0);
+                }
+                for i in 0..n {
+                    let first = i.saturating_sub(bandwidth);
+                    let last = (i + bandwidth).min(n - 1);
+                    for j in first..=last {
+                        block(i, j, &mut |row, column, value| {
+                            iteration_matrix.set(
+                                row,
+                                column,
+                                iteration_matrix.get(row, column) + value,
+                            )
+                        });
+                    }
+                }
+                IterationMatrix::Banded(iteration_matrix)
+            }
+        }
+    }
+
+    /// # Solve
+    ///
+    /// ## Description
+    /// `solve` solves the linear system with right hand side `rhs`. It
+    /// returns a `SingularJacobian` error if the matrix is singular.
+    ///
+    fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, NumericalMethodsError> {
+        match self {
+            IterationMatrix::Dense { size, elements } => {
+                solve_linear_system(elements, rhs, *size)
+            }
+            IterationMatrix::Banded(matrix) => {
+                matrix.solve(rhs).map_err(|error| match error {
+                    NumericalMethodsError::SingularMatrix => {
+                        NumericalMethodsError::SingularJacobian
+                    }
+                    error => error,
+                })
+            }
+        }
+    }
+}
+
+/// # State Jacobian function
+///
+/// ## Description
+/// `state_jacobian` evaluates the Jacobian matrix of f at (`t`, `y`), either
+/// with the user-supplied function `jacobian`, or with forward differences
+/// of `f` if `jacobian` is `None`. `derivative` is f(`t`, `y`).
+///
+/// With a dense `jacobian_structure`, `jacobian` returns the n by n matrix
+/// in row-major order. With a banded `jacobian_structure`, it returns only
+/// the band, as the 2 * bandwidth + 1 elements of each row from column
+/// i - bandwidth to column i + bandwidth, where the elements outside the
+/// matrix are ignored. A `LengthMismatch` error is returned if `jacobian`
+/// returns the wrong number of elements.
+///
+/// With a banded `jacobian_structure`, the forward differences perturb every
+/// (2 * bandwidth + 1)th component together, as in
+/// `get_banded_jacobian_matrix`.
+///
+fn state_jacobian<F, J>(
+    f: &F,
+    jacobian: Option<&J>,
+    t: f64,
+    y: &[f64],
+    derivative: &[f64],
+    options: &NewtonOptions,
+) -> Result<StateJacobian, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+    J: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let n = y.len();
+    let step_size = options.finite_difference_step;
+    let validate_length = |elements: &[f64], expected: usize| {
+        if elements.len() != expected {
+            return Err(NumericalMethodsError::LengthMismatch {
+                expected,
+                actual: elements.len(),
+            });
+        }
+        Ok(())
+    };
+
+    let state_jacobian = match (options.jacobian_structure, jacobian) {
+        (JacobianStructure::Dense, Some(jacobian)) => {
+            let elements = jacobian(t, y);
+            validate_length(&elements, n * n)?;
+            StateJacobian::Dense { size: n, elements }
+        }
+        (JacobianStructure::Banded { bandwidth }, Some(jacobian)) => {
+            let width = 2 * bandwidth + 1;
+            let elements = jacobian(t, y);
+            validate_length(&elements, n * width)?;
+            let mut matrix = BandedMatrix::new(n, bandwidth, bandwidth);
+            for i in 0..n {
+                let first = i.saturating_sub(bandwidth);
+                let last = (i + bandwidth).min(n - 1);
+                for j in first..=last {
+                    matrix.set(i, j, elements[i * width + j + bandwidth - i]);
+                }
+            }
+            StateJacobian::Banded(matrix)
+        }
+        (JacobianStructure::Dense, None) => {
+            let mut elements = vec![0.0; n * n];
+            let mut perturbed_y = y.to_vec();
+            for j in 0..n {
+                perturbed_y[j] += step_size;
+                let perturbed_derivative = f(t, &perturbed_y);
+                perturbed_y[j] = y[j];
+                for i in 0..n {
+                    elements[i * n + j] =
+                        (perturbed_derivative[i] - derivative[i]) / step_size;
+                }
+            }
+            StateJacobian::Dense { size: n, elements }
+        }
+        (JacobianStructure::Banded { bandwidth }, None) => {
+            let mut matrix = BandedMatrix::new(n, bandwidth, bandwidth);
+            let num_groups = (2 * bandwidth + 1).min(n);
+            let mut perturbed_y = y.to_vec();
+            for group in 0..num_groups {
+                for j in (group..n).step_by(num_groups) {
+                    perturbed_y[j] += step_size;
+                }
+                let perturbed_derivative = f(t, &perturbed_y);
+                for j in (group..n).step_by(num_groups) {
+                    perturbed_y[j] = y[j];
+                    let first = j.saturating_sub(bandwidth);
+                    let last = (j + bandwidth).min(n - 1);
+                    for i in first..=last {
+                        matrix.set(
+                            i,
+                            j,
+                            (perturbed_derivative[i] - derivative[i])
+                                / step_size,
+                        );
+                    }
+                }
+            }
+            StateJacobian::Banded(matrix)
+        }
+    };
+
+    Ok(state_jacobian)
+}
+
+/// # Finite difference evaluations
+///
+/// ## Description
+/// `finite_difference_evaluations` returns the number of evaluations of f
+/// used by `state_jacobian` to approximate the Jacobian matrix of a state
+/// with `n` components.
+///
+fn finite_difference_evaluations(n: usize, options: &NewtonOptions) -> usize {
+    match options.jacobian_structure {
+        JacobianStructure::Dense => n,
+        JacobianStructure::Banded { bandwidth } => (2 * bandwidth + 1).min(n),
+    }
+}
+
+/// # Step attempt
+///
+/// ## Description
+/// `StepAttempt` is the outcome of an attempted step whose Newton iteration
+/// converged: the new state and its derivative, the local error estimate, and
+/// the number of Newton iterations used.
+///
+struct StepAttempt {
+    state: Vec<f64>,
+    derivative: Vec<f64>,
+    error: Vec<f64>,
+    newton_iterations: usize,
+}
+
+/// # Solve stage equations
+///
+/// ## Description
+/// `solve_stage_equations` runs the simplified Newton iteration for the
+/// equations `residual_func`(x) = 0, starting from `initial_guess`, with the
+/// iteration matrix `iteration_matrix`. It returns the solution and the
+/// number of iterations, or `None` if the iteration did not converge or the
+/// iteration matrix is singular, so that the step can be retried with a
+/// smaller step size.
+///
+fn solve_stage_equations<R>(
+    initial_guess: Vec<f64>,
+    residual_func: R,
+    iteration_matrix: &IterationMatrix,
+    options: &NewtonOptions,
+) -> Result<Option<(Vec<f64>, usize)>, NumericalMethodsError>
+where
+    R: FnMut(&[f64]) -> Vec<f64>,
+{
+    let newton_result = newton_iteration(
+        initial_guess,
+        residual_func,
+        |_x, residual_vector| {
+            let negative_residual_vector: Vec<f64> =
+                residual_vector.iter().map(|r| -r).collect();
+            iteration_matrix.solve(&negative_residual_vector)
+        },
+        options,
+    );
+
+    match newton_result {
+        Ok(result) if result.converged => {
+            Ok(Some((result.solution, result.iterations)))
+        }
+        Ok(_) | Err(NumericalMethodsError::SingularJacobian) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// # Integrate implicit
+///
+/// ## Description
+/// `integrate_implicit` integrates y' = `f`(t, y) over `t_span` with the
+/// adaptive implicit time stepper `scheme`, using the Jacobian matrix
+/// function `jacobian` if it is given, and finite differences of `f`
+/// otherwise.
+///
+/// After each step, the step size is multiplied by
+/// 0.9 * err^(-1 / (q + 1)), limited to between 0.2 and 5 (and to at most 1
+/// just after a rejected step), where err is the error norm of the step and
+/// q is the order of the error estimate.
+///
+pub(crate) fn integrate_implicit<F, J>(
+    f: &F,
+    jacobian: Option<&J>,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    initial_derivative: Vec<f64>,
+    scheme: ImplicitScheme,
+    options: &ImplicitOptions,
+) -> Result<Trajectory, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+    J: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let step_options = &options.step_options;
+    let newton_options = &options.newton_options;
+    validate_adaptive_step_options(step_options)?;
+
+    let n = initial_state.len();
+
+    // Counts the evaluations of f made by the steps.
+    let evaluations = Cell::new(0);
+    let counted_f = |t: f64, y: &[f64]| {
+        evaluations.set(evaluations.get() + 1);
+        f(t, y)
+    };
+
+    let (start_time, end_time) = t_span;
+    let order = scheme.error_order();
+    let mut trajectory = Trajectory::new(start_time, initial_state);
+    evaluations.set(1);
+
+    let mut h = match step_options.initial_step_size {
+        Some(step_size) => step_size,
+        None => initial_step_size(
+            &counted_f,
+            start_time,
+            initial_state,
+            &initial_derivative,
+            order,
+            step_options,
+        ),
+    };
+
+    let mut t = start_time;
+    let mut state = initial_state.to_vec();
+    let mut derivative = initial_derivative;
+    // The previous step size and state, used by BDF2.
+    let mut history: Option<(f64, Vec<f64>)> = None;
+    let mut state_jacobian_at_t: Option<StateJacobian> = None;
+    let mut previous_step_rejected = false;
+
+    while t < end_time {
+        if trajectory.dense_output.len() + trajectory.rejected_steps
+            >= step_options.max_steps
+        {
+            return Err(NumericalMethodsError::MaxStepsExceeded {
+                max_steps: step_options.max_steps,
+                time: t,
+            });
+        }
+
+        h = h.min(step_options.max_step_size);
+        let min_step_size = step_options
+            .min_step_size
+            .max(10.0 * f64::EPSILON * t.abs());
+        if h < min_step_size {
+            return Err(NumericalMethodsError::StepSizeTooSmall {
+                time: t,
+                step_size: h,
+            });
+        }
+
+        // Lands exactly on the end time, unless that would exceed the
+        // maximum step size.
+        let last_step = t + 1.01 * h >= end_time
+            && end_time - t <= step_options.max_step_size;
+        if last_step {
+            h = end_time - t;
+        }
+
+        // The Jacobian matrix is kept until the step is accepted.
+        let jacobian_matrix = match state_jacobian_at_t {
+            Some(ref jacobian_matrix) => jacobian_matrix,
+            None => {
+                if jacobian.is_none() {
+                    evaluations.set(
+                        evaluations.get()
+                            + finite_difference_evaluations(n, newton_options),
+                    );
+                }
+                trajectory.jacobian_evaluations += 1;
+                state_jacobian_at_t.insert(state_jacobian(
+                    f,
+                    jacobian,
+                    t,
+                    &state,
+                    &derivative,
+                    newton_options,
+                )?)
+            }
+        };
+
+        let attempt = match (scheme, &history) {
+            (ImplicitScheme::Bdf2, Some((previous_h, previous_state))) => {
+                bdf2_step(
+                    &counted_f,
+                    jacobian_matrix,
+                    t,
+                    h,
+                    &state,
+                    &derivative,
+                    *previous_h,
+                    previous_state,
+                    newton_options,
+                )?
+            }
+            (ImplicitScheme::BackwardEuler | ImplicitScheme::Bdf2, _) => {
+                backward_euler_step(
+                    &counted_f,
+                    jacobian_matrix,
+                    t,
+                    h,
+                    &state,
+                    &derivative,
+                    newton_options,
+                )?
+            }
+            (ImplicitScheme::RadauIIA, _) => radau_step(
+                &counted_f,
+                jacobian_matrix,
+                t,
+                h,
+                &state,
+                &derivative,
+                newton_options,
+            )?,
+        };
+
+        let attempt = match attempt {
+            Some(attempt) => attempt,
+            None => {
+                // The Newton iteration failed, so the step is retried with
+                // half the step size.
+                h *= 0.5;
+                trajectory.rejected_steps += 1;
+                previous_step_rejected = true;
+                continue;
+            }
+        };
+        trajectory.newton_iterations += attempt.newton_iterations;
+
+        let error_norm =
+            error_norm(&attempt.error, &state, &attempt.state, step_options);
+        let exponent = -1.0 / (order + 1) as f64;
+
+        if error_norm <= 1.0 {
+            let segment = DenseSegment::hermite(
+                t,
+                h,
+                &state,
+                &derivative,
+                &attempt.state,
+                &attempt.derivative,
+            );
+            history = Some((h, state));
+            t = if last_step { end_time } else { t + h };
+            trajectory.accept_step(t, attempt.state.clone(), segment);
+            state = attempt.state;
+            derivative = attempt.derivative;
+            state_jacobian_at_t = None;
+
+            let mut factor = if error_norm == 0.0 {
+                5.0
+            } else {
+                (0.9 * error_norm.powf(exponent)).clamp(0.2, 5.0)
+            };
+            if previous_step_rejected {
+                factor = factor.min(1.0);
+            }
+            h *= factor;
+            previous_step_rejected = false;
+        } else {
+            h *= (0.9 * error_norm.powf(exponent)).max(0.2);
+            trajectory.rejected_steps += 1;
+            previous_step_rejected = true;
+        }
+    }
+
+    trajectory.function_evaluations = evaluations.get();
+    Ok(trajectory)
+}
+
+/// # Backward Euler step
+///
+/// ## Description
+/// `backward_euler_step` attempts the step y_1 = y_0 + h f(t + h, y_1). The
+/// local error is estimated as h / 2 * (f(t + h, y_1) - f(t, y_0)), the
+/// leading term of the error of backward Euler.
+///
+fn backward_euler_step<F>(
+    f: &F,
+    jacobian_matrix: &StateJacobian,
+    t: f64,
+    h: f64,
+    state: &[f64],
+    derivative: &[f64],
+    options: &NewtonOptions,
+) -> Result<Option<StepAttempt>, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let new_time = t + h;
+    let iteration_matrix = IterationMatrix::new(jacobian_matrix, &[vec![h]]);
+    let solution = solve_stage_equations(
+        state.to_vec(),
+        |y| {
+            let f_y = f(new_time, y);
+            (0..y.len()).map(|i| y[i] - state[i] - h * f_y[i]).collect()
+        },
+        &iteration_matrix,
+        options,
+    )?;
+
+    Ok(solution.map(|(new_state, newton_iterations)| {
+        let new_derivative = f(new_time, &new_state);
+        let error = new_derivative
+            .iter()
+            .zip(derivative)
+            .map(|(f1, f0)| 0.5 * h * (f1 - f0))
+            .collect();
+        StepAttempt {
+            state: new_state,
+            derivative: new_derivative,
+            error,
+            newton_iterations,
+        }
+    }))
+}
+
+/// # BDF2 step
+///
+/// ## Description
+/// `bdf2_step` attempts a variable step BDF2 step of size `h`, given the
+/// previous step size `previous_h` and the state before the previous step,
+/// `previous_state`. With ω = h / `previous_h`, the step solves
+/// y_1 - (1 + ω)^2 / (1 + 2ω) y_0 + ω^2 / (1 + 2ω) y_-1
+///     = h (1 + ω) / (1 + 2ω) f(t + h, y_1).
+///
+/// The local error is estimated by comparing y_1 with the quadratic
+/// predictor through y_-1 and y_0 with slope f(t, y_0) at t. For a solution
+/// with third derivative y''', the errors of the two are C * y''' and
+/// -P * y''', so the error of y_1 is C / (C + P) times their difference.
+///
+#[allow(clippy::too_many_arguments)]
+fn bdf2_step<F>(
+    f: &F,
+    jacobian_matrix: &StateJacobian,
+    t: f64,
+    h: f64,
+    state: &[f64],
+    derivative: &[f64],
+    previous_h: f64,
+    previous_state: &[f64],
+    options: &NewtonOptions,
+) -> Result<Option<StepAttempt>, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let new_time = t + h;
+    let omega = h / previous_h;
+    let beta = (1.0 + omega) / (1.0 + 2.0 * omega);
+    let current_weight = (1.0 + omega).powi(2) / (1.0 + 2.0 * omega);
+    let previous_weight = omega.powi(2) / (1.0 + 2.0 * omega);
+
+    // The part of the equations which does not depend on y_1.
+    let history_term: Vec<f64> = (0..state.len())
+        .map(|i| {
+            current_weight * state[i] - previous_weight * previous_state[i]
+        })
+        .collect();
+
+    let iteration_matrix =
+        IterationMatrix::new(jacobian_matrix, &[vec![beta * h]]);
+    let solution = solve_stage_equations(
+        state.to_vec(),
+        |y| {
+            let f_y = f(new_time, y);
+            (0..y.len())
+                .map(|i| y[i] - history_term[i] - beta * h * f_y[i])
+                .collect()
+        },
+        &iteration_matrix,
+        options,
+    )?;
+
+    // The error constants of the corrector and predictor.
+    let alpha_2 = 1.0 / beta;
+    let corrector_constant = (-(1.0 + omega) * h.powi(3)
+        + omega.powi(2) / (1.0 + omega) * (h + previous_h).powi(3))
+        / (6.0 * alpha_2);
+    let predictor_constant = h * h * (h + previous_h) / 6.0;
+    let error_fraction =
+        corrector_constant / (corrector_constant + predictor_constant);
+
+    Ok(solution.map(|(new_state, newton_iterations)| {
+        let error = (0..state.len())
+            .map(|i| {
+                let curvature = (previous_state[i] - state[i]
+                    + previous_h * derivative[i])
+                    / previous_h.powi(2);
+                let predictor =
+                    state[i] + h * derivative[i] + h * h * curvature;
+                error_fraction * (new_state[i] - predictor)
+            })
+            .collect();
+        StepAttempt {
+            derivative: f(new_time, &new_state),
+            state: new_state,
+            error,
+            newton_iterations,
+        }
+    }))
+}
+
+/// # Radau IIA coefficients
+///
+/// ## Description
+/// `radau_coefficients` returns the nodes c and the matrix A of the three
+/// stage Radau IIA method, the weights E of its error estimate, and the real
+/// eigenvalue μ of A^-1.
+///
+fn radau_coefficients() -> ([f64; 3], [[f64; 3]; 3], [f64; 3], f64) {
+    let s6 = 6.0_f64.sqrt();
+    let nodes = [(4.0 - s6) / 10.0, (4.0 + s6) / 10.0, 1.0];
+    let matrix = [
+        [
+            (88.0 - 7.0 * s6) / 360.0,
+            (296.0 - 169.0 * s6) / 1800.0,
+            (-2.0 + 3.0 * s6) / 225.0,
+        ],
+        [
+            (296.0 + 169.0 * s6) / 1800.0,
+            (88.0 + 7.0 * s6) / 360.0,
+            (-2.0 - 3.0 * s6) / 225.0,
+        ],
+        [(16.0 - s6) / 36.0, (16.0 + s6) / 36.0, 1.0 / 9.0],
+    ];
+    let error_weights = [
+        (-13.0 - 7.0 * s6) / 3.0,
+        (-13.0 + 7.0 * s6) / 3.0,
+        -1.0 / 3.0,
+    ];
+    let real_eigenvalue = 3.0 + 3.0_f64.powf(2.0 / 3.0) - 3.0_f64.cbrt();
+    (nodes, matrix, error_weights, real_eigenvalue)
+}
+
+/// # Radau step
+///
+/// ## Description
+/// `radau_step` attempts a Radau IIA step of size `h`. The unknowns are the
+/// stage increments Z_s = Y_s - y_0, which solve
+/// Z_s = h * sum over r of A[s][r] * f(t + c_r h, y_0 + Z_r), and the new
+/// state is y_0 + Z_3.
+///
+/// The local error is estimated as
+/// (μ I - h J)^-1 (h f(t, y_0) + sum over s of E_s Z_s).
+///
+fn radau_step<F>(
+    f: &F,
+    jacobian_matrix: &StateJacobian,
+    t: f64,
+    h: f64,
+    state: &[f64],
+    derivative: &[f64],
+    options: &NewtonOptions,
+) -> Result<Option<StepAttempt>, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let n = state.len();
+    let (nodes, matrix, error_weights, real_eigenvalue) = radau_coefficients();
+
+    let scaled_matrix: Vec<Vec<f64>> = matrix
+        .iter()
+        .map(|row| row.iter().map(|a| h * a).collect())
+        .collect();
+    let iteration_matrix =
+        IterationMatrix::new(jacobian_matrix, &scaled_matrix);
+
+    // The stage increments are ordered by component and then by stage.
+    let solution = solve_stage_equations(
+        vec![0.0; 3 * n],
+        |z| {
+            let stage_derivatives: Vec<Vec<f64>> = (0..3)
+                .map(|s| {
+                    let stage_state: Vec<f64> =
+                        (0..n).map(|i| state[i] + z[i * 3 + s]).collect();
+                    f(t + nodes[s] * h, &stage_state)
+                })
+                .collect();
+            (0..3 * n)
+                .map(|index| {
+                    let (i, s) = (index / 3, index % 3);
+                    z[index]
+                        - (0..3)
+                            .map(|r| {
+                                scaled_matrix[s][r] * stage_derivatives[r][i]
+                            })
+                            .sum::<f64>()
+                })
+                .collect()
+        },
+        &iteration_matrix,
+        options,
+    )?;
+
+    let (z, newton_iterations) = match solution {
+        Some(solution) => solution,
+        None => return Ok(None),
+    };
+
+    let error_rhs: Vec<f64> = (0..n)
+        .map(|i| {
+            (h * derivative[i]
+                + (0..3).map(|s| error_weights[s] * z[i * 3 + s]).sum::<f64>())
+                / real_eigenvalue
+        })
+        .collect();
+    let error_matrix =
+        IterationMatrix::new(jacobian_matrix, &[vec![h / real_eigenvalue]]);
+    let error = match error_matrix.solve(&error_rhs) {
+        Ok(error) => error,
+        Err(NumericalMethodsError::SingularJacobian) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    let new_state: Vec<f64> = (0..n).map(|i| state[i] + z[i * 3 + 2]).collect();
+    Ok(Some(StepAttempt {
+        derivative: f(t + h, &new_state),
+        state: new_state,
+        error,
+        newton_iterations,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::NumericalMethodsError;
+    use crate::initial_value_problems::{
+        solve_ivp, solve_ivp_with_jacobian, try_solve_ivp, AdaptiveStepOptions,
+        IvpMethod,
+    };
+
+    use super::*;
+
+    fn robertson(_t: f64, y: &[f64]) -> Vec<f64> {
+        vec![
+            -0.04 * y[0] + 1e4 * y[1] * y[2],
+            0.04 * y[0] - 1e4 * y[1] * y[2] - 3e7 * y[1] * y[1],
+            3e7 * y[1] * y[1],
+        ]
+    }
+
+    fn robertson_jacobian(_t: f64, y: &[f64]) -> Vec<f64> {
+        vec![
+            -0.04,
+            1e4 * y[2],
+            1e4 * y[1],
+            0.04,
+            -1e4 * y[2] - 6e7 * y[1],
+            -1e4 * y[1],
+            0.0,
+            6e7 * y[1],
+            0.0,
+        ]
+    }
+
+    type MethodConstructor = fn(ImplicitOptions) -> IvpMethod;
+
+    fn implicit_methods(options: ImplicitOptions) -> [IvpMethod; 3] {
+        [
+            IvpMethod::BackwardEuler(options),
+            IvpMethod::Bdf2(options),
+            IvpMethod::RadauIIA(options),
+        ]
+    }
+
+    #[test]
+    fn test_robertson_chemical_kinetics() {
+        // The reference solution at t = 40 (Hairer and Wanner).
+        let reference = [0.715827068, 9.185535e-6, 0.284163746];
+        let options = ImplicitOptions {
+            step_options: AdaptiveStepOptions {
+                absolute_tolerance: 1e-10,
+                relative_tolerance: 1e-6,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        for method in implicit_methods(options) {
+            for analytic_jacobian in [false, true] {
+                let result = if analytic_jacobian {
+                    solve_ivp_with_jacobian(
+                        robertson,
+                        robertson_jacobian,
+                        (0.0, 40.0),
+                        &[1.0, 0.0, 0.0],
+                        &method,
+                    )
+                } else {
+                    solve_ivp(robertson, (0.0, 40.0), &[1.0, 0.0, 0.0], &method)
+                };
+                let end_state = result.evaluate(40.0);
+                let tolerance = match method {
+                    IvpMethod::BackwardEuler(_) => 1e-2,
+                    _ => 1e-4,
+                };
+
+                for i in 0..3 {
+                    let relative_error =
+                        (end_state[i] - reference[i]).abs() / reference[i];
+                    assert!(
+                        relative_error < tolerance,
+                        "{:?} component {} error {}.",
+                        method,
+                        i,
+                        relative_error
+                    );
+                }
+                let total: f64 = end_state.iter().sum();
+                assert!((total - 1.0).abs() < 1e-8);
+
+                // An explicit method would need at least 40000 steps, as its
+                // step size is limited to about 1e-3 by stability.
+                assert!(result.accepted_steps < 5000, "{:?}", method);
+                assert_eq!(result.jacobian_evaluations, result.accepted_steps);
+                assert!(result.newton_iterations >= result.accepted_steps);
+            }
+        }
+    }
+
+    #[test]
+    fn test_implicit_orders() {
+        // With equal, fixed steps, the error at t = 1 of y' = -y + sin(t)
+        // converges at the order of each method.
+        let f = |t: f64, y: &[f64]| vec![-y[0] + t.sin()];
+        let exact = |t: f64| 1.5 * (-t).exp() + 0.5 * (t.sin() - t.cos());
+        let error = |method: fn(ImplicitOptions) -> IvpMethod, h: f64| {
+            let options = ImplicitOptions {
+                step_options: AdaptiveStepOptions {
+                    absolute_tolerance: 1.0,
+                    relative_tolerance: 1.0,
+                    initial_step_size: Some(h),
+                    max_step_size: h,
+                    ..Default::default()
+                },
+                newton_options: NewtonOptions {
+                    absolute_tolerance: 1e-14,
+                    relative_tolerance: 1e-14,
+                    ..ImplicitOptions::default().newton_options
+                },
+            };
+            let result = solve_ivp(f, (0.0, 1.0), &[1.0], &method(options));
+            (result.evaluate(1.0)[0] - exact(1.0)).abs()
+        };
+
+        let methods: [(MethodConstructor, i32); 3] = [
+            (IvpMethod::BackwardEuler, 1),
+            (IvpMethod::Bdf2, 2),
+            (IvpMethod::RadauIIA, 5),
+        ];
+        for (method, order) in methods {
+            let ratio = error(method, 0.1) / error(method, 0.05);
+            let expected = 2.0_f64.powi(order);
+            assert!(
+                ratio > 0.8 * expected && ratio < 1.25 * expected,
+                "Order {} ratio {}.",
+                order,
+                ratio
+            );
+        }
+    }
+
+    #[test]
+    fn test_banded_jacobian() {
+        // The heat equation u_t = u_xx on 50 interior points, whose
+        // eigenvalues reach -1e4, decays like exp(-PI^2 t).
+        let n = 50;
+        let dx = 1.0 / (n + 1) as f64;
+        let f = |_t: f64, u: &[f64]| {
+            (0..n)
+                .map(|i| {
+                    let left = if i > 0 { u[i - 1] } else { 0.0 };
+                    let right = if i + 1 < n { u[i + 1] } else { 0.0 };
+                    (left - 2.0 * u[i] + right) / (dx * dx)
+                })
+                .collect()
+        };
+        let pi = std::f64::consts::PI;
+        let initial_state: Vec<f64> =
+            (1..=n).map(|i| (pi * i as f64 * dx).sin()).collect();
+
+        let banded_options = ImplicitOptions {
+            newton_options: NewtonOptions {
+                jacobian_structure: JacobianStructure::Banded { bandwidth: 1 },
+                ..ImplicitOptions::default().newton_options
+            },
+            ..Default::default()
+        };
+        for method in implicit_methods(banded_options) {
+            let result = solve_ivp(f, (0.0, 0.1), &initial_state, &method);
+            let dense_method = match method {
+                IvpMethod::BackwardEuler(_) => {
+                    IvpMethod::BackwardEuler(ImplicitOptions::default())
+                }
+                IvpMethod::Bdf2(_) => {
+                    IvpMethod::Bdf2(ImplicitOptions::default())
+                }
+                _ => IvpMethod::RadauIIA(ImplicitOptions::default()),
+            };
+            let dense_result =
+                solve_ivp(f, (0.0, 0.1), &initial_state, &dense_method);
+
+            let middle = result.evaluate(0.1)[n / 2];
+            let dense_middle = dense_result.evaluate(0.1)[n / 2];
+            assert!((middle - dense_middle).abs() < 1e-6, "{:?}", method);
+            let decay = (-pi * pi * 0.1).exp() * initial_state[n / 2];
+            assert!((middle - decay).abs() < 1e-3, "{:?}", method);
+
+            // The banded Jacobian needs 3 evaluations rather than 50.
+            assert!(
+                result.function_evaluations < dense_result.function_evaluations
+            );
+
+            // The exact Jacobian only needs the band, and is evaluated once
+            // for each Jacobian evaluation counted.
+            let jacobian_calls = Cell::new(0);
+            let jacobian = |_t: f64, _u: &[f64]| {
+                jacobian_calls.set(jacobian_calls.get() + 1);
+                [1.0, -2.0, 1.0]
+                    .repeat(n)
+                    .iter()
+                    .map(|a| a / (dx * dx))
+                    .collect()
+            };
+            let analytic_result = solve_ivp_with_jacobian(
+                f,
+                jacobian,
+                (0.0, 0.1),
+                &initial_state,
+                &method,
+            );
+            let analytic_middle = analytic_result.evaluate(0.1)[n / 2];
+            assert!((analytic_middle - middle).abs() < 1e-6, "{:?}", method);
+            assert_eq!(
+                analytic_result.jacobian_evaluations,
+                jacobian_calls.get()
+            );
+        }
+
+        // A banded Jacobian with the wrong number of elements.
+        assert_eq!(
+            crate::initial_value_problems::try_solve_ivp_with_jacobian(
+                f,
+                |_t, _u| vec![0.0; n * n],
+                (0.0, 0.1),
+                &initial_state,
+                &IvpMethod::Bdf2(banded_options),
+            )
+            .map(|_| ()),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 3 * n,
+                actual: n * n
+            })
+        );
+    }
+
+    #[test]
+    fn test_implicit_errors() {
+        let options = ImplicitOptions::default();
+        assert_eq!(
+            try_solve_ivp(
+                robertson,
+                (0.0, 1.0),
+                &[1.0, 0.0, 0.0],
+                &IvpMethod::RadauIIA(ImplicitOptions {
+                    step_options: AdaptiveStepOptions {
+                        relative_tolerance: -1.0,
+                        ..Default::default()
+                    },
+                    ..options
+                }),
+            )
+            .map(|_| ()),
+            Err(NumericalMethodsError::InvalidParameter(
+                "The tolerances must be non-negative, and not both zero."
+                    .to_string()
+            ))
+        );
+
+        assert_eq!(
+            crate::initial_value_problems::try_solve_ivp_with_jacobian(
+                robertson,
+                |_t, _y| vec![0.0; 4],
+                (0.0, 1.0),
+                &[1.0, 0.0, 0.0],
+                &IvpMethod::Bdf2(options),
+            )
+            .map(|_| ()),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 9,
+                actual: 4
+            })
+        );
+    }
+}
@@ -0,0 +1,1312 @@
+use crate::error::{validate_tolerances, NumericalMethodsError};
+use crate::grid::Grid;
+use crate::implicit_ivp_solvers::{
+    integrate_implicit, ImplicitOptions, ImplicitScheme,
+};
+use crate::vector_grid_function::VectorGridFunction;
+
+/// # IVP method
+///
+/// ## Description
+/// `IvpMethod` chooses the time stepper used by `solve_ivp` to integrate the
+/// initial value problem (IVP) y' = f(t, y).
+///
+/// - `ExplicitEuler { step_size }` takes first order explicit Euler steps of
+///   (at most) `step_size`.
+/// - `Rk4 { step_size }` takes classical fourth order Runge-Kutta steps of
+///   (at most) `step_size`.
+/// - `DormandPrince(options)` takes adaptive steps with the fifth order
+///   Dormand-Prince method, choosing each step size so that the local error
+///   estimate from the embedded fourth order method meets the tolerances in
+///   `options`.
+/// - `BackwardEuler(options)`, `Bdf2(options)` and `RadauIIA(options)` take
+///   adaptive steps with the first order backward Euler method, the second
+///   order backward differentiation formula and the fifth order Radau IIA
+///   method. These implicit methods are stable for stiff problems, where the
+///   explicit methods need tiny steps. Each step is solved with Newton's
+///   method, using a Jacobian matrix of f which is either supplied by the
+///   user (see `solve_ivp_with_jacobian`) or approximated with finite
+///   differences.
+///
+/// The fixed step methods shorten `step_size` slightly if needed, so that
+/// the time interval is split into equal steps.
+///
+/// ## Example use case
+/// ```
+/// let method = IvpMethod::DormandPrince(AdaptiveStepOptions {
+///     relative_tolerance: 1e-8,
+///     ..Default::default()
+/// });
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IvpMethod {
+    ExplicitEuler { step_size: f64 },
+    Rk4 { step_size: f64 },
+    DormandPrince(AdaptiveStepOptions),
+    BackwardEuler(ImplicitOptions),
+    Bdf2(ImplicitOptions),
+    RadauIIA(ImplicitOptions),
+}
+
+/// # Adaptive step options
+///
+/// ## Description
+/// `AdaptiveStepOptions` controls the adaptive time steppers.
+///
+/// A step is accepted if the root mean square over the components of
+/// error_i / (`absolute_tolerance` + `relative_tolerance` * |y_i|) is at most
+/// 1, where error_i is the local error estimate of the ith component.
+///
+/// `initial_step_size` is the size of the first trial step, which is chosen
+/// automatically if it is `None`. The step sizes are limited to
+/// `max_step_size`, and an error is returned if a step size below
+/// `min_step_size` is needed, or if `max_steps` steps (accepted or rejected)
+/// are taken without reaching the end time.
+///
+/// ## Example use case
+/// ```
+/// let options = AdaptiveStepOptions {
+///     max_step_size: 0.1,
+///     ..Default::default()
+/// };
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveStepOptions {
+    pub absolute_tolerance: f64,
+    pub relative_tolerance: f64,
+    pub initial_step_size: Option<f64>,
+    pub max_step_size: f64,
+    pub min_step_size: f64,
+    pub max_steps: usize,
+}
+
+impl Default for AdaptiveStepOptions {
+    fn default() -> Self {
+        AdaptiveStepOptions {
+            absolute_tolerance: 1e-8,
+            relative_tolerance: 1e-6,
+            initial_step_size: None,
+            max_step_size: f64::INFINITY,
+            min_step_size: 1e-12,
+            max_steps: 100_000,
+        }
+    }
+}
+
+/// # IVP result
+///
+/// ## Description
+/// `IvpResult` is returned by `solve_ivp`. `solution` contains the state at
+/// the start time and after each accepted step, as a `VectorGridFunction`
+/// with one component per state variable, on the (generally non-uniform)
+/// `Grid` of accepted times.
+///
+/// `accepted_steps`, `rejected_steps` and `function_evaluations` record the
+/// cost of the integration. Rejected steps include those of the implicit
+/// methods whose Newton iteration failed to converge. `newton_iterations` and
+/// `jacobian_evaluations` count the work of the implicit methods, and are 0
+/// for the explicit methods.
+///
+/// Between the accepted times, the solution can be evaluated with `evaluate`
+/// (dense output). Dormand-Prince uses the fourth order interpolant built
+/// from the stages of each step. The fixed step and implicit methods use the
+/// cubic Hermite interpolant of the states and derivatives at the ends of
+/// each step.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct IvpResult {
+    pub solution: VectorGridFunction,
+    pub accepted_steps: usize,
+    pub rejected_steps: usize,
+    pub function_evaluations: usize,
+    pub newton_iterations: usize,
+    pub jacobian_evaluations: usize,
+    dense_output: Vec<DenseSegment>,
+}
+
+impl IvpResult {
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the state at the time `t`, using the dense output
+    /// of the step which contains `t`.
+    ///
+    /// If `t` lies outside the time interval of the solution, `evaluate`
+    /// panics. See `try_evaluate` for a version which returns an error
+    /// instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let result = solve_ivp(f, (0.0, 1.0), &[1.0], &method);
+    /// let state = result.evaluate(0.55);
+    /// ```
+    ///
+    pub fn evaluate(&self, t: f64) -> Vec<f64> {
+        match self.try_evaluate(t) {
+            Ok(state) => state,
+            Err(error) => panic!("Evaluation failed. {}", error),
+        }
+    }
+
+    /// # Try evaluate
+    ///
+    /// ## Description
+    /// `try_evaluate` is the fallible version of `evaluate`. It returns an
+    /// `OutOfDomain` error if `t` lies outside the time interval of the
+    /// solution.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let state = result.try_evaluate(0.55)?;
+    /// ```
+    ///
+    pub fn try_evaluate(
+        &self,
+        t: f64,
+    ) -> Result<Vec<f64>, NumericalMethodsError> {
+        let grid = self.solution.grid();
+        match grid.find_cell(t) {
+            Some(cell) => Ok(self.dense_output[cell].evaluate(t)),
+            None => Err(NumericalMethodsError::OutOfDomain {
+                point: t,
+                lower: grid.grid_points[0],
+                upper: grid.grid_points[grid.grid_points.len() - 1],
+            }),
+        }
+    }
+}
+
+/// # Dense segment
+///
+/// ## Description
+/// `DenseSegment` is the dense output of one step from `start_time` to
+/// `start_time + step_size`. With θ = (t - `start_time`) / `step_size`, the
+/// ith component of the state is
+/// `start_state[i]` + sum over k of `coefficients[i][k]` * θ^(k + 1).
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DenseSegment {
+    pub start_time: f64,
+    pub step_size: f64,
+    pub start_state: Vec<f64>,
+    pub coefficients: Vec<[f64; 4]>,
+}
+
+impl DenseSegment {
+    /// # Hermite
+    ///
+    /// ## Description
+    /// `hermite` returns the cubic Hermite interpolant of a step of size
+    /// `step_size` from the state `start_state` with derivative
+    /// `start_derivative` at `start_time`, to the state `end_state` with
+    /// derivative `end_derivative`. It is third order accurate, and is used
+    /// by the time steppers which have no dense output of their own.
+    ///
+    pub fn hermite(
+        start_time: f64,
+        step_size: f64,
+        start_state: &[f64],
+        start_derivative: &[f64],
+        end_state: &[f64],
+        end_derivative: &[f64],
+    ) -> Self {
+        let coefficients = (0..start_state.len())
+            .map(|i| {
+                let jump = end_state[i] - start_state[i];
+                let (f0, f1) = (
+                    step_size * start_derivative[i],
+                    step_size * end_derivative[i],
+                );
+                [f0, 3.0 * jump - 2.0 * f0 - f1, -2.0 * jump + f0 + f1, 0.0]
+            })
+            .collect();
+
+        DenseSegment {
+            start_time,
+            step_size,
+            start_state: start_state.to_vec(),
+            coefficients,
+        }
+    }
+
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the state at the time `t`.
+    ///
+    pub fn evaluate(&self, t: f64) -> Vec<f64> {
+        let theta = (t - self.start_time) / self.step_size;
+        self.start_state
+            .iter()
+            .zip(self.coefficients.iter())
+            .map(|(y, c)| {
+                y + theta
+                    * (c[0] + theta * (c[1] + theta * (c[2] + theta * c[3])))
+            })
+            .collect()
+    }
+}
+
+/// # Trajectory
+///
+/// ## Description
+/// `Trajectory` collects the accepted times and states, the dense output and
+/// the step statistics of a time stepper, and turns them into an `IvpResult`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Trajectory {
+    pub times: Vec<f64>,
+    pub states: Vec<Vec<f64>>,
+    pub dense_output: Vec<DenseSegment>,
+    pub rejected_steps: usize,
+    pub function_evaluations: usize,
+    pub newton_iterations: usize,
+    pub jacobian_evaluations: usize,
+}
+
+impl Trajectory {
+    /// # New
+    ///
+    /// ## Description
+    /// `new` starts a trajectory at the state `initial_state` at `start_time`.
+    ///
+    pub fn new(start_time: f64, initial_state: &[f64]) -> Self {
+        Trajectory {
+            times: vec![start_time],
+            states: vec![initial_state.to_vec()],
+            dense_output: Vec::new(),
+            rejected_steps: 0,
+            function_evaluations: 0,
+            newton_iterations: 0,
+            jacobian_evaluations: 0,
+        }
+    }
+
+    /// # Accept step
+    ///
+    /// ## Description
+    /// `accept_step` records an accepted step which ends at the state `state`
+    /// at `time`, with dense output `segment`.
+    ///
+    pub fn accept_step(
+        &mut self,
+        time: f64,
+        state: Vec<f64>,
+        segment: DenseSegment,
+    ) {
+        self.times.push(time);
+        self.states.push(state);
+        self.dense_output.push(segment);
+    }
+
+    /// # Into result
+    ///
+    /// ## Description
+    /// `into_result` converts the trajectory into an `IvpResult`.
+    ///
+    pub fn into_result(self) -> IvpResult {
+        let grid = Grid::new_grid_from_points(self.times);
+        let num_components = self.states[0].len();
+        let interleaved_values: Vec<f64> = self.states.concat();
+
+        IvpResult {
+            solution: VectorGridFunction::from_interleaved_values(
+                &grid,
+                num_components,
+                &interleaved_values,
+            ),
+            accepted_steps: self.dense_output.len(),
+            rejected_steps: self.rejected_steps,
+            function_evaluations: self.function_evaluations,
+            newton_iterations: self.newton_iterations,
+            jacobian_evaluations: self.jacobian_evaluations,
+            dense_output: self.dense_output,
+        }
+    }
+}
+
+/// # Solve IVP
+///
+/// ## Description
+/// `solve_ivp` integrates the IVP y' = `f`(t, y) with y(t_0) =
+/// `initial_state` over the time interval `t_span` = (t_0, t_end), using the
+/// time stepper `method`. The state y is a vector, and `f` must return a
+/// vector of the same length.
+///
+/// `solve_ivp` panics if the integration fails. See `try_solve_ivp` for a
+/// version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want to integrate the harmonic oscillator u'' = -u, with
+/// u(0) = 1 and u'(0) = 0, from t = 0 to t = 10. Writing the state as
+/// y = [u, u'], the code below does this.
+/// ```
+/// let result = solve_ivp(
+///     |_t, y| vec![y[1], -y[0]],
+///     (0.0, 10.0),
+///     &[1.0, 0.0],
+///     &IvpMethod::DormandPrince(AdaptiveStepOptions::default()),
+/// );
+/// let u = &result.solution.components[0];
+/// ```
+///
+pub fn solve_ivp<F>(
+    f: F,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    method: &IvpMethod,
+) -> IvpResult
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    match try_solve_ivp(f, t_span, initial_state, method) {
+        Ok(ivp_result) => ivp_result,
+        Err(error) => panic!("IVP solve failed. {}", error),
+    }
+}
+
+/// # Try solve IVP
+///
+/// ## Description
+/// `try_solve_ivp` is the fallible version of `solve_ivp`. It returns an
+/// `InvalidParameter` error if `t_span` is not a finite, increasing time
+/// interval, if the state is empty or if the step sizes or tolerances are
+/// not positive, a `LengthMismatch` error if `f` returns a vector of the
+/// wrong length, and a `StepSizeTooSmall` or `MaxStepsExceeded` error if an
+/// adaptive integration cannot reach the end time.
+///
+/// ## Example use case
+/// ```
+/// let result = try_solve_ivp(f, (0.0, 10.0), &[1.0, 0.0], &method)?;
+/// ```
+///
+pub fn try_solve_ivp<F>(
+    f: F,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    method: &IvpMethod,
+) -> Result<IvpResult, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    integrate(&f, None::<&F>, t_span, initial_state, method)
+}
+
+/// # Solve IVP with Jacobian
+///
+/// ## Description
+/// `solve_ivp_with_jacobian` is the version of `solve_ivp` for the implicit
+/// methods with a user-supplied Jacobian matrix. `jacobian(t, y)` returns the
+/// Jacobian matrix of `f` at (t, y), with elements ∂f_i/∂y_j, as a flat
+/// vector in row-major order. The explicit methods ignore `jacobian`.
+///
+/// For a banded `jacobian_structure` with bandwidth b, `jacobian` returns
+/// only the band, as 2b + 1 elements for each row i, which are
+/// ∂f_i/∂y_(i - b) to ∂f_i/∂y_(i + b). The elements of the first and last b
+/// rows which lie outside the matrix are ignored. This takes O(n b) memory
+/// for a state with n components, rather than O(n^2).
+///
+/// `solve_ivp_with_jacobian` panics if the integration fails. See
+/// `try_solve_ivp_with_jacobian` for a version which returns an error
+/// instead.
+///
+/// ## Example use case
+/// Suppose that we want to integrate the stiff IVP y' = -1000 (y - cos(t))
+/// with Radau IIA, using its exact Jacobian. The code below does this.
+/// ```
+/// let result = solve_ivp_with_jacobian(
+///     |t, y| vec![-1000.0 * (y[0] - t.cos())],
+///     |_t, _y| vec![-1000.0],
+///     (0.0, 1.0),
+///     &[0.0],
+///     &IvpMethod::RadauIIA(ImplicitOptions::default()),
+/// );
+/// ```
+///
+pub fn solve_ivp_with_jacobian<F, J>(
+    f: F,
+    jacobian: J,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    method: &IvpMethod,
+) -> IvpResult
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+    J: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    match try_solve_ivp_with_jacobian(
+        f,
+        jacobian,
+        t_span,
+        initial_state,
+        method,
+    ) {
+        Ok(ivp_result) => ivp_result,
+        Err(error) => panic!("IVP solve failed. {}", error),
+    }
+}
+
+/// # Try solve IVP with Jacobian
+///
+/// ## Description
+/// `try_solve_ivp_with_jacobian` is the fallible version of
+/// `solve_ivp_with_jacobian`. As well as the errors of `try_solve_ivp`, it
+/// returns a `LengthMismatch` error if `jacobian` does not return n^2
+/// elements for a state with n components, or n (2b + 1) elements for a
+/// banded `jacobian_structure` with bandwidth b. The length is checked each
+/// time `jacobian` is evaluated.
+///
+/// ## Example use case
+/// ```
+/// let result =
+///     try_solve_ivp_with_jacobian(f, jacobian, (0.0, 1.0), &[0.0], &method)?;
+/// ```
+///
+pub fn try_solve_ivp_with_jacobian<F, J>(
+    f: F,
+    jacobian: J,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    method: &IvpMethod,
+) -> Result<IvpResult, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+    J: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    integrate(&f, Some(&jacobian), t_span, initial_state, method)
+}
+
+/// # Integrate
+///
+/// ## Description
+/// `integrate` validates the IVP and runs the time stepper `method`, using
+/// the Jacobian matrix function `jacobian` for the implicit methods if it is
+/// given.
+///
+fn integrate<F, J>(
+    f: &F,
+    jacobian: Option<&J>,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    method: &IvpMethod,
+) -> Result<IvpResult, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+    J: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let initial_derivative = validate_ivp(f, t_span, initial_state)?;
+
+    let implicit = |scheme, options: &ImplicitOptions| {
+        integrate_implicit(
+            f,
+            jacobian,
+            t_span,
+            initial_state,
+            initial_derivative.clone(),
+            scheme,
+            options,
+        )
+    };
+
+    let trajectory = match method {
+        IvpMethod::ExplicitEuler { step_size } => integrate_fixed_step(
+            f,
+            t_span,
+            initial_state,
+            initial_derivative,
+            *step_size,
+            |_f, _t, y, derivative, h| {
+                let state = y.iter().zip(derivative).map(|(y, dy)| y + h * dy);
+                (state.collect(), 0)
+            },
+        )?,
+        IvpMethod::Rk4 { step_size } => integrate_fixed_step(
+            f,
+            t_span,
+            initial_state,
+            initial_derivative,
+            *step_size,
+            rk4_step,
+        )?,
+        IvpMethod::DormandPrince(options) => dormand_prince(
+            f,
+            t_span,
+            initial_state,
+            initial_derivative,
+            options,
+        )?,
+        IvpMethod::BackwardEuler(options) => {
+            implicit(ImplicitScheme::BackwardEuler, options)?
+        }
+        IvpMethod::Bdf2(options) => implicit(ImplicitScheme::Bdf2, options)?,
+        IvpMethod::RadauIIA(options) => {
+            implicit(ImplicitScheme::RadauIIA, options)?
+        }
+    };
+
+    Ok(trajectory.into_result())
+}
+
+/// # Solve scalar IVP
+///
+/// ## Description
+/// `solve_scalar_ivp` is the version of `solve_ivp` for a scalar IVP
+/// y' = `f`(t, y) with y(t_0) = `initial_value`. The solution has a single
+/// component.
+///
+/// ## Example use case
+/// ```
+/// let result = solve_scalar_ivp(
+///     |_t, y| -y,
+///     (0.0, 1.0),
+///     1.0,
+///     &IvpMethod::Rk4 { step_size: 0.01 },
+/// );
+/// let y = &result.solution.components[0];
+/// ```
+///
+pub fn solve_scalar_ivp<F>(
+    f: F,
+    t_span: (f64, f64),
+    initial_value: f64,
+    method: &IvpMethod,
+) -> IvpResult
+where
+    F: Fn(f64, f64) -> f64,
+{
+    match try_solve_scalar_ivp(f, t_span, initial_value, method) {
+        Ok(ivp_result) => ivp_result,
+        Err(error) => panic!("IVP solve failed. {}", error),
+    }
+}
+
+/// # Try solve scalar IVP
+///
+/// ## Description
+/// `try_solve_scalar_ivp` is the fallible version of `solve_scalar_ivp`.
+///
+/// ## Example use case
+/// ```
+/// let result = try_solve_scalar_ivp(|_t, y| -y, (0.0, 1.0), 1.0, &method)?;
+/// ```
+///
+pub fn try_solve_scalar_ivp<F>(
+    f: F,
+    t_span: (f64, f64),
+    initial_value: f64,
+    method: &IvpMethod,
+) -> Result<IvpResult, NumericalMethodsError>
+where
+    F: Fn(f64, f64) -> f64,
+{
+    try_solve_ivp(|t, y| vec![f(t, y[0])], t_span, &[initial_value], method)
+}
+
+/// # Validate IVP
+///
+/// ## Description
+/// `validate_ivp` checks that `t_span` is a finite, increasing time interval,
+/// that `initial_state` is not empty, and that `f` returns a vector of the
+/// same length as the state. It returns the initial derivative
+/// `f`(t_0, `initial_state`).
+///
+pub(crate) fn validate_ivp<F>(
+    f: &F,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+) -> Result<Vec<f64>, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let (start_time, end_time) = t_span;
+    if !(start_time.is_finite() && end_time.is_finite())
+        || start_time >= end_time
+    {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The time interval must be finite, with the end time greater than the start time."
+                .to_string(),
+        ));
+    }
+
+    if initial_state.is_empty() {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The initial state must have at least one component.".to_string(),
+        ));
+    }
+
+    let initial_derivative = f(start_time, initial_state);
+    if initial_derivative.len() != initial_state.len() {
+        return Err(NumericalMethodsError::LengthMismatch {
+            expected: initial_state.len(),
+            actual: initial_derivative.len(),
+        });
+    }
+
+    Ok(initial_derivative)
+}
+
+/// # Validate adaptive step options
+///
+/// ## Description
+/// `validate_adaptive_step_options` checks that the tolerances are
+/// non-negative and not both zero, and that the step size limits are
+/// positive.
+///
+pub(crate) fn validate_adaptive_step_options(
+    options: &AdaptiveStepOptions,
+) -> Result<(), NumericalMethodsError> {
+    validate_tolerances(
+        options.absolute_tolerance,
+        options.relative_tolerance,
+    )?;
+
+    let step_sizes_valid = options.max_step_size > 0.0
+        && options.min_step_size > 0.0
+        && options
+            .initial_step_size
+            .is_none_or(|step_size| step_size > 0.0 && step_size.is_finite());
+    if !step_sizes_valid {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The step sizes must be positive.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// # Error norm
+///
+/// ## Description
+/// `error_norm` returns the root mean square over the components of
+/// `error[i]` / (atol + rtol * max(|`state[i]`|, |`new_state[i]`|)). A step
+/// whose error norm is at most 1 meets the tolerances.
+///
+pub(crate) fn error_norm(
+    error: &[f64],
+    state: &[f64],
+    new_state: &[f64],
+    options: &AdaptiveStepOptions,
+) -> f64 {
+    let sum_of_squares: f64 = error
+        .iter()
+        .zip(state.iter().zip(new_state))
+        .map(|(e, (y, y_new))| {
+            let scale = options.absolute_tolerance
+                + options.relative_tolerance * y.abs().max(y_new.abs());
+            (e / scale).powi(2)
+        })
+        .sum();
+    (sum_of_squares / error.len() as f64).sqrt()
+}
+
+/// # Initial step size
+///
+/// ## Description
+/// `initial_step_size` returns the size of the first trial step of an
+/// adaptive method of order `order`, using the algorithm of Hairer, Nørsett
+/// and Wanner, which estimates the step size from the sizes of the initial
+/// state and its first two derivatives. It evaluates `f` once.
+///
+pub(crate) fn initial_step_size<F>(
+    f: &F,
+    start_time: f64,
+    initial_state: &[f64],
+    initial_derivative: &[f64],
+    order: i32,
+    options: &AdaptiveStepOptions,
+) -> f64
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let zeros = vec![0.0; initial_state.len()];
+    let state_norm = error_norm(initial_state, initial_state, &zeros, options);
+    let derivative_norm =
+        error_norm(initial_derivative, initial_state, &zeros, options);
+
+    let first_guess = if state_norm < 1e-5 || derivative_norm < 1e-5 {
+        1e-6
+    } else {
+        0.01 * state_norm / derivative_norm
+    };
+
+    let trial_state: Vec<f64> = initial_state
+        .iter()
+        .zip(initial_derivative)
+        .map(|(y, dy)| y + first_guess * dy)
+        .collect();
+    let derivative_change: Vec<f64> = f(start_time + first_guess, &trial_state)
+        .iter()
+        .zip(initial_derivative)
+        .map(|(df, dy)| df - dy)
+        .collect();
+    let second_derivative_norm =
+        error_norm(&derivative_change, initial_state, &zeros, options)
+            / first_guess;
+
+    let largest_norm = derivative_norm.max(second_derivative_norm);
+    let second_guess = if largest_norm <= 1e-15 {
+        (first_guess * 1e-3).max(1e-6)
+    } else {
+        (0.01 / largest_norm).powf(1.0 / (order + 1) as f64)
+    };
+
+    (100.0 * first_guess).min(second_guess)
+}
+
+/// # Integrate fixed step
+///
+/// ## Description
+/// `integrate_fixed_step` integrates y' = `f`(t, y) over `t_span` with equal
+/// steps of at most `step_size`. `step(f, t, y, f(t, y), h)` returns the
+/// state after a step of size h, and the number of evaluations of `f` it
+/// used. The dense output is the cubic Hermite interpolant of each step.
+///
+fn integrate_fixed_step<F, S>(
+    f: &F,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    initial_derivative: Vec<f64>,
+    step_size: f64,
+    step: S,
+) -> Result<Trajectory, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+    S: Fn(&F, f64, &[f64], &[f64], f64) -> (Vec<f64>, usize),
+{
+    if !(step_size > 0.0 && step_size.is_finite()) {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The step size must be positive and finite.".to_string(),
+        ));
+    }
+
+    let (start_time, end_time) = t_span;
+    let num_steps = ((end_time - start_time) / step_size - 1e-10)
+        .ceil()
+        .max(1.0) as usize;
+    let h = (end_time - start_time) / num_steps as f64;
+
+    let mut trajectory = Trajectory::new(start_time, initial_state);
+    let mut state = initial_state.to_vec();
+    let mut derivative = initial_derivative;
+    trajectory.function_evaluations = 1;
+
+    for n in 0..num_steps {
+        let t = start_time + n as f64 * h;
+        let new_time = if n + 1 == num_steps {
+            end_time
+        } else {
+            start_time + (n + 1) as f64 * h
+        };
+
+        let (new_state, evaluations) = step(f, t, &state, &derivative, h);
+        let new_derivative = f(new_time, &new_state);
+        trajectory.function_evaluations += evaluations + 1;
+
+        let segment = DenseSegment::hermite(
+            t,
+            new_time - t,
+            &state,
+            &derivative,
+            &new_state,
+            &new_derivative,
+        );
+        trajectory.accept_step(new_time, new_state.clone(), segment);
+        state = new_state;
+        derivative = new_derivative;
+    }
+
+    Ok(trajectory)
+}
+
+/// # RK4 step
+///
+/// ## Description
+/// `rk4_step` takes a classical fourth order Runge-Kutta step of size `h`
+/// from the state `y` at time `t`, given the derivative `derivative` =
+/// `f`(t, y). It evaluates `f` three more times. It is also used by the
+/// shooting methods to integrate across each grid cell.
+///
+pub(crate) fn rk4_step<F>(
+    f: &F,
+    t: f64,
+    y: &[f64],
+    derivative: &[f64],
+    h: f64,
+) -> (Vec<f64>, usize)
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let shifted = |k: &[f64], scale: f64| -> Vec<f64> {
+        y.iter().zip(k).map(|(y, k)| y + scale * k).collect()
+    };
+
+    let k1 = derivative;
+    let k2 = f(t + 0.5 * h, &shifted(k1, 0.5 * h));
+    let k3 = f(t + 0.5 * h, &shifted(&k2, 0.5 * h));
+    let k4 = f(t + h, &shifted(&k3, h));
+
+    let new_state = (0..y.len())
+        .map(|i| y[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+        .collect();
+    (new_state, 3)
+}
+
+// The Butcher tableau of the Dormand-Prince method. The fifth order weights
+// are the last row of DP_A, which makes the method "first same as last": the
+// final stage of each step is the first stage of the next.
+const DP_C: [f64; 7] =
+    [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const DP_A: [[f64; 6]; 7] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+        0.0,
+        0.0,
+    ],
+    [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+        0.0,
+    ],
+    [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+
+// The difference between the fifth and fourth order weights, which gives the
+// local error estimate.
+const DP_E: [f64; 7] = [
+    -71.0 / 57600.0,
+    0.0,
+    71.0 / 16695.0,
+    -71.0 / 1920.0,
+    17253.0 / 339200.0,
+    -22.0 / 525.0,
+    1.0 / 40.0,
+];
+
+// The coefficients of θ, θ^2, θ^3 and θ^4 in the weights of the fourth order
+// continuous extension of the Dormand-Prince method (Shampine, 1986).
+const DP_DENSE: [[f64; 4]; 7] = [
+    [
+        1.0,
+        -8048581381.0 / 2820520608.0,
+        8663915743.0 / 2820520608.0,
+        -12715105075.0 / 11282082432.0,
+    ],
+    [0.0, 0.0, 0.0, 0.0],
+    [
+        0.0,
+        131558114200.0 / 32700410799.0,
+        -68118460800.0 / 10900136933.0,
+        87487479700.0 / 32700410799.0,
+    ],
+    [
+        0.0,
+        -1754552775.0 / 470086768.0,
+        14199869525.0 / 1410260304.0,
+        -10690763975.0 / 1880347072.0,
+    ],
+    [
+        0.0,
+        127303824393.0 / 49829197408.0,
+        -318862633887.0 / 49829197408.0,
+        701980252875.0 / 199316789632.0,
+    ],
+    [
+        0.0,
+        -282668133.0 / 205662961.0,
+        2019193451.0 / 616988883.0,
+        -1453857185.0 / 822651844.0,
+    ],
+    [
+        0.0,
+        40617522.0 / 29380423.0,
+        -110615467.0 / 29380423.0,
+        69997945.0 / 29380423.0,
+    ],
+];
+
+/// # Dormand-Prince
+///
+/// ## Description
+/// `dormand_prince` integrates y' = `f`(t, y) over `t_span` with adaptive
+/// Dormand-Prince steps. After each step, the step size is multiplied by
+/// 0.9 * err^(-1/5), limited to between 0.2 and 10 (and to at most 1 just
+/// after a rejected step), where err is the error norm of the step.
+///
+fn dormand_prince<F>(
+    f: &F,
+    t_span: (f64, f64),
+    initial_state: &[f64],
+    initial_derivative: Vec<f64>,
+    options: &AdaptiveStepOptions,
+) -> Result<Trajectory, NumericalMethodsError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    validate_adaptive_step_options(options)?;
+
+    let (start_time, end_time) = t_span;
+    let num_components = initial_state.len();
+    let mut trajectory = Trajectory::new(start_time, initial_state);
+    trajectory.function_evaluations = 1;
+
+    let mut h = match options.initial_step_size {
+        Some(step_size) => step_size,
+        None => {
+            trajectory.function_evaluations += 1;
+            initial_step_size(
+                f,
+                start_time,
+                initial_state,
+                &initial_derivative,
+                4,
+                options,
+            )
+        }
+    };
+
+    let mut t = start_time;
+    let mut state = initial_state.to_vec();
+    let mut stages: Vec<Vec<f64>> = vec![initial_derivative];
+    let mut previous_step_rejected = false;
+
+    while t < end_time {
+        if trajectory.dense_output.len() + trajectory.rejected_steps
+            >= options.max_steps
+        {
+            return Err(NumericalMethodsError::MaxStepsExceeded {
+                max_steps: options.max_steps,
+                time: t,
+            });
+        }
+
+        h = h.min(options.max_step_size);
+        let min_step_size =
+            options.min_step_size.max(10.0 * f64::EPSILON * t.abs());
+        if h < min_step_size {
+            return Err(NumericalMethodsError::StepSizeTooSmall {
+                time: t,
+                step_size: h,
+            });
+        }
+
+        // Lands exactly on the end time, rather than stepping just short of
+        // it or past it, unless that would exceed the maximum step size.
+        let last_step =
+            t + 1.01 * h >= end_time && end_time - t <= options.max_step_size;
+        if last_step {
+            h = end_time - t;
+        }
+
+        stages.truncate(1);
+        for s in 1..7 {
+            let stage_state: Vec<f64> = (0..num_components)
+                .map(|i| {
+                    state[i]
+                        + h * (0..s)
+                            .map(|j| DP_A[s][j] * stages[j][i])
+                            .sum::<f64>()
+                })
+                .collect();
+            stages.push(f(t + DP_C[s] * h, &stage_state));
+        }
+        trajectory.function_evaluations += 6;
+
+        // The fifth order solution is the state of the final stage.
+        let new_state: Vec<f64> = (0..num_components)
+            .map(|i| {
+                state[i]
+                    + h * (0..6).map(|j| DP_A[6][j] * stages[j][i]).sum::<f64>()
+            })
+            .collect();
+        let error: Vec<f64> = (0..num_components)
+            .map(|i| h * (0..7).map(|j| DP_E[j] * stages[j][i]).sum::<f64>())
+            .collect();
+        let error_norm = error_norm(&error, &state, &new_state, options);
+
+        if error_norm <= 1.0 {
+            let coefficients = (0..num_components)
+                .map(|i| {
+                    let mut coefficients = [0.0; 4];
+                    for (k, coefficient) in coefficients.iter_mut().enumerate()
+                    {
+                        *coefficient = h
+                            * (0..7)
+                                .map(|j| DP_DENSE[j][k] * stages[j][i])
+                                .sum::<f64>();
+                    }
+                    coefficients
+                })
+                .collect();
+            let segment = DenseSegment {
+                start_time: t,
+                step_size: h,
+                start_state: state,
+                coefficients,
+            };
+
+            t = if last_step { end_time } else { t + h };
+            trajectory.accept_step(t, new_state.clone(), segment);
+            state = new_state;
+            stages.swap(0, 6);
+
+            let mut factor = if error_norm == 0.0 {
+                10.0
+            } else {
+                (0.9 * error_norm.powf(-0.2)).clamp(0.2, 10.0)
+            };
+            if previous_step_rejected {
+                factor = factor.min(1.0);
+            }
+            h *= factor;
+            previous_step_rejected = false;
+        } else {
+            // NaN error norms are rejected with the smallest factor.
+            h *= (0.9 * error_norm.powf(-0.2)).max(0.2);
+            trajectory.rejected_steps += 1;
+            previous_step_rejected = true;
+        }
+    }
+
+    Ok(trajectory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{max_cell_width, max_error};
+
+    #[test]
+    fn test_fixed_step_orders() {
+        let exact = |t: f64| (-t).exp();
+        let error = |method: IvpMethod| {
+            let result = solve_scalar_ivp(|_t, y| -y, (0.0, 2.0), 1.0, &method);
+            max_error(&result.solution.components[0], exact)
+        };
+
+        let coarse_error = error(IvpMethod::ExplicitEuler { step_size: 0.02 });
+        let fine_error = error(IvpMethod::ExplicitEuler { step_size: 0.01 });
+        assert!((coarse_error / fine_error - 2.0).abs() < 0.1);
+
+        let coarse_error = error(IvpMethod::Rk4 { step_size: 0.2 });
+        let fine_error = error(IvpMethod::Rk4 { step_size: 0.1 });
+        assert!((coarse_error / fine_error - 16.0).abs() < 2.0);
+
+        // Steps which do not divide the time interval are shortened.
+        let result = solve_scalar_ivp(
+            |_t, y| -y,
+            (0.0, 1.0),
+            1.0,
+            &IvpMethod::Rk4 { step_size: 0.3 },
+        );
+        assert_eq!(result.accepted_steps, 4);
+        assert_eq!(result.function_evaluations, 17);
+        assert_eq!(result.solution.grid().grid_points[4], 1.0);
+    }
+
+    #[test]
+    fn test_dormand_prince_vector_state() {
+        // The harmonic oscillator u'' = -u with u(0) = 1 and u'(0) = 0.
+        let result = solve_ivp(
+            |_t, y| vec![y[1], -y[0]],
+            (0.0, 10.0),
+            &[1.0, 0.0],
+            &IvpMethod::DormandPrince(AdaptiveStepOptions {
+                absolute_tolerance: 1e-10,
+                relative_tolerance: 1e-10,
+                ..Default::default()
+            }),
+        );
+
+        assert_eq!(result.solution.num_components(), 2);
+        assert!(max_error(&result.solution.components[0], f64::cos) < 1e-8);
+        assert!(max_error(&result.solution.components[1], |t| -t.sin()) < 1e-8);
+        assert_eq!(result.solution.grid().grid_points.last(), Some(&10.0));
+        assert_eq!(
+            result.function_evaluations,
+            2 + 6 * (result.accepted_steps + result.rejected_steps)
+        );
+
+        // The accepted steps are not all the same size.
+        let points = &result.solution.grid().grid_points;
+        let widths: Vec<f64> = points.windows(2).map(|w| w[1] - w[0]).collect();
+        let (min_width, max_width) = widths
+            .iter()
+            .fold((f64::INFINITY, 0.0_f64), |(min, max), &w| {
+                (min.min(w), max.max(w))
+            });
+        assert!(max_width > 1.5 * min_width);
+
+        // Dense output between the steps is accurate too.
+        for i in 0..100 {
+            let t = 0.1 * i as f64 + 0.037;
+            let state = result.evaluate(t);
+            assert!((state[0] - t.cos()).abs() < 1e-8, "{}", t);
+            assert!((state[1] + t.sin()).abs() < 1e-8, "{}", t);
+        }
+    }
+
+    #[test]
+    fn test_dense_output_order() {
+        // The dense output of large steps converges at fourth order for
+        // Dormand-Prince, and third order for the Hermite interpolant.
+        let exact = |t: f64| (-t).exp();
+        let dense_error = |method: IvpMethod| {
+            let result = solve_scalar_ivp(|_t, y| -y, (0.0, 1.0), 1.0, &method);
+            (0..result.accepted_steps)
+                .map(|n| {
+                    let points = &result.solution.grid().grid_points;
+                    let t = points[n] + 0.3 * (points[n + 1] - points[n]);
+                    (result.evaluate(t)[0] - exact(t)).abs()
+                })
+                .fold(0.0, f64::max)
+        };
+        let fixed_dormand_prince = |step_size| {
+            IvpMethod::DormandPrince(AdaptiveStepOptions {
+                absolute_tolerance: 1.0,
+                relative_tolerance: 1.0,
+                initial_step_size: Some(step_size),
+                max_step_size: step_size,
+                ..Default::default()
+            })
+        };
+
+        let ratio = dense_error(fixed_dormand_prince(0.1))
+            / dense_error(fixed_dormand_prince(0.05));
+        assert!(ratio > 24.0, "Dormand-Prince ratio {}.", ratio);
+        let ratio = dense_error(IvpMethod::Rk4 { step_size: 0.1 })
+            / dense_error(IvpMethod::Rk4 { step_size: 0.05 });
+        assert!(ratio > 6.0, "Hermite ratio {}.", ratio);
+    }
+
+    #[test]
+    fn test_dormand_prince_rejects_large_steps() {
+        // The solution follows cos(t) after a fast transient.
+        let result = solve_scalar_ivp(
+            |t, y| -50.0 * (y - t.cos()),
+            (0.0, 2.0),
+            0.0,
+            &IvpMethod::DormandPrince(AdaptiveStepOptions {
+                initial_step_size: Some(1.0),
+                ..Default::default()
+            }),
+        );
+        assert!(result.rejected_steps > 0);
+        let y_end = result.solution.components[0].function_values
+            [result.accepted_steps];
+        let exact = (2500.0 * 2.0_f64.cos() + 50.0 * 2.0_f64.sin()
+            - 2500.0 * (-100.0_f64).exp())
+            / 2501.0;
+        assert!((y_end - exact).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_final_step_respects_max_step_size() {
+        // Stretching the first step to reach the end time would exceed the
+        // maximum step size, so a second step is taken instead.
+        let result = solve_scalar_ivp(
+            |_t, y| -y,
+            (0.0, 0.1005),
+            1.0,
+            &IvpMethod::DormandPrince(AdaptiveStepOptions {
+                initial_step_size: Some(0.1),
+                max_step_size: 0.1,
+                ..Default::default()
+            }),
+        );
+        let grid = &result.solution.components[0].grid;
+        assert!(max_cell_width(grid) <= 0.1);
+        assert_eq!(grid.grid_points.last(), Some(&0.1005));
+    }
+
+    #[test]
+    fn test_try_solve_ivp_errors() {
+        let f = |_t: f64, y: &[f64]| vec![-y[0]];
+        let method = IvpMethod::DormandPrince(AdaptiveStepOptions::default());
+
+        assert!(matches!(
+            try_solve_ivp(f, (1.0, 0.0), &[1.0], &method),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_solve_ivp(f, (0.0, 1.0), &[], &method),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_solve_ivp(
+                f,
+                (0.0, 1.0),
+                &[1.0],
+                &IvpMethod::Rk4 { step_size: 0.0 }
+            ),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert_eq!(
+            try_solve_ivp(f, (0.0, 1.0), &[1.0, 2.0], &method),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert!(matches!(
+            try_solve_ivp(
+                f,
+                (0.0, 1.0),
+                &[1.0],
+                &IvpMethod::DormandPrince(AdaptiveStepOptions {
+                    max_step_size: 0.01,
+                    max_steps: 5,
+                    ..Default::default()
+                })
+            ),
+            Err(NumericalMethodsError::MaxStepsExceeded { max_steps: 5, .. })
+        ));
+        assert!(matches!(
+            try_solve_ivp(
+                f,
+                (0.0, 1.0),
+                &[1.0],
+                &IvpMethod::DormandPrince(AdaptiveStepOptions {
+                    min_step_size: 0.0,
+                    ..Default::default()
+                })
+            ),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+
+        // The solution of y' = y^2 with y(0) = 1 blows up at t = 1.
+        assert!(matches!(
+            try_solve_ivp(
+                |_t, y| vec![y[0] * y[0]],
+                (0.0, 2.0),
+                &[1.0],
+                &method
+            ),
+            Err(NumericalMethodsError::StepSizeTooSmall { .. })
+        ));
+
+        let result = solve_ivp(f, (0.0, 1.0), &[1.0], &method);
+        assert!(matches!(
+            result.try_evaluate(1.5),
+            Err(NumericalMethodsError::OutOfDomain { .. })
+        ));
+    }
+}
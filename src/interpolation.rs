@@ -0,0 +1,592 @@
+use crate::cubic_spline::{CubicSpline, SplineEndCondition};
+use crate::error::NumericalMethodsError;
+use crate::grid::{Grid, GRID_TOLERANCE};
+use crate::grid_function::GridFunction;
+use crate::quadratic_interpolation::quadratic_interpolation_coefficients;
+
+/// # Interpolation method
+///
+/// ## Description
+/// `InterpolationMethod` specifies how a `GridFunction` is evaluated between
+/// its grid points.
+///
+/// - `PiecewiseLinear` joins neighbouring function values with straight lines.
+/// - `PiecewiseQuadratic` fits a quadratic through each grid cell and the next
+///   grid point (the previous grid point for the last grid cell), using
+///   `quadratic_interpolation_coefficients`. It needs at least 3 grid points.
+/// - `CubicSpline` uses a `CubicSpline` with the given end condition.
+/// - `Pchip` uses the monotone piecewise cubic Hermite interpolant of Fritsch
+///   and Carlson, which does not overshoot the data.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterpolationMethod {
+    PiecewiseLinear,
+    PiecewiseQuadratic,
+    CubicSpline(SplineEndCondition),
+    Pchip,
+}
+
+impl GridFunction {
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the value at the point `x` of the interpolant of the
+    /// current `GridFunction` given by `method`.
+    ///
+    /// The grid cell containing `x` is found by binary search, so `evaluate`
+    /// takes O(log n) operations for the piecewise methods. The cubic spline
+    /// and PCHIP methods first compute their slopes in O(n) operations; use
+    /// `evaluate_many` to evaluate them at many points.
+    ///
+    /// If `x` lies outside the `Grid` of the current `GridFunction`, or there
+    /// are too few grid points for `method`, `evaluate` panics. See
+    /// `try_evaluate` for a version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// Suppose that we have a `GridFunction` `grid_func` and we want to
+    /// evaluate it at x = 0.55 using piecewise linear interpolation. The code
+    /// below does this.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+    /// let value =
+    ///     grid_func.evaluate(0.55, InterpolationMethod::PiecewiseLinear);
+    /// ```
+    ///
+    pub fn evaluate(&self, x: f64, method: InterpolationMethod) -> f64 {
+        match self.try_evaluate(x, method) {
+            Ok(value) => value,
+            Err(error) => panic!("Evaluation failed. {}", error),
+        }
+    }
+
+    /// # Try evaluate
+    ///
+    /// ## Description
+    /// `try_evaluate` is the fallible version of `evaluate`. It returns an
+    /// error if the current `GridFunction` is not valid, if it has too few
+    /// grid points for `method`, or if `x` lies outside its `Grid`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let value = grid_func.try_evaluate(0.55, InterpolationMethod::Pchip)?;
+    /// ```
+    ///
+    pub fn try_evaluate(
+        &self,
+        x: f64,
+        method: InterpolationMethod,
+    ) -> Result<f64, NumericalMethodsError> {
+        Interpolant::try_new(self, method)?.try_evaluate(x)
+    }
+
+    /// # Evaluate many
+    ///
+    /// ## Description
+    /// `evaluate_many` returns the values at each of the points `points` of
+    /// the interpolant of the current `GridFunction` given by `method`. The
+    /// interpolant is only constructed once, so this is faster than calling
+    /// `evaluate` at each point.
+    ///
+    /// If any of `points` lie outside the `Grid` of the current
+    /// `GridFunction`, or there are too few grid points for `method`,
+    /// `evaluate_many` panics. See `try_evaluate_many` for a version which
+    /// returns an error instead.
+    ///
+    /// ## Example use case
+    /// Suppose that we have a `GridFunction` `grid_func` and we want to
+    /// evaluate its natural cubic spline interpolant at x = 0.25 and x = 0.75.
+    /// The code below does this.
+    /// ```
+    /// let values = grid_func.evaluate_many(
+    ///     &[0.25, 0.75],
+    ///     InterpolationMethod::CubicSpline(SplineEndCondition::Natural),
+    /// );
+    /// ```
+    ///
+    pub fn evaluate_many(
+        &self,
+        points: &[f64],
+        method: InterpolationMethod,
+    ) -> Vec<f64> {
+        match self.try_evaluate_many(points, method) {
+            Ok(values) => values,
+            Err(error) => panic!("Evaluation failed. {}", error),
+        }
+    }
+
+    /// # Try evaluate many
+    ///
+    /// ## Description
+    /// `try_evaluate_many` is the fallible version of `evaluate_many`. It
+    /// returns an error if the current `GridFunction` is not valid, if it has
+    /// too few grid points for `method`, or if any of `points` lie outside its
+    /// `Grid`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let values = grid_func
+    ///     .try_evaluate_many(&[0.25, 0.75], InterpolationMethod::Pchip)?;
+    /// ```
+    ///
+    pub fn try_evaluate_many(
+        &self,
+        points: &[f64],
+        method: InterpolationMethod,
+    ) -> Result<Vec<f64>, NumericalMethodsError> {
+        let interpolant = Interpolant::try_new(self, method)?;
+        points
+            .iter()
+            .map(|&x| interpolant.try_evaluate(x))
+            .collect()
+    }
+}
+
+/// # Interpolant
+///
+/// ## Description
+/// `Interpolant` stores everything needed to evaluate a `GridFunction` with a
+/// particular `InterpolationMethod`, so that the slopes of the cubic methods
+/// are only computed once.
+///
+pub(crate) enum Interpolant<'a> {
+    PiecewiseLinear(&'a GridFunction),
+    PiecewiseQuadratic(&'a GridFunction),
+    CubicSpline(CubicSpline),
+    Pchip {
+        grid_func: &'a GridFunction,
+        derivatives: Vec<f64>,
+    },
+}
+
+impl<'a> Interpolant<'a> {
+    pub(crate) fn try_new(
+        grid_func: &'a GridFunction,
+        method: InterpolationMethod,
+    ) -> Result<Self, NumericalMethodsError> {
+        match method {
+            InterpolationMethod::PiecewiseLinear => {
+                grid_func.validate_with_min_points(2)?;
+                Ok(Interpolant::PiecewiseLinear(grid_func))
+            }
+            InterpolationMethod::PiecewiseQuadratic => {
+                grid_func.validate_with_min_points(3)?;
+                Ok(Interpolant::PiecewiseQuadratic(grid_func))
+            }
+            InterpolationMethod::CubicSpline(end_condition) => {
+                Ok(Interpolant::CubicSpline(CubicSpline::try_new(
+                    grid_func,
+                    end_condition,
+                )?))
+            }
+            InterpolationMethod::Pchip => {
+                grid_func.validate_with_min_points(2)?;
+                Ok(Interpolant::Pchip {
+                    grid_func,
+                    derivatives: pchip_derivatives(grid_func),
+                })
+            }
+        }
+    }
+
+    pub(crate) fn try_evaluate(
+        &self,
+        x: f64,
+    ) -> Result<f64, NumericalMethodsError> {
+        match self {
+            Interpolant::PiecewiseLinear(grid_func) => {
+                let (i, x) = locate_cell(&grid_func.grid, x)?;
+                let grid_points = &grid_func.grid.grid_points;
+                let function_values = &grid_func.function_values;
+
+                let weight = (x - grid_points[i])
+                    / (grid_points[i + 1] - grid_points[i]);
+                Ok((1.0 - weight) * function_values[i]
+                    + weight * function_values[i + 1])
+            }
+            Interpolant::PiecewiseQuadratic(grid_func) => {
+                let (i, x) = locate_cell(&grid_func.grid, x)?;
+                let grid_points = &grid_func.grid.grid_points;
+                let function_values = &grid_func.function_values;
+
+                // Uses the next grid point, or the previous grid point for the
+                // last grid cell.
+                let j = i.min(grid_points.len() - 3);
+
+                // The quadratic is fitted relative to grid_points[j] to
+                // reduce rounding errors far from the origin.
+                let origin = grid_points[j];
+                let quadratic = quadratic_interpolation_coefficients(
+                    (
+                        0.0,
+                        grid_points[j + 1] - origin,
+                        grid_points[j + 2] - origin,
+                    ),
+                    (
+                        function_values[j],
+                        function_values[j + 1],
+                        function_values[j + 2],
+                    ),
+                );
+                Ok(quadratic.evaluate(x - origin))
+            }
+            Interpolant::CubicSpline(spline) => spline.try_evaluate(x),
+            Interpolant::Pchip {
+                grid_func,
+                derivatives,
+            } => {
+                let (i, x) = locate_cell(&grid_func.grid, x)?;
+                let grid_points = &grid_func.grid.grid_points;
+                let function_values = &grid_func.function_values;
+
+                // Evaluates the cubic Hermite basis functions.
+                let step_size = grid_points[i + 1] - grid_points[i];
+                let t = (x - grid_points[i]) / step_size;
+                let h00 = (1.0 + 2.0 * t) * (1.0 - t).powi(2);
+                let h10 = t * (1.0 - t).powi(2);
+                let h01 = t * t * (3.0 - 2.0 * t);
+                let h11 = t * t * (t - 1.0);
+
+                Ok(h00 * function_values[i]
+                    + h10 * step_size * derivatives[i]
+                    + h01 * function_values[i + 1]
+                    + h11 * step_size * derivatives[i + 1])
+            }
+        }
+    }
+}
+
+/// # Locate cell
+///
+/// ## Description
+/// `locate_cell` finds the grid cell of `grid` containing the point `x` by
+/// binary search, and returns its index together with `x` clamped to the
+/// domain of `grid`.
+///
+/// Points within a small tolerance of the ends of `grid` are treated as lying
+/// on the ends, to allow for rounding errors. An `OutOfDomain` error is
+/// returned for points further outside `grid`, and an `InsufficientPoints`
+/// error is returned if `grid` has fewer than 2 grid points.
+///
+pub(crate) fn locate_cell(
+    grid: &Grid,
+    x: f64,
+) -> Result<(usize, f64), NumericalMethodsError> {
+    let grid_points = &grid.grid_points;
+    let num_points = grid_points.len();
+    if num_points < 2 {
+        return Err(NumericalMethodsError::InsufficientPoints {
+            required: 2,
+            actual: num_points,
+        });
+    }
+
+    let (lower, upper) = (grid_points[0], grid_points[num_points - 1]);
+    let tolerance = GRID_TOLERANCE * (upper - lower).max(1.0);
+    let out_of_domain = NumericalMethodsError::OutOfDomain {
+        point: x,
+        lower,
+        upper,
+    };
+
+    // The comparisons are written so that NaN is out of the domain.
+    if !(x >= lower - tolerance && x <= upper + tolerance) {
+        return Err(out_of_domain);
+    }
+
+    let x = x.clamp(lower, upper);
+    match grid.find_cell(x) {
+        Some(i) => Ok((i, x)),
+        None => Err(out_of_domain),
+    }
+}
+
+/// # PCHIP derivatives
+///
+/// ## Description
+/// `pchip_derivatives` returns the derivatives at the grid points used by the
+/// monotone piecewise cubic Hermite interpolant of `grid_func`.
+///
+/// At an interior grid point, the derivative is zero if the data has a local
+/// extremum there, and otherwise is the weighted harmonic mean of the slopes
+/// of the neighbouring grid cells. At the ends, a one-sided three-point
+/// estimate is used, limited so that the interpolant stays monotone.
+///
+/// `grid_func` must have at least 2 grid points.
+///
+fn pchip_derivatives(grid_func: &GridFunction) -> Vec<f64> {
+    let grid_points = &grid_func.grid.grid_points;
+    let function_values = &grid_func.function_values;
+    let num_points = grid_points.len();
+
+    let step_sizes: Vec<f64> = grid_points
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect();
+    let slopes: Vec<f64> = function_values
+        .windows(2)
+        .zip(step_sizes.iter())
+        .map(|(pair, step_size)| (pair[1] - pair[0]) / step_size)
+        .collect();
+
+    // With only one grid cell, the interpolant is a straight line.
+    if num_points == 2 {
+        return vec![slopes[0]; 2];
+    }
+
+    let mut derivatives = vec![0.0; num_points];
+    for i in 1..(num_points - 1) {
+        if slopes[i - 1] * slopes[i] > 0.0 {
+            let weight_1 = 2.0 * step_sizes[i] + step_sizes[i - 1];
+            let weight_2 = step_sizes[i] + 2.0 * step_sizes[i - 1];
+            derivatives[i] = (weight_1 + weight_2)
+                / (weight_1 / slopes[i - 1] + weight_2 / slopes[i]);
+        }
+    }
+
+    derivatives[0] = pchip_end_derivative(
+        step_sizes[0],
+        step_sizes[1],
+        slopes[0],
+        slopes[1],
+    );
+    derivatives[num_points - 1] = pchip_end_derivative(
+        step_sizes[num_points - 2],
+        step_sizes[num_points - 3],
+        slopes[num_points - 2],
+        slopes[num_points - 3],
+    );
+
+    derivatives
+}
+
+/// # PCHIP end derivative
+///
+/// ## Description
+/// `pchip_end_derivative` returns the derivative at an end of the grid from
+/// the widths and slopes of the end grid cell and its neighbour.
+///
+fn pchip_end_derivative(
+    end_step_size: f64,
+    next_step_size: f64,
+    end_slope: f64,
+    next_slope: f64,
+) -> f64 {
+    let derivative = ((2.0 * end_step_size + next_step_size) * end_slope
+        - end_step_size * next_slope)
+        / (end_step_size + next_step_size);
+
+    if derivative * end_slope <= 0.0 {
+        0.0
+    } else if end_slope * next_slope < 0.0
+        && derivative.abs() > 3.0 * end_slope.abs()
+    {
+        3.0 * end_slope
+    } else {
+        derivative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::test_grids;
+
+    const ALL_METHODS: [InterpolationMethod; 5] = [
+        InterpolationMethod::PiecewiseLinear,
+        InterpolationMethod::PiecewiseQuadratic,
+        InterpolationMethod::CubicSpline(SplineEndCondition::Natural),
+        InterpolationMethod::CubicSpline(SplineEndCondition::Clamped {
+            start_derivative: 2.0,
+            end_derivative: 2.0,
+        }),
+        InterpolationMethod::Pchip,
+    ];
+
+    #[test]
+    fn test_all_methods_reproduce_linear_functions() {
+        let sample_points = [-1.0, -0.61, 0.0, 0.33, 0.999, 2.0];
+        for (name, grid) in test_grids(-1.0, 2.0, 13) {
+            let grid_func =
+                GridFunction::new_grid_function(&grid, |x| 2.0 * x + 1.0);
+            for method in ALL_METHODS {
+                let values = grid_func.evaluate_many(&sample_points, method);
+                for (&x, value) in sample_points.iter().zip(values) {
+                    assert!(
+                        (value - (2.0 * x + 1.0)).abs() < 1e-10,
+                        "{:?} failed on the {} grid.",
+                        method,
+                        name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_methods_interpolate_grid_points() {
+        for (name, grid) in test_grids(0.0, 1.0, 9) {
+            let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+            for method in ALL_METHODS {
+                let values = grid_func.evaluate_many(&grid.grid_points, method);
+                for (value, y) in values.iter().zip(&grid_func.function_values)
+                {
+                    assert!(
+                        (value - y).abs() < 1e-12,
+                        "{:?} failed on the {} grid.",
+                        method,
+                        name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_piecewise_quadratic_reproduces_quadratics() {
+        let func = |x: f64| 3.0 * x * x - x + 2.0;
+        for (name, grid) in test_grids(10.0, 11.0, 8) {
+            let grid_func = GridFunction::new_grid_function(&grid, func);
+            for x in [10.0, 10.05, 10.5, 10.93, 11.0] {
+                let value = grid_func
+                    .evaluate(x, InterpolationMethod::PiecewiseQuadratic);
+                assert!(
+                    (value - func(x)).abs() < 1e-9,
+                    "Piecewise quadratic failed on the {} grid.",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_convergence_order() {
+        // Halving the grid spacing should reduce the maximum error by about
+        // 2^p for a method of order p.
+        let max_error = |num_points: usize, method: InterpolationMethod| {
+            let grid = Grid::new_uniform_grid(0.0, 1.0, num_points);
+            let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+            let sample_points: Vec<f64> =
+                (0..=200).map(|i| i as f64 / 200.0).collect();
+            grid_func
+                .evaluate_many(&sample_points, method)
+                .iter()
+                .zip(&sample_points)
+                .map(|(value, x)| (value - x.sin()).abs())
+                .fold(0.0, f64::max)
+        };
+
+        let cases = [
+            (InterpolationMethod::PiecewiseLinear, 2.0),
+            (InterpolationMethod::PiecewiseQuadratic, 3.0),
+            (
+                InterpolationMethod::CubicSpline(SplineEndCondition::Clamped {
+                    start_derivative: 1.0,
+                    end_derivative: 1.0_f64.cos(),
+                }),
+                4.0,
+            ),
+        ];
+        for (method, order) in cases {
+            let ratio = max_error(11, method) / max_error(21, method);
+            assert!(
+                ratio > 2.0_f64.powf(order - 0.5),
+                "{:?} converged with ratio {}.",
+                method,
+                ratio
+            );
+        }
+    }
+
+    #[test]
+    fn test_pchip_is_monotone() {
+        // A step-like function that makes splines overshoot.
+        let grid = Grid::new_grid_from_points(vec![
+            0.0, 1.0, 2.0, 2.5, 3.0, 3.1, 4.0, 6.0,
+        ]);
+        let grid_func = GridFunction::try_new_grid_function_from_values(
+            &grid,
+            vec![0.0, 0.0, 0.1, 0.9, 1.0, 1.0, 1.0, 2.0],
+        )
+        .unwrap();
+        let sample_points: Vec<f64> =
+            (0..=600).map(|i| i as f64 / 100.0).collect();
+        let values =
+            grid_func.evaluate_many(&sample_points, InterpolationMethod::Pchip);
+
+        for pair in values.windows(2) {
+            assert!(pair[1] >= pair[0] - 1e-14, "PCHIP is not monotone.");
+        }
+
+        // The natural cubic spline does overshoot this data.
+        let spline_values = grid_func.evaluate_many(
+            &sample_points,
+            InterpolationMethod::CubicSpline(SplineEndCondition::Natural),
+        );
+        assert!(spline_values.windows(2).any(|pair| pair[1] < pair[0]));
+    }
+
+    #[test]
+    fn test_binary_search_on_large_grid() {
+        let grid = Grid::new_geometric_grid(0.0, 1.0, 100_001, 1.00001);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x * x);
+        let sample_points: Vec<f64> =
+            (0..10_000).map(|i| i as f64 / 10_000.0).collect();
+        let values = grid_func.evaluate_many(
+            &sample_points,
+            InterpolationMethod::PiecewiseLinear,
+        );
+        for (x, value) in sample_points.iter().zip(values) {
+            assert!((value - x * x).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_try_evaluate_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+
+        for x in [-0.5, 1.5, f64::NAN] {
+            assert!(matches!(
+                grid_func.try_evaluate(x, InterpolationMethod::PiecewiseLinear),
+                Err(NumericalMethodsError::OutOfDomain { .. })
+            ));
+        }
+
+        // Points within rounding error of the ends are accepted.
+        assert_eq!(
+            grid_func.try_evaluate(1.0 + 1e-14, InterpolationMethod::Pchip),
+            Ok(1.0_f64.sin())
+        );
+
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 2);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func
+                .try_evaluate(0.5, InterpolationMethod::PiecewiseQuadratic),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            grid_func.try_evaluate_many(
+                &[0.5, 2.0],
+                InterpolationMethod::PiecewiseLinear
+            ),
+            Err(NumericalMethodsError::OutOfDomain {
+                point: 2.0,
+                lower: 0.0,
+                upper: 1.0
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Evaluation failed.")]
+    fn test_evaluate_panics() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        grid_func.evaluate(2.0, InterpolationMethod::PiecewiseLinear);
+    }
+}
@@ -0,0 +1,962 @@
+use crate::error::NumericalMethodsError;
+use nalgebra::{DMatrix, DVector, LU};
+
+/// # Solve tridiagonal system
+///
+/// ## Description
+/// `solve_tridiagonal` solves the system of linear equations `A * x = rhs`,
+/// where `A` is a tridiagonal matrix, using the Thomas algorithm. The Thomas
+/// algorithm is Gaussian elimination without pivoting, and takes O(n)
+/// operations for an n by n matrix.
+///
+/// `diagonal` contains the n diagonal elements of `A`. `lower` contains the
+/// n - 1 elements below the diagonal, so that `lower[i]` is the element in row
+/// i + 1 and column i. `upper` contains the n - 1 elements above the
+/// diagonal, so that `upper[i]` is the element in row i and column i + 1.
+///
+/// If a zero pivot is encountered, a `SingularMatrix` error is returned. The
+/// Thomas algorithm is stable for diagonally dominant matrices.
+///
+/// ## Example use case
+/// Suppose we want to solve the system
+/// [2 1 0] [x0]   [3]
+/// [1 2 1] [x1] = [4]
+/// [0 1 2] [x2]   [3]
+/// The code below does this.
+/// ```
+/// let solution = solve_tridiagonal(
+///     &[1.0, 1.0],
+///     &[2.0, 2.0, 2.0],
+///     &[1.0, 1.0],
+///     &[3.0, 4.0, 3.0],
+/// )?;
+/// ```
+///
+pub fn solve_tridiagonal(
+    lower: &[f64],
+    diagonal: &[f64],
+    upper: &[f64],
+    rhs: &[f64],
+) -> Result<Vec<f64>, NumericalMethodsError> {
+    let size = diagonal.len();
+
+    // Error handling - the diagonals must have consistent lengths.
+    if rhs.len() != size {
+        return Err(NumericalMethodsError::LengthMismatch {
+            expected: size,
+            actual: rhs.len(),
+        });
+    }
+    let off_diagonal_size = size.saturating_sub(1);
+    for off_diagonal in [lower, upper] {
+        if off_diagonal.len() != off_diagonal_size {
+            return Err(NumericalMethodsError::LengthMismatch {
+                expected: off_diagonal_size,
+                actual: off_diagonal.len(),
+            });
+        }
+    }
+
+    if size == 0 {
+        return Ok(vec![]);
+    }
+
+    // Forward sweep. modified_upper and modified_rhs store the coefficients
+    // of the upper triangular system produced by elimination.
+    let mut modified_upper = vec![0.0; off_diagonal_size];
+    let mut modified_rhs = vec![0.0; size];
+
+    let mut pivot = diagonal[0];
+    if pivot == 0.0 {
+        return Err(NumericalMethodsError::SingularMatrix);
+    }
+    modified_rhs[0] = rhs[0] / pivot;
+
+    for i in 1..size {
+        modified_upper[i - 1] = upper[i - 1] / pivot;
+        pivot = diagonal[i] - lower[i - 1] * modified_upper[i - 1];
+        if pivot == 0.0 || !pivot.is_finite() {
+            return Err(NumericalMethodsError::SingularMatrix);
+        }
+        modified_rhs[i] = (rhs[i] - lower[i - 1] * modified_rhs[i - 1]) / pivot;
+    }
+
+    // Back substitution.
+    let mut solution = modified_rhs;
+    for i in (0..off_diagonal_size).rev() {
+        solution[i] -= modified_upper[i] * solution[i + 1];
+    }
+
+    Ok(solution)
+}
+
+/// # Solve cyclic tridiagonal system
+///
+/// ## Description
+/// `solve_cyclic_tridiagonal` solves the system of linear equations
+/// `A * x = rhs`, where `A` is tridiagonal apart from two corner elements.
+/// `corners` is (top right, bottom left), where the top right element is in
+/// the first row and last column, and the bottom left element is in the
+/// last row and first column. Such systems come from periodic problems.
+/// `lower`, `diagonal` and `upper` are as for `solve_tridiagonal`.
+///
+/// The corner elements are removed by the Sherman-Morrison formula, so the
+/// system is solved with two calls to `solve_tridiagonal`, in O(n)
+/// operations. The same errors as `solve_tridiagonal` are returned, and the
+/// method is stable for diagonally dominant matrices.
+///
+/// ## Example use case
+/// Suppose we want to solve the system
+/// [4 1 1] [x0]   [6]
+/// [1 4 1] [x1] = [6]
+/// [1 1 4] [x2]   [6]
+/// The code below does this.
+/// ```
+/// let solution = solve_cyclic_tridiagonal(
+///     &[1.0, 1.0],
+///     &[4.0, 4.0, 4.0],
+///     &[1.0, 1.0],
+///     (1.0, 1.0),
+///     &[6.0, 6.0, 6.0],
+/// )?;
+/// ```
+///
+pub fn solve_cyclic_tridiagonal(
+    lower: &[f64],
+    diagonal: &[f64],
+    upper: &[f64],
+    corners: (f64, f64),
+    rhs: &[f64],
+) -> Result<Vec<f64>, NumericalMethodsError> {
+    let (top_right, bottom_left) = corners;
+    let size = diagonal.len();
+
+    // With fewer than 3 rows, the corners lie on the tridiagonal band.
+    if size <= 2 {
+        let mut lower = lower.to_vec();
+        let mut diagonal = diagonal.to_vec();
+        let mut upper = upper.to_vec();
+        match (size, lower.len(), upper.len()) {
+            (1, 0, 0) => diagonal[0] += top_right + bottom_left,
+            (2, 1, 1) => {
+                upper[0] += top_right;
+                lower[0] += bottom_left;
+            }
+            _ => {}
+        }
+        return solve_tridiagonal(&lower, &diagonal, &upper, rhs);
+    }
+
+    // A = T + u v^T, where u = (gamma, 0, ..., 0, bottom_left) and
+    // v = (1, 0, ..., 0, top_right / gamma), and T is tridiagonal. Any
+    // non-zero gamma will do. -diagonal[0] doubles the first element of T,
+    // and 1 is used instead when diagonal[0] is zero.
+    let gamma = if diagonal[0] == 0.0 {
+        1.0
+    } else {
+        -diagonal[0]
+    };
+    let mut modified_diagonal = diagonal.to_vec();
+    modified_diagonal[0] -= gamma;
+    modified_diagonal[size - 1] -= bottom_left * top_right / gamma;
+
+    let solution = solve_tridiagonal(lower, &modified_diagonal, upper, rhs)?;
+    let mut u = vec![0.0; size];
+    u[0] = gamma;
+    u[size - 1] = bottom_left;
+    let correction = solve_tridiagonal(lower, &modified_diagonal, upper, &u)?;
+
+    let denominator =
+        1.0 + correction[0] + top_right * correction[size - 1] / gamma;
+    if denominator == 0.0 || !denominator.is_finite() {
+        return Err(NumericalMethodsError::SingularMatrix);
+    }
+    let factor =
+        (solution[0] + top_right * solution[size - 1] / gamma) / denominator;
+
+    Ok(solution
+        .iter()
+        .zip(&correction)
+        .map(|(x, z)| x - factor * z)
+        .collect())
+}
+
+/// # Banded matrix
+///
+/// ## Description
+/// `BandedMatrix` represents an n by n matrix whose non-zero elements all lie
+/// within `lower_bandwidth` places below the diagonal and `upper_bandwidth`
+/// places above the diagonal. Only the elements within the band are stored,
+/// so a banded matrix takes O(n) memory rather than O(n^2).
+///
+/// Jacobian matrices of finite difference approximations to DEs are banded,
+/// because each residual only depends on neighbouring function values.
+///
+/// ## Example use case
+/// Suppose we want to solve the system
+/// [2 1 0] [x0]   [3]
+/// [1 2 1] [x1] = [4]
+/// [0 1 2] [x2]   [3]
+/// The code below does this.
+/// ```
+/// let mut matrix = BandedMatrix::new(3, 1, 1);
+/// for i in 0..3 {
+///     matrix.set(i, i, 2.0);
+/// }
+/// for i in 0..2 {
+///     matrix.set(i, i + 1, 1.0);
+///     matrix.set(i + 1, i, 1.0);
+/// }
+/// let solution = matrix.solve(&[3.0, 4.0, 3.0])?;
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandedMatrix {
+    pub size: usize,
+    pub lower_bandwidth: usize,
+    pub upper_bandwidth: usize,
+    // The elements of each row within the band, in row-major order. The
+    // element in row i and column j is stored at index
+    // i * (lower_bandwidth + upper_bandwidth + 1) + j + lower_bandwidth - i.
+    elements: Vec<f64>,
+}
+
+impl BandedMatrix {
+    /// # New banded matrix
+    ///
+    /// ## Description
+    /// `new` creates a `size` by `size` banded matrix with the given
+    /// bandwidths, with every element equal to zero.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let tridiagonal_matrix = BandedMatrix::new(100, 1, 1);
+    /// ```
+    ///
+    pub fn new(
+        size: usize,
+        lower_bandwidth: usize,
+        upper_bandwidth: usize,
+    ) -> Self {
+        BandedMatrix {
+            size,
+            lower_bandwidth,
+            upper_bandwidth,
+            elements: vec![0.0; size * (lower_bandwidth + upper_bandwidth + 1)],
+        }
+    }
+
+    /// # Index
+    ///
+    /// ## Description
+    /// `index` returns the position of the element in row `row` and column
+    /// `column` in `elements`, or `None` if the element lies outside the band.
+    ///
+    fn index(&self, row: usize, column: usize) -> Option<usize> {
+        if row >= self.size
+            || column >= self.size
+            || column + self.lower_bandwidth < row
+            || column > row + self.upper_bandwidth
+        {
+            return None;
+        }
+
+        let width = self.lower_bandwidth + self.upper_bandwidth + 1;
+        Some(row * width + column + self.lower_bandwidth - row)
+    }
+
+    /// # Get
+    ///
+    /// ## Description
+    /// `get` returns the element in row `row` and column `column`. Elements
+    /// outside the band are zero.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let diagonal_element = matrix.get(2, 2);
+    /// ```
+    ///
+    pub fn get(&self, row: usize, column: usize) -> f64 {
+        match self.index(row, column) {
+            Some(index) => self.elements[index],
+            None => 0.0,
+        }
+    }
+
+    /// # Set
+    ///
+    /// ## Description
+    /// `set` sets the element in row `row` and column `column` to `value`.
+    ///
+    /// `set` panics if the element lies outside the band, unless `value` is
+    /// zero.
+    ///
+    /// ## Example use case
+    /// ```
+    /// matrix.set(2, 3, 1.0);
+    /// ```
+    ///
+    pub fn set(&mut self, row: usize, column: usize, value: f64) {
+        match self.index(row, column) {
+            Some(index) => self.elements[index] = value,
+            None if value == 0.0 => {}
+            None => panic!(
+                "The element ({}, {}) lies outside the band of the matrix.",
+                row, column
+            ),
+        }
+    }
+
+    /// # Multiply vector
+    ///
+    /// ## Description
+    /// `multiply_vector` returns the product of the current `BandedMatrix` and
+    /// the column vector `vector`, which must have `size` elements.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let product = matrix.multiply_vector(&[1.0, 1.0, 1.0]);
+    /// ```
+    ///
+    pub fn multiply_vector(&self, vector: &[f64]) -> Vec<f64> {
+        (0..self.size)
+            .map(|row| {
+                let first = row.saturating_sub(self.lower_bandwidth);
+                let last = (row + self.upper_bandwidth).min(self.size - 1);
+                (first..=last)
+                    .map(|column| self.get(row, column) * vector[column])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// # Solve
+    ///
+    /// ## Description
+    /// `solve` solves the system of linear equations `A * x = rhs`, where `A`
+    /// is the current `BandedMatrix`, using Gaussian elimination with partial
+    /// pivoting restricted to the band.
+    ///
+    /// For an n by n matrix with lower bandwidth p and upper bandwidth q,
+    /// `solve` takes O(n * p * (p + q)) operations, so tridiagonal systems
+    /// are solved in O(n) operations. Pivoting can fill in up to p extra
+    /// elements above the band in each row, so O(n * (2p + q)) memory is used.
+    ///
+    /// If `rhs` does not have `size` elements, a `LengthMismatch` error is
+    /// returned. If the matrix is singular, a `SingularMatrix` error is
+    /// returned.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let solution = matrix.solve(&[3.0, 4.0, 3.0])?;
+    /// ```
+    ///
+    pub fn solve(
+        &self,
+        rhs: &[f64],
+    ) -> Result<Vec<f64>, NumericalMethodsError> {
+        let size = self.size;
+        if rhs.len() != size {
+            return Err(NumericalMethodsError::LengthMismatch {
+                expected: size,
+                actual: rhs.len(),
+            });
+        }
+
+        let mut columns = self.solve_columns(vec![rhs.to_vec()])?;
+        Ok(columns.remove(0))
+    }
+
+    /// # Solve columns
+    ///
+    /// ## Description
+    /// `solve_columns` solves `A * x = b` for each right-hand side b in
+    /// `columns`, each of which must have `size` elements, with a single
+    /// factorisation of `A`.
+    ///
+    fn solve_columns(
+        &self,
+        mut columns: Vec<Vec<f64>>,
+    ) -> Result<Vec<Vec<f64>>, NumericalMethodsError> {
+        let size = self.size;
+
+        // Copies the band into a working array with room for the fill-in
+        // from pivoting. The element in row i and column j is stored at index
+        // i * width + j + lower - i.
+        let lower = self.lower_bandwidth;
+        let upper = self.upper_bandwidth + lower;
+        let width = lower + upper + 1;
+        let mut elements = vec![0.0; size * width];
+        for row in 0..size {
+            let first = row.saturating_sub(lower);
+            let last = (row + self.upper_bandwidth).min(size - 1);
+            for column in first..=last {
+                elements[row * width + column + lower - row] =
+                    self.get(row, column);
+            }
+        }
+        let index =
+            |row: usize, column: usize| row * width + column + lower - row;
+
+        // Forward elimination.
+        for k in 0..size {
+            let last_row = (k + lower).min(size - 1);
+            let last_column = (k + upper).min(size - 1);
+
+            // Chooses the pivot with the largest magnitude in column k.
+            let mut pivot_row = k;
+            for row in (k + 1)..=last_row {
+                if elements[index(row, k)].abs()
+                    > elements[index(pivot_row, k)].abs()
+                {
+                    pivot_row = row;
+                }
+            }
+            let pivot = elements[index(pivot_row, k)];
+            if pivot == 0.0 || !pivot.is_finite() {
+                return Err(NumericalMethodsError::SingularMatrix);
+            }
+
+            if pivot_row != k {
+                for column in k..=last_column {
+                    elements.swap(index(k, column), index(pivot_row, column));
+                }
+                for solution in columns.iter_mut() {
+                    solution.swap(k, pivot_row);
+                }
+            }
+
+            for row in (k + 1)..=last_row {
+                let factor = elements[index(row, k)] / pivot;
+                if factor == 0.0 {
+                    continue;
+                }
+                for column in k..=last_column {
+                    elements[index(row, column)] -=
+                        factor * elements[index(k, column)];
+                }
+                for solution in columns.iter_mut() {
+                    solution[row] -= factor * solution[k];
+                }
+            }
+        }
+
+        // Back substitution.
+        for solution in columns.iter_mut() {
+            for row in (0..size).rev() {
+                let last_column = (row + upper).min(size - 1);
+                let mut value = solution[row];
+                for column in (row + 1)..=last_column {
+                    value -= elements[index(row, column)] * solution[column];
+                }
+                solution[row] = value / elements[index(row, row)];
+            }
+        }
+
+        Ok(columns)
+    }
+}
+
+/// # Bordered banded matrix
+///
+/// ## Description
+/// `BorderedBandedMatrix` represents an n by n matrix which is banded apart
+/// from a few rows, whose non-zero elements may lie in any column. `band`
+/// holds the banded rows, and each of `full_rows` is a row index together
+/// with the non-zero elements of that row, as (column, value) pairs. The
+/// full rows replace the corresponding rows of `band`.
+///
+/// Periodic BCs give Jacobian matrices of this form, because their rows
+/// couple the first and last grid points.
+///
+/// ## Example use case
+/// Suppose we want to solve the system
+/// [1 0 0 -1] [x0]   [0]
+/// [1 2 1  0] [x1] = [4]
+/// [0 1 2  1] [x2]   [4]
+/// [1 1 0  1] [x3]   [3]
+/// The code below does this.
+/// ```
+/// let mut matrix = BorderedBandedMatrix::new(4, 1, 1);
+/// matrix.set_row(0, &[(0, 1.0), (3, -1.0)]);
+/// matrix.set_row(1, &[(0, 1.0), (1, 2.0), (2, 1.0)]);
+/// matrix.set_row(2, &[(1, 1.0), (2, 2.0), (3, 1.0)]);
+/// matrix.set_row(3, &[(0, 1.0), (1, 1.0), (3, 1.0)]);
+/// let solution = matrix.solve(&[0.0, 4.0, 4.0, 3.0])?;
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorderedBandedMatrix {
+    pub band: BandedMatrix,
+    pub full_rows: Vec<(usize, Vec<(usize, f64)>)>,
+}
+
+impl BorderedBandedMatrix {
+    /// # New bordered banded matrix
+    ///
+    /// ## Description
+    /// `new` creates a `size` by `size` matrix with the given bandwidths and
+    /// no full rows, with every element equal to zero.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let matrix = BorderedBandedMatrix::new(100, 1, 1);
+    /// ```
+    ///
+    pub fn new(
+        size: usize,
+        lower_bandwidth: usize,
+        upper_bandwidth: usize,
+    ) -> Self {
+        BorderedBandedMatrix {
+            band: BandedMatrix::new(size, lower_bandwidth, upper_bandwidth),
+            full_rows: vec![],
+        }
+    }
+
+    /// # Set row
+    ///
+    /// ## Description
+    /// `set_row` sets row `row` to the (column, value) pairs `elements`, with
+    /// the values of repeated columns added together, and every other
+    /// element of the row equal to zero. If all the elements lie within the
+    /// band, the row is stored in `band`, and otherwise it is stored as a
+    /// full row.
+    ///
+    /// `set_row` panics if `row` or any of the columns is out of range.
+    ///
+    /// ## Example use case
+    /// ```
+    /// matrix.set_row(0, &[(0, 1.0), (99, -1.0)]);
+    /// ```
+    ///
+    pub fn set_row(&mut self, row: usize, elements: &[(usize, f64)]) {
+        let size = self.band.size;
+        if row >= size || elements.iter().any(|&(column, _)| column >= size) {
+            panic!(
+                "The row {} or one of its columns lies outside the matrix.",
+                row
+            );
+        }
+
+        self.full_rows.retain(|(full_row, _)| *full_row != row);
+        let first = row.saturating_sub(self.band.lower_bandwidth);
+        let last = (row + self.band.upper_bandwidth).min(size - 1);
+        for column in first..=last {
+            self.band.set(row, column, 0.0);
+        }
+
+        if elements
+            .iter()
+            .all(|&(column, _)| (first..=last).contains(&column))
+        {
+            for &(column, value) in elements {
+                self.band
+                    .set(row, column, self.band.get(row, column) + value);
+            }
+        } else {
+            self.full_rows.push((row, elements.to_vec()));
+        }
+    }
+
+    /// # Get
+    ///
+    /// ## Description
+    /// `get` returns the element in row `row` and column `column`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let corner_element = matrix.get(0, 99);
+    /// ```
+    ///
+    pub fn get(&self, row: usize, column: usize) -> f64 {
+        match self.full_rows.iter().find(|(full_row, _)| *full_row == row) {
+            Some((_, elements)) => elements
+                .iter()
+                .filter(|&&(j, _)| j == column)
+                .map(|&(_, value)| value)
+                .sum(),
+            None => self.band.get(row, column),
+        }
+    }
+
+    /// # Solve
+    ///
+    /// ## Description
+    /// `solve` solves the system of linear equations `A * x = rhs`, where `A`
+    /// is the current `BorderedBandedMatrix`.
+    ///
+    /// Let B be `band` with each full row replaced by the same row of the
+    /// identity matrix, so that `A` is B plus a matrix of rank k for k full
+    /// rows. The system is solved by the Woodbury formula, with one banded
+    /// factorisation of B for k + 1 right-hand sides and a k by k dense
+    /// solve, so a few full rows add little to the cost of `BandedMatrix`.
+    ///
+    /// If `rhs` does not have `size` elements, a `LengthMismatch` error is
+    /// returned. If `A` or B is singular, a `SingularMatrix` error is
+    /// returned. For the Jacobian matrix of a BVP with periodic BCs, B is the
+    /// Jacobian matrix with Dirichlet BCs in their place.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let solution = matrix.solve(&rhs)?;
+    /// ```
+    ///
+    pub fn solve(
+        &self,
+        rhs: &[f64],
+    ) -> Result<Vec<f64>, NumericalMethodsError> {
+        let size = self.band.size;
+        if rhs.len() != size {
+            return Err(NumericalMethodsError::LengthMismatch {
+                expected: size,
+                actual: rhs.len(),
+            });
+        }
+        if self.full_rows.is_empty() {
+            return self.band.solve(rhs);
+        }
+
+        // A = B + sum_k e_k v_k^T, where e_k is the kth full row's column of
+        // the identity matrix and v_k is the kth full row minus e_k^T.
+        let mut matrix = self.band.clone();
+        let mut columns = vec![rhs.to_vec()];
+        for &(row, _) in &self.full_rows {
+            let first = row.saturating_sub(matrix.lower_bandwidth);
+            let last = (row + matrix.upper_bandwidth).min(size - 1);
+            for column in first..=last {
+                matrix.set(row, column, 0.0);
+            }
+            matrix.set(row, row, 1.0);
+
+            let mut unit_vector = vec![0.0; size];
+            unit_vector[row] = 1.0;
+            columns.push(unit_vector);
+        }
+        let columns = matrix.solve_columns(columns)?;
+        let (solution, corrections) = (&columns[0], &columns[1..]);
+
+        // Solves the k by k system (I + V^T B^(-1) U) w = V^T B^(-1) rhs.
+        let num_rows = self.full_rows.len();
+        let product = |k: usize, vector: &[f64]| {
+            let (row, elements) = &self.full_rows[k];
+            elements
+                .iter()
+                .map(|&(column, value)| value * vector[column])
+                .sum::<f64>()
+                - vector[*row]
+        };
+        let capacitance = DMatrix::from_fn(num_rows, num_rows, |k, l| {
+            let identity = if k == l { 1.0 } else { 0.0 };
+            identity + product(k, &corrections[l])
+        });
+        let products = DVector::from_fn(num_rows, |k, _| product(k, solution));
+        let weights = LU::new(capacitance)
+            .solve(&products)
+            .filter(|weights| weights.iter().all(|w| w.is_finite()))
+            .ok_or(NumericalMethodsError::SingularMatrix)?;
+
+        Ok((0..size)
+            .map(|i| {
+                solution[i]
+                    - corrections
+                        .iter()
+                        .zip(weights.iter())
+                        .map(|(correction, weight)| correction[i] * weight)
+                        .sum::<f64>()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_tridiagonal() {
+        let solution = solve_tridiagonal(
+            &[1.0, 1.0],
+            &[2.0, 2.0, 2.0],
+            &[1.0, 1.0],
+            &[3.0, 4.0, 3.0],
+        )
+        .unwrap();
+        for x in solution {
+            assert!((x - 1.0).abs() < 1e-12, "solve_tridiagonal failed.");
+        }
+
+        // A larger, non-symmetric system with a known solution.
+        let size = 50;
+        let lower = vec![-1.0; size - 1];
+        let diagonal = vec![4.0; size];
+        let upper = vec![-2.0; size - 1];
+        let expected: Vec<f64> = (0..size).map(|i| (i as f64).sin()).collect();
+        let rhs: Vec<f64> = (0..size)
+            .map(|i| {
+                let mut value = diagonal[i] * expected[i];
+                if i > 0 {
+                    value += lower[i - 1] * expected[i - 1];
+                }
+                if i < size - 1 {
+                    value += upper[i] * expected[i + 1];
+                }
+                value
+            })
+            .collect();
+        let solution =
+            solve_tridiagonal(&lower, &diagonal, &upper, &rhs).unwrap();
+        for (a, b) in solution.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12, "solve_tridiagonal failed.");
+        }
+    }
+
+    #[test]
+    fn test_solve_tridiagonal_edge_cases() {
+        // A 1 by 1 system.
+        assert_eq!(solve_tridiagonal(&[], &[2.0], &[], &[4.0]), Ok(vec![2.0]));
+
+        // An empty system.
+        assert_eq!(solve_tridiagonal(&[], &[], &[], &[]), Ok(vec![]));
+
+        // A zero pivot.
+        assert_eq!(
+            solve_tridiagonal(&[1.0], &[0.0, 1.0], &[1.0], &[1.0, 1.0]),
+            Err(NumericalMethodsError::SingularMatrix)
+        );
+
+        // Inconsistent lengths.
+        assert_eq!(
+            solve_tridiagonal(&[1.0], &[1.0, 1.0], &[1.0, 1.0], &[1.0, 1.0]),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 1,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_solve_cyclic_tridiagonal() {
+        let solution = solve_cyclic_tridiagonal(
+            &[1.0, 1.0],
+            &[4.0, 4.0, 4.0],
+            &[1.0, 1.0],
+            (1.0, 1.0),
+            &[6.0, 6.0, 6.0],
+        )
+        .unwrap();
+        for x in solution {
+            assert!((x - 1.0).abs() < 1e-12);
+        }
+
+        // A zero leading diagonal element.
+        // [0 1 2] [1]   [8]
+        // [1 4 1] [2] = [12]
+        // [3 1 4] [3]   [17]
+        let solution = solve_cyclic_tridiagonal(
+            &[1.0, 1.0],
+            &[0.0, 4.0, 4.0],
+            &[1.0, 1.0],
+            (2.0, 3.0),
+            &[8.0, 12.0, 17.0],
+        )
+        .unwrap();
+        for (x, expected) in solution.iter().zip([1.0, 2.0, 3.0]) {
+            assert!((x - expected).abs() < 1e-12);
+        }
+
+        // A larger, non-symmetric system with a known solution.
+        let size = 40;
+        let (top_right, bottom_left) = (-0.5, 1.5);
+        let lower = vec![-1.0; size - 1];
+        let diagonal = vec![5.0; size];
+        let upper = vec![2.0; size - 1];
+        let expected: Vec<f64> = (0..size).map(|i| (i as f64).cos()).collect();
+        let rhs: Vec<f64> = (0..size)
+            .map(|i| {
+                let mut value = diagonal[i] * expected[i];
+                if i > 0 {
+                    value += lower[i - 1] * expected[i - 1];
+                }
+                if i < size - 1 {
+                    value += upper[i] * expected[i + 1];
+                }
+                if i == 0 {
+                    value += top_right * expected[size - 1];
+                }
+                if i == size - 1 {
+                    value += bottom_left * expected[0];
+                }
+                value
+            })
+            .collect();
+        let solution = solve_cyclic_tridiagonal(
+            &lower,
+            &diagonal,
+            &upper,
+            (top_right, bottom_left),
+            &rhs,
+        )
+        .unwrap();
+        for (a, b) in solution.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // With 2 rows, the corners add to the off-diagonal elements.
+        let solution = solve_cyclic_tridiagonal(
+            &[1.0],
+            &[4.0, 4.0],
+            &[1.0],
+            (1.0, 1.0),
+            &[6.0, 6.0],
+        )
+        .unwrap();
+        assert!((solution[0] - 1.0).abs() < 1e-12);
+        assert!((solution[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_banded_matrix_get_and_set() {
+        let mut matrix = BandedMatrix::new(4, 1, 2);
+        matrix.set(0, 2, 3.0);
+        matrix.set(3, 2, -1.0);
+        assert_eq!(matrix.get(0, 2), 3.0);
+        assert_eq!(matrix.get(3, 2), -1.0);
+
+        // Elements outside the band are zero, and can be set to zero.
+        assert_eq!(matrix.get(0, 3), 0.0);
+        assert_eq!(matrix.get(2, 0), 0.0);
+        matrix.set(3, 0, 0.0);
+
+        assert_eq!(
+            matrix.multiply_vector(&[1.0, 1.0, 1.0, 1.0]),
+            vec![3.0, 0.0, 0.0, -1.0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the band")]
+    fn test_banded_matrix_set_outside_band_panics() {
+        let mut matrix = BandedMatrix::new(4, 1, 1);
+        matrix.set(0, 2, 1.0);
+    }
+
+    #[test]
+    fn test_banded_matrix_solve() {
+        // The same system as test_solve_tridiagonal.
+        let size = 50;
+        let mut matrix = BandedMatrix::new(size, 1, 1);
+        for i in 0..size {
+            matrix.set(i, i, 4.0);
+            if i > 0 {
+                matrix.set(i, i - 1, -1.0);
+            }
+            if i < size - 1 {
+                matrix.set(i, i + 1, -2.0);
+            }
+        }
+        let expected: Vec<f64> = (0..size).map(|i| (i as f64).sin()).collect();
+        let rhs = matrix.multiply_vector(&expected);
+        let solution = matrix.solve(&rhs).unwrap();
+        for (a, b) in solution.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12, "BandedMatrix::solve failed.");
+        }
+
+        // A pentadiagonal matrix with zeros on the diagonal needs pivoting.
+        let size = 30;
+        let mut matrix = BandedMatrix::new(size, 2, 2);
+        for i in 0..size {
+            for j in i.saturating_sub(2)..(i + 3).min(size) {
+                if i != j {
+                    matrix.set(i, j, 1.0 + ((i * 7 + j * 3) % 5) as f64);
+                }
+            }
+        }
+        let rhs = matrix.multiply_vector(&expected[..size]);
+        let solution = matrix.solve(&rhs).unwrap();
+        for (a, b) in solution.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-10, "BandedMatrix::solve failed.");
+        }
+    }
+
+    #[test]
+    fn test_banded_matrix_solve_errors() {
+        let matrix = BandedMatrix::new(3, 1, 1);
+        assert_eq!(
+            matrix.solve(&[1.0, 1.0, 1.0]),
+            Err(NumericalMethodsError::SingularMatrix)
+        );
+        assert_eq!(
+            matrix.solve(&[1.0, 1.0]),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(BandedMatrix::new(0, 1, 1).solve(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_bordered_banded_matrix_solve() {
+        // The system from the example for BorderedBandedMatrix.
+        let mut matrix = BorderedBandedMatrix::new(4, 1, 1);
+        matrix.set_row(0, &[(0, 1.0), (3, -1.0)]);
+        matrix.set_row(1, &[(0, 1.0), (1, 2.0), (2, 1.0)]);
+        matrix.set_row(2, &[(1, 1.0), (2, 2.0), (3, 1.0)]);
+        matrix.set_row(3, &[(0, 1.0), (1, 1.0), (3, 1.0)]);
+        assert_eq!(matrix.full_rows.len(), 2);
+        assert_eq!(matrix.get(0, 3), -1.0);
+        assert_eq!(matrix.get(3, 2), 0.0);
+        let solution = matrix.solve(&[0.0, 4.0, 4.0, 3.0]).unwrap();
+        for x in solution {
+            assert!((x - 1.0).abs() < 1e-12);
+        }
+
+        // The same system as test_solve_cyclic_tridiagonal, with a repeated
+        // column in a full row.
+        let size = 40;
+        let (top_right, bottom_left) = (-0.5, 1.5);
+        let mut matrix = BorderedBandedMatrix::new(size, 1, 1);
+        for i in 0..size {
+            let mut elements = vec![(i, 5.0)];
+            if i > 0 {
+                elements.push((i - 1, -1.0));
+            }
+            if i < size - 1 {
+                elements.push((i + 1, 2.0));
+            }
+            if i == 0 {
+                elements.extend([(size - 1, 0.5 * top_right); 2]);
+            }
+            if i == size - 1 {
+                elements.push((0, bottom_left));
+            }
+            matrix.set_row(i, &elements);
+        }
+        let rhs: Vec<f64> = (0..size).map(|i| (i as f64).cos()).collect();
+        let expected = solve_cyclic_tridiagonal(
+            &vec![-1.0; size - 1],
+            &vec![5.0; size],
+            &vec![2.0; size - 1],
+            (top_right, bottom_left),
+            &rhs,
+        )
+        .unwrap();
+        let solution = matrix.solve(&rhs).unwrap();
+        for (a, b) in solution.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // Setting a full row within the band moves it back into the band.
+        matrix.set_row(0, &[(0, 1.0)]);
+        assert_eq!(matrix.full_rows.len(), 1);
+        assert_eq!(matrix.get(0, size - 1), 0.0);
+        assert_eq!(
+            matrix.solve(&[1.0]),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: size,
+                actual: 1
+            })
+        );
+    }
+}
@@ -1,11 +1,33 @@
+pub mod adaptive_quadrature;
 pub mod boundary_conditions;
 pub mod boundary_value_problems;
+pub mod boundary_value_systems;
+pub mod bvp_solvers;
+pub mod cubic_spline;
+pub mod cumulative_integration;
+pub mod double_exponential_quadrature;
+pub mod error;
 pub mod grid;
 pub mod grid_function;
 pub mod grid_function_arithmetic;
+pub mod grid_function_elementwise;
+pub mod grid_function_operators;
+pub mod grid_function_resampling;
+pub mod implicit_ivp_solvers;
+pub mod initial_value_problems;
+pub mod interpolation;
+pub mod linear_solvers;
+pub mod method_of_lines;
 pub mod numerical_differentiation;
 pub mod numerical_integration;
+pub mod polynomial;
+pub mod polynomial_interpolation;
 pub mod quadratic_interpolation;
+pub mod quadrature;
+pub mod richardson_extrapolation;
+pub mod shooting_methods;
+pub mod sturm_liouville;
+pub mod vector_grid_function;
 
 use boundary_conditions::BoundaryConditions;
 // use grid::Grid;
@@ -59,13 +81,18 @@ fn main() {
     );
 
     // Solves the BVP using Newton's method.
+    let newton_options = boundary_value_problems::NewtonOptions {
+        max_iterations: 20,
+        ..Default::default()
+    };
     let mut grid_func_approximate_solution =
         boundary_value_problems::newtons_method(
             differential_equation_function,
             &boundary_conditions,
             &grid_func_initial_guess,
-            20,
-        );
+            &newton_options,
+        )
+        .solution;
 
     // Normalizes the approximate solution so that the integral over the domain
     // is equal to 1.
@@ -109,10 +136,11 @@ fn initial_guess(x: f64) -> f64 {
 /// pub fn differential_equation_function(
 ///     grid_func: &GridFunction,
 /// ) -> GridFunction {
-/// grid_func
-///     .central_difference_derivative()
-///     .central_difference_derivative()
-///     .add(grid_func)
+///     let second_derivative = grid_func
+///         .central_difference_derivative()
+///         .central_difference_derivative();
+///
+///     second_derivative + grid_func
 /// }
 /// ```
 /// We know that sin(x) is a solution to the DE y'' + y = 0. We can use
@@ -134,8 +162,9 @@ fn initial_guess(x: f64) -> f64 {
 pub fn differential_equation_function(
     grid_func: &GridFunction,
 ) -> GridFunction {
-    grid_func
-        .central_difference_derivative()
+    let second_derivative = grid_func
         .central_difference_derivative()
-        .add(grid_func)
+        .central_difference_derivative();
+
+    second_derivative + grid_func
 }
@@ -0,0 +1,624 @@
+use crate::boundary_conditions::BoundaryConditions;
+use crate::boundary_value_problems::JacobianStructure;
+use crate::error::NumericalMethodsError;
+use crate::grid::Grid;
+use crate::grid_function::GridFunction;
+use crate::initial_value_problems::{try_solve_ivp, IvpMethod, IvpResult};
+
+/// # Periodic ghost points
+///
+/// ## Description
+/// `PERIODIC_GHOST_POINTS` is the number of wrapped grid points added to each
+/// end of the grid before the spatial operator is applied with periodic BCs.
+/// It is enough for operators built from two nested central differences,
+/// such as `central_difference_derivative().central_difference_derivative()`.
+///
+pub const PERIODIC_GHOST_POINTS: usize = 2;
+
+/// # Method of lines result
+///
+/// ## Description
+/// `MethodOfLinesResult` is returned by `solve_method_of_lines`. `snapshots`
+/// is the time series of the solution, with `snapshots[k]` the solution at
+/// `times[k]`, with the BCs applied. `ivp_result` is the result of the time
+/// integration, whose state is the vector of function values.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodOfLinesResult {
+    pub times: Vec<f64>,
+    pub snapshots: Vec<GridFunction>,
+    pub ivp_result: IvpResult,
+}
+
+/// # Solve method of lines
+///
+/// ## Description
+/// `solve_method_of_lines` solves the time-dependent PDE u_t = L(t, u) on the
+/// `Grid` of `initial_condition`, with BCs `boundary_conditions`, by the
+/// method of lines: the function values at the grid points are integrated in
+/// time as a system of ODEs with the IVP time stepper `method`.
+///
+/// The spatial operator L is the closure `spatial_operator(t, u)`, which
+/// takes the solution u as a `GridFunction` and returns u_t at every grid
+/// point. It is usually built from `central_difference_derivative` and the
+/// `GridFunction` operators. Its values at the end points are ignored,
+/// because the BCs are enforced at every stage of the time stepper instead:
+///
+/// - Dirichlet, Neumann and Robin BCs set the end values of u before L is
+///   applied, by solving the BCs (with the one-sided derivatives used by
+///   `newtons_method`) for them.
+/// - Periodic BCs identify the two end points, and L is applied to u
+///   extended by `PERIODIC_GHOST_POINTS` wrapped grid points at each end.
+///
+/// Snapshots are taken at `snapshot_times` using the dense output of the
+/// time stepper, or after every accepted step if `snapshot_times` is empty.
+///
+/// For the implicit time steppers with Dirichlet, Neumann or Robin BCs, the
+/// ith component of u_t usually depends only on nearby grid points, so a
+/// banded `jacobian_structure` should be used. Its bandwidth must cover the
+/// stencil of L, which is 2 for two nested central differences. Periodic BCs
+/// couple the first and last grid points to the other end of the grid, so
+/// they need a dense `jacobian_structure`.
+///
+/// `solve_method_of_lines` panics if the solve fails. See
+/// `try_solve_method_of_lines` for a version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want to solve the viscous Burgers equation
+/// u_t = -u u_x + 0.01 u_xx on [0, 1] with u = 0 at both ends, from
+/// u = sin(PI x) at t = 0 to t = 1. The code below does this, with snapshots
+/// every 0.1.
+/// ```
+/// let grid = Grid::new_uniform_grid(0.0, 1.0, 101);
+/// let initial_condition =
+///     GridFunction::new_grid_function(&grid, |x| (PI * x).sin());
+/// let snapshot_times: Vec<f64> = (0..=10).map(|k| 0.1 * k as f64).collect();
+/// let result = solve_method_of_lines(
+///     |_t, u| {
+///         let u_x = u.central_difference_derivative();
+///         let u_xx = u_x.central_difference_derivative();
+///         -(u * &u_x) + 0.01 * u_xx
+///     },
+///     &BoundaryConditions::new_dirichlet_bcs(0.0, 0.0),
+///     &initial_condition,
+///     (0.0, 1.0),
+///     &IvpMethod::DormandPrince(AdaptiveStepOptions::default()),
+///     &snapshot_times,
+/// );
+/// ```
+///
+pub fn solve_method_of_lines<S>(
+    spatial_operator: S,
+    boundary_conditions: &BoundaryConditions,
+    initial_condition: &GridFunction,
+    t_span: (f64, f64),
+    method: &IvpMethod,
+    snapshot_times: &[f64],
+) -> MethodOfLinesResult
+where
+    S: Fn(f64, &GridFunction) -> GridFunction,
+{
+    match try_solve_method_of_lines(
+        spatial_operator,
+        boundary_conditions,
+        initial_condition,
+        t_span,
+        method,
+        snapshot_times,
+    ) {
+        Ok(result) => result,
+        Err(error) => panic!("Method of lines failed. {}", error),
+    }
+}
+
+/// # Try solve method of lines
+///
+/// ## Description
+/// `try_solve_method_of_lines` is the fallible version of
+/// `solve_method_of_lines`. It returns an error if `initial_condition` is not
+/// valid or has fewer than 3 grid points, if the BCs are not valid or do not
+/// determine the end values (`InvalidBoundaryConditions`), if
+/// `spatial_operator` does not return one value per grid point, if a snapshot
+/// time lies outside `t_span`, or if the time integration fails. It returns
+/// an `InvalidParameter` error if the BCs are periodic and `method` is an
+/// implicit time stepper with a banded `jacobian_structure`.
+///
+/// ## Example use case
+/// ```
+/// let result = try_solve_method_of_lines(
+///     spatial_operator,
+///     &boundary_conditions,
+///     &initial_condition,
+///     (0.0, 1.0),
+///     &method,
+///     &[],
+/// )?;
+/// ```
+///
+pub fn try_solve_method_of_lines<S>(
+    spatial_operator: S,
+    boundary_conditions: &BoundaryConditions,
+    initial_condition: &GridFunction,
+    t_span: (f64, f64),
+    method: &IvpMethod,
+    snapshot_times: &[f64],
+) -> Result<MethodOfLinesResult, NumericalMethodsError>
+where
+    S: Fn(f64, &GridFunction) -> GridFunction,
+{
+    initial_condition.validate_with_min_points(3)?;
+    boundary_conditions.validate()?;
+
+    let grid = &initial_condition.grid;
+    let num_points = grid.grid_points.len();
+    let (start_time, end_time) = t_span;
+    if let Some(&time) = snapshot_times
+        .iter()
+        .find(|&&time| !(time >= start_time && time <= end_time))
+    {
+        return Err(NumericalMethodsError::OutOfDomain {
+            point: time,
+            lower: start_time,
+            upper: end_time,
+        });
+    }
+
+    if let IvpMethod::BackwardEuler(options)
+    | IvpMethod::Bdf2(options)
+    | IvpMethod::RadauIIA(options) = method
+    {
+        let banded = matches!(
+            options.newton_options.jacobian_structure,
+            JacobianStructure::Banded { .. }
+        );
+        if banded && boundary_conditions.is_periodic() {
+            return Err(NumericalMethodsError::InvalidParameter(
+                "Periodic boundary conditions need a dense Jacobian matrix."
+                    .to_string(),
+            ));
+        }
+    }
+
+    let ghost_grid = boundary_conditions
+        .is_periodic()
+        .then(|| periodic_ghost_grid(grid));
+
+    let initial_state = apply_boundary_conditions(
+        grid,
+        &initial_condition.function_values,
+        boundary_conditions,
+    )?
+    .function_values;
+
+    // The time derivative of the function values. The end values are
+    // determined by the BCs, so their time derivatives are zero.
+    let ode_func = |t: f64, values: &[f64]| -> Vec<f64> {
+        // The BCs were applied to the initial condition, and their 2 by 2
+        // system does not depend on the values, so this cannot fail.
+        let Ok(u) =
+            apply_boundary_conditions(grid, values, boundary_conditions)
+        else {
+            return Vec::new();
+        };
+        let mut u_t = match &ghost_grid {
+            Some(ghost_grid) => {
+                let u_extended = periodic_extension(ghost_grid, &u);
+                let u_t_extended = spatial_operator(t, &u_extended);
+                let mut u_t = u_t_extended.function_values;
+                if u_t.len() == ghost_grid.grid_points.len() {
+                    u_t.truncate(PERIODIC_GHOST_POINTS + num_points);
+                    u_t.drain(..PERIODIC_GHOST_POINTS);
+                }
+                u_t
+            }
+            None => spatial_operator(t, &u).function_values,
+        };
+
+        if u_t.len() == num_points {
+            if ghost_grid.is_some() {
+                u_t[num_points - 1] = u_t[0];
+            } else {
+                u_t[0] = 0.0;
+                u_t[num_points - 1] = 0.0;
+            }
+        }
+        u_t
+    };
+
+    let ivp_result = try_solve_ivp(ode_func, t_span, &initial_state, method)?;
+
+    let times = if snapshot_times.is_empty() {
+        ivp_result.solution.grid().grid_points.clone()
+    } else {
+        snapshot_times.to_vec()
+    };
+    let snapshots = times
+        .iter()
+        .map(|&t| {
+            let state = ivp_result.try_evaluate(t)?;
+            apply_boundary_conditions(grid, &state, boundary_conditions)
+        })
+        .collect::<Result<Vec<_>, NumericalMethodsError>>()?;
+
+    Ok(MethodOfLinesResult {
+        times,
+        snapshots,
+        ivp_result,
+    })
+}
+
+/// # Apply boundary conditions
+///
+/// ## Description
+/// `apply_boundary_conditions` returns the `GridFunction` on `grid` with the
+/// function values `values`, with its end values replaced so that it
+/// satisfies `boundary_conditions`.
+///
+/// The BC rows couple each end value to the interior values near it, and to
+/// the other end value on grids with 3 points or with periodic BCs, so the
+/// two end values are found by solving a 2 by 2 linear system. An
+/// `InvalidBoundaryConditions` error is returned if this system is singular.
+///
+fn apply_boundary_conditions(
+    grid: &Grid,
+    values: &[f64],
+    boundary_conditions: &BoundaryConditions,
+) -> Result<GridFunction, NumericalMethodsError> {
+    let last = values.len() - 1;
+    let mut function_values = values.to_vec();
+
+    if boundary_conditions.is_periodic() {
+        function_values[last] = function_values[0];
+    } else {
+        // Writes each BC as matrix[row][0] * u_0 + matrix[row][1] * u_last =
+        // rhs[row], moving the interior values to the right hand side.
+        let rows = boundary_conditions.boundary_rows(grid);
+        let mut matrix = [[0.0; 2]; 2];
+        let mut rhs = [0.0; 2];
+        for (row, boundary_row) in rows.iter().enumerate() {
+            rhs[row] = boundary_row.value;
+            for &(index, coefficient) in &boundary_row.coefficients {
+                if index == 0 {
+                    matrix[row][0] += coefficient;
+                } else if index == last {
+                    matrix[row][1] += coefficient;
+                } else {
+                    rhs[row] -= coefficient * values[index];
+                }
+            }
+        }
+
+        let determinant =
+            matrix[0][0] * matrix[1][1] - matrix[0][1] * matrix[1][0];
+        if determinant == 0.0 || !determinant.is_finite() {
+            return Err(NumericalMethodsError::InvalidBoundaryConditions(
+                "The boundary conditions do not determine the end values."
+                    .to_string(),
+            ));
+        }
+        function_values[0] =
+            (rhs[0] * matrix[1][1] - matrix[0][1] * rhs[1]) / determinant;
+        function_values[last] =
+            (matrix[0][0] * rhs[1] - rhs[0] * matrix[1][0]) / determinant;
+    }
+
+    Ok(GridFunction {
+        grid: grid.clone(),
+        function_values,
+    })
+}
+
+/// # Periodic ghost grid
+///
+/// ## Description
+/// `periodic_ghost_grid` returns `grid` extended by `PERIODIC_GHOST_POINTS`
+/// grid points at each end, copied from the other end of the grid and
+/// shifted by its length.
+///
+fn periodic_ghost_grid(grid: &Grid) -> Grid {
+    let points = &grid.grid_points;
+    let last = points.len() - 1;
+    let period = points[last] - points[0];
+
+    let mut extended_points: Vec<f64> = (1..=PERIODIC_GHOST_POINTS)
+        .rev()
+        .map(|k| {
+            points[(last - k % last) % last] - period * k.div_ceil(last) as f64
+        })
+        .collect();
+    extended_points.extend_from_slice(points);
+    extended_points.extend(
+        (1..=PERIODIC_GHOST_POINTS)
+            .map(|k| points[k % last] + period * (1 + k / last) as f64),
+    );
+
+    Grid {
+        grid_points: extended_points,
+    }
+}
+
+/// # Periodic extension
+///
+/// ## Description
+/// `periodic_extension` returns the periodic `GridFunction` `grid_func`
+/// extended to `ghost_grid` (see `periodic_ghost_grid`).
+///
+fn periodic_extension(
+    ghost_grid: &Grid,
+    grid_func: &GridFunction,
+) -> GridFunction {
+    let values = &grid_func.function_values;
+    let last = values.len() - 1;
+
+    let mut function_values: Vec<f64> = (1..=PERIODIC_GHOST_POINTS)
+        .rev()
+        .map(|k| values[(last - k % last) % last])
+        .collect();
+    function_values.extend_from_slice(values);
+    function_values
+        .extend((1..=PERIODIC_GHOST_POINTS).map(|k| values[k % last]));
+
+    GridFunction {
+        grid: ghost_grid.clone(),
+        function_values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_conditions::BoundaryCondition;
+    use crate::boundary_value_problems::NewtonOptions;
+    use crate::grid::max_error;
+    use crate::implicit_ivp_solvers::ImplicitOptions;
+    use crate::initial_value_problems::AdaptiveStepOptions;
+
+    const PI: f64 = std::f64::consts::PI;
+
+    fn heat_operator(_t: f64, u: &GridFunction) -> GridFunction {
+        u.central_difference_derivative()
+            .central_difference_derivative()
+    }
+
+    // The nested central differences in `heat_operator` use one-sided
+    // differences at the ends, which are inaccurate unless u'' = 0 there.
+    fn three_point_operator(_t: f64, u: &GridFunction) -> GridFunction {
+        let points = &u.grid.grid_points;
+        let values = &u.function_values;
+        let mut u_xx = vec![0.0; values.len()];
+        for i in 1..(values.len() - 1) {
+            let h1 = points[i] - points[i - 1];
+            let h2 = points[i + 1] - points[i];
+            u_xx[i] = 2.0
+                * (h2 * values[i - 1] - (h1 + h2) * values[i]
+                    + h1 * values[i + 1])
+                / (h1 * h2 * (h1 + h2));
+        }
+        GridFunction {
+            grid: u.grid.clone(),
+            function_values: u_xx,
+        }
+    }
+
+    #[test]
+    fn test_heat_equation() {
+        // u = exp(-PI^2 t) sin(PI x) with u = 0 at both ends.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 51);
+        let initial_condition =
+            GridFunction::new_grid_function(&grid, |x| (PI * x).sin());
+        let bcs = BoundaryConditions::new_dirichlet_bcs(0.0, 0.0);
+        let implicit_options = ImplicitOptions {
+            newton_options: NewtonOptions {
+                jacobian_structure: JacobianStructure::Banded { bandwidth: 2 },
+                ..ImplicitOptions::default().newton_options
+            },
+            ..Default::default()
+        };
+
+        for method in [
+            IvpMethod::Rk4 { step_size: 1e-4 },
+            IvpMethod::Bdf2(implicit_options),
+            IvpMethod::RadauIIA(implicit_options),
+        ] {
+            let result = solve_method_of_lines(
+                heat_operator,
+                &bcs,
+                &initial_condition,
+                (0.0, 0.2),
+                &method,
+                &[0.0, 0.1, 0.2],
+            );
+            assert_eq!(result.times, vec![0.0, 0.1, 0.2]);
+            for (t, snapshot) in result.times.iter().zip(&result.snapshots) {
+                let error = max_error(snapshot, |x| {
+                    (-PI * PI * t).exp() * (PI * x).sin()
+                });
+                assert!(
+                    error < 2e-3,
+                    "{:?} error {} at t = {}.",
+                    method,
+                    error,
+                    t
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_neumann_and_robin_bcs() {
+        // u = 1 + exp(-PI^2 t) cos(PI x) with u_x = 0 at both ends.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 41);
+        let initial_condition =
+            GridFunction::new_grid_function(&grid, |x| 1.0 + (PI * x).cos());
+        let result = solve_method_of_lines(
+            three_point_operator,
+            &BoundaryConditions::new_neumann_bcs(0.0, 0.0),
+            &initial_condition,
+            (0.0, 0.1),
+            &IvpMethod::DormandPrince(AdaptiveStepOptions::default()),
+            &[],
+        );
+        assert_eq!(
+            result.snapshots.len(),
+            result.ivp_result.accepted_steps + 1
+        );
+        let final_snapshot = &result.snapshots[result.snapshots.len() - 1];
+        let exact = |x: f64| 1.0 + (-PI * PI * 0.1).exp() * (PI * x).cos();
+        assert!(max_error(final_snapshot, exact) < 1e-3);
+
+        // The steady state of u_t = u_xx with u(0) = 1 and u(1) + u_x(1) = 0
+        // is u = 1 - x / 2.
+        let initial_condition =
+            GridFunction::new_constant_grid_function(&grid, 1.0);
+        let result = solve_method_of_lines(
+            three_point_operator,
+            &BoundaryConditions::new(
+                BoundaryCondition::Dirichlet(1.0),
+                BoundaryCondition::Robin {
+                    a: 1.0,
+                    b: 1.0,
+                    c: 0.0,
+                },
+            ),
+            &initial_condition,
+            (0.0, 5.0),
+            &IvpMethod::Bdf2(ImplicitOptions::default()),
+            &[5.0],
+        );
+        assert!(max_error(&result.snapshots[0], |x| 1.0 - x / 2.0) < 1e-4);
+    }
+
+    #[test]
+    fn test_periodic_advection_diffusion() {
+        // u_t = -u_x + 0.01 u_xx with periodic BCs on [0, 1] has the solution
+        // u = exp(-0.04 PI^2 t) sin(2 PI (x - t)).
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 101);
+        let initial_condition =
+            GridFunction::new_grid_function(&grid, |x| (2.0 * PI * x).sin());
+        let result = solve_method_of_lines(
+            |_t, u| {
+                let u_x = u.central_difference_derivative();
+                let u_xx = u_x.central_difference_derivative();
+                -u_x + 0.01 * u_xx
+            },
+            &BoundaryConditions::new_periodic_bcs(),
+            &initial_condition,
+            (0.0, 0.5),
+            &IvpMethod::Rk4 { step_size: 1e-3 },
+            &[0.5],
+        );
+
+        let exact = |x: f64| {
+            (-0.04 * PI * PI * 0.5).exp() * (2.0 * PI * (x - 0.5)).sin()
+        };
+        let snapshot = &result.snapshots[0];
+        assert!(max_error(snapshot, exact) < 5e-3);
+        assert_eq!(snapshot.function_values[0], snapshot.function_values[100]);
+    }
+
+    #[test]
+    fn test_periodic_ghost_points() {
+        let grid = Grid::new_grid_from_points(vec![0.0, 0.25, 1.0]);
+        let ghost_grid = periodic_ghost_grid(&grid);
+        assert_eq!(
+            ghost_grid.grid_points,
+            vec![-1.0, -0.75, 0.0, 0.25, 1.0, 1.25, 2.0]
+        );
+        assert!(ghost_grid.validate().is_ok());
+
+        let grid_func =
+            GridFunction::new_grid_function(&grid, |x| 4.0 * x * (1.0 - x));
+        let extended = periodic_extension(&ghost_grid, &grid_func);
+        assert_eq!(
+            extended.function_values,
+            vec![0.0, 0.75, 0.0, 0.75, 0.0, 0.75, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_try_solve_method_of_lines_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let initial_condition =
+            GridFunction::new_constant_grid_function(&grid, 0.0);
+        let bcs = BoundaryConditions::new_dirichlet_bcs(0.0, 0.0);
+        let method = IvpMethod::Rk4 { step_size: 0.01 };
+
+        assert_eq!(
+            try_solve_method_of_lines(
+                heat_operator,
+                &bcs,
+                &initial_condition,
+                (0.0, 1.0),
+                &method,
+                &[0.5, 1.5],
+            ),
+            Err(NumericalMethodsError::OutOfDomain {
+                point: 1.5,
+                lower: 0.0,
+                upper: 1.0
+            })
+        );
+
+        let short_grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        assert_eq!(
+            try_solve_method_of_lines(
+                |_t, _u| GridFunction::new_constant_grid_function(
+                    &short_grid,
+                    0.0
+                ),
+                &bcs,
+                &initial_condition,
+                (0.0, 1.0),
+                &method,
+                &[],
+            ),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 11,
+                actual: 5
+            })
+        );
+
+        // With h = 1/8 and u' = (-3 u_0 + 4 u_1 - u_2) / (2 h), the Robin BC
+        // 12 u + u' = 0 does not involve u_0, which is then not determined.
+        let robin_bcs = BoundaryConditions::new(
+            BoundaryCondition::Robin {
+                a: 12.0,
+                b: 1.0,
+                c: 0.0,
+            },
+            BoundaryCondition::Dirichlet(0.0),
+        );
+        let eighths_grid = Grid::new_uniform_grid(0.0, 1.0, 9);
+        assert!(matches!(
+            try_solve_method_of_lines(
+                heat_operator,
+                &robin_bcs,
+                &GridFunction::new_constant_grid_function(&eighths_grid, 0.0),
+                (0.0, 1.0),
+                &method,
+                &[],
+            ),
+            Err(NumericalMethodsError::InvalidBoundaryConditions(_))
+        ));
+
+        // The Jacobian matrix of periodic BCs is not banded.
+        let banded_options = ImplicitOptions {
+            newton_options: NewtonOptions {
+                jacobian_structure: JacobianStructure::Banded { bandwidth: 2 },
+                ..ImplicitOptions::default().newton_options
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            try_solve_method_of_lines(
+                heat_operator,
+                &BoundaryConditions::new_periodic_bcs(),
+                &initial_condition,
+                (0.0, 1.0),
+                &IvpMethod::Bdf2(banded_options),
+                &[],
+            ),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+    }
+}
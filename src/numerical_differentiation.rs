@@ -1,3 +1,4 @@
+use crate::error::NumericalMethodsError;
 use crate::grid_function::GridFunction;
 
 impl GridFunction {
@@ -21,7 +22,7 @@ impl GridFunction {
     /// let grid_func_derivative = grid_func.forward_difference_derivative();
     /// ```
     ///
-    pub fn forward_difference_derivative(self: &Self) -> Self {
+    pub fn forward_difference_derivative(&self) -> Self {
         let grid = &self.grid;
         let function_values = &self.function_values;
 
@@ -78,7 +79,7 @@ impl GridFunction {
     /// let grid_func_derivative = grid_func.central_difference_derivative();
     /// ```
     ///
-    pub fn central_difference_derivative(self: &Self) -> Self {
+    pub fn central_difference_derivative(&self) -> Self {
         let grid = &self.grid;
         let function_values = &self.function_values;
 
@@ -113,3 +114,171 @@ impl GridFunction {
         }
     }
 }
+
+// Fallible numerical differentiation algorithms.
+impl GridFunction {
+    /// # Try forwards difference derivative
+    ///
+    /// ## Description
+    /// `try_forward_difference_derivative` is the fallible version of
+    /// `forward_difference_derivative`. It returns an error if the current
+    /// `GridFunction` is not valid, or if it has fewer than 2 grid points.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(2));
+    /// let grid_func_derivative = grid_func.try_forward_difference_derivative()?;
+    /// ```
+    ///
+    pub fn try_forward_difference_derivative(
+        &self,
+    ) -> Result<Self, NumericalMethodsError> {
+        self.validate_with_min_points(2)?;
+        Ok(self.forward_difference_derivative())
+    }
+
+    /// # Try central difference derivative
+    ///
+    /// ## Description
+    /// `try_central_difference_derivative` is the fallible version of
+    /// `central_difference_derivative`. It returns an error if the current
+    /// `GridFunction` is not valid, or if it has fewer than 2 grid points.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(2));
+    /// let grid_func_derivative = grid_func.try_central_difference_derivative()?;
+    /// ```
+    ///
+    pub fn try_central_difference_derivative(
+        &self,
+    ) -> Result<Self, NumericalMethodsError> {
+        self.validate_with_min_points(2)?;
+        Ok(self.central_difference_derivative())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{max_cell_width, test_grids};
+
+    #[test]
+    fn test_derivatives_of_linear_function() {
+        // Both schemes are exact for linear functions on any grid.
+        for (name, grid) in test_grids(-1.0, 2.0, 21) {
+            let grid_func =
+                GridFunction::new_grid_function(&grid, |x| 3.0 * x + 1.0);
+
+            let forward = grid_func.forward_difference_derivative();
+            let central = grid_func.central_difference_derivative();
+
+            for (a, b) in forward
+                .function_values
+                .iter()
+                .zip(central.function_values.iter())
+            {
+                assert!(
+                    (a - 3.0).abs() < 1e-10,
+                    "forward_difference_derivative failed on the {} grid.",
+                    name
+                );
+                assert!(
+                    (b - 3.0).abs() < 1e-10,
+                    "central_difference_derivative failed on the {} grid.",
+                    name
+                );
+            }
+            assert_eq!(forward.grid, grid);
+            assert_eq!(central.grid, grid);
+        }
+    }
+
+    #[test]
+    fn test_derivatives_of_sin() {
+        // The error of both schemes is bounded by half the widest cell width,
+        // because |sin''(x)| <= 1.
+        for (name, grid) in test_grids(0.0, 1.0, 101) {
+            let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+            let tolerance = max_cell_width(&grid);
+
+            let forward = grid_func.forward_difference_derivative();
+            let central = grid_func.central_difference_derivative();
+
+            for (i, &x) in grid.grid_points.iter().enumerate() {
+                assert!(
+                    (forward.function_values[i] - x.cos()).abs() < tolerance,
+                    "forward_difference_derivative failed on the {} grid.",
+                    name
+                );
+                assert!(
+                    (central.function_values[i] - x.cos()).abs() < tolerance,
+                    "central_difference_derivative failed on the {} grid.",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_central_difference_second_order_on_uniform_grid() {
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 101);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        let central = grid_func.central_difference_derivative();
+        let step_size: f64 = 0.01;
+
+        // At the interior points, the central difference scheme is second
+        // order accurate on a uniform grid.
+        for i in 1..100 {
+            let x = grid.grid_points[i];
+            assert!(
+                (central.function_values[i] - x.cos()).abs()
+                    < step_size.powi(2),
+                "central_difference_derivative is not second order accurate."
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_derivatives() {
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func.try_forward_difference_derivative(),
+            Ok(grid_func.forward_difference_derivative())
+        );
+        assert_eq!(
+            grid_func.try_central_difference_derivative(),
+            Ok(grid_func.central_difference_derivative())
+        );
+
+        // A single grid point is not enough to calculate a derivative.
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 1);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        let expected_error = NumericalMethodsError::InsufficientPoints {
+            required: 2,
+            actual: 1,
+        };
+        assert_eq!(
+            grid_func.try_forward_difference_derivative(),
+            Err(expected_error.clone())
+        );
+        assert_eq!(
+            grid_func.try_central_difference_derivative(),
+            Err(expected_error)
+        );
+
+        // The number of function values must match the number of grid points.
+        let mut grid_func = GridFunction::new_grid_function(
+            &crate::grid::Grid::new_uniform_grid(0.0, 1.0, 11),
+            f64::sin,
+        );
+        grid_func.function_values.pop();
+        assert!(matches!(
+            grid_func.try_central_difference_derivative(),
+            Err(NumericalMethodsError::LengthMismatch { .. })
+        ));
+    }
+}
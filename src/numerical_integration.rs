@@ -1,5 +1,10 @@
+use std::ops::Range;
+
+use crate::error::NumericalMethodsError;
+use crate::grid::{Grid, GRID_TOLERANCE};
 use crate::grid_function::GridFunction;
-use crate::quadratic_interpolation;
+use crate::polynomial::Polynomial;
+use crate::richardson_extrapolation::richardson_extrapolate;
 
 // Fixed step size numerical integration algorithms.
 impl GridFunction {
@@ -25,7 +30,7 @@ impl GridFunction {
     /// let integral = grid_func.integrate_riemann_sum();
     /// ```
     ///
-    pub fn integrate_riemann_sum(self: &Self) -> f64 {
+    pub fn integrate_riemann_sum(&self) -> f64 {
         let num_points = &self.grid.grid_points.len();
         let grid_points = &self.grid.grid_points;
         let function_values = &self.function_values;
@@ -54,7 +59,11 @@ impl GridFunction {
     /// quadratic. The integral over each pair of grid cells is computed, and
     /// all of these integrals are summed to approximate the total integral.
     ///
-    /// The number of grid points must be odd.
+    /// If the number of grid points is even, the cells cannot all be paired,
+    /// so Simpson's 3/8 rule is used over the final three grid cells instead.
+    /// The interpolating polynomials are built from the actual grid points,
+    /// so non-uniform grids are handled correctly. At least 3 grid points are
+    /// needed.
     ///
     /// ## Example use case
     /// Suppose we want to calculate the integral of the function `f(x) = x^2`
@@ -66,48 +75,618 @@ impl GridFunction {
     /// let integral = grid_func.integrate_composite_simpsons_rule();
     /// ```
     ///
-    /// ## Todo
-    /// Modify the algorithm so that it can handle the case where the number of
-    /// grid points is even.
-    ///
-    pub fn integrate_composite_simpsons_rule(self: &Self) -> f64 {
+    pub fn integrate_composite_simpsons_rule(&self) -> f64 {
         let num_points = &self.grid.grid_points.len();
         let grid_points = &self.grid.grid_points;
-        let function_values = &self.function_values;
 
-        // Error handling - the number of grid points must be odd.
-        if num_points % 2 == 0 {
-            panic!("Integral failed to evaluate. The number of grid points must be odd.");
+        // Error handling - at least one pair of grid cells is needed.
+        if *num_points < 3 {
+            panic!("Integral failed to evaluate. At least 3 grid points are required.");
         }
 
+        // With an even number of grid points, the final three grid cells are
+        // left for Simpson's 3/8 rule.
+        let simpson_points = if num_points.is_multiple_of(2) {
+            num_points - 3
+        } else {
+            *num_points
+        };
+
         let mut integral = 0.0;
 
-        for n in (0..(num_points - 2)).step_by(2) {
+        for n in (0..simpson_points.saturating_sub(2)).step_by(2) {
             // Adding the contribution to the integral from each pair of grid
             // cells.
             // The function is approximated as being quadratic over each pair
             // of grid cells.
-            let points =
-                (grid_points[n], grid_points[n + 1], grid_points[n + 2]);
-            let function_values = (
-                function_values[n],
-                function_values[n + 1],
-                function_values[n + 2],
+            integral += self.stencil_integral(
+                n..(n + 3),
+                grid_points[n],
+                grid_points[n + 2],
+            );
+        }
+
+        if num_points.is_multiple_of(2) {
+            let first = num_points - 4;
+            integral += self.stencil_integral(
+                first..*num_points,
+                grid_points[first],
+                grid_points[num_points - 1],
+            );
+        }
+
+        integral
+    }
+
+    /// # Trapezoidal rule numerical integration algorithm.
+    ///
+    /// ## Description
+    /// `integrate_trapezoidal_rule` approximates the definite integral of a
+    /// real-valued function of a real variable. This function is represented
+    /// by a `GridFunction`. The lower and upper limits of the integral are
+    /// the first and last grid points of the `GridFunction`.
+    ///
+    /// Over each grid cell, the function is approximated as being linear. The
+    /// integral over each grid cell is computed, and all of these integrals are
+    /// summed to approximate the total integral. The error is second order in
+    /// the cell widths, on uniform and non-uniform grids.
+    ///
+    /// ## Example use case
+    /// Suppose we want to calculate the integral of the function `f(x) = x^2`
+    /// from `x = 0` to `x = 1`. We can represent this function as a
+    /// `GridFunction` and calculate the integral with the code below.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(2));
+    /// let integral = grid_func.integrate_trapezoidal_rule();
+    /// ```
+    ///
+    pub fn integrate_trapezoidal_rule(&self) -> f64 {
+        let grid_points = &self.grid.grid_points;
+        let function_values = &self.function_values;
+
+        grid_points
+            .windows(2)
+            .zip(function_values.windows(2))
+            .map(|(points, values)| {
+                0.5 * (values[0] + values[1]) * (points[1] - points[0])
+            })
+            .sum()
+    }
+
+    /// # Simpson's 3/8 rule numerical integration algorithm.
+    ///
+    /// ## Description
+    /// `integrate_simpsons_three_eighths_rule` approximates the definite
+    /// integral of a real-valued function of a real variable. This function
+    /// is represented by a `GridFunction`. The lower and upper limits of the
+    /// integral are the first and last grid points of the `GridFunction`.
+    ///
+    /// Over each group of three grid cells, the function is approximated as
+    /// being cubic. The integral over each group of grid cells is computed,
+    /// and all of these integrals are summed to approximate the total
+    /// integral. The rule is exact for cubics, on uniform and non-uniform
+    /// grids.
+    ///
+    /// The number of grid cells must be a multiple of 3, so the number of grid
+    /// points must be 1 more than a multiple of 3, and at least 4.
+    ///
+    /// ## Example use case
+    /// Suppose we want to calculate the integral of the function `f(x) = x^3`
+    /// from `x = 0` to `x = 1`. We can represent this function as a
+    /// `GridFunction` and calculate the integral with the code below.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 13);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(3));
+    /// let integral = grid_func.integrate_simpsons_three_eighths_rule();
+    /// ```
+    ///
+    pub fn integrate_simpsons_three_eighths_rule(&self) -> f64 {
+        let num_points = self.grid.grid_points.len();
+        let grid_points = &self.grid.grid_points;
+
+        // Error handling - the grid cells must form groups of three.
+        if num_points < 4 || !(num_points - 1).is_multiple_of(3) {
+            panic!("Integral failed to evaluate. The number of grid cells must be a multiple of 3.");
+        }
+
+        (0..(num_points - 1))
+            .step_by(3)
+            .map(|n| {
+                self.stencil_integral(
+                    n..(n + 4),
+                    grid_points[n],
+                    grid_points[n + 3],
+                )
+            })
+            .sum()
+    }
+
+    /// # Romberg integration algorithm.
+    ///
+    /// ## Description
+    /// `integrate_romberg` approximates the definite integral of a
+    /// real-valued function of a real variable. This function is represented
+    /// by a `GridFunction` on a uniform grid, such as one created by
+    /// `new_uniform_grid`. The lower and upper limits of the integral are the
+    /// first and last grid points of the `GridFunction`.
+    ///
+    /// The trapezoidal rule is applied using every 2^k-th grid point, from a
+    /// single cell up to all of the grid cells. The error of the trapezoidal
+    /// rule is a series in even powers of the cell width, so these successive
+    /// refinements are combined in a Richardson tableau by
+    /// `richardson_extrapolate`. With 2^m grid cells, the result is exact for
+    /// polynomials of degree up to 2m + 1.
+    ///
+    /// The grid must be uniform and the number of grid cells must be a power
+    /// of 2, so the number of grid points must be 2^m + 1, and at least 2.
+    ///
+    /// ## Example use case
+    /// Suppose we want to calculate the integral of the function `f(x) = e^x`
+    /// from `x = 0` to `x = 1`. We can represent this function as a
+    /// `GridFunction` and calculate the integral with the code below.
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 17);
+    /// let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+    /// let integral = grid_func.integrate_romberg();
+    /// ```
+    ///
+    pub fn integrate_romberg(&self) -> f64 {
+        if let Err(error) = self.validate_romberg_grid() {
+            panic!("Integral failed to evaluate. {}", error);
+        }
+
+        let function_values = &self.function_values;
+        let num_cells = function_values.len() - 1;
+        let num_levels = num_cells.trailing_zeros();
+        let length =
+            self.grid.grid_points[num_cells] - self.grid.grid_points[0];
+
+        // The trapezoidal rule with 2^level cells, coarsest first.
+        let trapezoidal_sums: Vec<f64> = (0..=num_levels)
+            .map(|level| {
+                let stride = num_cells >> level;
+                let interior_sum: f64 = function_values[stride..num_cells]
+                    .iter()
+                    .step_by(stride)
+                    .sum();
+                let cell_width = length / (1 << level) as f64;
+                cell_width
+                    * (0.5 * (function_values[0] + function_values[num_cells])
+                        + interior_sum)
+            })
+            .collect();
+
+        richardson_extrapolate(&trapezoidal_sums, 2.0, 2, 2)
+    }
+
+    /// # Validate Romberg grid
+    ///
+    /// ## Description
+    /// `validate_romberg_grid` checks that the grid is uniform, and that the
+    /// number of grid cells is a power of 2.
+    ///
+    fn validate_romberg_grid(&self) -> Result<(), NumericalMethodsError> {
+        let grid_points = &self.grid.grid_points;
+        let num_points = grid_points.len();
+
+        if num_points < 2 || !(num_points - 1).is_power_of_two() {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The number of grid cells must be a power of 2.".to_string(),
+            ));
+        }
+
+        let uniform_grid = Grid::new_uniform_grid(
+            grid_points[0],
+            grid_points[num_points - 1],
+            num_points,
+        );
+        if !self.grid.is_compatible(&uniform_grid, GRID_TOLERANCE) {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The grid must be uniform.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// # Stencil integral
+    ///
+    /// ## Description
+    /// `stencil_integral` returns the integral from `lower_limit` to
+    /// `upper_limit` of the `Polynomial` which interpolates the function
+    /// values at the grid points in `stencil`. The polynomial is built in
+    /// powers of x minus the first stencil point, so that its coefficients
+    /// stay well scaled on fine grids.
+    ///
+    pub(crate) fn stencil_integral(
+        &self,
+        stencil: Range<usize>,
+        lower_limit: f64,
+        upper_limit: f64,
+    ) -> f64 {
+        let origin = self.grid.grid_points[stencil.start];
+        Polynomial::interpolate(
+            &self.grid.grid_points[stencil.clone()],
+            &self.function_values[stencil],
+            origin,
+        )
+        .integrate(lower_limit - origin, upper_limit - origin)
+    }
+}
+
+// Fallible numerical integration algorithms.
+impl GridFunction {
+    /// # Try Riemann sum
+    ///
+    /// ## Description
+    /// `try_integrate_riemann_sum` is the fallible version of
+    /// `integrate_riemann_sum`. It returns an error if the current
+    /// `GridFunction` is not valid, or if it has fewer than 2 grid points.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(2));
+    /// let integral = grid_func.try_integrate_riemann_sum()?;
+    /// ```
+    ///
+    pub fn try_integrate_riemann_sum(
+        &self,
+    ) -> Result<f64, NumericalMethodsError> {
+        self.validate_with_min_points(2)?;
+        Ok(self.integrate_riemann_sum())
+    }
+
+    /// # Try Simpson's composite rule
+    ///
+    /// ## Description
+    /// `try_integrate_composite_simpsons_rule` is the fallible version of
+    /// `integrate_composite_simpsons_rule`. It returns an error if the current
+    /// `GridFunction` is not valid, or if it has fewer than 3 grid points.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(2));
+    /// let integral = grid_func.try_integrate_composite_simpsons_rule()?;
+    /// ```
+    ///
+    pub fn try_integrate_composite_simpsons_rule(
+        &self,
+    ) -> Result<f64, NumericalMethodsError> {
+        self.validate_with_min_points(3)?;
+        Ok(self.integrate_composite_simpsons_rule())
+    }
+
+    /// # Try trapezoidal rule
+    ///
+    /// ## Description
+    /// `try_integrate_trapezoidal_rule` is the fallible version of
+    /// `integrate_trapezoidal_rule`. It returns an error if the current
+    /// `GridFunction` is not valid, or if it has fewer than 2 grid points.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(2));
+    /// let integral = grid_func.try_integrate_trapezoidal_rule()?;
+    /// ```
+    ///
+    pub fn try_integrate_trapezoidal_rule(
+        &self,
+    ) -> Result<f64, NumericalMethodsError> {
+        self.validate_with_min_points(2)?;
+        Ok(self.integrate_trapezoidal_rule())
+    }
+
+    /// # Try Simpson's 3/8 rule
+    ///
+    /// ## Description
+    /// `try_integrate_simpsons_three_eighths_rule` is the fallible version of
+    /// `integrate_simpsons_three_eighths_rule`. It returns an error if the
+    /// current `GridFunction` is not valid, if it has fewer than 4 grid
+    /// points, or if the number of grid cells is not a multiple of 3.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 13);
+    /// let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(3));
+    /// let integral = grid_func.try_integrate_simpsons_three_eighths_rule()?;
+    /// ```
+    ///
+    pub fn try_integrate_simpsons_three_eighths_rule(
+        &self,
+    ) -> Result<f64, NumericalMethodsError> {
+        self.validate_with_min_points(4)?;
+
+        if !(self.grid.grid_points.len() - 1).is_multiple_of(3) {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The number of grid cells must be a multiple of 3.".to_string(),
+            ));
+        }
+
+        Ok(self.integrate_simpsons_three_eighths_rule())
+    }
+
+    /// # Try Romberg integration
+    ///
+    /// ## Description
+    /// `try_integrate_romberg` is the fallible version of
+    /// `integrate_romberg`. It returns an error if the current `GridFunction`
+    /// is not valid, if the grid is not uniform, or if the number of grid
+    /// cells is not a power of 2.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = Grid::new_uniform_grid(0.0, 1.0, 17);
+    /// let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+    /// let integral = grid_func.try_integrate_romberg()?;
+    /// ```
+    ///
+    pub fn try_integrate_romberg(&self) -> Result<f64, NumericalMethodsError> {
+        self.validate_with_min_points(2)?;
+        self.validate_romberg_grid()?;
+        Ok(self.integrate_romberg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{max_cell_width, test_grids};
+
+    #[test]
+    fn test_integrate_riemann_sum() {
+        for (name, grid) in test_grids(0.0, 2.0, 101) {
+            // The Riemann sum is exact for constant functions.
+            let grid_func =
+                GridFunction::new_constant_grid_function(&grid, 3.0);
+            assert!(
+                (grid_func.integrate_riemann_sum() - 6.0).abs() < 1e-12,
+                "integrate_riemann_sum failed for a constant on the {} grid.",
+                name
+            );
+
+            // The error is bounded by half the widest cell width times the
+            // length of the domain, because |sin'(x)| <= 1.
+            let max_cell_width = max_cell_width(&grid);
+            let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+            let exact = 1.0 - 2.0_f64.cos();
+            assert!(
+                (grid_func.integrate_riemann_sum() - exact).abs()
+                    < max_cell_width,
+                "integrate_riemann_sum failed for sin on the {} grid.",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_integrate_composite_simpsons_rule() {
+        for (name, grid) in test_grids(0.0, 2.0, 101) {
+            // Simpson's rule is exact for quadratics, even on non-uniform
+            // grids.
+            let grid_func = GridFunction::new_grid_function(&grid, |x| {
+                x * x - 3.0 * x + 2.0
+            });
+            let exact = 8.0 / 3.0 - 6.0 + 4.0;
+            assert!(
+                (grid_func.integrate_composite_simpsons_rule() - exact).abs()
+                    < 1e-10,
+                "integrate_composite_simpsons_rule failed for a quadratic on the {} grid.",
+                name
             );
 
-            let quadratic_coefficients =
-                quadratic_interpolation::quadratic_interpolation_coefficients(
-                    points,
-                    function_values,
+            let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+            let exact = 1.0 - 2.0_f64.cos();
+            assert!(
+                (grid_func.integrate_composite_simpsons_rule() - exact).abs()
+                    < 1e-6,
+                "integrate_composite_simpsons_rule failed for sin on the {} grid.",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_integrate_composite_simpsons_rule_even_points() {
+        for num_points in [4, 6, 100] {
+            for (name, grid) in test_grids(0.0, 2.0, num_points) {
+                // The 3/8 rule on the final three cells is also exact for
+                // quadratics.
+                let grid_func = GridFunction::new_grid_function(&grid, |x| {
+                    x * x - 3.0 * x + 2.0
+                });
+                let exact = 8.0 / 3.0 - 6.0 + 4.0;
+                assert!(
+                    (grid_func.integrate_composite_simpsons_rule() - exact)
+                        .abs()
+                        < 1e-10,
+                    "{} points on the {} grid.",
+                    num_points,
+                    name
                 );
+            }
+        }
+
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 2.0, 100);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        let exact = 1.0 - 2.0_f64.cos();
+        assert!(
+            (grid_func.integrate_composite_simpsons_rule() - exact).abs()
+                < 1e-7
+        );
+    }
+
+    #[test]
+    fn test_integrate_trapezoidal_rule() {
+        for (name, grid) in test_grids(0.0, 2.0, 101) {
+            // The trapezoidal rule is exact for linear functions.
+            let grid_func =
+                GridFunction::new_grid_function(&grid, |x| 3.0 * x - 1.0);
+            assert!(
+                (grid_func.integrate_trapezoidal_rule() - 4.0).abs() < 1e-12,
+                "integrate_trapezoidal_rule failed for a line on the {} grid.",
+                name
+            );
 
-            integral += quadratic_interpolation::quadratic_integral(
-                quadratic_coefficients,
-                points.0,
-                points.2,
+            // The error is bounded by the widest cell width squared times the
+            // length of the domain over 12, because |sin''(x)| <= 1.
+            let max_cell_width = max_cell_width(&grid);
+            let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+            let exact = 1.0 - 2.0_f64.cos();
+            assert!(
+                (grid_func.integrate_trapezoidal_rule() - exact).abs()
+                    <= max_cell_width.powi(2) * 2.0 / 12.0,
+                "integrate_trapezoidal_rule failed for sin on the {} grid.",
+                name
             );
         }
+    }
 
-        integral
+    #[test]
+    fn test_integrate_simpsons_three_eighths_rule() {
+        for (name, grid) in test_grids(0.0, 2.0, 100) {
+            // Simpson's 3/8 rule is exact for cubics, even on non-uniform
+            // grids.
+            let grid_func =
+                GridFunction::new_grid_function(&grid, |x| x * x * x - x);
+            assert!(
+                (grid_func.integrate_simpsons_three_eighths_rule() - 2.0).abs()
+                    < 1e-10,
+                "integrate_simpsons_three_eighths_rule failed for a cubic on the {} grid.",
+                name
+            );
+
+            let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+            let exact = 1.0 - 2.0_f64.cos();
+            assert!(
+                (grid_func.integrate_simpsons_three_eighths_rule() - exact)
+                    .abs()
+                    < 1e-6,
+                "integrate_simpsons_three_eighths_rule failed for sin on the {} grid.",
+                name
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of 3")]
+    fn test_integrate_simpsons_three_eighths_rule_wrong_cells() {
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        grid_func.integrate_simpsons_three_eighths_rule();
+    }
+
+    #[test]
+    fn test_integrate_romberg() {
+        // With 2^3 cells, Romberg integration is exact for polynomials of
+        // degree up to 7.
+        let grid = Grid::new_uniform_grid(0.0, 2.0, 9);
+        let grid_func = GridFunction::new_grid_function(&grid, |x| x.powi(7));
+        assert!((grid_func.integrate_romberg() - 32.0).abs() < 1e-10);
+
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 17);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+        let exact = 1.0_f64.exp() - 1.0;
+        assert!((grid_func.integrate_romberg() - exact).abs() < 1e-13);
+        assert!((grid_func.integrate_trapezoidal_rule() - exact).abs() > 1e-4);
+
+        // A single cell is the trapezoidal rule.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 2);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+        assert_eq!(
+            grid_func.integrate_romberg(),
+            grid_func.integrate_trapezoidal_rule()
+        );
+    }
+
+    #[test]
+    fn test_try_integrate_romberg_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func.try_integrate_romberg(),
+            Err(NumericalMethodsError::InvalidGrid(
+                "The number of grid cells must be a power of 2.".to_string()
+            ))
+        );
+
+        let grid = Grid::new_chebyshev_grid(0.0, 1.0, 17);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func.try_integrate_romberg(),
+            Err(NumericalMethodsError::InvalidGrid(
+                "The grid must be uniform.".to_string()
+            ))
+        );
+
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 1);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func.try_integrate_romberg(),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_integrals() {
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func.try_integrate_riemann_sum(),
+            Ok(grid_func.integrate_riemann_sum())
+        );
+        assert_eq!(
+            grid_func.try_integrate_composite_simpsons_rule(),
+            Ok(grid_func.integrate_composite_simpsons_rule())
+        );
+
+        assert_eq!(
+            grid_func.try_integrate_trapezoidal_rule(),
+            Ok(grid_func.integrate_trapezoidal_rule())
+        );
+
+        // An even number of grid points is handled by Simpson's rule, but a
+        // number of cells which is not a multiple of 3 gives an error for the
+        // 3/8 rule rather than a panic.
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 10);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func.try_integrate_composite_simpsons_rule(),
+            Ok(grid_func.integrate_composite_simpsons_rule())
+        );
+        assert_eq!(
+            grid_func.try_integrate_simpsons_three_eighths_rule(),
+            Ok(grid_func.integrate_simpsons_three_eighths_rule())
+        );
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 11);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert!(matches!(
+            grid_func.try_integrate_simpsons_three_eighths_rule(),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+
+        // An empty grid gives an error rather than a panic.
+        let grid = crate::grid::Grid::new_uniform_grid(0.0, 1.0, 0);
+        let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+        assert_eq!(
+            grid_func.try_integrate_riemann_sum(),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 0
+            })
+        );
+        assert_eq!(
+            grid_func.try_integrate_composite_simpsons_rule(),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 3,
+                actual: 0
+            })
+        );
     }
 }
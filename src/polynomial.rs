@@ -0,0 +1,926 @@
+use nalgebra::{Complex, DMatrix};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use crate::error::NumericalMethodsError;
+use crate::polynomial_interpolation::validate_interpolation_points;
+
+// Polynomials with real coefficients.
+//
+// The roots are the eigenvalues of the companion matrix of the monic
+// polynomial, computed with the real Schur decomposition in nalgebra. Each
+// root is then polished with a few steps of Newton's method in complex
+// arithmetic, which are only kept while they reduce |p|.
+//
+// `interpolate` computes the divided differences of the data and expands
+// the Newton form about a chosen origin. It is meant for the few points of a
+// quadrature stencil, since the powers of x become badly conditioned at high
+// degree.
+//
+// The binary operators between two `Polynomials` call the arithmetic
+// methods below, and are implemented for owned and borrowed operands. `/`
+// and `%` give the quotient and remainder of `div_rem`, and panic if the
+// divisor is zero. Note that when `std::ops::Add` is in scope,
+// `polynomial_1.add(&polynomial_2)` resolves to the trait method. Write
+// `Polynomial::add(&polynomial_1, &polynomial_2)` to call the arithmetic
+// method explicitly.
+
+const NEWTON_POLISHING_STEPS: usize = 3;
+
+/// # Polynomial
+///
+/// ## Description
+/// `Polynomial` represents the polynomial
+///
+/// p(x) = c_0 + c_1 x + c_2 x^2 + ... + c_n x^n,
+///
+/// where `coefficients[k]` is c_k, lowest degree first. `new` removes
+/// trailing zero coefficients, and the zero polynomial has the single
+/// coefficient 0.
+///
+/// ## Example use case
+/// Suppose that we want the roots and the integral from 0 to 1 of
+/// p(x) = x^3 - 2x + 1. The code below computes them.
+/// ```
+/// let polynomial = Polynomial::new(vec![1.0, -2.0, 0.0, 1.0]);
+/// let roots = polynomial.real_roots(1e-10);
+/// let integral = polynomial.integrate(0.0, 1.0);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    pub coefficients: Vec<f64>,
+}
+
+impl Polynomial {
+    /// # New polynomial
+    ///
+    /// ## Description
+    /// `new` creates the polynomial with the given `coefficients`, lowest
+    /// degree first, removing any trailing zero coefficients.
+    ///
+    /// ## Example use case
+    /// ```
+    /// // 3 + 2x + x^2
+    /// let polynomial = Polynomial::new(vec![3.0, 2.0, 1.0]);
+    /// ```
+    ///
+    pub fn new(coefficients: Vec<f64>) -> Self {
+        let mut coefficients = coefficients;
+        while coefficients.len() > 1 && coefficients.last() == Some(&0.0) {
+            coefficients.pop();
+        }
+        if coefficients.is_empty() {
+            coefficients.push(0.0);
+        }
+        Polynomial { coefficients }
+    }
+
+    /// # From roots
+    ///
+    /// ## Description
+    /// `from_roots` creates the monic polynomial whose roots are `roots`,
+    /// (x - roots[0]) (x - roots[1]) ... Repeated roots give repeated
+    /// factors.
+    ///
+    /// ## Example use case
+    /// ```
+    /// // (x - 1)(x + 2) = x^2 + x - 2
+    /// let polynomial = Polynomial::from_roots(&[1.0, -2.0]);
+    /// ```
+    ///
+    pub fn from_roots(roots: &[f64]) -> Self {
+        roots
+            .iter()
+            .fold(Polynomial::new(vec![1.0]), |product, &root| {
+                product.multiply(&Polynomial::new(vec![-root, 1.0]))
+            })
+    }
+
+    /// # Interpolate
+    ///
+    /// ## Description
+    /// `interpolate` creates the polynomial of lowest degree which takes the
+    /// value `values[i]` at `points[i]`, in powers of t = x - `origin`. So the
+    /// returned polynomial p satisfies p(points[i] - origin) = values[i], and
+    /// an `origin` of 0 gives the interpolant in powers of x. Choosing an
+    /// `origin` among the points keeps the coefficients well scaled when the
+    /// points are far from 0 compared with their spacing. It is intended for
+    /// a few points at a time, since the coefficients of a high degree
+    /// interpolant lose all accuracy to cancellation.
+    ///
+    /// The points must be finite and distinct, and there must be at least
+    /// one. For an error instead of a panic, use `try_interpolate`.
+    ///
+    /// ## Example use case
+    /// Suppose that we want the integral from 1 to 3 of the quadratic
+    /// through the values (0, 1, 4) at the points (1, 2, 3). The code below
+    /// builds the quadratic in powers of x - 2 and integrates it.
+    /// ```
+    /// let quadratic =
+    ///     Polynomial::interpolate(&[1.0, 2.0, 3.0], &[0.0, 1.0, 4.0], 2.0);
+    /// let integral = quadratic.integrate(-1.0, 1.0);
+    /// ```
+    ///
+    pub fn interpolate(points: &[f64], values: &[f64], origin: f64) -> Self {
+        match Polynomial::try_interpolate(points, values, origin) {
+            Ok(polynomial) => polynomial,
+            Err(error) => panic!("Polynomial interpolation failed. {error}"),
+        }
+    }
+
+    /// # Try interpolate
+    ///
+    /// ## Description
+    /// `try_interpolate` is the fallible version of `interpolate`. It returns
+    /// `LengthMismatch` if `points` and `values` have different lengths,
+    /// `InsufficientPoints` if there are no points, and `InvalidParameter` if
+    /// the points are not finite and distinct.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let quadratic =
+    ///     Polynomial::try_interpolate(&points, &values, points[0])?;
+    /// ```
+    ///
+    pub fn try_interpolate(
+        points: &[f64],
+        values: &[f64],
+        origin: f64,
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_interpolation_points(points, values, 1)?;
+
+        // Divided differences, computed in place.
+        let mut coefficients = values.to_vec();
+        for k in 1..points.len() {
+            for i in (k..points.len()).rev() {
+                coefficients[i] = (coefficients[i] - coefficients[i - 1])
+                    / (points[i] - points[i - k]);
+            }
+        }
+
+        Ok(Polynomial::from_newton_form(points, &coefficients, origin))
+    }
+
+    /// # From Newton form
+    ///
+    /// ## Description
+    /// `from_newton_form` expands the Newton form polynomial
+    /// c_0 + c_1 (x - x_0) + c_2 (x - x_0)(x - x_1) + ..., with `points` x_k
+    /// and `coefficients` c_k, in powers of t = x - `origin`. It uses
+    /// Horner's method with polynomial arithmetic.
+    ///
+    fn from_newton_form(
+        points: &[f64],
+        coefficients: &[f64],
+        origin: f64,
+    ) -> Self {
+        coefficients.iter().zip(points).rev().fold(
+            Polynomial::new(vec![0.0]),
+            |value, (&coefficient, &x_k)| {
+                value
+                    .multiply(&Polynomial::new(vec![origin - x_k, 1.0]))
+                    .add(&Polynomial::new(vec![coefficient]))
+            },
+        )
+    }
+
+    /// # Degree
+    ///
+    /// ## Description
+    /// `degree` returns the index of the highest nonzero coefficient. The
+    /// zero polynomial has degree 0.
+    ///
+    /// ## Example use case
+    /// ```
+    /// assert_eq!(Polynomial::new(vec![3.0, 2.0, 1.0]).degree(), 2);
+    /// ```
+    ///
+    pub fn degree(&self) -> usize {
+        self.coefficients
+            .iter()
+            .rposition(|&coefficient| coefficient != 0.0)
+            .unwrap_or(0)
+    }
+
+    /// # Is zero
+    ///
+    /// ## Description
+    /// `is_zero` returns true if every coefficient is zero.
+    ///
+    /// ## Example use case
+    /// ```
+    /// assert!(Polynomial::new(vec![]).is_zero());
+    /// ```
+    ///
+    pub fn is_zero(&self) -> bool {
+        self.coefficients
+            .iter()
+            .all(|&coefficient| coefficient == 0.0)
+    }
+
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the value of the polynomial at `x`, using Horner's
+    /// method.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let value = polynomial.evaluate(0.5);
+    /// ```
+    ///
+    pub fn evaluate(&self, x: f64) -> f64 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |value, coefficient| value * x + coefficient)
+    }
+
+    /// # Add
+    ///
+    /// ## Description
+    /// `add` returns the sum of the current `Polynomial` and `polynomial`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let sum = Polynomial::add(&polynomial_1, &polynomial_2);
+    /// ```
+    ///
+    pub fn add(&self, polynomial: &Polynomial) -> Self {
+        self.combine(polynomial, |a, b| a + b)
+    }
+
+    /// # Subtract
+    ///
+    /// ## Description
+    /// `subtract` returns the current `Polynomial` minus `polynomial`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let difference = polynomial_1.subtract(&polynomial_2);
+    /// ```
+    ///
+    pub fn subtract(&self, polynomial: &Polynomial) -> Self {
+        self.combine(polynomial, |a, b| a - b)
+    }
+
+    /// # Combine
+    ///
+    /// ## Description
+    /// `combine` applies `operation` to the coefficients of each degree,
+    /// treating missing coefficients as zero.
+    ///
+    fn combine<F>(&self, polynomial: &Polynomial, operation: F) -> Self
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let length = self.coefficients.len().max(polynomial.coefficients.len());
+        let coefficient = |coefficients: &[f64], k: usize| {
+            coefficients.get(k).copied().unwrap_or(0.0)
+        };
+        Polynomial::new(
+            (0..length)
+                .map(|k| {
+                    operation(
+                        coefficient(&self.coefficients, k),
+                        coefficient(&polynomial.coefficients, k),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// # Multiply
+    ///
+    /// ## Description
+    /// `multiply` returns the product of the current `Polynomial` and
+    /// `polynomial`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let product = Polynomial::multiply(&polynomial_1, &polynomial_2);
+    /// ```
+    ///
+    pub fn multiply(&self, polynomial: &Polynomial) -> Self {
+        let length =
+            self.coefficients.len() + polynomial.coefficients.len() - 1;
+        let mut coefficients = vec![0.0; length];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in polynomial.coefficients.iter().enumerate() {
+                coefficients[i + j] += a * b;
+            }
+        }
+        Polynomial::new(coefficients)
+    }
+
+    /// # Divide with remainder
+    ///
+    /// ## Description
+    /// `div_rem` divides the current `Polynomial` by `divisor` using long
+    /// division, and returns the quotient q and remainder r, so that
+    /// p = q `divisor` + r and the degree of r is less than that of
+    /// `divisor`.
+    ///
+    /// `div_rem` panics if `divisor` is zero. See `try_div_rem` for a version
+    /// which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let (quotient, remainder) = polynomial.div_rem(&divisor);
+    /// ```
+    ///
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Self, Self) {
+        match self.try_div_rem(divisor) {
+            Ok(result) => result,
+            Err(error) => panic!("Polynomial division failed. {}", error),
+        }
+    }
+
+    /// # Try divide with remainder
+    ///
+    /// ## Description
+    /// `try_div_rem` is the fallible version of `div_rem`. It returns an
+    /// `InvalidParameter` error if `divisor` is zero.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let (quotient, remainder) = polynomial.try_div_rem(&divisor)?;
+    /// ```
+    ///
+    pub fn try_div_rem(
+        &self,
+        divisor: &Polynomial,
+    ) -> Result<(Self, Self), NumericalMethodsError> {
+        if divisor.is_zero() {
+            return Err(NumericalMethodsError::InvalidParameter(
+                "The divisor must not be the zero polynomial.".to_string(),
+            ));
+        }
+
+        let divisor_degree = divisor.degree();
+        let leading = divisor.coefficients[divisor_degree];
+        let mut remainder = self.coefficients[..=self.degree()].to_vec();
+        if remainder.len() <= divisor_degree {
+            return Ok((
+                Polynomial::new(vec![0.0]),
+                Polynomial::new(remainder),
+            ));
+        }
+
+        // Eliminates the leading coefficient of the remainder, from the
+        // highest degree down.
+        let mut quotient = vec![0.0; remainder.len() - divisor_degree];
+        for k in (0..quotient.len()).rev() {
+            let factor = remainder[k + divisor_degree] / leading;
+            quotient[k] = factor;
+            for (j, coefficient) in
+                divisor.coefficients[..=divisor_degree].iter().enumerate()
+            {
+                remainder[k + j] -= factor * coefficient;
+            }
+            remainder[k + divisor_degree] = 0.0;
+        }
+        remainder.truncate(divisor_degree.max(1));
+
+        Ok((Polynomial::new(quotient), Polynomial::new(remainder)))
+    }
+
+    /// # Derivative
+    ///
+    /// ## Description
+    /// `derivative` returns the derivative of the current `Polynomial`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let slope = polynomial.derivative().evaluate(0.5);
+    /// ```
+    ///
+    pub fn derivative(&self) -> Self {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(k, coefficient)| k as f64 * coefficient)
+                .collect(),
+        )
+    }
+
+    /// # Antiderivative
+    ///
+    /// ## Description
+    /// `antiderivative` returns the antiderivative of the current
+    /// `Polynomial` which is zero at x = 0.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let antiderivative = polynomial.antiderivative();
+    /// ```
+    ///
+    pub fn antiderivative(&self) -> Self {
+        let mut coefficients = vec![0.0];
+        coefficients.extend(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .map(|(k, coefficient)| coefficient / (k + 1) as f64),
+        );
+        Polynomial::new(coefficients)
+    }
+
+    /// # Integrate
+    ///
+    /// ## Description
+    /// `integrate` returns the exact integral of the current `Polynomial`
+    /// from `lower_limit` to `upper_limit`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = polynomial.integrate(0.0, 1.0);
+    /// ```
+    ///
+    pub fn integrate(&self, lower_limit: f64, upper_limit: f64) -> f64 {
+        let antiderivative = self.antiderivative();
+        antiderivative.evaluate(upper_limit)
+            - antiderivative.evaluate(lower_limit)
+    }
+
+    /// # Compose
+    ///
+    /// ## Description
+    /// `compose` returns the composition p(q(x)) of the current `Polynomial`
+    /// p with `inner` q, using Horner's method with polynomial arithmetic.
+    ///
+    /// ## Example use case
+    /// Suppose that we want to shift a polynomial p to p(x - 1). The code
+    /// below does this.
+    /// ```
+    /// let shifted = polynomial.compose(&Polynomial::new(vec![-1.0, 1.0]));
+    /// ```
+    ///
+    pub fn compose(&self, inner: &Polynomial) -> Self {
+        self.coefficients.iter().rev().fold(
+            Polynomial::new(vec![0.0]),
+            |value, &coefficient| {
+                value
+                    .multiply(inner)
+                    .add(&Polynomial::new(vec![coefficient]))
+            },
+        )
+    }
+
+    /// # Roots
+    ///
+    /// ## Description
+    /// `roots` returns all of the real and complex roots of the current
+    /// `Polynomial`, repeated according to their multiplicity, sorted by
+    /// real part and then by imaginary part. The complex roots come in
+    /// conjugate pairs. A nonzero constant has no roots.
+    ///
+    /// The roots are the eigenvalues of the companion matrix, polished by
+    /// Newton's method. Simple roots are accurate to close to machine
+    /// precision relative to the size of the coefficients, but a root of
+    /// multiplicity m is only accurate to about the mth root of machine
+    /// precision, and may be returned as a complex pair with a small
+    /// imaginary part.
+    ///
+    /// `roots` panics for the zero polynomial, for which every x is a root.
+    /// See `try_roots` for a version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// // x^2 + 1 has the roots -i and i.
+    /// let roots = Polynomial::new(vec![1.0, 0.0, 1.0]).roots();
+    /// ```
+    ///
+    pub fn roots(&self) -> Vec<Complex<f64>> {
+        match self.try_roots() {
+            Ok(roots) => roots,
+            Err(error) => panic!("Polynomial roots failed. {}", error),
+        }
+    }
+
+    /// # Real roots
+    ///
+    /// ## Description
+    /// `real_roots` returns the real parts of the roots from `roots` whose
+    /// imaginary parts are at most `tolerance` times max(1, |root|), in
+    /// increasing order.
+    ///
+    /// `real_roots` panics for the zero polynomial. See `try_real_roots` for
+    /// a version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let roots = polynomial.real_roots(1e-10);
+    /// ```
+    ///
+    pub fn real_roots(&self, tolerance: f64) -> Vec<f64> {
+        match self.try_real_roots(tolerance) {
+            Ok(roots) => roots,
+            Err(error) => panic!("Polynomial roots failed. {}", error),
+        }
+    }
+
+    /// # Try roots
+    ///
+    /// ## Description
+    /// `try_roots` is the fallible version of `roots`. It returns an
+    /// `InvalidParameter` error for the zero polynomial, or if a coefficient
+    /// is not finite.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let roots = polynomial.try_roots()?;
+    /// ```
+    ///
+    pub fn try_roots(
+        &self,
+    ) -> Result<Vec<Complex<f64>>, NumericalMethodsError> {
+        if self.is_zero() {
+            return Err(NumericalMethodsError::InvalidParameter(
+                "Every point is a root of the zero polynomial.".to_string(),
+            ));
+        }
+        if !self.coefficients.iter().all(|c| c.is_finite()) {
+            return Err(NumericalMethodsError::InvalidParameter(
+                "The coefficients of the polynomial must be finite."
+                    .to_string(),
+            ));
+        }
+
+        let degree = self.degree();
+        let leading = self.coefficients[degree];
+
+        // Roots at zero are removed first, since they make the companion
+        // matrix singular and are known exactly.
+        let num_zero_roots = self
+            .coefficients
+            .iter()
+            .position(|&coefficient| coefficient != 0.0)
+            .unwrap_or(0);
+        let mut roots = vec![Complex::new(0.0, 0.0); num_zero_roots];
+        let reduced = &self.coefficients[num_zero_roots..=degree];
+        let size = reduced.len() - 1;
+
+        if size > 0 {
+            // The companion matrix has ones on the subdiagonal, and minus the
+            // monic coefficients in the last column.
+            let companion = DMatrix::from_fn(size, size, |row, column| {
+                if column == size - 1 {
+                    -reduced[row] / leading
+                } else if row == column + 1 {
+                    1.0
+                } else {
+                    0.0
+                }
+            });
+            roots.extend(
+                companion
+                    .complex_eigenvalues()
+                    .iter()
+                    .map(|&root| self.polish_root(root)),
+            );
+        }
+
+        roots.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+        Ok(roots)
+    }
+
+    /// # Try real roots
+    ///
+    /// ## Description
+    /// `try_real_roots` is the fallible version of `real_roots`. It returns
+    /// the same errors as `try_roots`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let roots = polynomial.try_real_roots(1e-10)?;
+    /// ```
+    ///
+    pub fn try_real_roots(
+        &self,
+        tolerance: f64,
+    ) -> Result<Vec<f64>, NumericalMethodsError> {
+        Ok(self
+            .try_roots()?
+            .iter()
+            .filter(|root| root.im.abs() <= tolerance * root.norm().max(1.0))
+            .map(|root| root.re)
+            .collect())
+    }
+
+    /// # Polish root
+    ///
+    /// ## Description
+    /// `polish_root` improves an approximate root with a few steps of
+    /// Newton's method in complex arithmetic, keeping each step only if it
+    /// reduces |p|. Real roots are kept real.
+    ///
+    fn polish_root(&self, root: Complex<f64>) -> Complex<f64> {
+        let value_and_derivative = |z: Complex<f64>| {
+            self.coefficients.iter().rev().fold(
+                (Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)),
+                |(value, derivative), &coefficient| {
+                    (value * z + coefficient, derivative * z + value)
+                },
+            )
+        };
+
+        let mut root = root;
+        let (mut value, mut derivative) = value_and_derivative(root);
+        for _ in 0..NEWTON_POLISHING_STEPS {
+            if derivative.norm() == 0.0 {
+                break;
+            }
+            let mut candidate = root - value / derivative;
+            if root.im == 0.0 {
+                candidate.im = 0.0;
+            }
+            let (new_value, new_derivative) = value_and_derivative(candidate);
+            if new_value.norm() >= value.norm() {
+                break;
+            }
+            root = candidate;
+            value = new_value;
+            derivative = new_derivative;
+        }
+
+        root
+    }
+}
+
+/// # Implement polynomial operator
+///
+/// ## Description
+/// `impl_polynomial_operator` implements the binary operator `$trait` for
+/// every combination of owned and borrowed `Polynomial` operands, by
+/// calling `$operation` on borrowed operands.
+///
+macro_rules! impl_polynomial_operator {
+    ($trait:ident, $method:ident, $operation:expr) => {
+        impl $trait<&Polynomial> for &Polynomial {
+            type Output = Polynomial;
+
+            fn $method(self, polynomial: &Polynomial) -> Polynomial {
+                ($operation)(self, polynomial)
+            }
+        }
+
+        impl $trait<Polynomial> for &Polynomial {
+            type Output = Polynomial;
+
+            fn $method(self, polynomial: Polynomial) -> Polynomial {
+                ($operation)(self, &polynomial)
+            }
+        }
+
+        impl $trait<&Polynomial> for Polynomial {
+            type Output = Polynomial;
+
+            fn $method(self, polynomial: &Polynomial) -> Polynomial {
+                ($operation)(&self, polynomial)
+            }
+        }
+
+        impl $trait<Polynomial> for Polynomial {
+            type Output = Polynomial;
+
+            fn $method(self, polynomial: Polynomial) -> Polynomial {
+                ($operation)(&self, &polynomial)
+            }
+        }
+    };
+}
+
+impl_polynomial_operator!(Add, add, Polynomial::add);
+impl_polynomial_operator!(Sub, sub, Polynomial::subtract);
+impl_polynomial_operator!(Mul, mul, Polynomial::multiply);
+impl_polynomial_operator!(Div, div, quotient);
+impl_polynomial_operator!(Rem, rem, remainder);
+
+/// # Quotient
+///
+/// ## Description
+/// `quotient` returns the quotient of `div_rem`, for the `/` operator.
+///
+fn quotient(polynomial: &Polynomial, divisor: &Polynomial) -> Polynomial {
+    polynomial.div_rem(divisor).0
+}
+
+/// # Remainder
+///
+/// ## Description
+/// `remainder` returns the remainder of `div_rem`, for the `%` operator.
+///
+fn remainder(polynomial: &Polynomial, divisor: &Polynomial) -> Polynomial {
+    polynomial.div_rem(divisor).1
+}
+
+impl Mul<f64> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, scalar: f64) -> Polynomial {
+        Polynomial::new(self.coefficients.iter().map(|c| c * scalar).collect())
+    }
+}
+
+impl Mul<f64> for Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, scalar: f64) -> Polynomial {
+        &self * scalar
+    }
+}
+
+impl Mul<&Polynomial> for f64 {
+    type Output = Polynomial;
+
+    fn mul(self, polynomial: &Polynomial) -> Polynomial {
+        polynomial * self
+    }
+}
+
+impl Mul<Polynomial> for f64 {
+    type Output = Polynomial;
+
+    fn mul(self, polynomial: Polynomial) -> Polynomial {
+        &polynomial * self
+    }
+}
+
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Polynomial {
+        self * -1.0
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Polynomial {
+        &self * -1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_and_evaluate() {
+        let polynomial = Polynomial::new(vec![1.0, -2.0, 0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(polynomial.coefficients, vec![1.0, -2.0, 0.0, 1.0]);
+        assert_eq!(polynomial.degree(), 3);
+        assert_eq!(polynomial.evaluate(2.0), 5.0);
+
+        let zero = Polynomial::new(vec![]);
+        assert_eq!(zero.coefficients, vec![0.0]);
+        assert!(zero.is_zero());
+        assert_eq!(zero.degree(), 0);
+
+        // (x - 1)(x + 2) = x^2 + x - 2
+        assert_eq!(
+            Polynomial::from_roots(&[1.0, -2.0]).coefficients,
+            vec![-2.0, 1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // The interpolant through 4 points reproduces 1 - 2x + 4x^3.
+        let p = Polynomial::new(vec![1.0, -2.0, 0.0, 4.0]);
+        let points = [-1.0, 0.5, 2.0, 3.0];
+        let values: Vec<f64> = points.iter().map(|&x| p.evaluate(x)).collect();
+        let interpolant = Polynomial::interpolate(&points, &values, 0.0);
+        for (a, b) in interpolant.coefficients.iter().zip(&p.coefficients) {
+            assert!((a - b).abs() < 1e-13);
+        }
+
+        // In powers of x - origin, the interpolant is p(t + origin), and
+        // stays accurate for points far from 0.
+        let origin = 1000.0;
+        let shifted_points: Vec<f64> =
+            points.iter().map(|x| x + origin).collect();
+        let shifted = Polynomial::interpolate(&shifted_points, &values, origin);
+        for (a, b) in shifted.coefficients.iter().zip(&p.coefficients) {
+            assert!((a - b).abs() < 1e-10);
+        }
+        assert!(
+            (shifted.integrate(-1.0, 2.0) - p.integrate(-1.0, 2.0)).abs()
+                < 1e-10
+        );
+
+        assert!(matches!(
+            Polynomial::try_interpolate(&[0.0, 1.0], &[1.0], 0.0),
+            Err(NumericalMethodsError::LengthMismatch { .. })
+        ));
+        assert!(matches!(
+            Polynomial::try_interpolate(&[1.0, 1.0], &[1.0, 2.0], 0.0),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Polynomial::new(vec![1.0, 2.0, 3.0]);
+        let q = Polynomial::new(vec![-1.0, 1.0]);
+        assert_eq!((&p + &q).coefficients, vec![0.0, 3.0, 3.0]);
+        assert_eq!((&p - &p).coefficients, vec![0.0]);
+        assert_eq!((&p * &q).coefficients, vec![-1.0, -1.0, -1.0, 3.0]);
+        assert_eq!((2.0 * &q).coefficients, vec![-2.0, 2.0]);
+        assert_eq!((-q.clone()).coefficients, vec![1.0, -1.0]);
+
+        // p = quotient * divisor + remainder, with a lower degree remainder.
+        let p = Polynomial::new(vec![5.0, -3.0, 0.0, 2.0, 1.0]);
+        let divisor = Polynomial::new(vec![1.0, 0.0, 2.0]);
+        let (quotient, remainder) = p.div_rem(&divisor);
+        assert!(remainder.degree() < divisor.degree());
+        let reconstructed = &quotient * &divisor + &remainder;
+        for (a, b) in reconstructed.coefficients.iter().zip(&p.coefficients) {
+            assert!((a - b).abs() < 1e-14);
+        }
+        assert_eq!(&p / &divisor, quotient);
+        assert_eq!(&p % &divisor, remainder);
+
+        // Exact division leaves a zero remainder.
+        let (quotient, remainder) =
+            Polynomial::from_roots(&[1.0, 2.0, 3.0]).div_rem(&q);
+        assert_eq!(quotient, Polynomial::from_roots(&[2.0, 3.0]));
+        assert!(remainder.is_zero());
+
+        // Dividing by a higher degree polynomial gives a zero quotient.
+        let (quotient, remainder) = q.div_rem(&divisor);
+        assert!(quotient.is_zero());
+        assert_eq!(remainder, q);
+
+        assert!(matches!(
+            p.try_div_rem(&Polynomial::new(vec![0.0])),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_calculus_and_composition() {
+        let p = Polynomial::new(vec![1.0, -2.0, 0.0, 4.0]);
+        assert_eq!(p.derivative().coefficients, vec![-2.0, 0.0, 12.0]);
+        assert_eq!(
+            p.antiderivative().coefficients,
+            vec![0.0, 1.0, -1.0, 0.0, 1.0]
+        );
+        assert_eq!(p.antiderivative().derivative(), p);
+        assert_eq!(
+            Polynomial::new(vec![3.0]).derivative().coefficients,
+            vec![0.0]
+        );
+
+        // The integral of 1 - 2x + 4x^3 from -1 to 2 is 3 - 3 + 15.
+        assert!((p.integrate(-1.0, 2.0) - 15.0).abs() < 1e-13);
+
+        // p(q(x)) agrees with p evaluated at q(x).
+        let q = Polynomial::new(vec![0.5, -1.0, 2.0]);
+        let composition = p.compose(&q);
+        assert_eq!(composition.degree(), 6);
+        for x in [-1.3, 0.0, 0.4, 2.2] {
+            let exact = p.evaluate(q.evaluate(x));
+            assert!(
+                (composition.evaluate(x) - exact).abs()
+                    < 1e-10 * exact.abs().max(1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_roots() {
+        // The roots of x^2 + 1 are -i and i.
+        let roots = Polynomial::new(vec![1.0, 0.0, 1.0]).roots();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] - Complex::new(0.0, -1.0)).norm() < 1e-14);
+        assert!((roots[1] - Complex::new(0.0, 1.0)).norm() < 1e-14);
+
+        // Real roots of moderate degree, including roots at 0.
+        let exact = [-3.5, -1.0, 0.0, 0.0, 0.25, 2.0, 7.0];
+        let polynomial = Polynomial::from_roots(&exact);
+        let roots = polynomial.real_roots(1e-8);
+        assert_eq!(roots.len(), exact.len());
+        for (root, exact) in roots.iter().zip(exact) {
+            assert!((root - exact).abs() < 1e-10);
+        }
+
+        // (x - 2)(x^2 + 2x + 5) has the real root 2 and the roots -1 +- 2i.
+        let polynomial = Polynomial::new(vec![-10.0, 1.0, 0.0, 1.0]);
+        assert_eq!(polynomial.real_roots(1e-10).len(), 1);
+        let roots = polynomial.roots();
+        assert!((roots[0] - Complex::new(-1.0, -2.0)).norm() < 1e-12);
+        assert!((roots[1] - Complex::new(-1.0, 2.0)).norm() < 1e-12);
+        assert!((roots[2] - Complex::new(2.0, 0.0)).norm() < 1e-12);
+
+        assert!(Polynomial::new(vec![4.0]).roots().is_empty());
+        assert!(matches!(
+            Polynomial::new(vec![0.0]).try_roots(),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+    }
+}
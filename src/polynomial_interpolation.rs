@@ -0,0 +1,875 @@
+use crate::error::NumericalMethodsError;
+use crate::quadrature::{QuadratureMethod, QuadratureRule};
+
+// Polynomial interpolation of arbitrary degree.
+//
+// Each interpolant is the unique polynomial of degree at most n - 1 through
+// n points with distinct x values, stored in a different form.
+//
+// - The barycentric form stores a weight for each point, so that evaluation
+//   takes O(n) operations and is numerically stable. The weights are scaled
+//   by a quarter of the width of the points to avoid overflow.
+// - The Newton form stores the divided differences of the points, so that a
+//   point can be added in O(n) operations.
+// - Neville's algorithm builds the interpolant at a single x from the
+//   interpolants through fewer points, nearest points first, so that the
+//   change made by the furthest point estimates the error.
+//
+// Each interpolant is integrated exactly by applying the Gauss-Legendre
+// rule with ceil(n / 2) points to its `evaluate`. Expanding a high degree
+// interpolant in powers of x instead would lose all accuracy to cancellation.
+
+/// # Barycentric interpolant
+///
+/// ## Description
+/// `BarycentricInterpolant` represents the polynomial interpolating `values`
+/// at `points` in the barycentric Lagrange form. The barycentric `weights`
+/// are computed once in O(n^2) operations, after which the interpolant is
+/// evaluated and differentiated in O(n) operations.
+///
+/// The interpolant may be evaluated at any x, but high degree interpolation
+/// on uniformly spaced points oscillates strongly near the ends (Runge's
+/// phenomenon). Chebyshev points, such as those of `new_chebyshev_grid`,
+/// avoid this.
+///
+/// ## Example use case
+/// Suppose that we want to interpolate sin at 12 Chebyshev points on [0, 3].
+/// The code below does this and evaluates the interpolant.
+/// ```
+/// let grid = Grid::new_chebyshev_grid(0.0, 3.0, 12);
+/// let grid_func = GridFunction::new_grid_function(&grid, f64::sin);
+/// let interpolant = BarycentricInterpolant::new(
+///     &grid_func.grid.grid_points,
+///     &grid_func.function_values,
+/// );
+/// let value = interpolant.evaluate(1.7);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarycentricInterpolant {
+    pub points: Vec<f64>,
+    pub values: Vec<f64>,
+    pub weights: Vec<f64>,
+}
+
+impl BarycentricInterpolant {
+    /// # New barycentric interpolant
+    ///
+    /// ## Description
+    /// `new` creates the polynomial interpolating `values` at `points`.
+    ///
+    /// `new` panics if the points are not valid. See `try_new` for a version
+    /// which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let interpolant =
+    ///     BarycentricInterpolant::new(&[0.0, 1.0, 2.0], &[1.0, 3.0, 2.0]);
+    /// ```
+    ///
+    pub fn new(points: &[f64], values: &[f64]) -> Self {
+        match Self::try_new(points, values) {
+            Ok(interpolant) => interpolant,
+            Err(error) => {
+                panic!("Interpolant failed to construct. {}", error)
+            }
+        }
+    }
+
+    /// # Try new barycentric interpolant
+    ///
+    /// ## Description
+    /// `try_new` is the fallible version of `new`. It returns a
+    /// `LengthMismatch` error if `values` and `points` have different
+    /// lengths, an `InsufficientPoints` error if there are no points, and an
+    /// `InvalidParameter` error if the points are not finite and distinct.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let interpolant = BarycentricInterpolant::try_new(&points, &values)?;
+    /// ```
+    ///
+    pub fn try_new(
+        points: &[f64],
+        values: &[f64],
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_interpolation_points(points, values, 1)?;
+
+        let (min, max) = points
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &x| {
+                (min.min(x), max.max(x))
+            });
+        let scale = if max > min { 0.25 * (max - min) } else { 1.0 };
+        let weights = points
+            .iter()
+            .enumerate()
+            .map(|(j, &x_j)| {
+                let product: f64 = points
+                    .iter()
+                    .enumerate()
+                    .filter(|&(k, _)| k != j)
+                    .map(|(_, &x_k)| (x_j - x_k) / scale)
+                    .product();
+                1.0 / product
+            })
+            .collect();
+
+        Ok(BarycentricInterpolant {
+            points: points.to_vec(),
+            values: values.to_vec(),
+            weights,
+        })
+    }
+
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the value of the interpolant at `x`, using the
+    /// second barycentric formula.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let value = interpolant.evaluate(1.7);
+    /// ```
+    ///
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+        for ((&x_j, &f_j), &w_j) in
+            self.points.iter().zip(&self.values).zip(&self.weights)
+        {
+            if x == x_j {
+                return f_j;
+            }
+            let term = w_j / (x - x_j);
+            numerator += term * f_j;
+            denominator += term;
+        }
+
+        numerator / denominator
+    }
+
+    /// # Derivative
+    ///
+    /// ## Description
+    /// `derivative` returns the first derivative of the interpolant at `x`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let slope = interpolant.derivative(1.7);
+    /// ```
+    ///
+    pub fn derivative(&self, x: f64) -> f64 {
+        let points = &self.points;
+        let values = &self.values;
+        let weights = &self.weights;
+
+        // At the ith point, the derivative is the ith row of the barycentric
+        // differentiation matrix applied to the values.
+        if let Some(i) = points.iter().position(|&x_i| x_i == x) {
+            return (0..points.len())
+                .filter(|&j| j != i)
+                .map(|j| {
+                    weights[j] / weights[i] * (values[j] - values[i])
+                        / (points[i] - points[j])
+                })
+                .sum();
+        }
+
+        // Elsewhere, differentiates p(x) = N(x) / D(x) from the second
+        // barycentric formula.
+        let value = self.evaluate(x);
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+        for ((&x_j, &f_j), &w_j) in points.iter().zip(values).zip(weights) {
+            let term = w_j / (x - x_j);
+            numerator += term * (value - f_j) / (x - x_j);
+            denominator += term;
+        }
+
+        numerator / denominator
+    }
+
+    /// # Integrate
+    ///
+    /// ## Description
+    /// `integrate` returns the exact integral of the interpolant from
+    /// `lower_limit` to `upper_limit`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = interpolant.integrate(0.0, 3.0);
+    /// ```
+    ///
+    pub fn integrate(&self, lower_limit: f64, upper_limit: f64) -> f64 {
+        exact_integral(
+            |x| self.evaluate(x),
+            self.points.len(),
+            lower_limit,
+            upper_limit,
+        )
+    }
+}
+
+/// # Newton interpolant
+///
+/// ## Description
+/// `NewtonInterpolant` represents the polynomial interpolating a set of
+/// points in the Newton form
+///
+/// p(x) = c_0 + c_1 (x - x_0) + c_2 (x - x_0)(x - x_1) + ...,
+///
+/// where the `coefficients` c_k are the divided differences of the data and
+/// x_k are the `points`. Adding a point with `add_point` adds one term in
+/// O(n) operations, without changing the existing coefficients, so the
+/// degree can be raised until the interpolant is accurate enough.
+///
+/// ## Example use case
+/// Suppose that we want to interpolate exp, adding points until the last
+/// coefficient is negligible. The code below does this.
+/// ```
+/// let mut interpolant = NewtonInterpolant::new(&[0.0], &[1.0]);
+/// let mut x = 1.0;
+/// while interpolant.coefficients.last().unwrap().abs() > 1e-12 {
+///     interpolant.add_point(x, x.exp());
+///     x /= 2.0;
+/// }
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewtonInterpolant {
+    pub points: Vec<f64>,
+    pub coefficients: Vec<f64>,
+    // differences[k] is the divided difference of the last k + 1 points,
+    // which is the bottom row of the divided difference table.
+    differences: Vec<f64>,
+}
+
+impl NewtonInterpolant {
+    /// # New Newton interpolant
+    ///
+    /// ## Description
+    /// `new` creates the polynomial interpolating `values` at `points`.
+    ///
+    /// `new` panics if the points are not valid. See `try_new` for a version
+    /// which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let interpolant =
+    ///     NewtonInterpolant::new(&[0.0, 1.0, 2.0], &[1.0, 3.0, 2.0]);
+    /// ```
+    ///
+    pub fn new(points: &[f64], values: &[f64]) -> Self {
+        match Self::try_new(points, values) {
+            Ok(interpolant) => interpolant,
+            Err(error) => {
+                panic!("Interpolant failed to construct. {}", error)
+            }
+        }
+    }
+
+    /// # Try new Newton interpolant
+    ///
+    /// ## Description
+    /// `try_new` is the fallible version of `new`. It returns the same
+    /// errors as `BarycentricInterpolant::try_new`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let interpolant = NewtonInterpolant::try_new(&points, &values)?;
+    /// ```
+    ///
+    pub fn try_new(
+        points: &[f64],
+        values: &[f64],
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_interpolation_points(points, values, 1)?;
+
+        let mut interpolant = NewtonInterpolant {
+            points: Vec::with_capacity(points.len()),
+            coefficients: Vec::with_capacity(points.len()),
+            differences: Vec::with_capacity(points.len()),
+        };
+        for (&x, &value) in points.iter().zip(values) {
+            interpolant.push_point(x, value);
+        }
+
+        Ok(interpolant)
+    }
+
+    /// # Add point
+    ///
+    /// ## Description
+    /// `add_point` adds the point `x` with the value `value` to the
+    /// interpolant, raising its degree by one.
+    ///
+    /// `add_point` panics if `x` is not finite or is already a point of the
+    /// interpolant. See `try_add_point` for a version which returns an error
+    /// instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// interpolant.add_point(0.5, 0.5_f64.exp());
+    /// ```
+    ///
+    pub fn add_point(&mut self, x: f64, value: f64) {
+        if let Err(error) = self.try_add_point(x, value) {
+            panic!("Interpolant failed to add a point. {}", error);
+        }
+    }
+
+    /// # Try add point
+    ///
+    /// ## Description
+    /// `try_add_point` is the fallible version of `add_point`. It returns an
+    /// `InvalidParameter` error if `x` is not finite or is already a point
+    /// of the interpolant, and leaves the interpolant unchanged.
+    ///
+    /// ## Example use case
+    /// ```
+    /// interpolant.try_add_point(0.5, 0.5_f64.exp())?;
+    /// ```
+    ///
+    pub fn try_add_point(
+        &mut self,
+        x: f64,
+        value: f64,
+    ) -> Result<(), NumericalMethodsError> {
+        if !x.is_finite() || self.points.contains(&x) {
+            return Err(NumericalMethodsError::InvalidParameter(
+                "The interpolation points must be finite and distinct."
+                    .to_string(),
+            ));
+        }
+        self.push_point(x, value);
+        Ok(())
+    }
+
+    /// # Push point
+    ///
+    /// ## Description
+    /// `push_point` adds a new row to the divided difference table, whose
+    /// last entry is the new coefficient. The point is assumed to be valid.
+    ///
+    fn push_point(&mut self, x: f64, value: f64) {
+        let num_points = self.points.len();
+        let mut row = Vec::with_capacity(num_points + 1);
+        row.push(value);
+        for k in 1..=num_points {
+            let difference = (row[k - 1] - self.differences[k - 1])
+                / (x - self.points[num_points - k]);
+            row.push(difference);
+        }
+
+        self.coefficients.push(row[num_points]);
+        self.points.push(x);
+        self.differences = row;
+    }
+
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the value of the interpolant at `x`, using nested
+    /// multiplication.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let value = interpolant.evaluate(0.3);
+    /// ```
+    ///
+    pub fn evaluate(&self, x: f64) -> f64 {
+        self.value_and_derivative(x).0
+    }
+
+    /// # Derivative
+    ///
+    /// ## Description
+    /// `derivative` returns the first derivative of the interpolant at `x`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let slope = interpolant.derivative(0.3);
+    /// ```
+    ///
+    pub fn derivative(&self, x: f64) -> f64 {
+        self.value_and_derivative(x).1
+    }
+
+    /// # Value and derivative
+    ///
+    /// ## Description
+    /// `value_and_derivative` evaluates the Newton form and its derivative
+    /// together by nested multiplication, from the highest coefficient down.
+    ///
+    fn value_and_derivative(&self, x: f64) -> (f64, f64) {
+        self.coefficients.iter().zip(&self.points).rev().fold(
+            (0.0, 0.0),
+            |(value, derivative), (coefficient, x_k)| {
+                (
+                    coefficient + (x - x_k) * value,
+                    value + (x - x_k) * derivative,
+                )
+            },
+        )
+    }
+
+    /// # Integrate
+    ///
+    /// ## Description
+    /// `integrate` returns the exact integral of the interpolant from
+    /// `lower_limit` to `upper_limit`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = interpolant.integrate(0.0, 1.0);
+    /// ```
+    ///
+    pub fn integrate(&self, lower_limit: f64, upper_limit: f64) -> f64 {
+        exact_integral(
+            |x| self.evaluate(x),
+            self.points.len(),
+            lower_limit,
+            upper_limit,
+        )
+    }
+}
+
+/// # Neville estimate
+///
+/// ## Description
+/// `NevilleEstimate` is returned by `NevilleInterpolant`. `value` is the
+/// value or derivative of the interpolant through all of the points, and
+/// `error_estimate` is its difference from the interpolant through all but
+/// the point furthest from x, which estimates the interpolation error.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NevilleEstimate {
+    pub value: f64,
+    pub error_estimate: f64,
+}
+
+/// # Neville interpolant
+///
+/// ## Description
+/// `NevilleInterpolant` represents the polynomial interpolating `values` at
+/// `points`, evaluated by Neville's algorithm. Each evaluation takes O(n^2)
+/// operations, and also returns an estimate of the interpolation error,
+/// which is useful for extrapolation and for choosing the degree.
+///
+/// ## Example use case
+/// Suppose that we want to interpolate sqrt from 6 points, and check the
+/// accuracy at x = 2.5. The code below does this.
+/// ```
+/// let points = [1.0, 1.5, 2.0, 3.0, 3.5, 4.0];
+/// let values = points.map(f64::sqrt);
+/// let interpolant = NevilleInterpolant::new(&points, &values);
+/// let estimate = interpolant.evaluate(2.5);
+/// assert!(estimate.error_estimate < 1e-3);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct NevilleInterpolant {
+    pub points: Vec<f64>,
+    pub values: Vec<f64>,
+}
+
+impl NevilleInterpolant {
+    /// # New Neville interpolant
+    ///
+    /// ## Description
+    /// `new` creates the polynomial interpolating `values` at `points`.
+    ///
+    /// `new` panics if the points are not valid. See `try_new` for a version
+    /// which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let interpolant =
+    ///     NevilleInterpolant::new(&[0.0, 1.0, 2.0], &[1.0, 3.0, 2.0]);
+    /// ```
+    ///
+    pub fn new(points: &[f64], values: &[f64]) -> Self {
+        match Self::try_new(points, values) {
+            Ok(interpolant) => interpolant,
+            Err(error) => {
+                panic!("Interpolant failed to construct. {}", error)
+            }
+        }
+    }
+
+    /// # Try new Neville interpolant
+    ///
+    /// ## Description
+    /// `try_new` is the fallible version of `new`. It returns the same
+    /// errors as `BarycentricInterpolant::try_new`, except that at least 2
+    /// points are needed for an error estimate.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let interpolant = NevilleInterpolant::try_new(&points, &values)?;
+    /// ```
+    ///
+    pub fn try_new(
+        points: &[f64],
+        values: &[f64],
+    ) -> Result<Self, NumericalMethodsError> {
+        validate_interpolation_points(points, values, 2)?;
+
+        Ok(NevilleInterpolant {
+            points: points.to_vec(),
+            values: values.to_vec(),
+        })
+    }
+
+    /// # Evaluate
+    ///
+    /// ## Description
+    /// `evaluate` returns the value of the interpolant at `x`, with an
+    /// estimate of the interpolation error.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let estimate = interpolant.evaluate(2.5);
+    /// let value = estimate.value;
+    /// ```
+    ///
+    pub fn evaluate(&self, x: f64) -> NevilleEstimate {
+        self.neville_tableau(x).0
+    }
+
+    /// # Derivative
+    ///
+    /// ## Description
+    /// `derivative` returns the first derivative of the interpolant at `x`,
+    /// with an estimate of its error.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let slope = interpolant.derivative(2.5).value;
+    /// ```
+    ///
+    pub fn derivative(&self, x: f64) -> NevilleEstimate {
+        self.neville_tableau(x).1
+    }
+
+    /// # Integrate
+    ///
+    /// ## Description
+    /// `integrate` returns the exact integral of the interpolant from
+    /// `lower_limit` to `upper_limit`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = interpolant.integrate(1.0, 4.0);
+    /// ```
+    ///
+    pub fn integrate(&self, lower_limit: f64, upper_limit: f64) -> f64 {
+        exact_integral(
+            |x| self.evaluate(x).value,
+            self.points.len(),
+            lower_limit,
+            upper_limit,
+        )
+    }
+
+    /// # Neville tableau
+    ///
+    /// ## Description
+    /// `neville_tableau` runs Neville's algorithm at `x` with the points
+    /// sorted by their distance from `x`, differentiating the recurrence to
+    /// find the derivative as well. It returns the estimates of the value
+    /// and of the derivative.
+    ///
+    fn neville_tableau(&self, x: f64) -> (NevilleEstimate, NevilleEstimate) {
+        let mut order: Vec<usize> = (0..self.points.len()).collect();
+        order.sort_by(|&i, &j| {
+            (x - self.points[i])
+                .abs()
+                .total_cmp(&(x - self.points[j]).abs())
+        });
+        let points: Vec<f64> = order.iter().map(|&i| self.points[i]).collect();
+
+        // After pass m, values[i] and derivatives[i] belong to the
+        // interpolant through points[i..=i + m].
+        let mut values: Vec<f64> =
+            order.iter().map(|&i| self.values[i]).collect();
+        let mut derivatives = vec![0.0; points.len()];
+        let num_points = points.len();
+        let mut previous = (values[0], derivatives[0]);
+        for m in 1..num_points {
+            previous = (values[0], derivatives[0]);
+            for i in 0..(num_points - m) {
+                let (x_i, x_j) = (points[i], points[i + m]);
+                let denominator = x_i - x_j;
+                derivatives[i] = (values[i] + (x - x_j) * derivatives[i]
+                    - values[i + 1]
+                    - (x - x_i) * derivatives[i + 1])
+                    / denominator;
+                values[i] = ((x - x_j) * values[i] - (x - x_i) * values[i + 1])
+                    / denominator;
+            }
+        }
+
+        (
+            NevilleEstimate {
+                value: values[0],
+                error_estimate: (values[0] - previous.0).abs(),
+            },
+            NevilleEstimate {
+                value: derivatives[0],
+                error_estimate: (derivatives[0] - previous.1).abs(),
+            },
+        )
+    }
+}
+
+/// # Validate interpolation points
+///
+/// ## Description
+/// `validate_interpolation_points` checks that `points` and `values` have
+/// the same length, that there are at least `min_points` points, and that
+/// the points are finite and distinct.
+///
+pub(crate) fn validate_interpolation_points(
+    points: &[f64],
+    values: &[f64],
+    min_points: usize,
+) -> Result<(), NumericalMethodsError> {
+    if values.len() != points.len() {
+        return Err(NumericalMethodsError::LengthMismatch {
+            expected: points.len(),
+            actual: values.len(),
+        });
+    }
+    if points.len() < min_points {
+        return Err(NumericalMethodsError::InsufficientPoints {
+            required: min_points,
+            actual: points.len(),
+        });
+    }
+
+    let mut sorted_points = points.to_vec();
+    sorted_points.sort_by(f64::total_cmp);
+    let points_valid = sorted_points.iter().all(|x| x.is_finite())
+        && sorted_points.windows(2).all(|pair| pair[0] < pair[1]);
+    if !points_valid {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The interpolation points must be finite and distinct.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// # Exact integral
+///
+/// ## Description
+/// `exact_integral` integrates the interpolant `func` through `num_points`
+/// points, which is a polynomial of degree at most `num_points` - 1, with
+/// the Gauss-Legendre rule which is exact for it. Unlike expanding the
+/// interpolant in powers of x, this stays accurate at high degree.
+///
+fn exact_integral<F>(
+    func: F,
+    num_points: usize,
+    lower_limit: f64,
+    upper_limit: f64,
+) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let rule = QuadratureRule::new(QuadratureMethod::GaussLegendre {
+        num_points: num_points.div_ceil(2).max(1),
+    });
+    rule.integrate(func, lower_limit, upper_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    // A quintic, its derivative and its antiderivative.
+    fn quintic(x: f64) -> f64 {
+        x.powi(5) - 2.0 * x.powi(3) + x - 1.0
+    }
+
+    fn quintic_derivative(x: f64) -> f64 {
+        5.0 * x.powi(4) - 6.0 * x * x + 1.0
+    }
+
+    fn quintic_integral(x: f64) -> f64 {
+        x.powi(6) / 6.0 - x.powi(4) / 2.0 + x * x / 2.0 - x
+    }
+
+    #[test]
+    fn test_interpolants_reproduce_polynomials() {
+        // Each interpolant through 6 points reproduces a quintic exactly,
+        // including at the points, where the formulas change.
+        let points = [-1.0, 0.7, -0.2, 1.5, 0.1, 2.0];
+        let values = points.map(quintic);
+        let barycentric = BarycentricInterpolant::new(&points, &values);
+        let newton = NewtonInterpolant::new(&points, &values);
+        let neville = NevilleInterpolant::new(&points, &values);
+
+        for x in [-1.0, -0.55, 0.1, 0.33, 1.5, 1.9, 2.4] {
+            let exact = quintic(x);
+            let exact_derivative = quintic_derivative(x);
+            for (name, value, derivative) in [
+                (
+                    "barycentric",
+                    barycentric.evaluate(x),
+                    barycentric.derivative(x),
+                ),
+                ("Newton", newton.evaluate(x), newton.derivative(x)),
+                (
+                    "Neville",
+                    neville.evaluate(x).value,
+                    neville.derivative(x).value,
+                ),
+            ] {
+                assert!(
+                    (value - exact).abs() < 1e-11,
+                    "The {} interpolant failed at {}.",
+                    name,
+                    x
+                );
+                assert!(
+                    (derivative - exact_derivative).abs() < 1e-10,
+                    "The {} derivative failed at {}.",
+                    name,
+                    x
+                );
+            }
+        }
+
+        let exact = quintic_integral(1.5) - quintic_integral(-0.5);
+        for integral in [
+            barycentric.integrate(-0.5, 1.5),
+            newton.integrate(-0.5, 1.5),
+            neville.integrate(-0.5, 1.5),
+        ] {
+            assert!((integral - exact).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_barycentric_chebyshev_convergence() {
+        // Interpolation of Runge's function at Chebyshev points converges
+        // exponentially, even for high degrees.
+        let runge = |x: f64| 1.0 / (1.0 + 25.0 * x * x);
+        let grid = Grid::new_chebyshev_grid(-1.0, 1.0, 101);
+        let values: Vec<f64> =
+            grid.grid_points.iter().map(|&x| runge(x)).collect();
+        let interpolant =
+            BarycentricInterpolant::new(&grid.grid_points, &values);
+        for k in 0..40 {
+            let x = -1.0 + 0.05 * k as f64 + 0.0123;
+            assert!((interpolant.evaluate(x) - runge(x)).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_high_degree_integrate() {
+        // The integrals of the interpolants of Runge's function at many
+        // Chebyshev points converge to (2 / 5) atan(5).
+        let runge = |x: f64| 1.0 / (1.0 + 25.0 * x * x);
+        let exact = 0.4 * 5.0_f64.atan();
+        for (num_points, tolerance) in [(41, 1e-4), (61, 1e-6), (101, 1e-9)] {
+            let grid = Grid::new_chebyshev_grid(-1.0, 1.0, num_points);
+            let values: Vec<f64> =
+                grid.grid_points.iter().map(|&x| runge(x)).collect();
+            let barycentric =
+                BarycentricInterpolant::new(&grid.grid_points, &values);
+            let neville = NevilleInterpolant::new(&grid.grid_points, &values);
+            assert!(
+                (barycentric.integrate(-1.0, 1.0) - exact).abs() < tolerance
+            );
+            assert!((neville.integrate(-1.0, 1.0) - exact).abs() < tolerance);
+        }
+    }
+
+    #[test]
+    fn test_newton_add_point() {
+        // Adding points one at a time gives the same interpolant as
+        // constructing it from all of the points.
+        let points = [0.0, 1.0, 0.5, 0.25, 0.75, 0.125];
+        let values = points.map(f64::exp);
+        let full = NewtonInterpolant::new(&points, &values);
+
+        let mut incremental =
+            NewtonInterpolant::new(&points[..1], &values[..1]);
+        for (&x, &value) in points.iter().zip(&values).skip(1) {
+            incremental.add_point(x, value);
+        }
+        assert_eq!(incremental.points, full.points);
+        for (a, b) in incremental.coefficients.iter().zip(&full.coefficients) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert!((full.evaluate(0.4) - 0.4_f64.exp()).abs() < 1e-6);
+
+        assert!(matches!(
+            incremental.try_add_point(0.5, 1.0),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert_eq!(incremental.points.len(), 6);
+    }
+
+    #[test]
+    fn test_neville_error_estimate() {
+        // The error estimate has the size of the actual error.
+        let points = [1.0, 1.5, 2.0, 3.0, 3.5, 4.0];
+        let values = points.map(f64::sqrt);
+        let interpolant = NevilleInterpolant::new(&points, &values);
+        for x in [1.2, 2.5, 3.7] {
+            let estimate = interpolant.evaluate(x);
+            let error = (estimate.value - x.sqrt()).abs();
+            assert!(error < 2.0 * estimate.error_estimate);
+            assert!(estimate.error_estimate < 10.0 * error);
+            assert!(estimate.error_estimate < 1e-3);
+        }
+
+        // Extrapolating further from the points gives a larger estimate.
+        assert!(
+            interpolant.evaluate(6.0).error_estimate
+                > interpolant.evaluate(2.5).error_estimate
+        );
+    }
+
+    #[test]
+    fn test_try_new_errors() {
+        assert_eq!(
+            BarycentricInterpolant::try_new(&[0.0, 1.0], &[1.0]),
+            Err(NumericalMethodsError::LengthMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            NewtonInterpolant::try_new(&[], &[]),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 1,
+                actual: 0
+            })
+        );
+        assert_eq!(
+            NevilleInterpolant::try_new(&[0.0], &[1.0]),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert!(matches!(
+            BarycentricInterpolant::try_new(&[0.0, 1.0, 0.0], &[1.0; 3]),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+
+        // A single point gives a constant interpolant.
+        let interpolant = BarycentricInterpolant::new(&[2.0], &[3.0]);
+        assert_eq!(interpolant.evaluate(-1.0), 3.0);
+        assert_eq!(interpolant.derivative(2.0), 0.0);
+        assert!((interpolant.integrate(0.0, 2.0) - 6.0).abs() < 1e-14);
+    }
+}
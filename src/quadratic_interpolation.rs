@@ -1,30 +1,33 @@
+use crate::polynomial::Polynomial;
+
 /// # Quadratic interpolation
 ///
 /// ## Description
 /// `quadratic_interpolation_coefficients` approximates a real-valued function
-/// of a real variable with a quadratic polynomial, and returns the
-/// coefficients of the quadratic polynomial.
+/// of a real variable with a quadratic polynomial, and returns the quadratic
+/// polynomial `a*x^2 + b*x + c` as a `Polynomial` with the coefficients
+/// `[c, b, a]`.
+///
+/// The three points must be distinct. For interpolation through more points,
+/// see `polynomial_interpolation.rs`.
 ///
 /// ## Example use case
 /// Suppose we have three points (0, 1, 2) and the function values at those
 /// points are (0, 1, 4). We can use `quadratic_interpolation_coefficients` to
-/// approximate the function with a quadratic polynomial and get the
-/// coefficients of the polynomial. We can then use these coefficients to
-/// evaluate the polynomial at other points.
+/// approximate the function with a quadratic polynomial, and then evaluate
+/// the polynomial at other points.
 /// ```
 /// let points = (0.0, 1.0, 2.0);
 /// let function_values = (0.0, 1.0, 4.0);
-/// let (a, b, c) = quadratic_interpolation_coefficients(points,
+/// let quadratic = quadratic_interpolation_coefficients(points,
 ///     function_values);
+/// let value = quadratic.evaluate(1.5);
 /// ```
 ///
-/// ## Todo
-/// This function is not currently working. I'm still troubleshooting.
-///
 pub fn quadratic_interpolation_coefficients(
     points: (f64, f64, f64),
     function_values: (f64, f64, f64),
-) -> (f64, f64, f64) {
+) -> Polynomial {
     let (x0, x1, x2) = points;
     let (f0, f1, f2) = function_values;
 
@@ -36,7 +39,7 @@ pub fn quadratic_interpolation_coefficients(
     let b = -(d0 * (x1 + x2)) - (d1 * (x0 + x2)) - (d2 * (x0 + x1));
     let c = (d0 * x1 * x2) + (d1 * x0 * x2) + (d2 * x0 * x1);
 
-    (a, b, c)
+    Polynomial::new(vec![c, b, a])
 }
 
 /// # Quadratic integral
@@ -45,39 +48,62 @@ pub fn quadratic_interpolation_coefficients(
 /// `quadratic_integral` calculates the definite integral of a quadratic
 /// polynomial `a*x^2 + b*x + c` from `lower_limit` to `upper_limit`.
 ///
-/// `quadratic_integral` takes a tuple `(a, b, c)` of the coefficients of the
-/// quadratic polynomial, and the `lower_limit` and `upper_limit` of the
-/// definite integral.
+/// `quadratic_integral` takes the quadratic polynomial as a `Polynomial`,
+/// such as one returned by `quadratic_interpolation_coefficients`, and the
+/// `lower_limit` and `upper_limit` of the definite integral. It is equivalent
+/// to `Polynomial::integrate`.
 ///
 /// ## Example use case
 /// Suppose we have a quadratic polynomial `f(x) = 2*x^2 + 3*x + 1` and we want
 /// to calculate the integral of this polynomial from `x = 0` to `x = 1`. We can
 /// use `quadratic_integral` to calculate the integral with the code below.
 /// ```
-/// let coefficients = (2.0, 3.0, 1.0);
-/// integral = quadratic_integral(coefficients, 0.0, 1.0);
+/// let quadratic = Polynomial::new(vec![1.0, 3.0, 2.0]);
+/// let integral = quadratic_integral(&quadratic, 0.0, 1.0);
 /// ```
 ///
-/// ## Todo
-/// This function is not currently working. I'm still troubleshooting.
-///
 pub fn quadratic_integral(
-    coefficients: (f64, f64, f64),
+    quadratic: &Polynomial,
     lower_limit: f64,
     upper_limit: f64,
 ) -> f64 {
-    let (a, b, c) = coefficients;
+    quadratic.integrate(lower_limit, upper_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Calculates the definite integral from 0 to upper_limit.
-    let integral_upper = (a / 3.0) * upper_limit.powi(3)
-        + (b / 2.0) * upper_limit.powi(2)
-        + c * upper_limit;
+    #[test]
+    fn test_quadratic_interpolation_coefficients() {
+        // The quadratic through (0, 0), (1, 1) and (2, 4) is x^2.
+        let quadratic = quadratic_interpolation_coefficients(
+            (0.0, 1.0, 2.0),
+            (0.0, 1.0, 4.0),
+        );
+        assert_eq!(quadratic.degree(), 2);
+        for (coefficient, exact) in
+            quadratic.coefficients.iter().zip([0.0, 0.0, 1.0])
+        {
+            assert!((coefficient - exact).abs() < 1e-14);
+        }
 
-    // Calculates the definite integral from 0 to lower_limit.
-    let integral_lower = (a / 3.0) * lower_limit.powi(3)
-        + (b / 2.0) * lower_limit.powi(2)
-        + c * lower_limit;
+        // Any quadratic is reproduced on non-uniform points.
+        let func = |x: f64| 2.0 * x * x + 3.0 * x + 1.0;
+        let quadratic = quadratic_interpolation_coefficients(
+            (-1.0, 0.3, 2.5),
+            (func(-1.0), func(0.3), func(2.5)),
+        );
+        for x in [-2.0, 0.0, 1.7] {
+            assert!((quadratic.evaluate(x) - func(x)).abs() < 1e-12);
+        }
+    }
 
-    // Evaluates the definite integral from lower_limit to upper_limit.
-    integral_upper - integral_lower
+    #[test]
+    fn test_quadratic_integral() {
+        // The integral of 2x^2 + 3x + 1 from 0 to 1 is 2/3 + 3/2 + 1.
+        let quadratic = Polynomial::new(vec![1.0, 3.0, 2.0]);
+        let integral = quadratic_integral(&quadratic, 0.0, 1.0);
+        assert!((integral - 19.0 / 6.0).abs() < 1e-14);
+    }
 }
@@ -0,0 +1,766 @@
+use nalgebra::{DMatrix, SymmetricEigen};
+
+use crate::adaptive_quadrature::{
+    try_adaptive_integrate, AdaptiveQuadratureOptions,
+};
+use crate::double_exponential_quadrature::{
+    try_exp_sinh_integrate, try_sinh_sinh_integrate, try_tanh_sinh_integrate,
+    DoubleExponentialOptions,
+};
+use crate::error::NumericalMethodsError;
+use crate::grid::{Grid, GRID_TOLERANCE};
+use crate::grid_function::GridFunction;
+
+// Quadrature rules for integrating closures.
+//
+// The Gauss rules are computed by the Golub-Welsch algorithm: the nodes are
+// the eigenvalues of the symmetric tridiagonal Jacobi matrix of the
+// three-term recurrence of the Legendre polynomials, and the weights are 2
+// times the squared first components of the normalized eigenvectors. For
+// the Gauss-Lobatto rule, the last off-diagonal element of the Jacobi
+// matrix is modified so that -1 and 1 are eigenvalues.
+
+/// # Quadrature method
+///
+/// ## Description
+/// `QuadratureMethod` specifies how `integrate` approximates the integral of
+/// a closure.
+///
+/// - `GaussLegendre` uses the `num_points` point Gauss-Legendre rule, whose
+///   nodes lie strictly inside the interval. It is exact for polynomials of
+///   degree up to 2 `num_points` - 1.
+/// - `GaussLobatto` uses the `num_points` point Gauss-Lobatto rule, whose
+///   nodes include both ends of the interval. It is exact for polynomials
+///   of degree up to 2 `num_points` - 3, and needs at least 2 points.
+/// - `ClenshawCurtis` uses the `num_points` point Clenshaw-Curtis rule, whose
+///   nodes are the Chebyshev extreme points, including both ends. It is
+///   exact for polynomials of degree up to `num_points` - 1, but converges
+///   almost as quickly as the Gauss rules for smooth functions, and needs at
+///   least 2 points.
+/// - `GaussKronrod` uses `adaptive_integrate`, which bisects the interval
+///   until the integral meets the tolerances in the options. Use
+///   `adaptive_integrate` directly to get its error estimate and warnings.
+/// - `TanhSinh` uses `tanh_sinh_integrate`, for finite intervals with
+///   integrable singularities at the ends.
+/// - `ExpSinh` uses `exp_sinh_integrate`, for intervals with exactly one
+///   infinite limit.
+/// - `SinhSinh` uses `sinh_sinh_integrate`, for the whole real line.
+///
+/// Each fixed rule converges exponentially quickly in `num_points` for
+/// functions which are analytic on the interval. Only the double exponential
+/// methods accept infinite limits.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuadratureMethod {
+    GaussLegendre { num_points: usize },
+    GaussLobatto { num_points: usize },
+    ClenshawCurtis { num_points: usize },
+    GaussKronrod(AdaptiveQuadratureOptions),
+    TanhSinh(DoubleExponentialOptions),
+    ExpSinh(DoubleExponentialOptions),
+    SinhSinh(DoubleExponentialOptions),
+}
+
+/// # Quadrature rule
+///
+/// ## Description
+/// `QuadratureRule` stores the nodes and weights of a quadrature rule on the
+/// reference interval [-1, 1], with the nodes in increasing order, so that
+/// the integral of f over [-1, 1] is approximated by the sum of
+/// `weights[i] * f(nodes[i])`.
+///
+/// The rule is mapped onto other intervals by `integrate` and `grid`.
+///
+/// ## Example use case
+/// Suppose that we want to integrate several functions from 0 to 1 with the
+/// 10 point Gauss-Legendre rule. The code below computes the rule once and
+/// uses it for each function.
+/// ```
+/// let rule =
+///     QuadratureRule::new(QuadratureMethod::GaussLegendre { num_points: 10 });
+/// let integral_1 = rule.integrate(f64::exp, 0.0, 1.0);
+/// let integral_2 = rule.integrate(|x| 1.0 / (1.0 + x * x), 0.0, 1.0);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuadratureRule {
+    pub nodes: Vec<f64>,
+    pub weights: Vec<f64>,
+}
+
+impl QuadratureRule {
+    /// # New quadrature rule
+    ///
+    /// ## Description
+    /// `new` computes the nodes and weights of the quadrature rule given by
+    /// `method`.
+    ///
+    /// `new` panics if `method` has too few points. See `try_new` for a
+    /// version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let method = QuadratureMethod::GaussLobatto { num_points: 5 };
+    /// let rule = QuadratureRule::new(method);
+    /// ```
+    ///
+    pub fn new(method: QuadratureMethod) -> Self {
+        match Self::try_new(method) {
+            Ok(rule) => rule,
+            Err(error) => {
+                panic!("Quadrature rule failed to construct. {}", error)
+            }
+        }
+    }
+
+    /// # Try new quadrature rule
+    ///
+    /// ## Description
+    /// `try_new` is the fallible version of `new`. It returns an
+    /// `InvalidParameter` error if `method` has too few points, or is not a
+    /// fixed rule.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let rule = QuadratureRule::try_new(QuadratureMethod::ClenshawCurtis {
+    ///     num_points: 17,
+    /// })?;
+    /// ```
+    ///
+    pub fn try_new(
+        method: QuadratureMethod,
+    ) -> Result<Self, NumericalMethodsError> {
+        let (num_points, min_points) = match method {
+            QuadratureMethod::GaussLegendre { num_points } => (num_points, 1),
+            QuadratureMethod::GaussLobatto { num_points }
+            | QuadratureMethod::ClenshawCurtis { num_points } => {
+                (num_points, 2)
+            }
+            QuadratureMethod::GaussKronrod(_)
+            | QuadratureMethod::TanhSinh(_)
+            | QuadratureMethod::ExpSinh(_)
+            | QuadratureMethod::SinhSinh(_) => {
+                return Err(NumericalMethodsError::InvalidParameter(
+                    "The quadrature method does not have a fixed rule."
+                        .to_string(),
+                ));
+            }
+        };
+        if num_points < min_points {
+            return Err(NumericalMethodsError::InvalidParameter(format!(
+                "The quadrature rule needs at least {} points, but {} were \
+                 requested.",
+                min_points, num_points
+            )));
+        }
+
+        Ok(match method {
+            QuadratureMethod::GaussLegendre { .. } => {
+                gauss_legendre_rule(num_points)
+            }
+            QuadratureMethod::GaussLobatto { .. } => {
+                gauss_lobatto_rule(num_points)
+            }
+            QuadratureMethod::ClenshawCurtis { .. } => {
+                clenshaw_curtis_rule(num_points)
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    /// # Integrate
+    ///
+    /// ## Description
+    /// `integrate` approximates the integral of `func` from `lower_limit` to
+    /// `upper_limit` with the current `QuadratureRule`, mapped linearly onto
+    /// [`lower_limit`, `upper_limit`]. If `lower_limit` is greater than
+    /// `upper_limit`, the integral is negated.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = rule.integrate(f64::sin, 0.0, PI);
+    /// ```
+    ///
+    pub fn integrate<F>(
+        &self,
+        func: F,
+        lower_limit: f64,
+        upper_limit: f64,
+    ) -> f64
+    where
+        F: Fn(f64) -> f64,
+    {
+        let midpoint = 0.5 * (lower_limit + upper_limit);
+        let half_length = 0.5 * (upper_limit - lower_limit);
+        half_length
+            * self
+                .nodes
+                .iter()
+                .zip(&self.weights)
+                .map(|(node, weight)| {
+                    weight * func(midpoint + half_length * node)
+                })
+                .sum::<f64>()
+    }
+
+    /// # Grid
+    ///
+    /// ## Description
+    /// `grid` returns the nodes of the current `QuadratureRule` mapped onto
+    /// [`lower_limit`, `upper_limit`], as a `Grid`. A `GridFunction` sampled
+    /// on this `Grid` can be integrated with `integrate_grid_function`.
+    ///
+    /// `grid` panics if `lower_limit` is not less than `upper_limit`. See
+    /// `try_grid` for a version which returns an error instead.
+    ///
+    /// ## Example use case
+    /// Suppose that we want to sample a function at the 8 point
+    /// Gauss-Legendre nodes on [0, 2] and integrate it. The code below does
+    /// this.
+    /// ```
+    /// let method = QuadratureMethod::GaussLegendre { num_points: 8 };
+    /// let rule = QuadratureRule::new(method);
+    /// let grid = rule.grid(0.0, 2.0);
+    /// let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+    /// let integral = rule.integrate_grid_function(&grid_func, 0.0, 2.0);
+    /// ```
+    ///
+    pub fn grid(&self, lower_limit: f64, upper_limit: f64) -> Grid {
+        match self.try_grid(lower_limit, upper_limit) {
+            Ok(grid) => grid,
+            Err(error) => panic!("Grid failed to construct. {}", error),
+        }
+    }
+
+    /// # Integrate grid function
+    ///
+    /// ## Description
+    /// `integrate_grid_function` approximates the integral from
+    /// `lower_limit` to `upper_limit` of `grid_func`, which must be sampled
+    /// on the `Grid` returned by `grid` for the same limits.
+    ///
+    /// `integrate_grid_function` panics if the `Grid` of `grid_func` does not
+    /// match. See `try_integrate_grid_function` for a version which returns
+    /// an error instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = rule.integrate_grid_function(&grid_func, 0.0, 2.0);
+    /// ```
+    ///
+    pub fn integrate_grid_function(
+        &self,
+        grid_func: &GridFunction,
+        lower_limit: f64,
+        upper_limit: f64,
+    ) -> f64 {
+        match self.try_integrate_grid_function(
+            grid_func,
+            lower_limit,
+            upper_limit,
+        ) {
+            Ok(integral) => integral,
+            Err(error) => panic!("Integral failed to evaluate. {}", error),
+        }
+    }
+
+    /// # Try grid
+    ///
+    /// ## Description
+    /// `try_grid` is the fallible version of `grid`. It returns an
+    /// `InvalidGrid` error if the limits are not finite, or if `lower_limit`
+    /// is not less than `upper_limit`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let grid = rule.try_grid(0.0, 2.0)?;
+    /// ```
+    ///
+    pub fn try_grid(
+        &self,
+        lower_limit: f64,
+        upper_limit: f64,
+    ) -> Result<Grid, NumericalMethodsError> {
+        let limits_valid = lower_limit < upper_limit;
+        if !limits_valid {
+            return Err(NumericalMethodsError::InvalidGrid(
+                "The lower limit must be less than the upper limit."
+                    .to_string(),
+            ));
+        }
+
+        let midpoint = 0.5 * (lower_limit + upper_limit);
+        let half_length = 0.5 * (upper_limit - lower_limit);
+        let grid = Grid {
+            grid_points: self
+                .nodes
+                .iter()
+                .map(|node| midpoint + half_length * node)
+                .collect(),
+        };
+        grid.validate()?;
+
+        Ok(grid)
+    }
+
+    /// # Try integrate grid function
+    ///
+    /// ## Description
+    /// `try_integrate_grid_function` is the fallible version of
+    /// `integrate_grid_function`. It returns an error if `grid_func` is not
+    /// valid, if the limits are not valid, or a `GridMismatch` error if the
+    /// `Grid` of `grid_func` does not match the nodes of the current
+    /// `QuadratureRule` on [`lower_limit`, `upper_limit`].
+    ///
+    /// ## Example use case
+    /// ```
+    /// let integral = rule.try_integrate_grid_function(&grid_func, 0.0, 2.0)?;
+    /// ```
+    ///
+    pub fn try_integrate_grid_function(
+        &self,
+        grid_func: &GridFunction,
+        lower_limit: f64,
+        upper_limit: f64,
+    ) -> Result<f64, NumericalMethodsError> {
+        grid_func.validate()?;
+        let grid = self.try_grid(lower_limit, upper_limit)?;
+        if !grid.is_compatible(&grid_func.grid, GRID_TOLERANCE) {
+            return Err(NumericalMethodsError::GridMismatch);
+        }
+
+        let half_length = 0.5 * (upper_limit - lower_limit);
+        Ok(half_length
+            * self
+                .weights
+                .iter()
+                .zip(&grid_func.function_values)
+                .map(|(weight, value)| weight * value)
+                .sum::<f64>())
+    }
+}
+
+/// # Integrate
+///
+/// ## Description
+/// `integrate` approximates the integral of `func` from `lower_limit` to
+/// `upper_limit` with the quadrature rule given by `method`. If
+/// `lower_limit` is greater than `upper_limit`, the integral is negated.
+///
+/// The rule is computed on every call, which takes O(n^3) operations for the
+/// Gauss rules with n points. Use `QuadratureRule` to reuse a rule for
+/// several integrals.
+///
+/// `integrate` panics if the integral fails. See `try_integrate` for a
+/// version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want the integral of exp(-x^2) from 0 to 1. The code below
+/// computes it with the 12 point Gauss-Legendre rule.
+/// ```
+/// let integral = integrate(
+///     |x| (-x * x).exp(),
+///     0.0,
+///     1.0,
+///     &QuadratureMethod::GaussLegendre { num_points: 12 },
+/// );
+/// ```
+///
+pub fn integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    method: &QuadratureMethod,
+) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    match try_integrate(func, lower_limit, upper_limit, method) {
+        Ok(integral) => integral,
+        Err(error) => panic!("Integral failed to evaluate. {}", error),
+    }
+}
+
+/// # Try integrate
+///
+/// ## Description
+/// `try_integrate` is the fallible version of `integrate`. It returns an
+/// `InvalidParameter` error if the limits are not valid for `method`, if
+/// `method` has too few points, or if its adaptive options are not valid.
+///
+/// ## Example use case
+/// ```
+/// let integral = try_integrate(
+///     f64::exp,
+///     0.0,
+///     1.0,
+///     &QuadratureMethod::ClenshawCurtis { num_points: 17 },
+/// )?;
+/// ```
+///
+pub fn try_integrate<F>(
+    func: F,
+    lower_limit: f64,
+    upper_limit: f64,
+    method: &QuadratureMethod,
+) -> Result<f64, NumericalMethodsError>
+where
+    F: Fn(f64) -> f64,
+{
+    match method {
+        QuadratureMethod::GaussKronrod(options) => Ok(try_adaptive_integrate(
+            func,
+            lower_limit,
+            upper_limit,
+            options,
+        )?
+        .value),
+        QuadratureMethod::TanhSinh(options) => Ok(try_tanh_sinh_integrate(
+            func,
+            lower_limit,
+            upper_limit,
+            options,
+        )?
+        .value),
+        QuadratureMethod::ExpSinh(options) => Ok(try_exp_sinh_integrate(
+            func,
+            lower_limit,
+            upper_limit,
+            options,
+        )?
+        .value),
+        QuadratureMethod::SinhSinh(options) => Ok(try_sinh_sinh_integrate(
+            func,
+            lower_limit,
+            upper_limit,
+            options,
+        )?
+        .value),
+        _ => {
+            if !(lower_limit.is_finite() && upper_limit.is_finite()) {
+                return Err(NumericalMethodsError::InvalidParameter(
+                    "The limits of integration must be finite.".to_string(),
+                ));
+            }
+            let rule = QuadratureRule::try_new(*method)?;
+            Ok(rule.integrate(func, lower_limit, upper_limit))
+        }
+    }
+}
+
+/// # Gauss-Legendre rule
+///
+/// ## Description
+/// `gauss_legendre_rule` returns the `num_points` point Gauss-Legendre rule,
+/// computed by the Golub-Welsch algorithm.
+///
+fn gauss_legendre_rule(num_points: usize) -> QuadratureRule {
+    let off_diagonal: Vec<f64> = (1..num_points)
+        .map(|k| {
+            let k = k as f64;
+            k / (4.0 * k * k - 1.0).sqrt()
+        })
+        .collect();
+    golub_welsch(&off_diagonal)
+}
+
+/// # Gauss-Lobatto rule
+///
+/// ## Description
+/// `gauss_lobatto_rule` returns the `num_points` point Gauss-Lobatto rule,
+/// computed by the Golub-Welsch algorithm. The last off-diagonal element of
+/// the Jacobi matrix is set to sqrt((n - 1) / (2n - 3)) for n points, which
+/// makes -1 and 1 eigenvalues.
+///
+fn gauss_lobatto_rule(num_points: usize) -> QuadratureRule {
+    let n = num_points as f64;
+    let mut off_diagonal: Vec<f64> = (1..(num_points - 1))
+        .map(|k| {
+            let k = k as f64;
+            k / (4.0 * k * k - 1.0).sqrt()
+        })
+        .collect();
+    off_diagonal.push(((n - 1.0) / (2.0 * n - 3.0)).sqrt());
+
+    let mut rule = golub_welsch(&off_diagonal);
+    rule.nodes[0] = -1.0;
+    rule.nodes[num_points - 1] = 1.0;
+    rule
+}
+
+/// # Golub-Welsch
+///
+/// ## Description
+/// `golub_welsch` returns the quadrature rule for the weight function 1 on
+/// [-1, 1] whose Jacobi matrix has zero diagonal and the off-diagonal
+/// elements `off_diagonal`.
+///
+/// The rules are symmetric about 0, so the nodes and weights are averaged
+/// with their mirror images to remove rounding errors from the eigensolver.
+///
+fn golub_welsch(off_diagonal: &[f64]) -> QuadratureRule {
+    let size = off_diagonal.len() + 1;
+    let jacobi_matrix = DMatrix::from_fn(size, size, |row, column| {
+        if row + 1 == column {
+            off_diagonal[row]
+        } else if column + 1 == row {
+            off_diagonal[column]
+        } else {
+            0.0
+        }
+    });
+    let eigen = SymmetricEigen::new(jacobi_matrix);
+
+    let mut order: Vec<usize> = (0..size).collect();
+    order.sort_by(|&i, &j| {
+        eigen.eigenvalues[i].total_cmp(&eigen.eigenvalues[j])
+    });
+    let nodes: Vec<f64> = order.iter().map(|&i| eigen.eigenvalues[i]).collect();
+    let weights: Vec<f64> = order
+        .iter()
+        .map(|&i| 2.0 * eigen.eigenvectors[(0, i)].powi(2))
+        .collect();
+
+    QuadratureRule {
+        nodes: (0..size)
+            .map(|i| 0.5 * (nodes[i] - nodes[size - 1 - i]))
+            .collect(),
+        weights: (0..size)
+            .map(|i| 0.5 * (weights[i] + weights[size - 1 - i]))
+            .collect(),
+    }
+}
+
+/// # Clenshaw-Curtis rule
+///
+/// ## Description
+/// `clenshaw_curtis_rule` returns the `num_points` point Clenshaw-Curtis
+/// rule. The nodes are cos(k PI / N) for k = 0, ..., N, where
+/// N = `num_points` - 1, and the weights are found by integrating the
+/// Chebyshev interpolant exactly, using the explicit formula of Waldvogel.
+///
+fn clenshaw_curtis_rule(num_points: usize) -> QuadratureRule {
+    let intervals = num_points - 1;
+    let n = intervals as f64;
+    let angle = |k: usize| k as f64 * std::f64::consts::PI / n;
+
+    let mut nodes = Vec::with_capacity(num_points);
+    let mut weights = Vec::with_capacity(num_points);
+    for k in (0..=intervals).rev() {
+        // cos(k PI / N) is written as a sine to make the nodes exactly
+        // symmetric about 0.
+        let offset = intervals as f64 - 2.0 * k as f64;
+        nodes.push((offset * std::f64::consts::PI / (2.0 * n)).sin());
+
+        let sum: f64 = (1..=(intervals / 2))
+            .map(|j| {
+                let b = if 2 * j == intervals { 1.0 } else { 2.0 };
+                b * (2.0 * angle(j * k)).cos() / (4.0 * (j * j) as f64 - 1.0)
+            })
+            .sum();
+        let c = if k == 0 || k == intervals { 1.0 } else { 2.0 };
+        weights.push(c / n * (1.0 - sum));
+    }
+
+    QuadratureRule { nodes, weights }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PI: f64 = std::f64::consts::PI;
+
+    #[test]
+    fn test_known_rules() {
+        let rule = QuadratureRule::new(QuadratureMethod::GaussLegendre {
+            num_points: 3,
+        });
+        let node = (0.6_f64).sqrt();
+        for (value, exact) in rule.nodes.iter().zip([-node, 0.0, node]) {
+            assert!((value - exact).abs() < 1e-15);
+        }
+        for (value, exact) in
+            rule.weights.iter().zip([5.0 / 9.0, 8.0 / 9.0, 5.0 / 9.0])
+        {
+            assert!((value - exact).abs() < 1e-15);
+        }
+
+        // The 3 point Gauss-Lobatto and Clenshaw-Curtis rules are both
+        // Simpson's rule.
+        for method in [
+            QuadratureMethod::GaussLobatto { num_points: 3 },
+            QuadratureMethod::ClenshawCurtis { num_points: 3 },
+        ] {
+            let rule = QuadratureRule::new(method);
+            for (value, exact) in rule.nodes.iter().zip([-1.0, 0.0, 1.0]) {
+                assert!((value - exact).abs() < 1e-15, "{:?}", method);
+            }
+            for (value, exact) in
+                rule.weights.iter().zip([1.0 / 3.0, 4.0 / 3.0, 1.0 / 3.0])
+            {
+                assert!((value - exact).abs() < 1e-15, "{:?}", method);
+            }
+        }
+    }
+
+    #[test]
+    fn test_polynomial_exactness() {
+        // Each rule is exact for monomials up to its degree of exactness.
+        for num_points in 2..=12 {
+            let cases = [
+                (
+                    QuadratureMethod::GaussLegendre { num_points },
+                    2 * num_points - 1,
+                ),
+                (
+                    QuadratureMethod::GaussLobatto { num_points },
+                    2 * num_points - 3,
+                ),
+                (
+                    QuadratureMethod::ClenshawCurtis { num_points },
+                    num_points - 1,
+                ),
+            ];
+            for (method, degree) in cases {
+                let rule = QuadratureRule::new(method);
+                for power in 0..=degree {
+                    let integral =
+                        rule.integrate(|x| x.powi(power as i32), 0.0, 2.0);
+                    let exact =
+                        2.0_f64.powi(power as i32 + 1) / (power + 1) as f64;
+                    assert!(
+                        (integral - exact).abs() < 1e-12 * exact,
+                        "{:?} failed for x^{}.",
+                        method,
+                        power
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_high_order_convergence() {
+        // The integral of 1 / (1 + 16 x^2) from -1 to 1 is atan(4) / 2.
+        let exact = 4.0_f64.atan() / 2.0;
+        for method in [
+            QuadratureMethod::GaussLegendre { num_points: 60 },
+            QuadratureMethod::GaussLobatto { num_points: 60 },
+            QuadratureMethod::ClenshawCurtis { num_points: 80 },
+        ] {
+            let integral =
+                integrate(|x| 1.0 / (1.0 + 16.0 * x * x), -1.0, 1.0, &method);
+            assert!((integral - exact).abs() < 1e-12, "{:?}", method);
+        }
+
+        for method in [
+            QuadratureMethod::GaussLegendre { num_points: 10 },
+            QuadratureMethod::GaussKronrod(Default::default()),
+        ] {
+            let integral = integrate(f64::sin, PI, 0.0, &method);
+            assert!((integral + 2.0).abs() < 1e-12, "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn test_double_exponential_methods() {
+        // The double exponential methods handle endpoint singularities and
+        // infinite limits through the same API as the fixed rules.
+        let options = DoubleExponentialOptions::default();
+        let infinity = f64::INFINITY;
+        let integral = integrate(
+            |x| 1.0 / x.sqrt(),
+            0.0,
+            4.0,
+            &QuadratureMethod::TanhSinh(options),
+        );
+        assert!((integral - 4.0).abs() < 1e-10);
+
+        let integral = integrate(
+            |x| (-x).exp(),
+            0.0,
+            infinity,
+            &QuadratureMethod::ExpSinh(options),
+        );
+        assert!((integral - 1.0).abs() < 1e-10);
+
+        let integral = integrate(
+            |x| 1.0 / (1.0 + x * x),
+            -infinity,
+            infinity,
+            &QuadratureMethod::SinhSinh(options),
+        );
+        assert!((integral - PI).abs() < 1e-10);
+
+        assert!(matches!(
+            QuadratureRule::try_new(QuadratureMethod::TanhSinh(options)),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_integrate(
+                f64::exp,
+                0.0,
+                1.0,
+                &QuadratureMethod::SinhSinh(options)
+            ),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_grid_and_grid_function() {
+        let rule = QuadratureRule::new(QuadratureMethod::GaussLobatto {
+            num_points: 9,
+        });
+        let grid = rule.grid(1.0, 3.0);
+        assert_eq!(grid.grid_points.len(), 9);
+        assert!((grid.grid_points[0] - 1.0).abs() < 1e-15);
+        assert!((grid.grid_points[8] - 3.0).abs() < 1e-15);
+
+        let grid_func = GridFunction::new_grid_function(&grid, f64::exp);
+        let integral = rule.integrate_grid_function(&grid_func, 1.0, 3.0);
+        assert!((integral - (3.0_f64.exp() - 1.0_f64.exp())).abs() < 1e-10);
+        assert_eq!(integral, rule.integrate(f64::exp, 1.0, 3.0));
+
+        assert_eq!(
+            rule.try_integrate_grid_function(&grid_func, 0.0, 3.0),
+            Err(NumericalMethodsError::GridMismatch)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            QuadratureRule::try_new(QuadratureMethod::GaussLegendre {
+                num_points: 0
+            }),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            QuadratureRule::try_new(QuadratureMethod::GaussLobatto {
+                num_points: 1
+            }),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            try_integrate(
+                f64::exp,
+                0.0,
+                f64::INFINITY,
+                &QuadratureMethod::ClenshawCurtis { num_points: 5 }
+            ),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+
+        let rule = QuadratureRule::new(QuadratureMethod::GaussLegendre {
+            num_points: 1,
+        });
+        assert_eq!(rule.nodes, vec![0.0]);
+        assert_eq!(rule.weights, vec![2.0]);
+        assert!(matches!(
+            rule.try_grid(1.0, 1.0),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+    }
+}
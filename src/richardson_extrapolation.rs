@@ -0,0 +1,353 @@
+use crate::error::NumericalMethodsError;
+use crate::grid::{Grid, GRID_TOLERANCE};
+use crate::grid_function::GridFunction;
+
+/// # Richardson extrapolate
+///
+/// ## Description
+/// `richardson_extrapolate` combines approximations `values` of a quantity,
+/// computed with step sizes h, h / r, h / r^2, ..., where r is
+/// `refinement_ratio`, to cancel the leading terms of their errors.
+///
+/// The error of each approximation must have an expansion in powers of the
+/// step size, starting at `error_order` and increasing by
+/// `order_increment`. For example, the trapezoidal rule and central
+/// differences have errors in h^2, h^4, h^6, ..., so `error_order` and
+/// `order_increment` are both 2, while forward differences have errors in
+/// h, h^2, h^3, ..., so both are 1. With k values, k - 1 terms of the error
+/// are cancelled, using the Neville-like Richardson tableau.
+///
+/// `richardson_extrapolate` panics if the parameters are not valid. See
+/// `try_richardson_extrapolate` for a version which returns an error
+/// instead.
+///
+/// ## Example use case
+/// Suppose that we have approximated the derivative of sin at x = 1 by
+/// central differences with h = 0.1, 0.05 and 0.025. The code below
+/// combines them into a sixth order approximation.
+/// ```
+/// let central_difference =
+///     |h: f64| ((1.0 + h).sin() - (1.0 - h).sin()) / (2.0 * h);
+/// let values = [0.1, 0.05, 0.025].map(central_difference);
+/// let derivative = richardson_extrapolate(&values, 2.0, 2, 2);
+/// ```
+///
+pub fn richardson_extrapolate(
+    values: &[f64],
+    refinement_ratio: f64,
+    error_order: u32,
+    order_increment: u32,
+) -> f64 {
+    match try_richardson_extrapolate(
+        values,
+        refinement_ratio,
+        error_order,
+        order_increment,
+    ) {
+        Ok(value) => value,
+        Err(error) => panic!("Richardson extrapolation failed. {}", error),
+    }
+}
+
+/// # Try Richardson extrapolate
+///
+/// ## Description
+/// `try_richardson_extrapolate` is the fallible version of
+/// `richardson_extrapolate`. It returns an `InsufficientPoints` error if
+/// `values` is empty, and an `InvalidParameter` error if
+/// `refinement_ratio` is not greater than 1 or `error_order` is 0.
+///
+/// ## Example use case
+/// ```
+/// let derivative = try_richardson_extrapolate(&values, 2.0, 2, 2)?;
+/// ```
+///
+pub fn try_richardson_extrapolate(
+    values: &[f64],
+    refinement_ratio: f64,
+    error_order: u32,
+    order_increment: u32,
+) -> Result<f64, NumericalMethodsError> {
+    validate_richardson_parameters(
+        values.len(),
+        refinement_ratio,
+        error_order,
+    )?;
+
+    // Each pass over the tableau cancels the next term of the error. After
+    // pass k, tableau[i] combines values[i - k..=i].
+    let mut tableau = values.to_vec();
+    for k in 1..values.len() {
+        let order = error_order + (k as u32 - 1) * order_increment;
+        let factor = refinement_ratio.powi(order as i32) - 1.0;
+        for i in (k..values.len()).rev() {
+            tableau[i] += (tableau[i] - tableau[i - 1]) / factor;
+        }
+    }
+
+    Ok(tableau[values.len() - 1])
+}
+
+/// # Richardson extrapolate grid functions
+///
+/// ## Description
+/// `richardson_extrapolate_grid_functions` applies `richardson_extrapolate`
+/// point by point to `grid_funcs`, which must be computed on successively
+/// 2x refined grids, so that the grid points of each `GridFunction` are the
+/// even-numbered grid points of the next. The result is returned on the
+/// coarsest `Grid`, which is that of `grid_funcs[0]`.
+///
+/// This sharpens, for example, the results of `central_difference_derivative`
+/// or the solutions of BVPs on refined grids. `error_order` and
+/// `order_increment` describe the error expansion, as for
+/// `richardson_extrapolate`.
+///
+/// `richardson_extrapolate_grid_functions` panics if the `GridFunctions`
+/// are not valid or not refined correctly. See
+/// `try_richardson_extrapolate_grid_functions` for a version which returns
+/// an error instead.
+///
+/// ## Example use case
+/// Suppose that we have solved a BVP by second order finite differences on
+/// grids with 11, 21 and 41 points. The code below combines the solutions
+/// into a sixth order accurate solution on the 11 point grid.
+/// ```
+/// let solutions: Vec<GridFunction> = [11, 21, 41]
+///     .iter()
+///     .map(|&num_points| {
+///         let grid = Grid::new_uniform_grid(0.0, 1.0, num_points);
+///         let initial_guess = GridFunction::new_grid_function(&grid, |x| x);
+///         solver.solve(rhs, &bcs, &initial_guess).solution
+///     })
+///     .collect();
+/// let solution = richardson_extrapolate_grid_functions(&solutions, 2, 2);
+/// ```
+///
+pub fn richardson_extrapolate_grid_functions(
+    grid_funcs: &[GridFunction],
+    error_order: u32,
+    order_increment: u32,
+) -> GridFunction {
+    match try_richardson_extrapolate_grid_functions(
+        grid_funcs,
+        error_order,
+        order_increment,
+    ) {
+        Ok(grid_func) => grid_func,
+        Err(error) => panic!("Richardson extrapolation failed. {}", error),
+    }
+}
+
+/// # Try Richardson extrapolate grid functions
+///
+/// ## Description
+/// `try_richardson_extrapolate_grid_functions` is the fallible version of
+/// `richardson_extrapolate_grid_functions`. It returns an error if
+/// `grid_funcs` is empty, if any `GridFunction` is not valid, if
+/// `error_order` is 0, or a `GridMismatch` error if a `Grid` is not the 2x
+/// refinement of the previous one.
+///
+/// ## Example use case
+/// ```
+/// let derivative = try_richardson_extrapolate_grid_functions(
+///     &derivatives,
+///     2,
+///     2,
+/// )?;
+/// ```
+///
+pub fn try_richardson_extrapolate_grid_functions(
+    grid_funcs: &[GridFunction],
+    error_order: u32,
+    order_increment: u32,
+) -> Result<GridFunction, NumericalMethodsError> {
+    validate_richardson_parameters(grid_funcs.len(), 2.0, error_order)?;
+    for grid_func in grid_funcs {
+        grid_func.validate()?;
+    }
+
+    // Checks that each grid is the 2x refinement of the previous one.
+    for pair in grid_funcs.windows(2) {
+        let coarse_points = &pair[0].grid.grid_points;
+        let fine_points = &pair[1].grid.grid_points;
+        if fine_points.len() != 2 * coarse_points.len() - 1 {
+            return Err(NumericalMethodsError::GridMismatch);
+        }
+        let restricted_grid = Grid {
+            grid_points: fine_points.iter().step_by(2).copied().collect(),
+        };
+        if !pair[0].grid.is_compatible(&restricted_grid, GRID_TOLERANCE) {
+            return Err(NumericalMethodsError::GridMismatch);
+        }
+    }
+
+    let coarse_grid = &grid_funcs[0].grid;
+    let function_values = (0..coarse_grid.grid_points.len())
+        .map(|i| {
+            // The ith coarse grid point is point i * 2^level of each level.
+            let values: Vec<f64> = grid_funcs
+                .iter()
+                .enumerate()
+                .map(|(level, grid_func)| grid_func.function_values[i << level])
+                .collect();
+            try_richardson_extrapolate(
+                &values,
+                2.0,
+                error_order,
+                order_increment,
+            )
+        })
+        .collect::<Result<Vec<f64>, NumericalMethodsError>>()?;
+
+    Ok(GridFunction {
+        grid: coarse_grid.clone(),
+        function_values,
+    })
+}
+
+/// # Validate Richardson parameters
+///
+/// ## Description
+/// `validate_richardson_parameters` checks that there is at least one
+/// approximation, that `refinement_ratio` is greater than 1, and that
+/// `error_order` is positive.
+///
+fn validate_richardson_parameters(
+    num_values: usize,
+    refinement_ratio: f64,
+    error_order: u32,
+) -> Result<(), NumericalMethodsError> {
+    if num_values == 0 {
+        return Err(NumericalMethodsError::InsufficientPoints {
+            required: 1,
+            actual: 0,
+        });
+    }
+    let parameters_valid = refinement_ratio > 1.0
+        && refinement_ratio.is_finite()
+        && error_order > 0;
+    if !parameters_valid {
+        return Err(NumericalMethodsError::InvalidParameter(
+            "The refinement ratio must be greater than 1, and the error order \
+             must be positive."
+                .to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_conditions::BoundaryConditions;
+    use crate::bvp_solvers::{BoundaryValueSolver, FiniteDifferenceSolver};
+    use crate::grid::max_error;
+
+    #[test]
+    fn test_richardson_extrapolate() {
+        // Central differences have errors in even powers of h.
+        let central_difference =
+            |h: f64| ((1.0 + h).sin() - (1.0 - h).sin()) / (2.0 * h);
+        let values = [0.2, 0.1, 0.05].map(central_difference);
+        let exact = 1.0_f64.cos();
+        let derivative = richardson_extrapolate(&values, 2.0, 2, 2);
+        assert!((values[2] - exact).abs() > 1e-4);
+        assert!((derivative - exact).abs() < 1e-9);
+
+        // Forward differences have errors in all powers of h, here with a
+        // refinement ratio of 3.
+        let forward_difference = |h: f64| ((1.0 + h).exp() - 1.0_f64.exp()) / h;
+        let values = [0.09, 0.03, 0.01].map(forward_difference);
+        let derivative = richardson_extrapolate(&values, 3.0, 1, 1);
+        assert!((derivative - 1.0_f64.exp()).abs() < 1e-5);
+        assert!((values[2] - 1.0_f64.exp()).abs() > 1e-2);
+
+        // A single value is returned unchanged.
+        assert_eq!(richardson_extrapolate(&[2.5], 2.0, 2, 2), 2.5);
+    }
+
+    #[test]
+    fn test_richardson_extrapolate_grid_functions() {
+        // u'' = -PI^2 sin(PI x) with u(0) = u(1) = 0 has the solution
+        // u = sin(PI x). The finite difference error is in even powers of h.
+        let pi = std::f64::consts::PI;
+        let bcs = BoundaryConditions::new_dirichlet_bcs(0.0, 0.0);
+        let solutions: Vec<GridFunction> = [11, 21, 41]
+            .iter()
+            .map(|&num_points| {
+                let grid = Grid::new_uniform_grid(0.0, 1.0, num_points);
+                let initial_guess =
+                    GridFunction::new_constant_grid_function(&grid, 0.0);
+                FiniteDifferenceSolver::default()
+                    .solve(
+                        |x, _u, _u_derivative| -pi * pi * (pi * x).sin(),
+                        &bcs,
+                        &initial_guess,
+                    )
+                    .solution
+            })
+            .collect();
+
+        let exact = |x: f64| (pi * x).sin();
+        let extrapolated =
+            richardson_extrapolate_grid_functions(&solutions, 2, 2);
+        assert_eq!(extrapolated.grid, solutions[0].grid);
+        assert!(max_error(&solutions[2], exact) > 1e-4);
+        assert!(max_error(&extrapolated, exact) < 1e-7);
+
+        // Central difference derivatives, away from the one-sided ends.
+        let derivatives: Vec<GridFunction> = [11, 21]
+            .iter()
+            .map(|&num_points| {
+                let grid = Grid::new_uniform_grid(0.0, 1.0, num_points);
+                GridFunction::new_grid_function(&grid, f64::exp)
+                    .central_difference_derivative()
+            })
+            .collect();
+        let extrapolated =
+            richardson_extrapolate_grid_functions(&derivatives, 2, 2);
+        for (x, value) in extrapolated
+            .grid
+            .grid_points
+            .iter()
+            .zip(&extrapolated.function_values)
+            .skip(1)
+            .take(9)
+        {
+            assert!((value - x.exp()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_try_richardson_extrapolate_errors() {
+        assert_eq!(
+            try_richardson_extrapolate(&[], 2.0, 2, 2),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 1,
+                actual: 0
+            })
+        );
+        assert!(matches!(
+            try_richardson_extrapolate(&[1.0, 2.0], 1.0, 2, 2),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+
+        let coarse = GridFunction::new_constant_grid_function(
+            &Grid::new_uniform_grid(0.0, 1.0, 11),
+            1.0,
+        );
+        let wrong_fine = GridFunction::new_constant_grid_function(
+            &Grid::new_uniform_grid(0.0, 2.0, 21),
+            1.0,
+        );
+        assert_eq!(
+            try_richardson_extrapolate_grid_functions(
+                &[coarse, wrong_fine],
+                2,
+                2
+            ),
+            Err(NumericalMethodsError::GridMismatch)
+        );
+    }
+}
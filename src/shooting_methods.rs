@@ -0,0 +1,461 @@
+use crate::boundary_conditions::BoundaryConditions;
+use crate::boundary_value_problems::{
+    newton_iteration, solve_linear_system, NewtonOptions, NewtonResult,
+};
+use crate::bvp_solvers::BoundaryValueSolver;
+use crate::error::NumericalMethodsError;
+use crate::grid_function::GridFunction;
+use crate::initial_value_problems::rk4_step;
+
+/// # Shooting solver
+///
+/// ## Description
+/// `ShootingSolver` solves u'' = f(x, u, u') by simple shooting. The unknowns
+/// are the state [u, u'] at the left end of the domain. For each trial state,
+/// the DE is integrated across the grid of the initial guess with the
+/// classical fourth order Runge-Kutta (RK4) method, taking `steps_per_cell`
+/// equal steps in each grid cell, and the trial state is updated with
+/// Newton's method until the BCs are met. It needs at least 2 grid points.
+///
+/// The initial guess only provides the grid and the starting state, which is
+/// the first value of the initial guess and its forward difference
+/// derivative there.
+///
+/// Simple shooting is cheap and very accurate for well-behaved problems, but
+/// for problems whose solutions grow quickly, small changes in the starting
+/// state cause huge changes at the right end of the domain. Use
+/// `MultipleShootingSolver` for these problems.
+///
+/// ## Example use case
+/// ```
+/// let solver = ShootingSolver {
+///     steps_per_cell: 4,
+///     ..Default::default()
+/// };
+/// let result = solver.solve(rhs, &bcs, &initial_guess);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShootingSolver {
+    pub steps_per_cell: usize,
+    pub options: NewtonOptions,
+}
+
+impl Default for ShootingSolver {
+    fn default() -> Self {
+        ShootingSolver {
+            steps_per_cell: 10,
+            options: NewtonOptions::default(),
+        }
+    }
+}
+
+/// # Multiple shooting solver
+///
+/// ## Description
+/// `MultipleShootingSolver` solves u'' = f(x, u, u') by multiple shooting. The
+/// grid of the initial guess is split into `num_intervals` subintervals with
+/// roughly equal numbers of grid cells, and the unknowns are the states
+/// [u, u'] at the start of each subinterval. The DE is integrated across
+/// each subinterval with RK4, taking `steps_per_cell` steps in each grid
+/// cell, and Newton's method updates the states until the BCs are met and
+/// the solutions on neighbouring subintervals meet continuously.
+///
+/// The starting states are taken from the initial guess and its central
+/// difference derivative, so a good initial guess is used everywhere rather
+/// than just at the left end. `num_intervals` is limited to the number of
+/// grid cells.
+///
+/// ## Example use case
+/// ```
+/// let solver = MultipleShootingSolver {
+///     num_intervals: 16,
+///     ..Default::default()
+/// };
+/// let result = solver.solve(rhs, &bcs, &initial_guess);
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultipleShootingSolver {
+    pub num_intervals: usize,
+    pub steps_per_cell: usize,
+    pub options: NewtonOptions,
+}
+
+impl Default for MultipleShootingSolver {
+    fn default() -> Self {
+        MultipleShootingSolver {
+            num_intervals: 8,
+            steps_per_cell: 10,
+            options: NewtonOptions::default(),
+        }
+    }
+}
+
+impl BoundaryValueSolver for ShootingSolver {
+    fn try_solve<F>(
+        &self,
+        rhs: F,
+        boundary_conditions: &BoundaryConditions,
+        initial_guess: &GridFunction,
+    ) -> Result<NewtonResult, NumericalMethodsError>
+    where
+        F: Fn(f64, f64, f64) -> f64,
+    {
+        initial_guess.validate_with_min_points(2)?;
+        boundary_conditions.validate()?;
+
+        let points = &initial_guess.grid.grid_points;
+        let values = &initial_guess.function_values;
+        let starting_state =
+            vec![values[0], (values[1] - values[0]) / (points[1] - points[0])];
+
+        let residual_func = |state: &[f64]| {
+            let states = integrate_cells(
+                &rhs,
+                points,
+                [state[0], state[1]],
+                self.steps_per_cell,
+            );
+            boundary_conditions
+                .state_residuals(states[0], states[states.len() - 1])
+                .to_vec()
+        };
+
+        let newton_result = newton_iteration(
+            starting_state,
+            residual_func,
+            |state, residual_vector| {
+                finite_difference_update(
+                    &residual_func,
+                    state,
+                    residual_vector,
+                    self.options.finite_difference_step,
+                )
+            },
+            &self.options,
+        )?;
+
+        Ok(newton_result.map_solution(|state| GridFunction {
+            grid: initial_guess.grid.clone(),
+            function_values: integrate_cells(
+                &rhs,
+                points,
+                [state[0], state[1]],
+                self.steps_per_cell,
+            )
+            .iter()
+            .map(|state| state[0])
+            .collect(),
+        }))
+    }
+}
+
+impl BoundaryValueSolver for MultipleShootingSolver {
+    fn try_solve<F>(
+        &self,
+        rhs: F,
+        boundary_conditions: &BoundaryConditions,
+        initial_guess: &GridFunction,
+    ) -> Result<NewtonResult, NumericalMethodsError>
+    where
+        F: Fn(f64, f64, f64) -> f64,
+    {
+        initial_guess.validate_with_min_points(2)?;
+        boundary_conditions.validate()?;
+
+        // The indices of the grid points which start and end the subintervals.
+        let points = &initial_guess.grid.grid_points;
+        let num_cells = points.len() - 1;
+        let num_intervals = self.num_intervals.clamp(1, num_cells);
+        let breakpoints: Vec<usize> = (0..=num_intervals)
+            .map(|k| k * num_cells / num_intervals)
+            .collect();
+
+        let derivative = initial_guess.central_difference_derivative();
+        let starting_states: Vec<f64> = breakpoints[..num_intervals]
+            .iter()
+            .flat_map(|&i| {
+                [
+                    initial_guess.function_values[i],
+                    derivative.function_values[i],
+                ]
+            })
+            .collect();
+
+        // Integrates across each subinterval from its starting state.
+        let integrate_intervals = |states: &[f64]| -> Vec<Vec<[f64; 2]>> {
+            (0..num_intervals)
+                .map(|k| {
+                    integrate_cells(
+                        &rhs,
+                        &points[breakpoints[k]..=breakpoints[k + 1]],
+                        [states[2 * k], states[2 * k + 1]],
+                        self.steps_per_cell,
+                    )
+                })
+                .collect()
+        };
+
+        // The residuals are the left BC, the mismatches between the end state
+        // of each subinterval and the starting state of the next, and the
+        // right BC, in that order.
+        let residual_func = |states: &[f64]| {
+            let trajectories = integrate_intervals(states);
+            let end_state =
+                |k: usize| trajectories[k][trajectories[k].len() - 1];
+            let [left_residual, right_residual] = boundary_conditions
+                .state_residuals(
+                    [states[0], states[1]],
+                    end_state(num_intervals - 1),
+                );
+
+            let mut residual_vector = vec![left_residual];
+            for k in 0..(num_intervals - 1) {
+                let [u, u_derivative] = end_state(k);
+                residual_vector.push(u - states[2 * k + 2]);
+                residual_vector.push(u_derivative - states[2 * k + 3]);
+            }
+            residual_vector.push(right_residual);
+            residual_vector
+        };
+
+        let newton_result = newton_iteration(
+            starting_states,
+            residual_func,
+            |states, residual_vector| {
+                finite_difference_update(
+                    &residual_func,
+                    states,
+                    residual_vector,
+                    self.options.finite_difference_step,
+                )
+            },
+            &self.options,
+        )?;
+
+        Ok(newton_result.map_solution(|states| {
+            // Each subinterval provides the values up to, but not including,
+            // its last grid point, which is provided by the next subinterval.
+            let trajectories = integrate_intervals(&states);
+            let mut function_values: Vec<f64> = trajectories
+                .iter()
+                .flat_map(|trajectory| {
+                    trajectory[..trajectory.len() - 1]
+                        .iter()
+                        .map(|state| state[0])
+                })
+                .collect();
+            let last_trajectory = &trajectories[num_intervals - 1];
+            function_values.push(last_trajectory[last_trajectory.len() - 1][0]);
+
+            GridFunction {
+                grid: initial_guess.grid.clone(),
+                function_values,
+            }
+        }))
+    }
+}
+
+/// # Finite difference update
+///
+/// ## Description
+/// `finite_difference_update` returns the Newton update Δ for the small
+/// system of equations `residual_func`(x) = 0 at `x`, given the residual
+/// vector there, `residual_vector`. The dense Jacobian matrix is approximated
+/// one column at a time with forward differences of step `step_size`.
+///
+fn finite_difference_update<R>(
+    residual_func: &R,
+    x: &[f64],
+    residual_vector: &[f64],
+    step_size: f64,
+) -> Result<Vec<f64>, NumericalMethodsError>
+where
+    R: Fn(&[f64]) -> Vec<f64>,
+{
+    let n = x.len();
+    let mut jacobian_matrix = vec![0.0; n * n];
+    let mut perturbed_x = x.to_vec();
+    for j in 0..n {
+        perturbed_x[j] += step_size;
+        let perturbed_residual = residual_func(&perturbed_x);
+        perturbed_x[j] = x[j];
+        for i in 0..n {
+            jacobian_matrix[i * n + j] =
+                (perturbed_residual[i] - residual_vector[i]) / step_size;
+        }
+    }
+
+    let negative_residual_vector: Vec<f64> =
+        residual_vector.iter().map(|r| -r).collect();
+    solve_linear_system(&jacobian_matrix, &negative_residual_vector, n)
+}
+
+/// # Integrate cells
+///
+/// ## Description
+/// `integrate_cells` integrates u'' = `rhs`(x, u, u') as a first order system
+/// for the state [u, u'], starting from `state` at `points[0]`, and returns
+/// the state at each of `points`. Each cell between neighbouring points is
+/// crossed with `steps_per_cell` (at least 1) equal steps of `rk4_step`.
+///
+fn integrate_cells<F>(
+    rhs: &F,
+    points: &[f64],
+    state: [f64; 2],
+    steps_per_cell: usize,
+) -> Vec<[f64; 2]>
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    let steps_per_cell = steps_per_cell.max(1);
+    let f = |x: f64, state: &[f64]| vec![state[1], rhs(x, state[0], state[1])];
+
+    let mut states = vec![state];
+    let mut state = state.to_vec();
+    for cell in points.windows(2) {
+        let h = (cell[1] - cell[0]) / steps_per_cell as f64;
+        for step in 0..steps_per_cell {
+            let x = cell[0] + step as f64 * h;
+            let derivative = f(x, &state);
+            state = rk4_step(&f, x, &state, &derivative, h).0;
+        }
+        states.push([state[0], state[1]]);
+    }
+
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_conditions::BoundaryCondition;
+    use crate::bvp_solvers::FiniteDifferenceSolver;
+    use crate::grid::{max_error, test_grids, Grid};
+
+    #[test]
+    fn test_shooting_matches_finite_differences() {
+        // u'' = 1.5 u^2 with u(0) = 4 and u(1) = 1 has the solution
+        // u = 4 / (1 + x)^2.
+        let rhs = |_x: f64, u: f64, _u_derivative: f64| 1.5 * u * u;
+        let exact = |x: f64| 4.0 / (1.0 + x).powi(2);
+        let bcs = BoundaryConditions::new_dirichlet_bcs(4.0, 1.0);
+
+        for (name, grid) in test_grids(0.0, 1.0, 21) {
+            let initial_guess =
+                GridFunction::new_grid_function(&grid, |x| 4.0 - 3.0 * x);
+
+            let fd_result = FiniteDifferenceSolver::default().solve(
+                rhs,
+                &bcs,
+                &initial_guess,
+            );
+            let shooting_result =
+                ShootingSolver::default().solve(rhs, &bcs, &initial_guess);
+            let multiple_shooting_result = MultipleShootingSolver::default()
+                .solve(rhs, &bcs, &initial_guess);
+
+            assert!(fd_result.converged, "{}", name);
+            assert!(shooting_result.converged, "{}", name);
+            assert!(multiple_shooting_result.converged, "{}", name);
+            assert_eq!(shooting_result.solution.grid, grid);
+            assert_eq!(multiple_shooting_result.solution.grid, grid);
+
+            // RK4 is far more accurate than second order finite differences.
+            assert!(max_error(&fd_result.solution, exact) < 5e-2, "{}", name);
+            assert!(
+                max_error(&shooting_result.solution, exact) < 1e-6,
+                "{}",
+                name
+            );
+            assert!(
+                max_error(&multiple_shooting_result.solution, exact) < 1e-6,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_shooting_with_other_bcs() {
+        // u'' = u - 2 sin(x) has the solution u = sin(x).
+        let rhs = |x: f64, u: f64, _u_derivative: f64| u - 2.0 * x.sin();
+        let two_pi = 2.0 * std::f64::consts::PI;
+        let grid = Grid::new_uniform_grid(0.0, two_pi, 41);
+        let initial_guess =
+            GridFunction::new_constant_grid_function(&grid, 0.0);
+
+        for bcs in [
+            BoundaryConditions::new_periodic_bcs(),
+            BoundaryConditions::new(
+                BoundaryCondition::Neumann(1.0),
+                BoundaryCondition::Robin {
+                    a: 1.0,
+                    b: 2.0,
+                    c: 2.0,
+                },
+            ),
+        ] {
+            let shooting_result =
+                ShootingSolver::default().solve(rhs, &bcs, &initial_guess);
+            let multiple_shooting_result = MultipleShootingSolver::default()
+                .solve(rhs, &bcs, &initial_guess);
+            assert!(max_error(&shooting_result.solution, f64::sin) < 1e-6);
+            assert!(
+                max_error(&multiple_shooting_result.solution, f64::sin) < 1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn test_multiple_shooting_on_a_growing_problem() {
+        // u'' = 900 u with u(0) = u(1) = 1 has the solution
+        // u = cosh(30 (x - 0.5)) / cosh(15), whose growing and decaying parts
+        // vary by a factor of e^30 across the domain.
+        let rhs = |_x: f64, u: f64, _u_derivative: f64| 900.0 * u;
+        let exact = |x: f64| (30.0 * (x - 0.5)).cosh() / 15.0_f64.cosh();
+        let bcs = BoundaryConditions::new_dirichlet_bcs(1.0, 1.0);
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 101);
+        let initial_guess =
+            GridFunction::new_constant_grid_function(&grid, 1.0);
+
+        let solver = MultipleShootingSolver {
+            num_intervals: 20,
+            ..Default::default()
+        };
+        let result = solver.solve(rhs, &bcs, &initial_guess);
+        assert!(result.converged);
+        assert!(max_error(&result.solution, exact) < 1e-6);
+    }
+
+    #[test]
+    fn test_shooting_errors() {
+        let grid = Grid::new_grid_from_points(vec![0.0]);
+        let initial_guess =
+            GridFunction::new_constant_grid_function(&grid, 1.0);
+        let bcs = BoundaryConditions::new_dirichlet_bcs(1.0, 1.0);
+        let rhs = |_x: f64, u: f64, _u_derivative: f64| u;
+
+        assert_eq!(
+            ShootingSolver::default().try_solve(rhs, &bcs, &initial_guess),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 2,
+                actual: 1
+            })
+        );
+
+        // Two Neumann BCs do not determine the solution of u'' = 0.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let initial_guess =
+            GridFunction::new_constant_grid_function(&grid, 1.0);
+        assert_eq!(
+            MultipleShootingSolver::default().try_solve(
+                |_x, _u, _u_derivative| 0.0,
+                &BoundaryConditions::new_neumann_bcs(0.0, 1.0),
+                &initial_guess
+            ),
+            Err(NumericalMethodsError::SingularJacobian)
+        );
+    }
+}
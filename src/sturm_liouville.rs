@@ -0,0 +1,852 @@
+use nalgebra::{DMatrix, SymmetricEigen};
+
+use crate::boundary_conditions::{robin_coefficients, BoundaryConditions};
+use crate::error::NumericalMethodsError;
+use crate::grid::Grid;
+use crate::grid_function::GridFunction;
+use crate::linear_solvers::BandedMatrix;
+
+// Sturm-Liouville eigenvalue problems -(p u')' + q u = lambda w u.
+//
+// The operator is discretised by a finite volume method: each grid point
+// owns the interval between the midpoints of its neighbouring cells, and
+// the flux p u' is approximated by a central difference at each midpoint.
+// This gives a symmetric stiffness matrix K and a diagonal mass matrix M,
+// even on non-uniform grids, so the discrete problem K u = lambda M u is
+// rewritten as the symmetric eigenvalue problem
+// (M^(-1/2) K M^(-1/2)) v = lambda v, with u = M^(-1/2) v.
+//
+// Dirichlet ends are eliminated from the unknowns. Neumann and Robin ends
+// are kept, with a half control volume whose outer flux is given by the BC.
+// With periodic BCs the two end points are the same unknown.
+
+/// # Eigen method
+///
+/// ## Description
+/// `EigenMethod` selects how the discrete eigenvalue problem is solved by
+/// `solve_sturm_liouville`.
+///
+/// - `SymmetricEigen` computes every eigenpair with nalgebra's symmetric
+///   eigensolver, which takes O(n^3) operations and O(n^2) memory for n
+///   grid points.
+/// - `InverseIteration` computes only the lowest eigenpairs, by subspace
+///   inverse iteration with the tridiagonal matrix of the discrete operator,
+///   which takes O(n) operations and memory per iteration. With periodic BCs
+///   the matrix has non-zero corner elements, so it is stored with a full
+///   band and `SymmetricEigen` is usually faster.
+///
+/// ## Example use case
+/// ```
+/// let method = EigenMethod::InverseIteration(InverseIterationOptions {
+///     tolerance: 1e-12,
+///     ..Default::default()
+/// });
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EigenMethod {
+    SymmetricEigen,
+    InverseIteration(InverseIterationOptions),
+}
+
+/// # Inverse iteration options
+///
+/// ## Description
+/// `InverseIterationOptions` controls `EigenMethod::InverseIteration`. The
+/// iteration stops once the residual norm of every requested eigenpair of
+/// the symmetric discrete operator is at most `tolerance` times the norm of
+/// the operator, or after `max_iterations` iterations.
+///
+/// ## Example use case
+/// ```
+/// let options = InverseIterationOptions::default();
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseIterationOptions {
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl Default for InverseIterationOptions {
+    fn default() -> Self {
+        InverseIterationOptions {
+            tolerance: 1e-10,
+            max_iterations: 500,
+        }
+    }
+}
+
+/// # Sturm-Liouville result
+///
+/// ## Description
+/// `SturmLiouvilleResult` is returned by `solve_sturm_liouville`.
+/// `eigenvalues` contains the lowest eigenvalues in increasing order, and
+/// `eigenfunctions[k]` is the eigenfunction of `eigenvalues[k]`.
+///
+/// The eigenfunctions are normalized so that the discrete weighted norm
+/// sum_i w(x_i) u(x_i)^2 dx_i, which approximates the integral of w u^2, is
+/// 1, and are orthogonal in the corresponding inner product. The sign of
+/// each eigenfunction is chosen so that its first non-zero value is
+/// positive.
+///
+/// `iterations` is the number of inverse iterations, and is 0 for
+/// `EigenMethod::SymmetricEigen`. `converged` is false if the inverse
+/// iteration reached `max_iterations` before meeting its tolerance.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SturmLiouvilleResult {
+    pub eigenvalues: Vec<f64>,
+    pub eigenfunctions: Vec<GridFunction>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+/// # Solve Sturm-Liouville
+///
+/// ## Description
+/// `solve_sturm_liouville` computes the lowest `num_eigenpairs` eigenvalues
+/// lambda and eigenfunctions u of the Sturm-Liouville problem
+/// -(p u')' + q u = lambda w u on `grid`, with the homogeneous BCs
+/// `boundary_conditions`, using `method`. The coefficients are passed as the
+/// closures `p(x)`, `q(x)` and `w(x)`, where p and w must be positive.
+///
+/// The eigenvalues converge at second order in the grid spacing on uniform
+/// grids, and the eigenfunctions are returned as `GridFunction`s on `grid`.
+///
+/// `solve_sturm_liouville` panics if the solve fails. See
+/// `try_solve_sturm_liouville` for a version which returns an error instead.
+///
+/// ## Example use case
+/// Suppose that we want the three lowest energy levels of a quantum
+/// harmonic oscillator, -u'' + x^2 u = lambda u, which are 1, 3 and 5. The
+/// code below approximates them on [-8, 8], where the eigenfunctions are
+/// negligible at the ends.
+/// ```
+/// let grid = Grid::new_uniform_grid(-8.0, 8.0, 401);
+/// let result = solve_sturm_liouville(
+///     |_x| 1.0,
+///     |x| x * x,
+///     |_x| 1.0,
+///     &BoundaryConditions::new_dirichlet_bcs(0.0, 0.0),
+///     &grid,
+///     3,
+///     &EigenMethod::SymmetricEigen,
+/// );
+/// ```
+///
+pub fn solve_sturm_liouville<P, Q, W>(
+    p: P,
+    q: Q,
+    w: W,
+    boundary_conditions: &BoundaryConditions,
+    grid: &Grid,
+    num_eigenpairs: usize,
+    method: &EigenMethod,
+) -> SturmLiouvilleResult
+where
+    P: Fn(f64) -> f64,
+    Q: Fn(f64) -> f64,
+    W: Fn(f64) -> f64,
+{
+    match try_solve_sturm_liouville(
+        p,
+        q,
+        w,
+        boundary_conditions,
+        grid,
+        num_eigenpairs,
+        method,
+    ) {
+        Ok(result) => result,
+        Err(error) => panic!("Sturm-Liouville solve failed. {}", error),
+    }
+}
+
+/// # Try solve Sturm-Liouville
+///
+/// ## Description
+/// `try_solve_sturm_liouville` is the fallible version of
+/// `solve_sturm_liouville`. It returns an error if `grid` is not valid or
+/// has fewer than 3 points, if the BCs are not valid or not homogeneous, if
+/// p or w is not positive or a coefficient is not finite, if
+/// `num_eigenpairs` is 0 or more than the number of unknowns, if the inverse
+/// iteration options are not valid, or if a linear solve fails.
+///
+/// ## Example use case
+/// ```
+/// let result = try_solve_sturm_liouville(
+///     p,
+///     q,
+///     w,
+///     &boundary_conditions,
+///     &grid,
+///     5,
+///     &EigenMethod::InverseIteration(InverseIterationOptions::default()),
+/// )?;
+/// ```
+///
+pub fn try_solve_sturm_liouville<P, Q, W>(
+    p: P,
+    q: Q,
+    w: W,
+    boundary_conditions: &BoundaryConditions,
+    grid: &Grid,
+    num_eigenpairs: usize,
+    method: &EigenMethod,
+) -> Result<SturmLiouvilleResult, NumericalMethodsError>
+where
+    P: Fn(f64) -> f64,
+    Q: Fn(f64) -> f64,
+    W: Fn(f64) -> f64,
+{
+    grid.validate()?;
+    let num_points = grid.grid_points.len();
+    if num_points < 3 {
+        return Err(NumericalMethodsError::InsufficientPoints {
+            required: 3,
+            actual: num_points,
+        });
+    }
+    boundary_conditions.validate()?;
+
+    let operator = assemble_operator(p, q, w, boundary_conditions, grid)?;
+    let num_unknowns = operator.unknowns.len();
+    if num_eigenpairs == 0 || num_eigenpairs > num_unknowns {
+        return Err(NumericalMethodsError::InvalidParameter(format!(
+            "The number of eigenpairs must be between 1 and the number of \
+             unknowns, {}.",
+            num_unknowns
+        )));
+    }
+
+    let (eigenvalues, eigenvectors, iterations, converged) = match method {
+        EigenMethod::SymmetricEigen => {
+            let (eigenvalues, eigenvectors) =
+                symmetric_eigenpairs(&operator.matrix, num_eigenpairs);
+            (eigenvalues, eigenvectors, 0, true)
+        }
+        EigenMethod::InverseIteration(options) => {
+            let options_valid =
+                options.tolerance > 0.0 && options.max_iterations > 0;
+            if !options_valid {
+                return Err(NumericalMethodsError::InvalidParameter(
+                    "The tolerance and maximum number of iterations must be \
+                     positive."
+                        .to_string(),
+                ));
+            }
+            inverse_iteration(&operator, num_eigenpairs, options)?
+        }
+    };
+
+    let eigenfunctions = eigenvectors
+        .column_iter()
+        .map(|eigenvector| {
+            let mut function_values = vec![0.0; num_points];
+            for (row, &node) in operator.unknowns.iter().enumerate() {
+                function_values[node] =
+                    eigenvector[row] / operator.mass[node].sqrt();
+            }
+            if boundary_conditions.is_periodic() {
+                function_values[num_points - 1] = function_values[0];
+            }
+
+            let max_value =
+                function_values.iter().fold(0.0, |max, u| u.abs().max(max));
+            if let Some(first_value) =
+                function_values.iter().find(|u| u.abs() > 1e-3 * max_value)
+            {
+                if *first_value < 0.0 {
+                    function_values.iter_mut().for_each(|u| *u = -*u);
+                }
+            }
+
+            GridFunction {
+                grid: grid.clone(),
+                function_values,
+            }
+        })
+        .collect();
+
+    Ok(SturmLiouvilleResult {
+        eigenvalues,
+        eigenfunctions,
+        iterations,
+        converged,
+    })
+}
+
+/// # Discrete operator
+///
+/// ## Description
+/// `DiscreteOperator` is the discretised Sturm-Liouville operator.
+/// `matrix` is the symmetric matrix M^(-1/2) K M^(-1/2) for the unknowns,
+/// where `unknowns[i]` is the grid index of the ith unknown, and `mass[j]`
+/// is the mass matrix element of grid point j. `lower_bound` and `norm` are
+/// a lower bound for the eigenvalues and an upper bound for their
+/// magnitudes.
+///
+struct DiscreteOperator {
+    matrix: BandedMatrix,
+    unknowns: Vec<usize>,
+    mass: Vec<f64>,
+    lower_bound: f64,
+    norm: f64,
+}
+
+/// # Assemble operator
+///
+/// ## Description
+/// `assemble_operator` discretises -(p u')' + q u = lambda w u on `grid`
+/// with the BCs `boundary_conditions`, as described at the top of this
+/// module.
+///
+fn assemble_operator<P, Q, W>(
+    p: P,
+    q: Q,
+    w: W,
+    boundary_conditions: &BoundaryConditions,
+    grid: &Grid,
+) -> Result<DiscreteOperator, NumericalMethodsError>
+where
+    P: Fn(f64) -> f64,
+    Q: Fn(f64) -> f64,
+    W: Fn(f64) -> f64,
+{
+    let points = &grid.grid_points;
+    let last = points.len() - 1;
+    let periodic = boundary_conditions.is_periodic();
+
+    // With periodic BCs the last grid point is the same node as the first.
+    let num_nodes = if periodic { last } else { last + 1 };
+    let node = |i: usize| if i == num_nodes { 0 } else { i };
+    let bandwidth = if periodic { num_nodes - 1 } else { 1 };
+    let mut stiffness = BandedMatrix::new(num_nodes, bandwidth, bandwidth);
+    let mut add = |row: usize, column: usize, value: f64| {
+        stiffness.set(row, column, stiffness.get(row, column) + value);
+    };
+
+    let invalid_coefficient = |name: &str, x: f64| {
+        Err(NumericalMethodsError::InvalidParameter(format!(
+            "The coefficient {} is not valid at x = {}.",
+            name, x
+        )))
+    };
+
+    let mut volumes = vec![0.0; num_nodes];
+    for j in 0..last {
+        let width = points[j + 1] - points[j];
+        let midpoint = 0.5 * (points[j] + points[j + 1]);
+        let p_midpoint = p(midpoint);
+        if !(p_midpoint > 0.0 && p_midpoint.is_finite()) {
+            return invalid_coefficient("p", midpoint);
+        }
+
+        let conductance = p_midpoint / width;
+        let (left, right) = (node(j), node(j + 1));
+        add(left, left, conductance);
+        add(right, right, conductance);
+        add(left, right, -conductance);
+        add(right, left, -conductance);
+        volumes[left] += 0.5 * width;
+        volumes[right] += 0.5 * width;
+    }
+
+    let mut mass = vec![0.0; num_nodes];
+    for i in 0..num_nodes {
+        let (q_value, w_value) = (q(points[i]), w(points[i]));
+        if !q_value.is_finite() {
+            return invalid_coefficient("q", points[i]);
+        }
+        if !(w_value > 0.0 && w_value.is_finite()) {
+            return invalid_coefficient("w", points[i]);
+        }
+        add(i, i, q_value * volumes[i]);
+        mass[i] = w_value * volumes[i];
+    }
+
+    let mut unknowns: Vec<usize> = (0..num_nodes).collect();
+    if !periodic {
+        // The BC a * u + b * u' = 0 gives the flux p u' = -(p a / b) u at
+        // the end, or removes the end from the unknowns if b = 0.
+        let ends = [
+            (boundary_conditions.left_bc, 0, -1.0),
+            (boundary_conditions.right_bc, last, 1.0),
+        ];
+        for (bc, end, sign) in ends {
+            let (a, b, c) = robin_coefficients(bc);
+            if c != 0.0 {
+                return Err(NumericalMethodsError::InvalidBoundaryConditions(
+                    "The BCs of an eigenvalue problem must be homogeneous."
+                        .to_string(),
+                ));
+            }
+
+            if b == 0.0 {
+                unknowns.retain(|&i| i != end);
+            } else {
+                let p_end = p(points[end]);
+                if !(p_end > 0.0 && p_end.is_finite()) {
+                    return invalid_coefficient("p", points[end]);
+                }
+                add(end, end, sign * p_end * a / b);
+            }
+        }
+    }
+
+    let size = unknowns.len();
+    let bandwidth = if periodic { size.saturating_sub(1) } else { 1 };
+    let mut matrix = BandedMatrix::new(size, bandwidth, bandwidth);
+    let mut lower_bound = f64::INFINITY;
+    let mut norm: f64 = 0.0;
+    for (row, &i) in unknowns.iter().enumerate() {
+        let first = row.saturating_sub(bandwidth);
+        let last = (row + bandwidth).min(size.saturating_sub(1));
+        let mut radius = 0.0;
+        for (column, &j) in
+            unknowns.iter().enumerate().take(last + 1).skip(first)
+        {
+            let value = stiffness.get(i, j);
+            matrix.set(row, column, value / (mass[i] * mass[j]).sqrt());
+            if column != row {
+                radius += value.abs() / mass[i];
+            }
+        }
+
+        // Gershgorin bounds for M^(-1) K, which has the same eigenvalues as
+        // the symmetric matrix but much tighter bounds.
+        let diagonal = stiffness.get(i, i) / mass[i];
+        lower_bound = lower_bound.min(diagonal - radius);
+        norm = norm.max(diagonal.abs() + radius);
+    }
+
+    let mut full_mass = mass;
+    if periodic {
+        full_mass.push(full_mass[0]);
+    }
+
+    Ok(DiscreteOperator {
+        matrix,
+        unknowns,
+        mass: full_mass,
+        lower_bound,
+        norm,
+    })
+}
+
+/// # Symmetric eigenpairs
+///
+/// ## Description
+/// `symmetric_eigenpairs` returns the lowest `num_eigenpairs` eigenvalues of
+/// the symmetric `matrix` in increasing order, and the matrix whose columns
+/// are the corresponding orthonormal eigenvectors.
+///
+fn symmetric_eigenpairs(
+    matrix: &BandedMatrix,
+    num_eigenpairs: usize,
+) -> (Vec<f64>, DMatrix<f64>) {
+    let size = matrix.size;
+    let dense =
+        DMatrix::from_fn(size, size, |row, column| matrix.get(row, column));
+    sorted_eigenpairs(SymmetricEigen::new(dense), num_eigenpairs)
+}
+
+/// # Sorted eigenpairs
+///
+/// ## Description
+/// `sorted_eigenpairs` returns the lowest `num_eigenpairs` eigenvalues of
+/// the decomposition `eigen` in increasing order, with their eigenvectors as
+/// the columns of a matrix.
+///
+fn sorted_eigenpairs(
+    eigen: SymmetricEigen<f64, nalgebra::Dyn>,
+    num_eigenpairs: usize,
+) -> (Vec<f64>, DMatrix<f64>) {
+    let mut order: Vec<usize> = (0..eigen.eigenvalues.len()).collect();
+    order.sort_by(|&i, &j| {
+        eigen.eigenvalues[i].total_cmp(&eigen.eigenvalues[j])
+    });
+    order.truncate(num_eigenpairs);
+
+    let eigenvalues = order.iter().map(|&i| eigen.eigenvalues[i]).collect();
+    let eigenvectors = DMatrix::from_fn(
+        eigen.eigenvectors.nrows(),
+        num_eigenpairs,
+        |row, column| eigen.eigenvectors[(row, order[column])],
+    );
+    (eigenvalues, eigenvectors)
+}
+
+/// # Inverse iteration
+///
+/// ## Description
+/// `inverse_iteration` computes the lowest `num_eigenpairs` eigenpairs of
+/// the symmetric matrix of `operator` by subspace inverse iteration, and
+/// returns them as for `symmetric_eigenpairs`, with the number of iterations
+/// and whether the iteration converged.
+///
+/// A block of extra vectors is iterated alongside the requested ones, which
+/// speeds up convergence and handles repeated eigenvalues. Each iteration
+/// solves with the matrix shifted below the lower bound of `operator`, so that
+/// the shifted matrix is positive definite, then finds the eigenpairs within
+/// the new subspace by the Rayleigh-Ritz method.
+///
+fn inverse_iteration(
+    operator: &DiscreteOperator,
+    num_eigenpairs: usize,
+    options: &InverseIterationOptions,
+) -> Result<(Vec<f64>, DMatrix<f64>, usize, bool), NumericalMethodsError> {
+    let matrix = &operator.matrix;
+    let norm = operator.norm;
+    let size = matrix.size;
+    let block_size = (2 * num_eigenpairs).max(num_eigenpairs + 4).min(size);
+
+    let shift = operator.lower_bound - 1e-6 * norm - f64::MIN_POSITIVE;
+    let mut shifted_matrix = matrix.clone();
+    for i in 0..size {
+        shifted_matrix.set(i, i, matrix.get(i, i) - shift);
+    }
+
+    // A fixed, well spread starting block, so that results are repeatable.
+    const GOLDEN_RATIO: f64 = 1.618_033_988_749_895;
+    let mut basis = DMatrix::from_fn(size, block_size, |row, column| {
+        ((row * block_size + column + 1) as f64 * GOLDEN_RATIO).fract() - 0.5
+    });
+    let mut eigenvalues = vec![0.0; block_size];
+
+    for iteration in 1..=options.max_iterations {
+        let mut solved = DMatrix::zeros(size, block_size);
+        for (column, basis_column) in basis.column_iter().enumerate() {
+            let rhs: Vec<f64> = basis_column.iter().copied().collect();
+            let solution = shifted_matrix.solve(&rhs)?;
+            solved.set_column(column, &nalgebra::DVector::from_vec(solution));
+        }
+
+        // Rayleigh-Ritz in the orthonormalised subspace.
+        let subspace = solved.qr().q();
+        let mut image = DMatrix::zeros(size, block_size);
+        for (column, subspace_column) in subspace.column_iter().enumerate() {
+            let vector: Vec<f64> = subspace_column.iter().copied().collect();
+            let product = matrix.multiply_vector(&vector);
+            image.set_column(column, &nalgebra::DVector::from_vec(product));
+        }
+        let projected = subspace.transpose() * &image;
+        let projected = 0.5 * (&projected + projected.transpose());
+        let (ritz_values, ritz_vectors) =
+            sorted_eigenpairs(SymmetricEigen::new(projected), block_size);
+
+        basis = &subspace * &ritz_vectors;
+        let image = &image * &ritz_vectors;
+        eigenvalues = ritz_values;
+
+        let converged = (0..num_eigenpairs).all(|k| {
+            let residual = image.column(k) - basis.column(k) * eigenvalues[k];
+            residual.norm() <= options.tolerance * norm
+        });
+        if converged {
+            eigenvalues.truncate(num_eigenpairs);
+            let eigenvectors = basis.columns(0, num_eigenpairs).into_owned();
+            return Ok((eigenvalues, eigenvectors, iteration, true));
+        }
+    }
+
+    eigenvalues.truncate(num_eigenpairs);
+    let eigenvectors = basis.columns(0, num_eigenpairs).into_owned();
+    Ok((eigenvalues, eigenvectors, options.max_iterations, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_conditions::BoundaryCondition;
+    use crate::grid::test_grids;
+
+    const PI: f64 = std::f64::consts::PI;
+
+    fn methods() -> [EigenMethod; 2] {
+        [
+            EigenMethod::SymmetricEigen,
+            EigenMethod::InverseIteration(InverseIterationOptions::default()),
+        ]
+    }
+
+    // The discrete weighted inner product used to normalize eigenfunctions.
+    fn inner_product(u: &GridFunction, v: &GridFunction, w: f64) -> f64 {
+        let points = &u.grid.grid_points;
+        let last = points.len() - 1;
+        (0..=last)
+            .map(|i| {
+                let left = if i > 0 {
+                    points[i] - points[i - 1]
+                } else {
+                    0.0
+                };
+                let right = if i < last {
+                    points[i + 1] - points[i]
+                } else {
+                    0.0
+                };
+                0.5 * (left + right)
+                    * w
+                    * u.function_values[i]
+                    * v.function_values[i]
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_dirichlet_eigenpairs() {
+        // -u'' = lambda u on [0, PI] with u = 0 at both ends has eigenvalues
+        // k^2 and normalized eigenfunctions sqrt(2 / PI) sin(k x).
+        let bcs = BoundaryConditions::new_dirichlet_bcs(0.0, 0.0);
+        for (name, grid) in test_grids(0.0, PI, 101) {
+            for method in methods() {
+                let result = solve_sturm_liouville(
+                    |_x| 1.0,
+                    |_x| 0.0,
+                    |_x| 1.0,
+                    &bcs,
+                    &grid,
+                    4,
+                    &method,
+                );
+                assert!(result.converged, "{} {:?}", name, method);
+                for (k, (eigenvalue, eigenfunction)) in result
+                    .eigenvalues
+                    .iter()
+                    .zip(&result.eigenfunctions)
+                    .enumerate()
+                {
+                    let wavenumber = (k + 1) as f64;
+                    let exact_eigenvalue = wavenumber * wavenumber;
+                    assert!(
+                        (eigenvalue - exact_eigenvalue).abs()
+                            < 1e-2 * exact_eigenvalue,
+                        "{} {:?} eigenvalue {}",
+                        name,
+                        method,
+                        eigenvalue
+                    );
+                    for (x, u) in grid
+                        .grid_points
+                        .iter()
+                        .zip(&eigenfunction.function_values)
+                    {
+                        let exact = (2.0 / PI).sqrt() * (wavenumber * x).sin();
+                        assert!(
+                            (u - exact).abs() < 1e-2,
+                            "{} {:?}",
+                            name,
+                            method
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_methods_agree() {
+        // A variable coefficient problem with a Robin BC.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 201);
+        let bcs = BoundaryConditions::new(
+            BoundaryCondition::Neumann(0.0),
+            BoundaryCondition::Robin {
+                a: 2.0,
+                b: 1.0,
+                c: 0.0,
+            },
+        );
+        let solve = |method: EigenMethod| {
+            solve_sturm_liouville(
+                |x| 1.0 + x,
+                |x| x * x,
+                |x| 2.0 - x,
+                &bcs,
+                &grid,
+                5,
+                &method,
+            )
+        };
+        let dense_result = solve(EigenMethod::SymmetricEigen);
+        let iteration_result = solve(EigenMethod::InverseIteration(
+            InverseIterationOptions::default(),
+        ));
+        assert!(iteration_result.converged);
+        assert!(iteration_result.iterations > 0);
+        for k in 0..5 {
+            let eigenvalue = dense_result.eigenvalues[k];
+            assert!(
+                (iteration_result.eigenvalues[k] - eigenvalue).abs()
+                    < 1e-8 * eigenvalue
+            );
+            let dense_values = &dense_result.eigenfunctions[k].function_values;
+            let iteration_values =
+                &iteration_result.eigenfunctions[k].function_values;
+            for (u, v) in dense_values.iter().zip(iteration_values) {
+                assert!((u - v).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_robin_and_neumann_bcs() {
+        // -u'' = lambda u with u(0) = 0 and u(1) + u'(1) = 0 has the lowest
+        // eigenvalue k^2, where tan(k) = -k, so k = 2.028757838110434.
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 201);
+        let bcs = BoundaryConditions::new(
+            BoundaryCondition::Dirichlet(0.0),
+            BoundaryCondition::Robin {
+                a: 1.0,
+                b: 1.0,
+                c: 0.0,
+            },
+        );
+        let result = solve_sturm_liouville(
+            |_x| 1.0,
+            |_x| 0.0,
+            |_x| 1.0,
+            &bcs,
+            &grid,
+            1,
+            &EigenMethod::SymmetricEigen,
+        );
+        let wavenumber = 2.028757838110434_f64;
+        assert!((result.eigenvalues[0] - wavenumber.powi(2)).abs() < 1e-3);
+
+        // With Neumann BCs the lowest eigenfunction is constant, with
+        // eigenvalue 0, and the others are cos(k PI x).
+        let result = solve_sturm_liouville(
+            |_x| 1.0,
+            |_x| 0.0,
+            |_x| 1.0,
+            &BoundaryConditions::new_neumann_bcs(0.0, 0.0),
+            &grid,
+            3,
+            &EigenMethod::InverseIteration(InverseIterationOptions::default()),
+        );
+        assert!(result.eigenvalues[0].abs() < 1e-8);
+        for value in &result.eigenfunctions[0].function_values {
+            assert!((value - 1.0).abs() < 1e-8);
+        }
+        for k in 1..3 {
+            let exact = (k as f64 * PI).powi(2);
+            assert!((result.eigenvalues[k] - exact).abs() < 1e-3 * exact);
+        }
+    }
+
+    #[test]
+    fn test_periodic_eigenpairs() {
+        // -u'' = lambda u on [0, 2 PI] with periodic BCs has eigenvalues
+        // 0, 1, 1, 4, 4, ... and orthonormal eigenfunctions.
+        let grid = Grid::new_uniform_grid(0.0, 2.0 * PI, 101);
+        for method in methods() {
+            let result = solve_sturm_liouville(
+                |_x| 1.0,
+                |_x| 0.0,
+                |_x| 1.0,
+                &BoundaryConditions::new_periodic_bcs(),
+                &grid,
+                5,
+                &method,
+            );
+            for (eigenvalue, exact) in
+                result.eigenvalues.iter().zip([0.0, 1.0, 1.0, 4.0, 4.0])
+            {
+                assert!((eigenvalue - exact).abs() < 1e-2, "{:?}", method);
+            }
+
+            for (i, u) in result.eigenfunctions.iter().enumerate() {
+                let values = &u.function_values;
+                assert_eq!(values[0], values[100]);
+                for (j, v) in result.eigenfunctions.iter().enumerate() {
+                    let product = inner_product(u, v, 1.0);
+                    let expected = if i == j { 1.0 } else { 0.0 };
+                    assert!((product - expected).abs() < 1e-8, "{:?}", method);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_harmonic_oscillator() {
+        // -u'' + x^2 u = lambda u has eigenvalues 1, 3, 5 and 7.
+        let grid = Grid::new_uniform_grid(-8.0, 8.0, 401);
+        let result = solve_sturm_liouville(
+            |_x| 1.0,
+            |x| x * x,
+            |_x| 1.0,
+            &BoundaryConditions::new_dirichlet_bcs(0.0, 0.0),
+            &grid,
+            4,
+            &EigenMethod::InverseIteration(InverseIterationOptions::default()),
+        );
+        for (k, eigenvalue) in result.eigenvalues.iter().enumerate() {
+            assert!((eigenvalue - (2 * k + 1) as f64).abs() < 1e-2);
+        }
+        let ground_state = &result.eigenfunctions[0];
+        assert!(
+            (inner_product(ground_state, ground_state, 1.0) - 1.0).abs()
+                < 1e-12
+        );
+        for (x, u) in grid.grid_points.iter().zip(&ground_state.function_values)
+        {
+            let exact = PI.powf(-0.25) * (-x * x / 2.0).exp();
+            assert!((u - exact).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_try_solve_sturm_liouville_errors() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+        let bcs = BoundaryConditions::new_dirichlet_bcs(0.0, 0.0);
+        let method = EigenMethod::SymmetricEigen;
+        let one = |_x: f64| 1.0;
+
+        assert!(matches!(
+            try_solve_sturm_liouville(
+                one,
+                one,
+                one,
+                &BoundaryConditions::new_dirichlet_bcs(0.0, 1.0),
+                &grid,
+                1,
+                &method,
+            ),
+            Err(NumericalMethodsError::InvalidBoundaryConditions(_))
+        ));
+        assert!(matches!(
+            try_solve_sturm_liouville(
+                |x| x - 0.5,
+                one,
+                one,
+                &bcs,
+                &grid,
+                1,
+                &method
+            ),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        // There are only 9 unknowns, as the Dirichlet ends are eliminated.
+        assert!(matches!(
+            try_solve_sturm_liouville(one, one, one, &bcs, &grid, 10, &method),
+            Err(NumericalMethodsError::InvalidParameter(_))
+        ));
+        assert_eq!(
+            try_solve_sturm_liouville(
+                one,
+                one,
+                one,
+                &bcs,
+                &Grid::new_uniform_grid(0.0, 1.0, 2),
+                1,
+                &method
+            ),
+            Err(NumericalMethodsError::InsufficientPoints {
+                required: 3,
+                actual: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use crate::error::NumericalMethodsError;
+use crate::grid::{Grid, GRID_TOLERANCE};
+use crate::grid_function::GridFunction;
+
+/// # Vector grid function
+///
+/// ## Description
+/// `VectorGridFunction` represents a vector-valued function of a real variable
+/// sampled on a grid of 1D points, such as the unknowns (u(x), v(x)) of a
+/// system of coupled DEs. Each component is stored as a `GridFunction` in
+/// `components`, and all of the components share the same `Grid`.
+///
+/// ## Example use case
+/// Suppose that we want to sample the functions u(x) = sin(x) and v(x) =
+/// cos(x) on a uniform grid. The code below does this.
+/// ```
+/// let grid = Grid::new_uniform_grid(0.0, 1.0, 11);
+/// let vector_grid_func = VectorGridFunction::new_vector_grid_function(vec![
+///     GridFunction::new_grid_function(&grid, f64::sin),
+///     GridFunction::new_grid_function(&grid, f64::cos),
+/// ]);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorGridFunction {
+    pub components: Vec<GridFunction>,
+}
+
+impl VectorGridFunction {
+    /// # New vector grid function
+    ///
+    /// ## Description
+    /// `new_vector_grid_function` creates a `VectorGridFunction` from the
+    /// `GridFunction`s `components`.
+    ///
+    /// If there are no components, or the components are not valid or do not
+    /// share the same `Grid`, `new_vector_grid_function` panics. See
+    /// `try_new_vector_grid_function` for a version which returns an error
+    /// instead.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let vector_grid_func =
+    ///     VectorGridFunction::new_vector_grid_function(vec![u, v]);
+    /// ```
+    ///
+    pub fn new_vector_grid_function(components: Vec<GridFunction>) -> Self {
+        match VectorGridFunction::try_new_vector_grid_function(components) {
+            Ok(vector_grid_func) => vector_grid_func,
+            Err(error) => {
+                panic!("Vector grid function failed to construct. {}", error)
+            }
+        }
+    }
+
+    /// # Try new vector grid function
+    ///
+    /// ## Description
+    /// `try_new_vector_grid_function` is the fallible version of
+    /// `new_vector_grid_function`. It returns an error if there are no
+    /// components, or the components are not valid or do not share the same
+    /// `Grid`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let vector_grid_func =
+    ///     VectorGridFunction::try_new_vector_grid_function(vec![u, v])?;
+    /// ```
+    ///
+    pub fn try_new_vector_grid_function(
+        components: Vec<GridFunction>,
+    ) -> Result<Self, NumericalMethodsError> {
+        let vector_grid_func = VectorGridFunction { components };
+        vector_grid_func.validate()?;
+        Ok(vector_grid_func)
+    }
+
+    /// # New constant vector grid function
+    ///
+    /// ## Description
+    /// `new_constant_vector_grid_function` creates a `VectorGridFunction` on
+    /// `grid` whose ith component is the constant `values[i]`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// let initial_guess =
+    ///     VectorGridFunction::new_constant_vector_grid_function(&grid, &[1.0, 0.0]);
+    /// ```
+    ///
+    pub fn new_constant_vector_grid_function(
+        grid: &Grid,
+        values: &[f64],
+    ) -> Self {
+        VectorGridFunction {
+            components: values
+                .iter()
+                .map(|&value| {
+                    GridFunction::new_constant_grid_function(grid, value)
+                })
+                .collect(),
+        }
+    }
+
+    /// # Number of components
+    ///
+    /// ## Description
+    /// `num_components` returns the number of components.
+    ///
+    pub fn num_components(&self) -> usize {
+        self.components.len()
+    }
+
+    /// # Grid
+    ///
+    /// ## Description
+    /// `grid` returns the `Grid` shared by the components. `grid` panics if
+    /// there are no components.
+    ///
+    pub fn grid(&self) -> &Grid {
+        &self.components[0].grid
+    }
+
+    /// # Validate
+    ///
+    /// ## Description
+    /// `validate` checks that there is at least one component, that each
+    /// component is a valid `GridFunction`, and that the components have
+    /// compatible `Grids`.
+    ///
+    /// ## Example use case
+    /// ```
+    /// vector_grid_func.validate()?;
+    /// ```
+    ///
+    pub fn validate(&self) -> Result<(), NumericalMethodsError> {
+        let first_component = match self.components.first() {
+            Some(component) => component,
+            None => {
+                return Err(NumericalMethodsError::InvalidGrid(
+                    "A vector grid function needs at least one component."
+                        .to_string(),
+                ))
+            }
+        };
+
+        for component in &self.components {
+            component.validate()?;
+            if !component
+                .grid
+                .is_compatible(&first_component.grid, GRID_TOLERANCE)
+            {
+                return Err(NumericalMethodsError::GridMismatch);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Validate with minimum points
+    ///
+    /// ## Description
+    /// `validate_with_min_points` runs `validate`, and also checks that the
+    /// `Grid` has at least `min_points` grid points.
+    ///
+    pub(crate) fn validate_with_min_points(
+        &self,
+        min_points: usize,
+    ) -> Result<(), NumericalMethodsError> {
+        self.validate()?;
+        self.components[0].validate_with_min_points(min_points)
+    }
+
+    /// # To interleaved values
+    ///
+    /// ## Description
+    /// `to_interleaved_values` returns the function values of all of the
+    /// components in a single vector, ordered by grid point and then by
+    /// component, i.e. [u_0, v_0, u_1, v_1, ...].
+    ///
+    /// This ordering keeps the unknowns at neighbouring grid points close
+    /// together, so the Jacobian matrices of systems of DEs are banded.
+    ///
+    pub(crate) fn to_interleaved_values(&self) -> Vec<f64> {
+        let num_points = self.grid().grid_points.len();
+        (0..num_points)
+            .flat_map(|i| {
+                self.components
+                    .iter()
+                    .map(move |component| component.function_values[i])
+            })
+            .collect()
+    }
+
+    /// # From interleaved values
+    ///
+    /// ## Description
+    /// `from_interleaved_values` is the inverse of `to_interleaved_values`. It
+    /// creates a `VectorGridFunction` on `grid` with `num_components`
+    /// components from the interleaved function values `values`.
+    ///
+    pub(crate) fn from_interleaved_values(
+        grid: &Grid,
+        num_components: usize,
+        values: &[f64],
+    ) -> Self {
+        VectorGridFunction {
+            components: (0..num_components)
+                .map(|c| GridFunction {
+                    grid: grid.clone(),
+                    function_values: values
+                        .iter()
+                        .skip(c)
+                        .step_by(num_components)
+                        .copied()
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_vector_grid_function() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let vector_grid_func =
+            VectorGridFunction::new_vector_grid_function(vec![
+                GridFunction::new_grid_function(&grid, |x| x),
+                GridFunction::new_grid_function(&grid, |x| 2.0 * x),
+            ]);
+        assert_eq!(vector_grid_func.num_components(), 2);
+        assert_eq!(vector_grid_func.grid(), &grid);
+
+        let constant = VectorGridFunction::new_constant_vector_grid_function(
+            &grid,
+            &[1.0, 2.0, 3.0],
+        );
+        assert_eq!(constant.num_components(), 3);
+        assert_eq!(constant.components[2].function_values, vec![3.0; 5]);
+    }
+
+    #[test]
+    fn test_try_new_vector_grid_function_errors() {
+        assert!(matches!(
+            VectorGridFunction::try_new_vector_grid_function(vec![]),
+            Err(NumericalMethodsError::InvalidGrid(_))
+        ));
+
+        let grid_1 = Grid::new_uniform_grid(0.0, 1.0, 5);
+        let grid_2 = Grid::new_uniform_grid(0.0, 1.0, 6);
+        assert_eq!(
+            VectorGridFunction::try_new_vector_grid_function(vec![
+                GridFunction::new_constant_grid_function(&grid_1, 1.0),
+                GridFunction::new_constant_grid_function(&grid_2, 1.0),
+            ]),
+            Err(NumericalMethodsError::GridMismatch)
+        );
+    }
+
+    #[test]
+    fn test_interleaved_values() {
+        let grid = Grid::new_uniform_grid(0.0, 1.0, 3);
+        let vector_grid_func =
+            VectorGridFunction::new_vector_grid_function(vec![
+                GridFunction::new_grid_function(&grid, |x| x),
+                GridFunction::new_grid_function(&grid, |x| 10.0 + x),
+            ]);
+
+        let values = vector_grid_func.to_interleaved_values();
+        assert_eq!(values, vec![0.0, 10.0, 0.5, 10.5, 1.0, 11.0]);
+        assert_eq!(
+            VectorGridFunction::from_interleaved_values(&grid, 2, &values),
+            vector_grid_func
+        );
+    }
+}